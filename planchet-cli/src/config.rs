@@ -0,0 +1,175 @@
+//! The configuration directory of the CLI, holding the profiles of its
+//! configuration file, the tokens cached by `login`, and the watchlists of
+//! `watch`.
+//!
+//! The profiles of the configuration file, `config.toml`, are described in
+//! the `--profile` section of the documentation of the CLI.
+use anyhow::{bail, Context, Result};
+use planchet::oauth::Token;
+use planchet::watchlist::Watchlist;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// The name of the configuration file.
+const CONFIG_FILE: &str = "config.toml";
+
+/// The name of the file of the token cached without a profile.
+const TOKEN_FILE: &str = "token.json";
+
+/// The name of the file of the watchlist without a profile.
+const WATCHLIST_FILE: &str = "watchlist.json";
+
+/// The name of the profile in use, if any.
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+/// The content of the configuration file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Config {
+    /// The profile used when none is given.
+    default_profile: Option<String>,
+    #[serde(default)]
+    profiles: BTreeMap<String, Profile>,
+}
+
+/// The settings of a Numista account, each overridden by its command line
+/// argument.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    pub api_key: Option<String>,
+    pub user_id: Option<i64>,
+    pub lang: Option<String>,
+}
+
+/// Returns the configuration directory: `$PLANCHET_CONFIG_DIR` if set, or a
+/// `planchet` directory in the user's configuration directory.
+pub fn config_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("PLANCHET_CONFIG_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))?;
+    Some(base.join("planchet"))
+}
+
+fn load_config() -> Result<Config> {
+    let Some(path) = config_dir().map(|dir| dir.join(CONFIG_FILE)) else {
+        return Ok(Config::default());
+    };
+    if !path.exists() {
+        return Ok(Config::default());
+    }
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    toml::from_str(&content).with_context(|| format!("Invalid configuration in {}", path.display()))
+}
+
+/// Checks if a profile name can be used in the names of its files: it must
+/// only hold ASCII letters, digits, `_` and `-`.
+fn is_valid_profile_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-')
+}
+
+/// Selects the profile in use: the one named, or else the default profile of
+/// the configuration file. Without either, an empty profile is returned.
+pub fn select_profile(name: Option<&str>) -> Result<Profile> {
+    let config = load_config()?;
+    let Some(name) = name.or(config.default_profile.as_deref()) else {
+        return Ok(Profile::default());
+    };
+    if !is_valid_profile_name(name) {
+        bail!(
+            "Invalid profile name {:?}: use only letters, digits, '_' and '-'",
+            name
+        );
+    }
+    let Some(profile) = config.profiles.get(name) else {
+        let names: Vec<&str> = config.profiles.keys().map(String::as_str).collect();
+        bail!(
+            "No profile named {} in the configuration file (profiles: {})",
+            name,
+            if names.is_empty() {
+                "none".to_string()
+            } else {
+                names.join(", ")
+            }
+        );
+    };
+    let _ = PROFILE.set(name.to_string());
+    Ok(profile.clone())
+}
+
+/// Returns the path of the cached token of the profile in use.
+fn token_path() -> Option<PathBuf> {
+    let file = match PROFILE.get() {
+        Some(profile) => format!("token-{}.json", profile),
+        None => TOKEN_FILE.to_string(),
+    };
+    Some(config_dir()?.join(file))
+}
+
+/// Returns the path of the watchlist of the profile in use.
+fn watchlist_path() -> Result<PathBuf> {
+    let file = match PROFILE.get() {
+        Some(profile) => format!("watchlist-{}.json", profile),
+        None => WATCHLIST_FILE.to_string(),
+    };
+    let dir = config_dir().context("No configuration directory found, set PLANCHET_CONFIG_DIR")?;
+    Ok(dir.join(file))
+}
+
+/// Loads the watchlist of the profile in use, empty if there is none yet.
+pub fn load_watchlist() -> Result<Watchlist> {
+    let path = watchlist_path()?;
+    if !path.exists() {
+        return Ok(Watchlist::new());
+    }
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&content)
+        .with_context(|| format!("Invalid watchlist in {}", path.display()))
+}
+
+/// Saves the watchlist of the profile in use.
+pub fn save_watchlist(watchlist: &Watchlist) -> Result<()> {
+    let path = watchlist_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let content = serde_json::to_string_pretty(watchlist)?;
+    fs::write(&path, content).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Loads the cached token of the profile in use, if there is one.
+pub fn load_token() -> Option<Token> {
+    let content = fs::read_to_string(token_path()?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Caches a token for the profile in use, readable only by the user,
+/// returning the path of the file.
+pub fn save_token(token: &Token) -> Result<PathBuf> {
+    let path = token_path().context("No configuration directory found, set PLANCHET_CONFIG_DIR")?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let file = options
+        .open(&path)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    serde_json::to_writer_pretty(file, token)?;
+    Ok(path)
+}
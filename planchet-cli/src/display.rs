@@ -1,4 +1,5 @@
-use planchet::model::{
+use crate::locale;
+use planchet::models::{
     CoinSide, Demonetization, Issuer, IssuingEntity, NumistaType, Printer, Reference, RelatedType,
     RulingAuthority,
 };
@@ -43,7 +44,8 @@ fn print_demonetization(label: &str, demonetization: Option<&Demonetization>, in
         let next_indent = indent + 2;
         print_key_value("is_demonetized", Some(d.is_demonetized), next_indent);
         if let Some(date) = d.demonetization_date {
-            print_key_value("demonetization_date", Some(date.format("%Y-%m-%d").to_string()), next_indent);
+            let date = locale::current().partial_date(date);
+            print_key_value("demonetization_date", Some(date), next_indent);
         }
     }
 }
@@ -151,10 +153,12 @@ pub fn print_numista_type(type_: Option<&NumistaType>, indent: usize) {
         }
 
         print_demonetization("demonetization", t.demonetization.as_ref(), indent);
-        print_key_value("weight", t.weight, indent);
-        print_key_value("size", t.size, indent);
-        print_key_value("size2", t.size2, indent);
-        print_key_value("thickness", t.thickness, indent);
+        let locale = locale::current();
+        print_key_value("weight", t.weight.map(|v| locale.number(v)), indent);
+        print_key_value("size", t.size.map(|v| locale.number(v)), indent);
+        print_key_value("size2", t.size2.map(|v| locale.number(v)), indent);
+        print_key_value("thickness", t.thickness.map(|v| locale.number(v)), indent);
+        print_key_value("orientation", t.orientation.as_ref(), indent);
 
         print_coin_side("obverse", t.obverse.as_ref(), indent);
         print_coin_side("reverse", t.reverse.as_ref(), indent);
@@ -171,7 +175,7 @@ pub fn print_numista_type(type_: Option<&NumistaType>, indent: usize) {
         print_printers("printers", t.printers.as_ref(), indent);
         print_key_value("series", t.series.as_ref(), indent);
         print_key_value("commemorated_topic", t.commemorated_topic.as_ref(), indent);
-        print_key_value("comments", t.comments.as_ref(), indent);
+        print_key_value("comments", t.comments_plain(), indent);
 
         if let Some(tags) = &t.tags {
             if !tags.is_empty() {
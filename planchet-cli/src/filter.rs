@@ -0,0 +1,120 @@
+//! The filters of the items of a collection, shared by the commands listing
+//! them.
+//!
+//! The filters the API supports are sent with the request, and the others are
+//! applied to the items returned.
+use clap::Args;
+use planchet::models::{Category, CollectedItem, GetCollectedItemsParams, Grade};
+
+/// A range of years, either end of which may be open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct YearRange {
+    pub start: Option<i32>,
+    pub end: Option<i32>,
+}
+
+impl YearRange {
+    /// Checks if a year is within the range.
+    pub fn contains(&self, year: i32) -> bool {
+        self.start.is_none_or(|start| start <= year) && self.end.is_none_or(|end| year <= end)
+    }
+}
+
+/// Parses a range of years such as `1850..1900`, `1850..`, `..1900`, or
+/// `1858`. Years before the common era are negative, e.g. `-500..-300`.
+pub fn parse_year_range(s: &str) -> Result<YearRange, String> {
+    let year = |s: &str| -> Result<Option<i32>, String> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Ok(None);
+        }
+        s.parse()
+            .map(Some)
+            .map_err(|_| format!("Invalid year '{}'", s))
+    };
+    let range = match s.split_once("..") {
+        Some((start, end)) => YearRange {
+            start: year(start)?,
+            end: year(end)?,
+        },
+        None => {
+            let year = year(s)?;
+            YearRange {
+                start: year,
+                end: year,
+            }
+        }
+    };
+    if range.start.is_none() && range.end.is_none() {
+        return Err("The range must have a start or an end".to_string());
+    }
+    Ok(range)
+}
+
+/// The filters of the items of a collection.
+#[derive(Args, Default)]
+pub struct ItemFilters {
+    /// Only include the items of an issuer, given by its code or name.
+    #[arg(long)]
+    pub issuer: Option<String>,
+
+    /// Only include the items of a category.
+    #[arg(long, value_enum, ignore_case = true)]
+    pub category: Option<Category>,
+
+    /// Only include the items issued in a range of years, e.g. `1850..1900`,
+    /// `1850..`, `..1900`, or `1858`. Years before the common era are
+    /// negative, e.g. `-500..-300`.
+    #[arg(long, value_parser = parse_year_range, allow_hyphen_values = true)]
+    pub year_range: Option<YearRange>,
+
+    /// Only include the items in a grade, e.g. `vf` or `f/vf`.
+    #[arg(long, value_enum, ignore_case = true)]
+    pub grade: Option<Grade>,
+
+    /// Only include the items of a collection, given by its ID.
+    #[arg(long)]
+    pub collection: Option<i64>,
+
+    /// Only include the items for swap.
+    #[arg(long)]
+    pub for_swap: bool,
+}
+
+impl ItemFilters {
+    /// Returns the parameters of the request, with the filters the API
+    /// supports.
+    pub fn params(&self) -> GetCollectedItemsParams {
+        let mut params = GetCollectedItemsParams::new();
+        if let Some(category) = &self.category {
+            params = params.category(category.clone());
+        }
+        if let Some(collection) = self.collection {
+            params = params.collection(collection);
+        }
+        params
+    }
+
+    /// Checks if an item passes the filters the API does not support.
+    pub fn matches(&self, item: &CollectedItem) -> bool {
+        if let Some(issuer) = &self.issuer {
+            let matched = item.type_info.issuer.as_ref().is_some_and(|i| {
+                i.code.eq_ignore_ascii_case(issuer)
+                    || i.name.to_lowercase() == issuer.to_lowercase()
+            });
+            if !matched {
+                return false;
+            }
+        }
+        if let Some(range) = self.year_range {
+            let year = item.issue.as_ref().and_then(|i| i.gregorian_year);
+            if !year.is_some_and(|year| range.contains(year)) {
+                return false;
+            }
+        }
+        if self.grade.is_some() && item.grade != self.grade {
+            return false;
+        }
+        !self.for_swap || item.for_swap
+    }
+}
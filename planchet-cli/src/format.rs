@@ -0,0 +1,148 @@
+//! The orders and groupings of the items of a collection, shared by the
+//! commands listing them.
+use clap::ValueEnum;
+use planchet::models::CollectedItem;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+/// The label of the group of items without the field grouped by.
+pub const UNKNOWN: &str = "<Unknown>";
+
+/// The orders of items.
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum SortKey {
+    /// By issuer name, then year and title.
+    #[default]
+    Issuer,
+    /// By year, oldest first.
+    Year,
+    /// By title.
+    Title,
+    /// By grade, best first.
+    Grade,
+    /// By price paid, highest first, whatever the currency.
+    Value,
+}
+
+/// The groupings of items.
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum GroupKey {
+    /// By issuer.
+    #[default]
+    Issuer,
+    /// By the decade of their year, e.g. 1850s.
+    Decade,
+    /// By category: coin, banknote, or exonumia.
+    Category,
+    /// By the collection they are sorted into.
+    Collection,
+}
+
+impl GroupKey {
+    /// Returns the name of the groups, e.g. for the header of a table.
+    pub fn name(&self) -> &'static str {
+        match self {
+            GroupKey::Issuer => "Issuer",
+            GroupKey::Decade => "Decade",
+            GroupKey::Category => "Category",
+            GroupKey::Collection => "Collection",
+        }
+    }
+}
+
+/// Returns the name of the issuer of an item.
+pub fn issuer_name(item: &CollectedItem) -> String {
+    item.type_info
+        .issuer
+        .as_ref()
+        .map(|i| i.name.clone())
+        .unwrap_or_else(|| UNKNOWN.to_string())
+}
+
+/// Returns the year of an item, as written on it.
+pub fn year(item: &CollectedItem) -> Option<i32> {
+    item.issue.as_ref().and_then(|i| i.year)
+}
+
+/// Returns the year of an item in the Gregorian calendar.
+pub fn gregorian_year(item: &CollectedItem) -> Option<i32> {
+    item.issue.as_ref().and_then(|i| i.gregorian_year)
+}
+
+/// Orders optional values, the missing ones last.
+fn missing_last<T>(a: Option<T>, b: Option<T>, cmp: impl FnOnce(T, T) -> Ordering) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => cmp(a, b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// Compares two items by a key, breaking ties by issuer, year, and title.
+pub fn compare(key: SortKey, a: &CollectedItem, b: &CollectedItem) -> Ordering {
+    let by_key = match key {
+        SortKey::Issuer => issuer_name(a).cmp(&issuer_name(b)),
+        SortKey::Year => missing_last(gregorian_year(a), gregorian_year(b), |a, b| a.cmp(&b)),
+        SortKey::Title => a.type_info.title.cmp(&b.type_info.title),
+        SortKey::Grade => missing_last(a.grade.as_ref(), b.grade.as_ref(), |a, b| b.cmp(a)),
+        SortKey::Value => missing_last(
+            a.price.as_ref().map(|p| p.value),
+            b.price.as_ref().map(|p| p.value),
+            |a, b| b.partial_cmp(&a).unwrap_or(Ordering::Equal),
+        ),
+    };
+    by_key
+        .then_with(|| issuer_name(a).cmp(&issuer_name(b)))
+        .then_with(|| gregorian_year(a).cmp(&gregorian_year(b)))
+        .then_with(|| a.type_info.title.cmp(&b.type_info.title))
+}
+
+/// Sorts items by a key.
+pub fn sort_items(items: &mut [CollectedItem], key: SortKey) {
+    items.sort_by(|a, b| compare(key, a, b));
+}
+
+/// The position of a group among the others: decades in order, other groups
+/// by label, and the unknown group last.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum GroupOrder {
+    Decade(i32),
+    Label(String),
+    Unknown,
+}
+
+fn group_of(key: GroupKey, item: &CollectedItem) -> (GroupOrder, String) {
+    let label = match key {
+        GroupKey::Issuer => item.type_info.issuer.as_ref().map(|i| i.name.clone()),
+        GroupKey::Decade => {
+            if let Some(year) = gregorian_year(item) {
+                let decade = year.div_euclid(10) * 10;
+                return (GroupOrder::Decade(decade), format!("{}s", decade));
+            }
+            None
+        }
+        GroupKey::Category => Some(item.type_info.category.to_string()),
+        GroupKey::Collection => item.collection.as_ref().map(|c| c.name.clone()),
+    };
+    match label {
+        Some(label) => (GroupOrder::Label(label.clone()), label),
+        None => (GroupOrder::Unknown, UNKNOWN.to_string()),
+    }
+}
+
+/// Groups items by a key, returning the label of each group with its items,
+/// in their original order. Decades are in order, other groups by label, and
+/// the items without the field grouped by last.
+pub fn group_items(items: Vec<CollectedItem>, key: GroupKey) -> Vec<(String, Vec<CollectedItem>)> {
+    let mut groups: BTreeMap<GroupOrder, (String, Vec<CollectedItem>)> = BTreeMap::new();
+    for item in items {
+        let (order, label) = group_of(key, &item);
+        groups
+            .entry(order)
+            .or_insert((label, Vec::new()))
+            .1
+            .push(item);
+    }
+    groups.into_values().collect()
+}
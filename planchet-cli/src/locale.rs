@@ -0,0 +1,211 @@
+//! The formatting of numbers, prices, and dates in the output of the commands,
+//! in the conventions of a locale.
+//!
+//! The locale is the one given with `--locale`, or else the language of the
+//! client. Without either, numbers have a decimal point and no grouping, dates
+//! are in the ISO 8601 format, and prices are followed by the code of their
+//! currency.
+use chrono::NaiveDate;
+use planchet::models::{CurrencyCode, PartialDate};
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+/// The locale of the output, if set.
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+/// The languages with known conventions.
+const LANGUAGES: [&str; 7] = ["de", "en", "es", "fr", "it", "nl", "pt"];
+
+/// The symbols shared by several currencies, with the one currency shown with
+/// it. The others are shown with their code, e.g. `CAD` rather than `$`.
+const SHARED_SYMBOLS: [(&str, CurrencyCode); 3] = [
+    ("$", CurrencyCode::USD),
+    ("£", CurrencyCode::GBP),
+    ("¥", CurrencyCode::JPY),
+];
+
+/// Where the currency of a price is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CurrencyPosition {
+    /// The code after the value, e.g. `20.00 EUR`.
+    Code,
+    /// The symbol right before the value, e.g. `€20.00`.
+    Before,
+    /// The symbol before the value, with a space, e.g. `€ 20,00`.
+    BeforeSpaced,
+    /// The symbol after the value, with a space, e.g. `20,00 €`.
+    After,
+}
+
+/// The conventions of a locale for numbers, prices, and dates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Locale {
+    decimal_separator: char,
+    group_separator: Option<char>,
+    currency: CurrencyPosition,
+    /// The `strftime` format of full dates.
+    date_format: &'static str,
+    /// The `strftime` format of dates only known to the month.
+    month_format: &'static str,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self {
+            decimal_separator: '.',
+            group_separator: None,
+            currency: CurrencyPosition::Code,
+            date_format: "%Y-%m-%d",
+            month_format: "%Y-%m",
+        }
+    }
+}
+
+impl Locale {
+    /// Returns the locale of a language, with an optional region, e.g. `fr`,
+    /// `en-GB`, or `pt_BR`. Returns `None` if the language is not known.
+    pub fn new(tag: &str) -> Option<Self> {
+        let tag = tag.trim().replace('_', "-");
+        let (language, region) = match tag.split_once('-') {
+            Some((language, region)) => (language.to_lowercase(), Some(region.to_uppercase())),
+            None => (tag.to_lowercase(), None),
+        };
+        let european = Self {
+            decimal_separator: ',',
+            group_separator: Some('.'),
+            currency: CurrencyPosition::After,
+            date_format: "%d/%m/%Y",
+            month_format: "%m/%Y",
+        };
+        let locale = match language.as_str() {
+            "en" => Self {
+                decimal_separator: '.',
+                group_separator: Some(','),
+                currency: CurrencyPosition::Before,
+                date_format: match region.as_deref() {
+                    None | Some("US") => "%m/%d/%Y",
+                    Some(_) => "%d/%m/%Y",
+                },
+                month_format: "%m/%Y",
+            },
+            "de" => Self {
+                date_format: "%d.%m.%Y",
+                month_format: "%m.%Y",
+                ..european
+            },
+            "fr" => Self {
+                group_separator: Some(' '),
+                ..european
+            },
+            "nl" => Self {
+                currency: CurrencyPosition::BeforeSpaced,
+                date_format: "%d-%m-%Y",
+                month_format: "%m-%Y",
+                ..european
+            },
+            "pt" if region.as_deref() == Some("BR") => Self {
+                currency: CurrencyPosition::BeforeSpaced,
+                ..european
+            },
+            "es" | "it" | "pt" => european,
+            _ => return None,
+        };
+        Some(locale)
+    }
+
+    /// Formats a number written with a decimal point, e.g. `1234.5`.
+    fn localize(&self, number: &str) -> String {
+        let (sign, number) = match number.strip_prefix('-') {
+            Some(number) => ("-", number),
+            None => ("", number),
+        };
+        let (integer, fraction) = match number.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (number, None),
+        };
+        let mut s = sign.to_string();
+        for (i, digit) in integer.chars().enumerate() {
+            if i > 0 && (integer.len() - i) % 3 == 0 {
+                s.extend(self.group_separator);
+            }
+            s.push(digit);
+        }
+        if let Some(fraction) = fraction {
+            s.push(self.decimal_separator);
+            s.push_str(fraction);
+        }
+        s
+    }
+
+    /// Formats a number, e.g. a weight.
+    pub fn number(&self, value: impl Display) -> String {
+        self.localize(&value.to_string())
+    }
+
+    /// Formats a price, with two decimals.
+    pub fn price(&self, value: impl Display, currency: CurrencyCode) -> String {
+        let value = self.localize(&format!("{:.2}", value));
+        let symbol = currency.symbol().symbol;
+        let shared = SHARED_SYMBOLS.iter().find(|(s, _)| *s == symbol);
+        let symbol = match shared {
+            Some((_, owner)) if *owner != currency => currency.code().to_string(),
+            _ if symbol == "¤" => currency.code().to_string(),
+            _ => symbol,
+        };
+        match self.currency {
+            CurrencyPosition::Code => format!("{} {}", value, currency.code()),
+            CurrencyPosition::Before if symbol.chars().all(char::is_alphabetic) => {
+                format!("{} {}", symbol, value)
+            }
+            CurrencyPosition::Before => format!("{}{}", symbol, value),
+            CurrencyPosition::BeforeSpaced => format!("{} {}", symbol, value),
+            CurrencyPosition::After => format!("{} {}", value, symbol),
+        }
+    }
+
+    /// Formats a date.
+    pub fn date(&self, date: NaiveDate) -> String {
+        date.format(self.date_format).to_string()
+    }
+
+    /// Formats a date which may only be known to the year or to the month.
+    pub fn partial_date(&self, date: PartialDate) -> String {
+        match date {
+            PartialDate::Year(year) => format!("{:04}", year),
+            PartialDate::YearMonth(..) => match date.first_day() {
+                Some(first_day) => first_day.format(self.month_format).to_string(),
+                None => date.to_string(),
+            },
+            PartialDate::Date(date) => self.date(date),
+        }
+    }
+}
+
+/// Parses a locale given on the command line.
+pub fn parse_locale(s: &str) -> Result<Locale, String> {
+    Locale::new(s).ok_or_else(|| {
+        format!(
+            "Unknown locale '{}' (languages: {})",
+            s,
+            LANGUAGES.join(", ")
+        )
+    })
+}
+
+/// Sets the locale of the output, unless already set.
+pub fn set_locale(locale: Locale) {
+    let _ = LOCALE.set(locale);
+}
+
+/// Sets the locale of the output from the language of the client, unless
+/// already set, e.g. by `--locale`.
+pub fn set_default(lang: Option<&str>) {
+    if let Some(locale) = lang.and_then(Locale::new) {
+        set_locale(locale);
+    }
+}
+
+/// Returns the locale of the output.
+pub fn current() -> Locale {
+    LOCALE.get().copied().unwrap_or_default()
+}
@@ -0,0 +1,100 @@
+//! The interactive OAuth login: the user grants access in their browser,
+//! which is redirected to a listener on localhost.
+use anyhow::{bail, Context, Result};
+use planchet::oauth::Authorization;
+use std::process::{Command, Stdio};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use url::Url;
+
+/// The path of the redirect URI on the listener.
+const CALLBACK_PATH: &str = "/callback";
+
+/// Returns the redirect URI of the listener on a port.
+pub fn redirect_uri(port: u16) -> Url {
+    Url::parse(&format!("http://localhost:{}{}", port, CALLBACK_PATH))
+        .expect("the redirect URI is valid")
+}
+
+/// Waits for the browser to be redirected to the listener, returning the
+/// code of the redirect.
+pub async fn receive_code(listener: &TcpListener, authorization: &Authorization) -> Result<String> {
+    loop {
+        let (mut stream, _) = listener.accept().await?;
+        let Some(target) = read_request_target(&mut stream).await? else {
+            continue;
+        };
+        let redirect = Url::parse("http://localhost")?.join(&target)?;
+        if redirect.path() != CALLBACK_PATH {
+            // E.g. the favicon requested by the browser.
+            respond(&mut stream, "404 Not Found", "Not found.").await?;
+            continue;
+        }
+        return match authorization.code_from_redirect(&redirect) {
+            Ok(code) => {
+                let message = "planchet-cli is authorized. You can close this window.";
+                respond(&mut stream, "200 OK", message).await?;
+                Ok(code)
+            }
+            Err(e) => {
+                respond(&mut stream, "400 Bad Request", &e.to_string()).await?;
+                Err(e.into())
+            }
+        };
+    }
+}
+
+/// Reads the target of an HTTP request, e.g. `/callback?code=abc`, and skips
+/// its headers.
+async fn read_request_target(stream: &mut TcpStream) -> Result<Option<String>> {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+    let mut header = String::new();
+    while reader.read_line(&mut header).await? > 2 {
+        header.clear();
+    }
+    let mut parts = request_line.split_whitespace();
+    match (parts.next(), parts.next()) {
+        (Some("GET"), Some(target)) => Ok(Some(target.to_string())),
+        _ => Ok(None),
+    }
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, message: &str) -> Result<()> {
+    let body = format!(
+        "<!DOCTYPE html><html><body><p>{}</p></body></html>",
+        message.replace('&', "&amp;").replace('<', "&lt;")
+    );
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    Ok(stream.shutdown().await?)
+}
+
+/// Opens a URL in the default browser of the user.
+pub fn open_browser(url: &Url) -> Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = Command::new("rundll32");
+        command.arg("url.dll,FileProtocolHandler");
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    let status = command
+        .arg(url.as_str())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .context("Failed to start a browser")?;
+    if !status.success() {
+        bail!("The browser exited with {}", status);
+    }
+    Ok(())
+}
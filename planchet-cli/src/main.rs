@@ -12,14 +12,106 @@
 //!
 //! ## `--api-key`
 //!
-//! The Numista API key. This argument is mandatory, but can be omitted if the `NUMISTA_API_KEY` environment variable is set.
+//! The Numista API key. This argument is mandatory, but can be omitted if the `NUMISTA_API_KEY` environment variable is set,
+//! or if the profile in use has one.
+//!
+//! ## `--profile`
+//!
+//! The profile of the configuration file to use (`~/.config/planchet/config.toml`, or
+//! `config.toml` in `$PLANCHET_CONFIG_DIR`), instead of its `default_profile`. It can also be
+//! set with the `PLANCHET_PROFILE` environment variable. A profile holds the API key, user ID,
+//! and language of a Numista account, each used unless given on the command line, so that
+//! several accounts can be used in turn:
+//!
+//! ```toml
+//! default_profile = "main"
+//!
+//! [profiles.main]
+//! api_key = "my-secret-key"
+//! user_id = 123
+//!
+//! [profiles.collector2]
+//! api_key = "another-secret-key"
+//! user_id = 456
+//! lang = "fr"
+//! ```
+//!
+//! ```bash
+//! $ planchet-cli --profile collector2 dump
+//! ```
+//!
+//! Each profile has its own token cached by `login`. Profile names may only hold
+//! ASCII letters, digits, `_` and `-`, since they are part of the names of
+//! these files.
 //!
 //! ## `--lang`
 //!
-//! The language to use for the API response (2-letter ISO code). This argument is optional.
+//! The language to use for the API response (2-letter ISO code): `en`, `fr`, `es`, `de`, `it`, `nl`, or `pt`. This argument is optional.
+//!
+//! ## `--dry-run`
+//!
+//! Shows the requests a command would send to change the collection, without
+//! sending them. This argument is optional.
+//!
+//! ## `--columns` and `--style`
+//!
+//! Tailor the tables printed by the commands, e.g. to paste them into a forum
+//! post or a document. `--columns` selects which columns to show, and in which
+//! order, by their header in lower case with underscores for spaces, e.g.
+//! `total_items`. `--style` is `ascii` (the default), `markdown`, or `csv`.
+//!
+//! ```bash
+//! $ planchet-cli --api-key my-secret-key dump --user-id 123 --columns id,title,year,grade,price --style markdown
+//! | ID | Title              | Year | Grade | Price     |
+//! |----|--------------------|------|-------|-----------|
+//! | 1  | 5 Cents - Victoria | 1858 | VF    | 12.50 CAD |
+//! | 2  | 1 Cent - George V  | 1920 | F     |           |
+//! ```
+//!
+//! ## `--locale`
+//!
+//! The conventions for the numbers, prices, and dates shown, e.g. `fr` or
+//! `en-GB`: decimal separators, currency symbols, and the order of days and
+//! months. Defaults to the language of the API responses, given with `--lang`
+//! or by the profile, and otherwise to decimal points, currency codes, and ISO
+//! 8601 dates.
+//!
+//! ```bash
+//! $ planchet-cli --api-key my-secret-key dump --user-id 123 --columns title,price,acquired --locale fr
+//! +--------------------+------------+------------+
+//! | Title              | Price      | Acquired   |
+//! +--------------------+------------+------------+
+//! | 5 Cents - Victoria | 1 250,00 € | 14/03/2021 |
+//! +--------------------+------------+------------+
+//! ```
 //!
 //! # Commands
 //!
+//! ## `login`
+//!
+//! Authorizes `planchet-cli` to access your collection: the Numista page
+//! granting access is opened in your browser, which is then redirected to a
+//! listener on `localhost`. The token received is cached in the configuration
+//! directory (`~/.config/planchet`, or `$PLANCHET_CONFIG_DIR`) until it
+//! expires, and used by the following commands which access the collection,
+//! instead of requesting a token for the API key.
+//!
+//! ```bash
+//! $ planchet-cli --api-key my-secret-key login --client-id 1234
+//! Open this URL to authorize planchet-cli:
+//! https://en.numista.com/api/oauth_authorize.php?response_type=code&client_id=1234&...
+//! Logged in as user 123 until 2025-01-01 12:00:00 UTC.
+//! ```
+//!
+//! With `--no-browser`, the URL is only printed, e.g. to open it on another
+//! device forwarding the port of the listener.
+//!
+//! The commands which only read a collection (`dump`, `summarize`,
+//! `collections`, `stats`, `report`, and `export`) do not need a token to read
+//! the collections made public by their owner. Without one, they fall back to
+//! the API key alone, and fail with a clear error if the collection is not
+//! public.
+//!
 //! ## `dump`
 //!
 //! Dumps the user's collection to the console, sorted by issuer name, year, and title.
@@ -30,6 +122,37 @@
 //! Canada - 1 Cent - George V (1920)
 //! ```
 //!
+//! The items can be filtered with:
+//!
+//! * `--issuer`: the code or name of their issuer, e.g. `canada`.
+//! * `--category`: `coin`, `banknote`, or `exonumia`.
+//! * `--year-range`: the range of their years, e.g. `1850..1900`, `1850..`,
+//!   `..1900`, or `1858`. Years before the common era are negative, e.g.
+//!   `-500..-300`.
+//! * `--grade`: their grade, e.g. `vf` or `f/vf`.
+//! * `--collection`: the ID of their collection, see `collections`.
+//! * `--for-swap`: only the items for swap.
+//!
+//! ```bash
+//! $ planchet-cli --api-key my-secret-key dump --user-id 123 --issuer canada --year-range 1900..
+//! Canada - 1 Cent - George V (1920)
+//! ```
+//!
+//! Pass `--sort-by` to list the items by `issuer` (the default), `year`,
+//! `title`, `grade` (best first), or `value` (the highest price paid first),
+//! and `--group-by` to list them in groups by `issuer`, `decade`, `category`,
+//! or `collection`. With `--columns`, the items are listed as a table of the
+//! columns selected among `id`, `issuer`, `title`, `year`, `grade`, `quantity`,
+//! `price`, `acquired`, and `collection`.
+//!
+//! ```bash
+//! $ planchet-cli --api-key my-secret-key dump --user-id 123 --sort-by year --group-by decade
+//! 1850s (1)
+//!   Canada - 5 Cents - Victoria (1858)
+//! 1920s (1)
+//!   Canada - 1 Cent - George V (1920)
+//! ```
+//!
 //! ## `summarize`
 //!
 //! Summarizes the user's collection by issuer, showing the total number of items,
@@ -44,6 +167,83 @@
 //! +--------+-------------+-------------+-------------+
 //! ```
 //!
+//! Pass `--flags emoji` or `--flags code` to prefix issuers which are current
+//! countries with their flag or ISO 3166 code, and `--group-by` to summarize
+//! the collection by `decade`, `category`, or `collection` instead.
+//!
+//! ## `collections`
+//!
+//! Lists the collections of the user, into which their items are sorted.
+//!
+//! ```bash
+//! $ planchet-cli --api-key my-secret-key collections --user-id 123 --detail
+//! +----+-----------+-------+-------+------------+
+//! | ID | Name      | Items | Types | Price Paid |
+//! +----+-----------+-------+-------+------------+
+//! | 5  | Canada    | 12    | 9     | 84.50 CAD  |
+//! +----+-----------+-------+-------+------------+
+//! | 6  | World War | 3     | 3     |            |
+//! +----+-----------+-------+-------+------------+
+//! ```
+//!
+//! Pass `--detail` to also fetch the items of each collection, counting them
+//! and adding up their prices paid in each currency.
+//!
+//! ## `stats`
+//!
+//! Shows the distribution of the user's collection by issuer, category,
+//! decade, grade, and composition.
+//!
+//! ```bash
+//! $ planchet-cli --api-key my-secret-key stats --user-id 123 --hydrate
+//! Items: 2 (2 types)
+//!
+//! By issuer:
+//!   Canada: 2
+//! ...
+//! ```
+//!
+//! Pass `--hydrate` to fetch the full type of each item, which adds the
+//! composition, and `--json` to print the statistics as JSON.
+//!
+//! ## `report`
+//!
+//! Writes the statistics of the user's collection to a file collectors can
+//! share: an HTML page with a chart per distribution, or a Markdown document
+//! if the file ends in `.md`.
+//!
+//! ```bash
+//! $ planchet-cli --api-key my-secret-key report --user-id 123 --output report.html
+//! Report written to report.html
+//! ```
+//!
+//! ## `export`
+//!
+//! Exports the user's collection as a printable document. With `--format pdf`,
+//! the default, this is an inventory for insurance purposes, listing the
+//! description, serial number, acquisition, and price paid of each item.
+//!
+//! ```bash
+//! $ planchet-cli --api-key my-secret-key export --user-id 123 --output inventory.pdf --photos --estimate EUR
+//! Inventory written to inventory.pdf
+//! ```
+//!
+//! Pass `--photos` to add the first picture of each item, and `--estimate`
+//! with a currency to add the value of each item, estimated from the prices
+//! of its issue in its grade.
+//!
+//! With `--format labels`, this is a label per item for 2x2 flips, with the
+//! title, year and mint, grade, and catalogue references of the item, and a
+//! QR code linking to the page of its type. The labels are laid out on A4
+//! sheets, as a PDF document or, if the file ends in `.svg`, as an SVG
+//! picture per sheet. Pass `--item-id` for each item to label, or leave it
+//! out to label every item.
+//!
+//! ```bash
+//! $ planchet-cli --api-key my-secret-key export --user-id 123 --format labels --item-id 7 --item-id 9 --output labels.pdf
+//! Labels written to labels.pdf
+//! ```
+//!
 //! ## `types`
 //!
 //! Searches the catalogue by types using a keyword and an optional year.
@@ -58,6 +258,16 @@
 //! +----+--------------------+----------+--------+----------+----------+
 //! ```
 //!
+//! With `--thumbnails`, each type is listed on a line followed by its obverse
+//! and reverse thumbnails, in terminals which can show pictures. The protocol
+//! of the terminal is detected, or can be given: `kitty`, `iterm` (also for
+//! WezTerm), or `sixel`. This needs planchet-cli to be built with the
+//! `thumbnails` feature.
+//!
+//! ```bash
+//! $ planchet-cli --api-key my-secret-key types --query "Victoria" --thumbnails sixel
+//! ```
+//!
 //! ## `type`
 //!
 //! Gets a single type by its ID.
@@ -65,29 +275,300 @@
 //! ```bash
 //! $ planchet-cli --api-key my-secret-key type --id 42
 //! ```
-use anyhow::Result;
-use clap::{Parser, Subcommand};
-use futures::stream::TryStreamExt;
+//!
+//! ## `issues`
+//!
+//! Lists the issues of a type, sorted by year or by mintage, followed by a
+//! mintage breakdown per mint letter.
+//!
+//! ```bash
+//! $ planchet-cli --api-key my-secret-key issues --type-id 42 --sort mintage
+//! +----+------+-------------+---------+
+//! | ID | Year | Mint Letter | Mintage |
+//! +----+------+-------------+---------+
+//! | 2  | 1859 | H           | 500     |
+//! +----+------+-------------+---------+
+//! | 1  | 1858 |             | 1500    |
+//! +----+------+-------------+---------+
+//! Total mintage: 2000
+//!   H: 500
+//! ```
+//!
+//! ## `verify`
+//!
+//! Compares the weight, size, and thickness measured on a piece with those of
+//! its type, to flag possible counterfeits. Each measurement may be off by a
+//! few percent (3% for the weight, 2% for the size, 10% for the thickness by
+//! default, or `--tolerance` for all of them).
+//!
+//! ```bash
+//! $ planchet-cli --api-key my-secret-key verify --type-id 42 --weight 5.61 --size 24.2
+//! +-------------+----------+-----------+-----------+------------+
+//! | Measurement | Measured | Catalogue | Deviation | Result     |
+//! +-------------+----------+-----------+-----------+------------+
+//! | Weight      | 5.61     | 5.67      | -1.1%     | OK         |
+//! +-------------+----------+-----------+-----------+------------+
+//! | Size        | 24.2     | 21.5      | +12.6%    | Suspicious |
+//! +-------------+----------+-----------+-----------+------------+
+//! Verdict: suspicious
+//! ```
+//!
+//! ## `value`
+//!
+//! Shows the estimated prices of an issue, by grade. With `--melt`, also
+//! shows the melt value of the precious metal of the type, from the spot
+//! prices of gold-api.com, converted from US dollars into the currency of the
+//! prices with the reference rates of the European Central Bank.
+//!
+//! The spot prices and exchange rates are read from
+//! `https://api.gold-api.com` and the daily rates of the ECB, unless the
+//! `SPOT_PRICE_API_URL` or `EXCHANGE_RATES_URL` environment variables give
+//! another URL in the same format, e.g. a mirror or a test server.
+//!
+//! ```bash
+//! $ planchet-cli --api-key my-secret-key value --type-id 42 --issue-id 7 --currency USD --melt
+//! +-------+-----------+
+//! | Grade | Price     |
+//! +-------+-----------+
+//! | F     | 25.00 USD |
+//! +-------+-----------+
+//! | VF    | 40.00 USD |
+//! +-------+-----------+
+//! Melt value: 21.66 USD (22.5 g of Silver)
+//! ```
+//!
+//! ## `watch`
+//!
+//! Watches the prices of issues in a grade, to report those crossing a
+//! threshold: `watch add` adds an issue to the watchlist of the profile,
+//! `watch list` lists them with their prices at the last check, and
+//! `watch remove` removes one. `watch check` fetches the current prices, and
+//! reports the issues whose price fell to their threshold (or rose to it, for
+//! those added with `--above`) since the previous check. It prints nothing
+//! otherwise, so that it can be run from cron.
+//!
+//! ```bash
+//! $ planchet-cli --api-key my-secret-key watch add --type-id 42 --grade vf --threshold 12.50 --currency EUR
+//! Watching issue 7 of 5 Cents - Victoria in VF, below 12.50 EUR (watch 1)
+//! $ planchet-cli --api-key my-secret-key watch check
+//! 5 Cents - Victoria (type 42, issue 7) in VF: 11.00 EUR, below 12.50 EUR
+//! ```
+//!
+//! With `--notify`, `watch check` sends the issues elsewhere than to the
+//! standard output, once per target: `stdout`, an `http` or `https` URL to
+//! post them to, or an `smtp` or `smtps` URL to email them through, with the
+//! `smtp` feature. Webhooks receive a JSON object with the `version` of its
+//! schema, the `event` (`watch.alert`), a `subject`, the `lines` printed
+//! otherwise, and the `data` of the alerts, each with its `watch`, `price`,
+//! and `currency`. If any target fails, the check fails and its prices are
+//! not kept, so that the next check sends the alerts again. A watch whose
+//! prices cannot be fetched does not stop the others: its error is printed,
+//! and the check fails once the alerts of the others are sent. The password of
+//! the SMTP server can be given by `PLANCHET_SMTP_PASSWORD`; with a user, an
+//! `smtp` server must support STARTTLS, so that credentials are never sent in
+//! clear.
+//!
+//! ```bash
+//! $ planchet-cli watch check --notify https://hooks.example.com/planchet
+//! $ planchet-cli watch check --notify 'smtps://me@mail.example.com?from=me@example.com&to=me@example.com'
+//! ```
+//!
+//! ## `mint`
+//!
+//! Shows a mint: its name, place, country, and years of activity. With
+//! `--summary`, also counts the types struck at the mint, and their issues.
+//!
+//! ```bash
+//! $ planchet-cli --api-key my-secret-key mint --id 7 --summary
+//! Royal Mint
+//! Place: London
+//! Country: United Kingdom
+//! Active: 1500 - 1968
+//! Types: 2
+//! Issues: 5
+//! ```
+//!
+//! The API cannot search types by mint, so the types of the mint's country are
+//! looked through; `--max-types` caps how many (500 by default).
+//!
+//! ## `issuers`
+//!
+//! Lists the issuers, with the issuer they are part of. Pass `--filter` to
+//! only list those whose code or name contains a text, ignoring case.
+//!
+//! ```bash
+//! $ planchet-cli --api-key my-secret-key issuers --filter germ
+//! +---------------+--------------+--------+
+//! | Code          | Name         | Parent |
+//! +---------------+--------------+--------+
+//! | allemagne     | Germany      |        |
+//! +---------------+--------------+--------+
+//! | allemagne_est | East Germany |        |
+//! +---------------+--------------+--------+
+//! Total: 2
+//! ```
+//!
+//! ## `mints`
+//!
+//! Lists the mints, with their place, country, and years of activity. Pass
+//! `--filter` to only list those whose name, place, or country contains a
+//! text.
+//!
+//! ```bash
+//! $ planchet-cli --api-key my-secret-key mints --filter london
+//! +----+------------+--------+----------------+-------------+
+//! | ID | Name       | Place  | Country        | Active      |
+//! +----+------------+--------+----------------+-------------+
+//! | 7  | Royal Mint | London | United Kingdom | 1500 - 1968 |
+//! +----+------------+--------+----------------+-------------+
+//! Total: 1
+//! ```
+//!
+//! ## `catalogues`
+//!
+//! Lists the catalogues, whose codes are used in the references of types,
+//! e.g. `KM`. Pass `--filter` to only list those whose code, title, or author
+//! contains a text.
+//!
+//! ```bash
+//! $ planchet-cli --api-key my-secret-key catalogues --filter km
+//! +----+------+---------------------------------+-------------------+
+//! | ID | Code | Title                           | Author            |
+//! +----+------+---------------------------------+-------------------+
+//! | 3  | KM   | Standard Catalog of World Coins | Chester L. Krause |
+//! +----+------+---------------------------------+-------------------+
+//! Total: 1
+//! ```
+//!
+//! ## `images`
+//!
+//! Downloads the obverse, reverse, edge, and watermark pictures of a type into
+//! a `type-<ID>` subdirectory of the destination directory. Pictures which have
+//! already been downloaded are skipped.
+//!
+//! ```bash
+//! $ planchet-cli --api-key my-secret-key images --type-id 42 --dest pictures
+//! Downloaded pictures/type-42/obverse.jpg
+//! Skipped pictures/type-42/reverse.jpg (already exists)
+//! ```
+//!
+//! ## `qr`
+//!
+//! Writes a QR code linking to the page of a type on Numista, e.g. to print it
+//! next to a coin on display. The code is an SVG picture if the output file
+//! ends in `.svg`, and a PNG picture otherwise.
+//!
+//! ```bash
+//! $ planchet-cli --api-key my-secret-key qr --type-id 42 -o qr.png
+//! QR code written to qr.png
+//! ```
+//!
+//! ## `identify`
+//!
+//! Identifies a coin from photos of its obverse and, optionally, its reverse,
+//! listing the candidate types from the most to the least similar.
+//!
+//! ```bash
+//! $ planchet-cli --api-key my-secret-key identify obverse.jpg reverse.jpg
+//! +------+----+--------------------+--------+------------+---------------------+---------------------+
+//! | Rank | ID | Title              | Issuer | Similarity | Obverse Thumbnail   | Reverse Thumbnail   |
+//! +------+----+--------------------+--------+------------+---------------------+---------------------+
+//! | 1    | 42 | 5 Cents - Victoria | Canada | 0.12       | https://.../180.jpg | https://.../180.jpg |
+//! +------+----+--------------------+--------+------------+---------------------+---------------------+
+//! Tentative year: 1858
+//! Tentative grade: VF
+//! ```
+//!
+//! As with `types`, `--thumbnails` shows the thumbnails of each candidate
+//! rather than their URLs.
+//!
+//! ## `import`
+//!
+//! Imports collected items from a CSV file into the user's collection. Each
+//! field is read from the column named after it (e.g. `type`, `quantity`,
+//! `grade`); use `--column FIELD=COLUMN` to read it from another column. With
+//! `--resolve-references`, rows without a type ID have it looked up from their
+//! `catalogue` and `number` columns.
+//!
+//! ```bash
+//! $ planchet-cli --api-key my-secret-key import --user-id 123 --column type="Numista ID" coins.csv
+//! Row 1: added item 9001 (5 Cents - Victoria)
+//! Row 2: Import error: invalid quantity "two": invalid digit found in string
+//! Imported 1 of 2 rows.
+//! ```
+//!
+//! With `--dry-run`, the items are not added; the requests which would add
+//! them are shown instead.
+//!
+//! ## `audit`
+//!
+//! Compares the types of a local store, written by the `planchet` library,
+//! with the catalogue, and reports the types whose title or demonetization
+//! changed, whose issues were added or removed, and those no longer in the
+//! catalogue. Titles are compared in the language of `--lang`, which should be
+//! the one the types were stored in.
+//!
+//! ```bash
+//! $ planchet-cli --api-key my-secret-key audit catalogue.db
+//! +----+--------------------+----------------------+
+//! | ID | Title              | Change               |
+//! +----+--------------------+----------------------+
+//! | 42 | 5 Cents - Victoria | new issues: 97, 98   |
+//! | 51 | 1 Cent - Victoria  | demonetized on 1920  |
+//! +----+--------------------+----------------------+
+//! 2 of 130 types changed.
+//! ```
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use futures::stream::{self, StreamExt, TryStreamExt};
 use planchet::{
-    model::{
-        CollectedItem, GetCollectedItemsParams, GrantType, OAuthTokenParams, SearchTypeResult,
+    analysis,
+    bullion::{self, GoldApiPrices, SpotPrices},
+    country,
+    currency::{CurrencyConverter, EcbRates},
+    export::{labels::Labels, pdf::Inventory},
+    hydrate::HYDRATE_CONCURRENCY,
+    image_search::SearchByImageBuilder,
+    import::{ColumnMapping, ImportField},
+    measurements::{MeasurementCheck, Tolerances, Verdict},
+    models::{
+        CatalogueDetail, CollectedItem, CollectedItems, Collection, CurrencyCode,
+        GetCollectedItemsParams, Grade, GrantType, Issue, Issuer, IssuerDetail, MintDetail, Number,
+        NumistaType, OAuthTokenParams, SearchByImageTypeResult, SearchTypeResult,
         SearchTypesParams,
     },
-    Client, ClientBuilder,
+    oauth::{Authorization, Token},
+    qr::QrCode,
+    reference::ReferenceCache,
+    report::{self, ReportFormat},
+    store::SqliteStore,
+    urls::TypeLink,
+    watchlist::{Direction, Watch},
+    Client, ClientBuilder, SupportedLanguage, WriteOutcome,
 };
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::env;
 use std::io::{self, Write};
-use tabled::{Table, Tabled};
+use std::path::PathBuf;
+use std::time::Duration;
+use tabled::Tabled;
 
+mod config;
 mod display;
+mod filter;
+mod format;
+mod locale;
+mod login;
+mod notify;
+mod table;
+mod thumbnail;
 
-// Client creation helper
-fn build_client(
+// Client creation helpers
+fn client_builder(
     api_key: String,
     bearer_token: Option<String>,
     lang: Option<String>,
-) -> Result<Client> {
+) -> ClientBuilder {
     let mut client_builder = ClientBuilder::new().api_key(api_key);
     if let Some(token) = bearer_token {
         client_builder = client_builder.bearer_token(token);
@@ -98,14 +579,41 @@ fn build_client(
     if let Ok(url) = env::var("NUMISTA_API_URL") {
         client_builder = client_builder.base_url(url);
     }
-    Ok(client_builder.build()?)
+    client_builder
 }
 
-async fn fetch_collection(
+fn build_client(
     api_key: String,
-    user_id: i64,
+    bearer_token: Option<String>,
     lang: Option<String>,
-) -> Result<Vec<CollectedItem>> {
+) -> Result<Client> {
+    build(client_builder(api_key, bearer_token, lang))
+}
+
+// Builds a client, whose language is the locale of the output unless
+// `--locale` was given
+fn build(client_builder: ClientBuilder) -> Result<Client> {
+    let client = client_builder.build()?;
+    locale::set_default(client.lang());
+    Ok(client)
+}
+
+// Creates a client authorized with a token for the given scope: the token
+// cached by `login` if it has the scope and has not expired, or else a token
+// requested for the API key
+async fn build_authorized_client(
+    api_key: String,
+    scope: &str,
+    lang: Option<String>,
+    dry_run: bool,
+) -> Result<Client> {
+    if let Some(token) = config::load_token().filter(|t| !t.is_expired() && t.has_scope(scope)) {
+        return build(
+            client_builder(api_key, None, lang)
+                .token(&token)
+                .dry_run(dry_run),
+        );
+    }
     let client = build_client(api_key.clone(), None, lang.clone())?;
     let token_params = OAuthTokenParams {
         grant_type: GrantType::ClientCredentials,
@@ -113,13 +621,91 @@ async fn fetch_collection(
         client_secret: None,
         code: None,
         redirect_uri: None,
-        scope: Some("view_collection".to_string()),
+        scope: Some(scope.to_string()),
     };
-    let token = client.get_oauth_token(&token_params).await?;
-    let client = build_client(api_key, Some(token.access_token), lang)?;
+    let token = Token::new(
+        &client.get_oauth_token(&token_params).await?,
+        &[scope.to_string()],
+    );
+    build(
+        client_builder(api_key, None, lang)
+            .token(&token)
+            .dry_run(dry_run),
+    )
+}
+
+// Creates a client to read a collection: authorized for `view_collection` if
+// a token can be had, or else with the API key alone, which can read the
+// collections made public. Only a refusal of the token falls back to the API
+// key: any other failure, e.g. of the network, is returned.
+async fn build_viewer_client(api_key: String, lang: Option<String>) -> Result<Client> {
+    match build_authorized_client(api_key.clone(), "view_collection", lang.clone(), false).await {
+        Ok(client) => Ok(client),
+        Err(e)
+            if e.downcast_ref::<planchet::Error>()
+                .is_some_and(planchet::Error::is_auth) =>
+        {
+            tracing::debug!(
+                "Reading public collections only, as no token was had: {:#}",
+                e
+            );
+            build_client(api_key, None, lang)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+async fn login(
+    api_key: String,
+    client_id: String,
+    scope: String,
+    port: u16,
+    no_browser: bool,
+    lang: Option<String>,
+) -> Result<()> {
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", port))
+        .await
+        .with_context(|| format!("Failed to listen on port {}", port))?;
+    let mut authorization = Authorization::new(client_id, login::redirect_uri(port)).scope(&scope);
+    if let Some(lang) = &lang {
+        authorization = authorization.lang(lang);
+    }
+
+    let url = authorization.url();
+    println!("Open this URL to authorize planchet-cli:");
+    println!("{}", url);
+    io::stdout().flush()?;
+    if !no_browser {
+        if let Err(e) = login::open_browser(&url) {
+            eprintln!("{:#}", e);
+        }
+    }
+    let code = login::receive_code(&listener, &authorization).await?;
 
-    let params = GetCollectedItemsParams::new();
-    let response = client.get_collected_items(user_id, &params).await?;
+    let client = build_client(api_key.clone(), None, lang)?;
+    let params = authorization.token_params(&code).client_secret(api_key);
+    let token = Token::new(
+        &client.get_oauth_token(&params).await?,
+        authorization.scopes(),
+    );
+    config::save_token(&token)?;
+    println!(
+        "Logged in as user {} until {}.",
+        token.user_id,
+        token.expires_at.format("%Y-%m-%d %H:%M:%S UTC")
+    );
+    Ok(())
+}
+
+async fn fetch_collection(
+    api_key: String,
+    user_id: i64,
+    params: &GetCollectedItemsParams,
+    lang: Option<String>,
+) -> Result<Vec<CollectedItem>> {
+    let client = build_viewer_client(api_key, lang).await?;
+
+    let response = client.get_collected_items(user_id, params).await?;
     Ok(response.items)
 }
 
@@ -127,9 +713,14 @@ async fn fetch_collection(
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
-    /// Your Numista API key. Can also be provided via the NUMISTA_API_KEY environment variable.
+    /// Your Numista API key. Can also be provided via the NUMISTA_API_KEY environment variable,
+    /// or by the profile.
     #[arg(short, long, env = "NUMISTA_API_KEY")]
-    api_key: String,
+    api_key: Option<String>,
+
+    /// The profile of the configuration file to use, instead of its default one.
+    #[arg(long, global = true, env = "PLANCHET_PROFILE")]
+    profile: Option<String>,
 
     /// The language for the API response (2-letter ISO code).
     #[arg(long, global = true, value_parser = parse_lang)]
@@ -139,33 +730,173 @@ struct Cli {
     #[arg(long, global = true)]
     debug: bool,
 
+    /// Show the changes a command would make to the collection without
+    /// making them.
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// The columns of the tables to show, in order, separated by commas,
+    /// e.g. `id,title,year,grade,price`.
+    #[arg(long = "columns", global = true, value_delimiter = ',')]
+    table_columns: Vec<String>,
+
+    /// The style of the tables.
+    #[arg(long, global = true, value_enum, default_value_t)]
+    style: table::TableStyle,
+
+    /// The locale of the numbers, prices, and dates shown, e.g. `fr` or
+    /// `en-GB`. Defaults to the language of the API responses.
+    #[arg(long, global = true, value_parser = locale::parse_locale)]
+    locale: Option<locale::Locale>,
+
     #[command(subcommand)]
     command: Commands,
 }
 
 fn parse_lang(s: &str) -> Result<String, String> {
-    if s.len() != 2 {
-        return Err("Language code must be exactly 2 characters".to_string());
+    match SupportedLanguage::from_code(s) {
+        Some(lang) => Ok(lang.code().to_string()),
+        None => Err(format!(
+            "Unsupported language '{}' (languages: {})",
+            s,
+            SupportedLanguage::ALL.map(|lang| lang.code()).join(", ")
+        )),
     }
-    if !s.chars().all(|c| c.is_ascii_alphabetic()) {
-        return Err("Language code must contain only letters".to_string());
-    }
-    Ok(s.to_string())
 }
 
 #[derive(Subcommand)]
 enum Commands {
+    /// Authorize access to your collection in the browser, and cache the token.
+    Login {
+        /// The client ID of your API key, shown on the API page of Numista.
+        #[arg(long)]
+        client_id: String,
+
+        /// The scopes to request, separated by commas.
+        #[arg(long, default_value = "view_collection,edit_collection")]
+        scope: String,
+
+        /// The port on localhost to receive the redirect of the browser on.
+        #[arg(long, default_value_t = 8765)]
+        port: u16,
+
+        /// Print the authorization URL without opening a browser.
+        #[arg(long)]
+        no_browser: bool,
+    },
     /// Dump the user's collection to the console.
     Dump {
-        /// The ID of the user to fetch the collection for.
+        /// The ID of the user to fetch the collection for. Defaults to the
+        /// user of the profile.
         #[arg(long)]
-        user_id: i64,
+        user_id: Option<i64>,
+
+        #[command(flatten)]
+        filters: filter::ItemFilters,
+
+        /// The order in which to list the items.
+        #[arg(long, value_enum, default_value_t)]
+        sort_by: format::SortKey,
+
+        /// List the items in groups, with the number of items of each.
+        #[arg(long, value_enum)]
+        group_by: Option<format::GroupKey>,
     },
     /// Summarize the user's collection by issuer.
     Summarize {
-        /// The ID of the user to fetch the collection for.
+        /// The ID of the user to fetch the collection for. Defaults to the
+        /// user of the profile.
+        #[arg(long)]
+        user_id: Option<i64>,
+
+        /// Show the country of each issuer, as a flag emoji or an ISO 3166
+        /// code. Issuers which are not current countries are left as is.
+        #[arg(long, value_enum)]
+        flags: Option<FlagStyle>,
+
+        /// What to summarize the collection by.
+        #[arg(long, value_enum, default_value_t)]
+        group_by: format::GroupKey,
+    },
+    /// List the user's collections, optionally with their items and value.
+    Collections {
+        /// The ID of the user to list the collections of. Defaults to the
+        /// user of the profile.
+        #[arg(long)]
+        user_id: Option<i64>,
+
+        /// Count the items of each collection, and add up their prices paid.
+        #[arg(long)]
+        detail: bool,
+    },
+    /// Show the distribution of the user's collection.
+    Stats {
+        /// The ID of the user to fetch the collection for. Defaults to the
+        /// user of the profile.
+        #[arg(long)]
+        user_id: Option<i64>,
+
+        /// Fetch the full type of each item, to add its composition.
+        #[arg(long)]
+        hydrate: bool,
+
+        /// Print the statistics as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Write a report of the user's collection, with charts.
+    Report {
+        /// The ID of the user to fetch the collection for. Defaults to the
+        /// user of the profile.
+        #[arg(long)]
+        user_id: Option<i64>,
+
+        /// The file to write the report to. Files ending in `.md` get a
+        /// Markdown report, others an HTML one.
+        #[arg(long)]
+        output: PathBuf,
+
+        /// Fetch the full type of each item, to add its composition.
+        #[arg(long)]
+        hydrate: bool,
+
+        /// The title of the report.
         #[arg(long)]
-        user_id: i64,
+        title: Option<String>,
+    },
+    /// Export the user's collection as a printable document.
+    Export {
+        /// The ID of the user to fetch the collection for. Defaults to the
+        /// user of the profile.
+        #[arg(long)]
+        user_id: Option<i64>,
+
+        /// Only export the items with these IDs. Defaults to every item.
+        #[arg(long = "item-id")]
+        item_ids: Vec<i64>,
+
+        /// The format of the document.
+        #[arg(long, value_enum, default_value_t = ExportFormat::Pdf)]
+        format: ExportFormat,
+
+        /// The file to write the document to. Labels are written as SVG
+        /// pictures if the file ends in `.svg`, with a file per sheet.
+        #[arg(long)]
+        output: PathBuf,
+
+        /// The title of the inventory.
+        #[arg(long)]
+        title: Option<String>,
+
+        /// Add the first picture of each item to the inventory.
+        #[arg(long)]
+        photos: bool,
+
+        /// Estimate the value of each item of the inventory from the prices
+        /// of its issue in its grade, in the given currency (3-letter ISO
+        /// code).
+        #[arg(long, value_name = "CURRENCY")]
+        estimate: Option<String>,
     },
     /// Search the catalogue by types.
     Types {
@@ -180,6 +911,11 @@ enum Commands {
         /// Retrieve all items at once.
         #[arg(long)]
         all: bool,
+
+        /// Show the obverse and reverse thumbnails of each type, in terminals
+        /// which can show pictures.
+        #[arg(long, value_enum, num_args = 0..=1, default_missing_value = "auto")]
+        thumbnails: Option<thumbnail::Protocol>,
     },
     /// Get a single type by ID.
     Type {
@@ -187,32 +923,390 @@ enum Commands {
         #[arg(long)]
         id: i64,
     },
-}
+    /// List the issues of a type with mintage statistics.
+    Issues {
+        /// The ID of the type to list the issues for.
+        #[arg(long)]
+        type_id: i64,
 
-// Data structures and helpers for formatting
-#[derive(Tabled)]
-struct IssuerSummary {
-    #[tabled(rename = "Issuer")]
-    issuer: String,
-    #[tabled(rename = "Total Items")]
-    total_items: usize,
-    #[tabled(rename = "Oldest Item")]
-    oldest_item: String,
-    #[tabled(rename = "Newest Item")]
-    newest_item: String,
-}
+        /// The order in which to list the issues.
+        #[arg(long, value_enum, default_value_t = IssueSort::Year)]
+        sort: IssueSort,
+    },
+    /// Compare the measurements of a piece with those of its type.
+    Verify {
+        /// The ID of the type of the piece.
+        #[arg(long)]
+        type_id: i64,
 
-#[derive(Tabled)]
-struct TypeResult {
-    #[tabled(rename = "ID")]
-    id: i64,
-    #[tabled(rename = "Title")]
-    title: String,
-    #[tabled(rename = "Category")]
-    category: String,
-    #[tabled(rename = "Issuer")]
-    issuer: String,
-    #[tabled(rename = "Min Year")]
+        /// The weight of the piece, in grams.
+        #[arg(long, required_unless_present_any = ["size", "thickness"])]
+        weight: Option<Number>,
+
+        /// The size of the piece, in millimetres.
+        #[arg(long)]
+        size: Option<Number>,
+
+        /// The thickness of the piece, in millimetres.
+        #[arg(long)]
+        thickness: Option<Number>,
+
+        /// The tolerance on every measurement, in percent.
+        #[arg(long)]
+        tolerance: Option<Number>,
+    },
+    /// Show the estimated prices of an issue, and its melt value.
+    Value {
+        /// The ID of the type.
+        #[arg(long)]
+        type_id: i64,
+
+        /// The ID of the issue.
+        #[arg(long)]
+        issue_id: i64,
+
+        /// The currency of the prices (3-letter ISO code).
+        #[arg(long)]
+        currency: Option<String>,
+
+        /// Also show the melt value of the precious metal of the type, in the
+        /// currency of the prices.
+        #[arg(long)]
+        melt: bool,
+    },
+    /// Watch the prices of issues, and report those crossing a threshold.
+    Watch {
+        #[command(subcommand)]
+        command: WatchCommand,
+    },
+    /// Show a mint, and optionally count the types struck there.
+    Mint {
+        /// The ID of the mint.
+        #[arg(long)]
+        id: i64,
+
+        /// Count the types struck at the mint, and their issues.
+        #[arg(long)]
+        summary: bool,
+
+        /// The maximum number of types of the mint's country to look through.
+        #[arg(long, default_value_t = 500)]
+        max_types: usize,
+    },
+    /// List the issuers, optionally filtered by code or name.
+    Issuers {
+        /// Only list the issuers whose code or name contains this text.
+        #[arg(long)]
+        filter: Option<String>,
+    },
+    /// List the mints, optionally filtered by name, place, or country.
+    Mints {
+        /// Only list the mints whose name, place, or country contains this
+        /// text.
+        #[arg(long)]
+        filter: Option<String>,
+    },
+    /// List the catalogues, optionally filtered by code, title, or author.
+    Catalogues {
+        /// Only list the catalogues whose code, title, or author contains
+        /// this text.
+        #[arg(long)]
+        filter: Option<String>,
+    },
+    /// Download the pictures of a type.
+    Images {
+        /// The ID of the type to download the pictures of.
+        #[arg(long)]
+        type_id: i64,
+
+        /// The directory to save the pictures in.
+        #[arg(long, default_value = ".")]
+        dest: PathBuf,
+    },
+    /// Write a QR code linking to the page of a type.
+    Qr {
+        /// The ID of the type.
+        #[arg(long)]
+        type_id: i64,
+
+        /// The file to write the QR code to: an SVG picture if it ends in
+        /// `.svg`, a PNG picture otherwise.
+        #[arg(short, long)]
+        output: PathBuf,
+
+        /// The size of a module of PNG pictures, in pixels.
+        #[arg(long, default_value_t = 8)]
+        scale: u32,
+    },
+    /// Identify a coin from photos of its obverse and reverse.
+    Identify {
+        /// The photo of the obverse (JPEG or PNG).
+        obverse: PathBuf,
+
+        /// The photo of the reverse (JPEG or PNG).
+        reverse: Option<PathBuf>,
+
+        /// The maximum number of candidates to list.
+        #[arg(long, default_value_t = 10)]
+        max_results: i64,
+
+        /// Show the obverse and reverse thumbnails of each candidate, in
+        /// terminals which can show pictures.
+        #[arg(long, value_enum, num_args = 0..=1, default_missing_value = "auto")]
+        thumbnails: Option<thumbnail::Protocol>,
+    },
+    /// Import collected items from a CSV file into the user's collection.
+    Import {
+        /// The ID of the user to add the items to. Defaults to the user of
+        /// the profile.
+        #[arg(long)]
+        user_id: Option<i64>,
+
+        /// The CSV file to import, with a header row.
+        file: PathBuf,
+
+        /// Read a field from a differently named column, as FIELD=COLUMN.
+        #[arg(long = "column", value_parser = parse_column)]
+        columns: Vec<(ImportField, String)>,
+
+        /// Resolve missing type IDs from the catalogue and number columns.
+        #[arg(long)]
+        resolve_references: bool,
+
+        /// The delay between two items being added, in milliseconds.
+        #[arg(long, default_value_t = 250)]
+        delay_ms: u64,
+    },
+    /// Compare the types of a local store with the catalogue, and report the
+    /// changes.
+    Audit {
+        /// The SQLite store written by the `planchet` library.
+        store: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum WatchCommand {
+    /// Watch the price of an issue in a grade.
+    Add(NewWatch),
+    /// List the watched issues, with their prices at the last check.
+    List,
+    /// Stop watching an issue.
+    Remove {
+        /// The ID of the watch, as listed.
+        id: u32,
+    },
+    /// Check the prices of the watched issues, and report those which crossed
+    /// their threshold since the previous check. Nothing is printed if none
+    /// did, e.g. when run from cron.
+    Check {
+        /// Where to report the issues: `stdout`, a URL to post them to as
+        /// JSON, or an `smtp` or `smtps` URL to email them through. Can be
+        /// given several times. Defaults to `stdout`.
+        #[arg(long, value_parser = notify::parse_target)]
+        notify: Vec<notify::Target>,
+    },
+}
+
+/// The price of an issue to watch.
+#[derive(Args)]
+struct NewWatch {
+    /// The ID of the type.
+    #[arg(long)]
+    type_id: i64,
+
+    /// The ID of the issue. Can be left out for types with a single issue.
+    #[arg(long)]
+    issue_id: Option<i64>,
+
+    /// The grade to watch the price in, e.g. `vf`.
+    #[arg(long, value_enum, ignore_case = true)]
+    grade: Grade,
+
+    /// The price to report the issue at.
+    #[arg(long)]
+    threshold: Number,
+
+    /// The currency of the threshold (3-letter ISO code).
+    #[arg(long)]
+    currency: Option<String>,
+
+    /// Report the issue when its price rises to the threshold, rather than
+    /// when it falls to it.
+    #[arg(long)]
+    above: bool,
+}
+
+fn parse_column(s: &str) -> Result<(ImportField, String), String> {
+    let (field, column) = s
+        .split_once('=')
+        .ok_or_else(|| "Column must be given as FIELD=COLUMN".to_string())?;
+    let field = field.parse::<ImportField>().map_err(|_| {
+        let fields: Vec<_> = ImportField::ALL.iter().map(|f| f.to_string()).collect();
+        format!("Unknown field '{}', expected one of: {}", field, fields.join(", "))
+    })?;
+    Ok((field, column.to_string()))
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum IssueSort {
+    /// Sort by year, oldest first.
+    Year,
+    /// Sort by mintage, rarest first.
+    Mintage,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    /// A PDF inventory, with the description, acquisition, and value of
+    /// each item, for insurance purposes.
+    Pdf,
+    /// Labels for 2x2 flips, with a QR code linking to the page of the type
+    /// on Numista, as a PDF document or SVG pictures.
+    Labels,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum FlagStyle {
+    /// A flag emoji, e.g. 🇨🇦.
+    Emoji,
+    /// An ISO 3166-1 alpha-2 code, e.g. CA.
+    Code,
+}
+
+// Data structures and helpers for formatting
+#[derive(Tabled)]
+struct ItemRow {
+    #[tabled(rename = "ID")]
+    id: i64,
+    #[tabled(rename = "Issuer")]
+    issuer: String,
+    #[tabled(rename = "Title")]
+    title: String,
+    #[tabled(rename = "Year")]
+    year: String,
+    #[tabled(rename = "Grade")]
+    grade: String,
+    #[tabled(rename = "Quantity")]
+    quantity: i64,
+    #[tabled(rename = "Price")]
+    price: String,
+    #[tabled(rename = "Acquired")]
+    acquired: String,
+    #[tabled(rename = "Collection")]
+    collection: String,
+}
+
+impl From<&CollectedItem> for ItemRow {
+    fn from(item: &CollectedItem) -> Self {
+        let locale = locale::current();
+        Self {
+            id: item.id,
+            issuer: format::issuer_name(item),
+            title: item.type_info.title.clone(),
+            year: format::year(item)
+                .map(|y| y.to_string())
+                .unwrap_or_else(|| format::UNKNOWN.to_string()),
+            grade: item
+                .grade
+                .as_ref()
+                .map(|g| g.to_string())
+                .unwrap_or_default(),
+            quantity: item.quantity,
+            price: item
+                .price
+                .as_ref()
+                .map(|p| locale.price(p.value, p.currency))
+                .unwrap_or_default(),
+            acquired: item
+                .acquisition_date
+                .map(|d| locale.date(d))
+                .unwrap_or_default(),
+            collection: item
+                .collection
+                .as_ref()
+                .map(|c| c.name.clone())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Tabled)]
+struct AuditRow {
+    #[tabled(rename = "ID")]
+    id: i64,
+    #[tabled(rename = "Title")]
+    title: String,
+    #[tabled(rename = "Change")]
+    change: String,
+}
+
+#[derive(Tabled)]
+struct CollectionRow {
+    #[tabled(rename = "ID")]
+    id: i64,
+    #[tabled(rename = "Name")]
+    name: String,
+}
+
+impl From<&Collection> for CollectionRow {
+    fn from(c: &Collection) -> Self {
+        Self {
+            id: c.id,
+            name: c.name.clone(),
+        }
+    }
+}
+
+#[derive(Tabled)]
+struct CollectionDetailRow {
+    #[tabled(rename = "ID")]
+    id: i64,
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Items")]
+    items: i64,
+    #[tabled(rename = "Types")]
+    types: i64,
+    #[tabled(rename = "Price Paid")]
+    price_paid: String,
+}
+
+impl CollectionDetailRow {
+    fn new(collection: &Collection, items: &CollectedItems) -> Self {
+        let mut paid: BTreeMap<&str, (CurrencyCode, Number)> = BTreeMap::new();
+        for price in items.items.iter().filter_map(|i| i.price.as_ref()) {
+            let entry = paid
+                .entry(price.currency.code())
+                .or_insert((price.currency, Number::default()));
+            entry.1 += price.value;
+        }
+        let locale = locale::current();
+        Self {
+            id: collection.id,
+            name: collection.name.clone(),
+            items: items.item_count,
+            types: items.item_type_count,
+            price_paid: paid
+                .values()
+                .map(|(currency, value)| locale.price(value, *currency))
+                .collect::<Vec<_>>()
+                .join(", "),
+        }
+    }
+}
+
+#[derive(Tabled)]
+struct TypeResult {
+    #[tabled(rename = "ID")]
+    id: i64,
+    #[tabled(rename = "Title")]
+    title: String,
+    #[tabled(rename = "Category")]
+    category: String,
+    #[tabled(rename = "Issuer")]
+    issuer: String,
+    #[tabled(rename = "Min Year")]
     min_year: String,
     #[tabled(rename = "Max Year")]
     max_year: String,
@@ -243,69 +1337,314 @@ impl From<SearchTypeResult> for TypeResult {
     }
 }
 
-fn get_issuer_name(item: &CollectedItem) -> String {
-    item.type_info
-        .issuer
-        .as_ref()
-        .map(|i| i.name.clone())
-        .unwrap_or_else(|| "<Unknown>".to_string())
+#[derive(Tabled)]
+struct MeasurementRow {
+    #[tabled(rename = "Measurement")]
+    measurement: String,
+    #[tabled(rename = "Measured")]
+    measured: String,
+    #[tabled(rename = "Catalogue")]
+    expected: String,
+    #[tabled(rename = "Deviation")]
+    deviation: String,
+    #[tabled(rename = "Result")]
+    result: String,
 }
 
-fn get_year(item: &CollectedItem) -> Option<i32> {
-    item.issue.as_ref().and_then(|i| i.year)
+impl From<&MeasurementCheck> for MeasurementRow {
+    fn from(check: &MeasurementCheck) -> Self {
+        let unknown = || "<Unknown>".to_string();
+        let locale = locale::current();
+        Self {
+            measurement: format!("{:?}", check.measurement),
+            measured: locale.number(check.measured),
+            expected: check
+                .expected
+                .map(|e| locale.number(e))
+                .unwrap_or_else(unknown),
+            deviation: check
+                .deviation
+                .map(|d| {
+                    let sign = if d.is_sign_positive() { "+" } else { "" };
+                    format!("{}{}%", sign, locale.number(d.round_dp(1)))
+                })
+                .unwrap_or_else(unknown),
+            result: match check.within_tolerance {
+                Some(true) => "OK".to_string(),
+                Some(false) => "Suspicious".to_string(),
+                None => unknown(),
+            },
+        }
+    }
 }
 
-fn get_gregorian_year(item: &CollectedItem) -> Option<i32> {
-    item.issue.as_ref().and_then(|i| i.gregorian_year)
+#[derive(Tabled)]
+struct PriceRow {
+    #[tabled(rename = "Grade")]
+    grade: String,
+    #[tabled(rename = "Price")]
+    price: String,
 }
 
-// Command handlers
-async fn dump_collection(api_key: String, user_id: i64, lang: Option<String>) -> Result<()> {
-    let mut items = fetch_collection(api_key, user_id, lang).await?;
-
-    items.sort_by(|a, b| {
-        let a_issuer = get_issuer_name(a);
-        let b_issuer = get_issuer_name(b);
-        let a_year = get_gregorian_year(a);
-        let b_year = get_gregorian_year(b);
-        let a_title = &a.type_info.title;
-        let b_title = &b.type_info.title;
-
-        a_issuer
-            .cmp(&b_issuer)
-            .then_with(|| a_year.cmp(&b_year))
-            .then_with(|| a_title.cmp(b_title))
-    });
+#[derive(Tabled)]
+struct WatchRow {
+    #[tabled(rename = "ID")]
+    id: u32,
+    #[tabled(rename = "Type")]
+    type_id: i64,
+    #[tabled(rename = "Issue")]
+    issue_id: i64,
+    #[tabled(rename = "Title")]
+    title: String,
+    #[tabled(rename = "Grade")]
+    grade: String,
+    #[tabled(rename = "Threshold")]
+    threshold: String,
+    #[tabled(rename = "Last Price")]
+    last_price: String,
+}
 
-    for item in items {
-        let issuer_name = get_issuer_name(&item);
-        let year_str = get_year(&item)
-            .map(|y| y.to_string())
-            .unwrap_or_else(|| "<Unknown>".to_string());
+impl From<&Watch> for WatchRow {
+    fn from(watch: &Watch) -> Self {
+        Self {
+            id: watch.id,
+            type_id: watch.type_id,
+            issue_id: watch.issue_id,
+            title: watch.title.clone().unwrap_or_default(),
+            grade: watch.grade.to_string(),
+            threshold: threshold_label(watch),
+            last_price: watch
+                .last_price
+                .map(|price| watch_amount(watch, price))
+                .unwrap_or_default(),
+        }
+    }
+}
 
-        println!(
-            "{} - {} ({})",
-            issuer_name, item.type_info.title, year_str
-        );
+#[derive(Tabled)]
+struct IssueRow {
+    #[tabled(rename = "ID")]
+    id: i64,
+    #[tabled(rename = "Year")]
+    year: String,
+    #[tabled(rename = "Mint Letter")]
+    mint_letter: String,
+    #[tabled(rename = "Mintage")]
+    mintage: String,
+}
+
+impl From<&Issue> for IssueRow {
+    fn from(i: &Issue) -> Self {
+        Self {
+            id: i.id,
+            year: i
+                .year
+                .map(|y| y.to_string())
+                .unwrap_or_else(|| "<Unknown>".to_string()),
+            mint_letter: i.mint_letter.clone().unwrap_or_default(),
+            mintage: i
+                .mintage
+                .map(|m| m.to_string())
+                .unwrap_or_else(|| "<Unknown>".to_string()),
+        }
     }
+}
 
-    Ok(())
+#[derive(Tabled)]
+struct IssuerRow {
+    #[tabled(rename = "Code")]
+    code: String,
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Parent")]
+    parent: String,
+}
+
+impl From<&IssuerDetail> for IssuerRow {
+    fn from(i: &IssuerDetail) -> Self {
+        Self {
+            code: i.code.clone(),
+            name: i.name.clone(),
+            parent: i
+                .parent
+                .as_ref()
+                .map(|p| p.name.clone())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Tabled)]
+struct MintRow {
+    #[tabled(rename = "ID")]
+    id: i64,
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Place")]
+    place: String,
+    #[tabled(rename = "Country")]
+    country: String,
+    #[tabled(rename = "Active")]
+    active: String,
+}
+
+impl From<&MintDetail> for MintRow {
+    fn from(m: &MintDetail) -> Self {
+        let year = |y: Option<i32>| y.map(|y| y.to_string()).unwrap_or_default();
+        Self {
+            id: m.id,
+            name: m.name.clone().unwrap_or_else(|| "<Unknown>".to_string()),
+            place: m.place.clone().unwrap_or_default(),
+            country: m
+                .country
+                .as_ref()
+                .map(|c| c.name.clone())
+                .unwrap_or_default(),
+            active: match (m.start_year, m.end_year) {
+                (None, None) => String::new(),
+                (start, end) => format!("{} - {}", year(start), year(end)),
+            },
+        }
+    }
+}
+
+#[derive(Tabled)]
+struct CatalogueRow {
+    #[tabled(rename = "ID")]
+    id: i64,
+    #[tabled(rename = "Code")]
+    code: String,
+    #[tabled(rename = "Title")]
+    title: String,
+    #[tabled(rename = "Author")]
+    author: String,
+}
+
+impl From<&CatalogueDetail> for CatalogueRow {
+    fn from(c: &CatalogueDetail) -> Self {
+        Self {
+            id: c.id,
+            code: c.code.clone(),
+            title: c.title.clone(),
+            author: c.author.clone(),
+        }
+    }
+}
+
+#[derive(Tabled)]
+struct CandidateRow {
+    #[tabled(rename = "Rank")]
+    rank: usize,
+    #[tabled(rename = "ID")]
+    id: i64,
+    #[tabled(rename = "Title")]
+    title: String,
+    #[tabled(rename = "Issuer")]
+    issuer: String,
+    #[tabled(rename = "Similarity")]
+    similarity: String,
+    #[tabled(rename = "Obverse Thumbnail")]
+    obverse_thumbnail: String,
+    #[tabled(rename = "Reverse Thumbnail")]
+    reverse_thumbnail: String,
+}
+
+impl CandidateRow {
+    fn new(rank: usize, t: SearchByImageTypeResult) -> Self {
+        Self {
+            rank,
+            id: t.id,
+            title: t.title,
+            issuer: t
+                .issuer
+                .map(|i| i.name)
+                .unwrap_or_else(|| "<Unknown>".to_string()),
+            similarity: t
+                .similarity_distance
+                .map(|d| d.to_string())
+                .unwrap_or_else(|| "<Unknown>".to_string()),
+            obverse_thumbnail: t.obverse_thumbnail.map(|u| u.to_string()).unwrap_or_default(),
+            reverse_thumbnail: t.reverse_thumbnail.map(|u| u.to_string()).unwrap_or_default(),
+        }
+    }
 }
 
-async fn summarize_collection(api_key: String, user_id: i64, lang: Option<String>) -> Result<()> {
-    let items = fetch_collection(api_key, user_id, lang).await?;
+// Command handlers
+async fn dump_collection(
+    api_key: String,
+    user_id: i64,
+    filters: filter::ItemFilters,
+    sort_by: format::SortKey,
+    group_by: Option<format::GroupKey>,
+    lang: Option<String>,
+) -> Result<()> {
+    let mut items = fetch_collection(api_key, user_id, &filters.params(), lang).await?;
+    items.retain(|item| filters.matches(item));
+    format::sort_items(&mut items, sort_by);
+
+    // A line per item, or a table of the columns selected
+    let print_items = |items: &[CollectedItem], indent: &str| -> Result<()> {
+        if table::has_columns() {
+            return table::print(items.iter().map(ItemRow::from));
+        }
+        for item in items {
+            let year_str = format::year(item)
+                .map(|y| y.to_string())
+                .unwrap_or_else(|| format::UNKNOWN.to_string());
+            println!(
+                "{}{} - {} ({})",
+                indent,
+                format::issuer_name(item),
+                item.type_info.title,
+                year_str
+            );
+        }
+        Ok(())
+    };
+    match group_by {
+        None => print_items(&items, "")?,
+        Some(key) => {
+            for (label, items) in format::group_items(items, key) {
+                println!("{} ({})", label, items.len());
+                print_items(&items, "  ")?;
+            }
+        }
+    }
+
+    Ok(())
+}
 
-    let mut by_issuer: HashMap<String, Vec<CollectedItem>> = HashMap::new();
-    for item in items {
-        let issuer_name = get_issuer_name(&item);
-        by_issuer.entry(issuer_name).or_default().push(item);
+fn issuer_label(item: &CollectedItem, flags: Option<FlagStyle>) -> String {
+    let name = format::issuer_name(item);
+    let country = item.type_info.issuer.as_ref().and_then(Issuer::iso_country);
+    match (flags, country) {
+        (Some(FlagStyle::Emoji), Some(code)) => match country::flag_emoji(code) {
+            Some(flag) => format!("{} {}", flag, name),
+            None => name,
+        },
+        (Some(FlagStyle::Code), Some(code)) => format!("[{}] {}", code, name),
+        _ => name,
     }
+}
 
-    let mut summaries = by_issuer
+async fn summarize_collection(
+    api_key: String,
+    user_id: i64,
+    flags: Option<FlagStyle>,
+    group_by: format::GroupKey,
+    lang: Option<String>,
+) -> Result<()> {
+    let items = fetch_collection(api_key, user_id, &GetCollectedItemsParams::new(), lang).await?;
+
+    let summaries = format::group_items(items, group_by)
         .into_iter()
-        .map(|(issuer, items)| {
+        .map(|(group, items)| {
+            let group = match group_by {
+                format::GroupKey::Issuer => issuer_label(&items[0], flags),
+                _ => group,
+            };
             let total_items = items.len();
-            let mut years: Vec<i32> = items.iter().filter_map(get_gregorian_year).collect();
+            let mut years: Vec<i32> = items.iter().filter_map(format::gregorian_year).collect();
             years.sort_unstable();
             let oldest_item = years
                 .first()
@@ -316,40 +1655,278 @@ async fn summarize_collection(api_key: String, user_id: i64, lang: Option<String
                 .map(|y| y.to_string())
                 .unwrap_or_else(|| "<Unknown>".to_string());
 
-            IssuerSummary {
-                issuer,
-                total_items,
-                oldest_item,
-                newest_item,
-            }
+            [group, total_items.to_string(), oldest_item, newest_item]
         })
         .collect::<Vec<_>>();
 
-    summaries.sort_by(|a, b| a.issuer.cmp(&b.issuer));
+    let header = [group_by.name(), "Total Items", "Oldest Item", "Newest Item"];
+    let mut records = vec![header.map(String::from).to_vec()];
+    records.extend(summaries.into_iter().map(Vec::from));
+    println!("{}", table::render_records(records)?);
+
+    Ok(())
+}
+
+async fn list_collections(
+    api_key: String,
+    user_id: i64,
+    detail: bool,
+    lang: Option<String>,
+) -> Result<()> {
+    let client = build_viewer_client(api_key, lang).await?;
+    let collections = client.get_user_collections(user_id).await?.collections;
+    if collections.is_empty() {
+        println!("No collections found for user {}.", user_id);
+        return Ok(());
+    }
+    if !detail {
+        let rows: Vec<CollectionRow> = collections.iter().map(CollectionRow::from).collect();
+        table::print(rows)?;
+        return Ok(());
+    }
 
-    let table = Table::new(summaries).to_string();
-    println!("{}", table);
+    let rows: Vec<CollectionDetailRow> = stream::iter(&collections)
+        .map(|collection| {
+            let params = GetCollectedItemsParams::new().collection(collection.id);
+            let client = &client;
+            async move {
+                let items = client.get_collected_items(user_id, &params).await?;
+                Ok::<_, planchet::Error>(CollectionDetailRow::new(collection, &items))
+            }
+        })
+        .buffered(HYDRATE_CONCURRENCY)
+        .try_collect()
+        .await?;
+    table::print(rows)?;
 
     Ok(())
 }
 
+async fn fetch_stats(
+    api_key: String,
+    user_id: i64,
+    hydrate: bool,
+    lang: Option<String>,
+) -> Result<analysis::CollectionStats> {
+    let client = build_viewer_client(api_key, lang).await?;
+    let items = client
+        .get_collected_items(user_id, &GetCollectedItemsParams::new())
+        .await?
+        .items;
+
+    let mut types = HashMap::new();
+    if hydrate {
+        let mut type_ids: Vec<i64> = items.iter().map(|i| i.type_info.id).collect();
+        type_ids.sort_unstable();
+        type_ids.dedup();
+        for type_id in type_ids {
+            types.insert(type_id, client.get_type(type_id).await?);
+        }
+    }
+    Ok(analysis::collection_stats(&items, &types))
+}
+
+async fn collection_stats(
+    api_key: String,
+    user_id: i64,
+    hydrate: bool,
+    json: bool,
+    lang: Option<String>,
+) -> Result<()> {
+    let stats = fetch_stats(api_key, user_id, hydrate, lang).await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+
+    println!(
+        "Items: {} ({} types)",
+        stats.total_items, stats.distinct_types
+    );
+    print_distribution("issuer", &stats.by_issuer);
+    print_distribution("category", &stats.by_category);
+    let by_decade = stats
+        .by_decade
+        .iter()
+        .map(|(decade, count)| (format!("{}s", decade), *count))
+        .collect();
+    print_distribution("decade", &by_decade);
+    print_distribution("grade", &stats.by_grade);
+    print_distribution("composition", &stats.by_composition);
+    if stats.unknown_year_count > 0 {
+        println!();
+        println!("Items of unknown year: {}", stats.unknown_year_count);
+    }
+
+    Ok(())
+}
+
+async fn write_report(
+    api_key: String,
+    user_id: i64,
+    output: PathBuf,
+    hydrate: bool,
+    title: Option<String>,
+    lang: Option<String>,
+) -> Result<()> {
+    let stats = fetch_stats(api_key, user_id, hydrate, lang).await?;
+    let format = match output.extension().and_then(|e| e.to_str()) {
+        Some("md" | "markdown") => ReportFormat::Markdown,
+        _ => ReportFormat::Html,
+    };
+    let title = title.unwrap_or_else(|| format!("Collection of user {}", user_id));
+    std::fs::write(&output, report::render(&stats, &title, format)?)?;
+    println!("Report written to {}", output.display());
+    Ok(())
+}
+
+/// The items of a collection to export, with their full types.
+struct Export {
+    client: Client,
+    items: Vec<CollectedItem>,
+    types: HashMap<i64, NumistaType>,
+}
+
+async fn fetch_export(
+    api_key: String,
+    user_id: i64,
+    item_ids: &[i64],
+    lang: Option<String>,
+) -> Result<Export> {
+    let client = build_viewer_client(api_key, lang).await?;
+    let mut items = client
+        .get_collected_items(user_id, &GetCollectedItemsParams::new())
+        .await?
+        .items;
+    if !item_ids.is_empty() {
+        items.retain(|i| item_ids.contains(&i.id));
+    }
+
+    let mut type_ids: Vec<i64> = items.iter().map(|i| i.type_info.id).collect();
+    type_ids.sort_unstable();
+    type_ids.dedup();
+    let types = stream::iter(type_ids)
+        .map(|id| client.get_type(id))
+        .buffered(HYDRATE_CONCURRENCY)
+        .map_ok(|t| (t.id, t))
+        .try_collect()
+        .await?;
+    Ok(Export {
+        client,
+        items,
+        types,
+    })
+}
+
+async fn export_pdf(
+    export: &Export,
+    output: PathBuf,
+    title: String,
+    photos: bool,
+    estimate: Option<String>,
+) -> Result<()> {
+    let client = &export.client;
+    let mut inventory = Inventory::new(title, &export.items).types(&export.types);
+    for item in &export.items {
+        if photos {
+            if let Some(picture) = item.pictures.iter().flatten().next() {
+                let photo = client.fetch_picture(&picture.thumbnail_url).await?;
+                inventory = inventory.photo(item.id, photo);
+            }
+        }
+        if let (Some(currency), Some(issue), Some(grade)) = (&estimate, &item.issue, &item.grade) {
+            let prices = client
+                .get_prices(item.type_info.id, issue.id, Some(currency))
+                .await?;
+            if let Some(price) = prices.prices.iter().find(|p| p.grade == *grade) {
+                let value = price.price * Number::from(item.quantity);
+                inventory = inventory.valuation(item.id, value, prices.currency);
+            }
+        }
+    }
+
+    std::fs::write(&output, inventory.render())?;
+    println!("Inventory written to {}", output.display());
+    Ok(())
+}
+
+fn export_labels(export: &Export, output: PathBuf) -> Result<()> {
+    let labels = Labels::new(&export.items).types(&export.types);
+    if output.extension().and_then(|e| e.to_str()) != Some("svg") {
+        std::fs::write(&output, labels.pdf())?;
+        println!("Labels written to {}", output.display());
+        return Ok(());
+    }
+
+    // SVG pictures hold a single sheet, so the following sheets get a
+    // numbered file each.
+    let stem = output.file_stem().unwrap_or_default().to_string_lossy();
+    let mut paths = Vec::new();
+    for (i, sheet) in labels.svg().into_iter().enumerate() {
+        let path = match i {
+            0 => output.clone(),
+            _ => output.with_file_name(format!("{}-{}.svg", stem, i + 1)),
+        };
+        std::fs::write(&path, sheet)?;
+        paths.push(path.display().to_string());
+    }
+    println!("Labels written to {}", paths.join(", "));
+    Ok(())
+}
+
+fn print_distribution(name: &str, counts: &BTreeMap<String, i64>) {
+    if counts.is_empty() {
+        return;
+    }
+    println!();
+    println!("By {}:", name);
+    for (key, count) in counts {
+        println!("  {}: {}", key, count);
+    }
+}
+
 fn print_search_header(count: i64, query: &str, year: Option<i32>) {
     let search_details = format!(
         "query: '{}'{}",
         query,
         year.map(|y| format!(", year: {}", y))
-            .unwrap_or_else(|| "".to_string())
+            .unwrap_or_default()
     );
     println!("Found {} results for {}.", count, search_details);
 }
 
+// Prints the types found, as a table, or with thumbnails a line and the
+// thumbnails of each
+async fn print_type_results(
+    client: &Client,
+    types: Vec<SearchTypeResult>,
+    thumbnails: Option<thumbnail::Protocol>,
+) -> Result<()> {
+    let Some(protocol) = thumbnails else {
+        return table::print(types.into_iter().map(TypeResult::from));
+    };
+    for t in types {
+        let urls = [t.obverse_thumbnail.clone(), t.reverse_thumbnail.clone()];
+        let row = TypeResult::from(t);
+        println!(
+            "{}: {} ({}, {}-{})",
+            row.id, row.title, row.issuer, row.min_year, row.max_year
+        );
+        thumbnail::print(client, urls.each_ref().map(Option::as_ref), protocol).await?;
+    }
+    Ok(())
+}
+
 async fn search_types(
     api_key: String,
     query: String,
     year: Option<i32>,
     all: bool,
+    thumbnails: Option<thumbnail::Protocol>,
     lang: Option<String>,
 ) -> Result<()> {
+    let thumbnails = thumbnails.map(thumbnail::Protocol::resolve).transpose()?;
     let client = build_client(api_key, None, lang)?;
     let mut params = SearchTypesParams::new().q(&query);
     if let Some(y) = year {
@@ -362,9 +1939,7 @@ async fn search_types(
             .try_collect::<Vec<_>>()
             .await?;
         print_search_header(types.len() as i64, &query, year);
-        let results: Vec<TypeResult> = types.into_iter().map(TypeResult::from).collect();
-        let table = Table::new(results).to_string();
-        println!("{}", table);
+        print_type_results(&client, types, thumbnails).await?;
     } else {
         let mut page = 1;
         let count = 25;
@@ -381,10 +1956,7 @@ async fn search_types(
                 break;
             }
 
-            let results: Vec<TypeResult> =
-                response.types.into_iter().map(TypeResult::from).collect();
-            let table = Table::new(results).to_string();
-            println!("{}", table);
+            print_type_results(&client, response.types, thumbnails).await?;
 
             if page * count >= response.count {
                 break;
@@ -417,6 +1989,596 @@ async fn get_type(api_key: String, id: i64, lang: Option<String>) -> Result<()>
     Ok(())
 }
 
+async fn list_issues(
+    api_key: String,
+    type_id: i64,
+    sort: IssueSort,
+    lang: Option<String>,
+) -> Result<()> {
+    let client = build_client(api_key, None, lang)?;
+    let mut issues = client.get_issues(type_id).await?;
+    let stats = analysis::mintage_stats(&issues);
+
+    match sort {
+        IssueSort::Year => issues.sort_by_key(|i| (i.gregorian_year, i.id)),
+        IssueSort::Mintage => issues.sort_by_key(|i| {
+            (
+                stats.by_rarity.iter().position(|id| *id == i.id).unwrap_or(usize::MAX),
+                i.id,
+            )
+        }),
+    }
+
+    let rows: Vec<IssueRow> = issues.iter().map(IssueRow::from).collect();
+    table::print(rows)?;
+    println!("Total mintage: {}", stats.total);
+    for (mint_letter, mintage) in &stats.by_mint_letter {
+        if let Some(letter) = mint_letter {
+            println!("  {}: {}", letter, mintage);
+        }
+    }
+
+    Ok(())
+}
+
+async fn verify_measurements(
+    api_key: String,
+    type_id: i64,
+    measured: [Option<Number>; 3],
+    tolerance: Option<Number>,
+    lang: Option<String>,
+) -> Result<()> {
+    let [weight, size, thickness] = measured;
+    let client = build_client(api_key, None, lang)?;
+    let type_ = client.get_type(type_id).await?;
+
+    let mut tolerances = Tolerances::new();
+    if let Some(tolerance) = tolerance {
+        tolerances = tolerances
+            .weight(tolerance)
+            .size(tolerance)
+            .thickness(tolerance);
+    }
+    let report = type_.check_measurements(weight, size, thickness, &tolerances);
+    let rows: Vec<MeasurementRow> = report.checks.iter().map(MeasurementRow::from).collect();
+    println!("{}", type_.title);
+    table::print(rows)?;
+    let verdict = match report.verdict() {
+        Verdict::Consistent => "consistent with the catalogue",
+        Verdict::Suspicious => "suspicious",
+        Verdict::Unknown => "unknown (the catalogue has none of these measurements)",
+    };
+    println!("Verdict: {}", verdict);
+
+    Ok(())
+}
+
+async fn show_value(
+    api_key: String,
+    type_id: i64,
+    issue_id: i64,
+    currency: Option<String>,
+    melt: bool,
+    lang: Option<String>,
+) -> Result<()> {
+    let client = build_client(api_key, None, lang)?;
+    let prices = client
+        .get_prices(type_id, issue_id, currency.as_deref())
+        .await?;
+
+    let locale = locale::current();
+    if prices.prices.is_empty() {
+        println!("No prices found for issue {}.", issue_id);
+    } else {
+        let rows: Vec<PriceRow> = prices
+            .prices
+            .iter()
+            .map(|p| PriceRow {
+                grade: p.grade.to_string(),
+                price: locale.price(p.price, prices.currency),
+            })
+            .collect();
+        table::print(rows)?;
+    }
+    if !melt {
+        return Ok(());
+    }
+
+    let type_ = client.get_type(type_id).await?;
+    let Some(content) = bullion::metal_content(&type_) else {
+        println!("Melt value: unknown (no known precious metal content)");
+        return Ok(());
+    };
+    let provider = match env::var("SPOT_PRICE_API_URL") {
+        Ok(url) => GoldApiPrices::with_url(url),
+        Err(_) => GoldApiPrices::new(),
+    };
+    let spot_prices = SpotPrices::fetch(&provider, std::slice::from_ref(&content.metal)).await?;
+    if let Some(value) = bullion::melt_value(&type_, &spot_prices) {
+        // Spot prices are in US dollars: show the melt value in the currency
+        // of the estimated prices instead
+        let rates = match env::var("EXCHANGE_RATES_URL") {
+            Ok(url) => EcbRates::with_url(url),
+            Err(_) => EcbRates::new(),
+        };
+        let converter = CurrencyConverter::new(rates, prices.currency);
+        let value = converter.convert(value, CurrencyCode::USD).await?;
+        println!(
+            "Melt value: {} ({} g of {:?})",
+            locale.price(value, prices.currency),
+            locale.number(content.fine_weight.normalize()),
+            content.metal
+        );
+    }
+
+    Ok(())
+}
+
+// Formats an amount in the currency of the threshold of a watch, or as a
+// number if it has none
+fn watch_amount(watch: &Watch, amount: Number) -> String {
+    let locale = locale::current();
+    match watch.currency {
+        Some(currency) => locale.price(amount, currency),
+        None => locale.number(format!("{:.2}", amount)),
+    }
+}
+
+fn threshold_label(watch: &Watch) -> String {
+    let side = match watch.direction {
+        Direction::Below => "below",
+        Direction::Above => "above",
+    };
+    format!("{} {}", side, watch_amount(watch, watch.threshold))
+}
+
+async fn add_watch(api_key: String, new: NewWatch, lang: Option<String>) -> Result<()> {
+    let client = build_client(api_key, None, lang)?;
+    let type_ = client.get_type(new.type_id).await?;
+    let issue_id = match new.issue_id {
+        Some(issue_id) => issue_id,
+        None => match client.get_issues(new.type_id).await?.as_slice() {
+            [issue] => issue.id,
+            issues => bail!(
+                "{} has {} issues: give one with --issue-id (see `issues --type-id {}`)",
+                type_.title,
+                issues.len(),
+                new.type_id
+            ),
+        },
+    };
+
+    let mut watch =
+        Watch::new(new.type_id, issue_id, new.grade.clone(), new.threshold).title(&type_.title);
+    if let Some(currency) = &new.currency {
+        let code = currency
+            .parse()
+            .map_err(|_| anyhow!("Unknown currency '{}'", currency))?;
+        watch = watch.currency(code);
+    }
+    if new.above {
+        watch = watch.direction(Direction::Above);
+    }
+    let mut watchlist = config::load_watchlist()?;
+    let label = threshold_label(&watch);
+    let id = watchlist.add(watch);
+    config::save_watchlist(&watchlist)?;
+    println!(
+        "Watching issue {} of {} in {}, {} (watch {})",
+        issue_id, type_.title, new.grade, label, id
+    );
+    Ok(())
+}
+
+fn list_watches() -> Result<()> {
+    let watchlist = config::load_watchlist()?;
+    if watchlist.watches.is_empty() {
+        println!("No issues watched.");
+        return Ok(());
+    }
+    table::print(watchlist.watches.iter().map(WatchRow::from))
+}
+
+fn remove_watch(id: u32) -> Result<()> {
+    let mut watchlist = config::load_watchlist()?;
+    let watch = watchlist
+        .remove(id)
+        .with_context(|| format!("No watch {} in the watchlist", id))?;
+    config::save_watchlist(&watchlist)?;
+    println!(
+        "Stopped watching issue {} of {}",
+        watch.issue_id,
+        watch
+            .title
+            .unwrap_or_else(|| format!("type {}", watch.type_id))
+    );
+    Ok(())
+}
+
+async fn check_watches(
+    api_key: String,
+    targets: &[notify::Target],
+    lang: Option<String>,
+) -> Result<()> {
+    let mut watchlist = config::load_watchlist()?;
+    if watchlist.watches.is_empty() {
+        return Ok(());
+    }
+    let client = build_client(api_key, None, lang)?;
+    let report = watchlist.check(&client).await;
+    for (id, e) in &report.errors {
+        eprintln!("Failed to check watch {}: {}", id, e);
+    }
+    let alerts = report.alerts;
+    if alerts.is_empty() {
+        config::save_watchlist(&watchlist)?;
+        return check_errors(&report.errors, watchlist.watches.len());
+    }
+
+    let locale = locale::current();
+    let lines = alerts
+        .iter()
+        .map(|alert| {
+            let watch = &alert.watch;
+            format!(
+                "{} (type {}, issue {}) in {}: {}, {}",
+                watch.title.as_deref().unwrap_or("<Unknown>"),
+                watch.type_id,
+                watch.issue_id,
+                watch.grade,
+                locale.price(alert.price, alert.currency),
+                threshold_label(watch)
+            )
+        })
+        .collect();
+    let subject = match alerts.len() {
+        1 => "1 watched price crossed its threshold".to_string(),
+        n => format!("{} watched prices crossed their threshold", n),
+    };
+    let data = serde_json::json!({ "alerts": alerts });
+    let notification = notify::Notification::new("watch.alert", subject, lines, data);
+    // The prices are only kept once the alerts are sent, so that those which
+    // failed are sent again by the next check.
+    notify::send(targets, &notification).await?;
+    config::save_watchlist(&watchlist)?;
+    check_errors(&report.errors, watchlist.watches.len())
+}
+
+/// Fails if any watch could not be checked, once the others are handled.
+fn check_errors(errors: &[(u32, planchet::Error)], watches: usize) -> Result<()> {
+    if !errors.is_empty() {
+        bail!(
+            "{} of {} watches could not be checked",
+            errors.len(),
+            watches
+        );
+    }
+    Ok(())
+}
+
+async fn show_mint(
+    api_key: String,
+    id: i64,
+    summary: bool,
+    max_types: usize,
+    lang: Option<String>,
+) -> Result<()> {
+    let client = build_client(api_key, None, lang)?;
+    let mint = client.get_mint(id).await?;
+
+    println!("{}", mint.name.as_deref().unwrap_or("<Unknown>"));
+    if let Some(local_name) = &mint.local_name {
+        println!("Local name: {}", local_name);
+    }
+    if let Some(place) = &mint.place {
+        println!("Place: {}", place);
+    }
+    if let Some(country) = &mint.country {
+        println!("Country: {}", country.name);
+    }
+    match (mint.start_year, mint.end_year) {
+        (Some(start), Some(end)) => println!("Active: {} - {}", start, end),
+        (Some(start), None) => println!("Active since: {}", start),
+        (None, Some(end)) => println!("Active until: {}", end),
+        (None, None) => {}
+    }
+    if !summary {
+        return Ok(());
+    }
+
+    let types = client.get_types_by_mint(id, max_types).await?;
+    let issue_counts: Vec<usize> = stream::iter(&types.items)
+        .map(|t| client.get_issues(t.id))
+        .buffered(HYDRATE_CONCURRENCY)
+        .map_ok(|issues| issues.len())
+        .try_collect()
+        .await?;
+    let more = if types.truncated { " (or more)" } else { "" };
+    println!("Types: {}{}", types.items.len(), more);
+    println!("Issues: {}", issue_counts.iter().sum::<usize>());
+
+    Ok(())
+}
+
+// Checks if any of the fields contains the filter, ignoring case
+fn matches_filter<'a>(filter: Option<&str>, fields: impl IntoIterator<Item = &'a str>) -> bool {
+    let Some(filter) = filter else {
+        return true;
+    };
+    let filter = filter.to_lowercase();
+    fields
+        .into_iter()
+        .any(|field| field.to_lowercase().contains(&filter))
+}
+
+async fn list_issuers(api_key: String, filter: Option<String>, lang: Option<String>) -> Result<()> {
+    let reference = ReferenceCache::new(build_client(api_key, None, lang)?);
+    let rows: Vec<IssuerRow> = reference
+        .issuers()
+        .await?
+        .iter()
+        .filter(|i| matches_filter(filter.as_deref(), [i.code.as_str(), i.name.as_str()]))
+        .map(IssuerRow::from)
+        .collect();
+    print_reference_table(rows, "issuers")
+}
+
+async fn list_mints(api_key: String, filter: Option<String>, lang: Option<String>) -> Result<()> {
+    let reference = ReferenceCache::new(build_client(api_key, None, lang)?);
+    let rows: Vec<MintRow> = reference
+        .mints()
+        .await?
+        .iter()
+        .filter(|m| {
+            let fields = [&m.name, &m.local_name, &m.place]
+                .into_iter()
+                .flatten()
+                .map(String::as_str)
+                .chain(m.country.as_ref().map(|c| c.name.as_str()));
+            matches_filter(filter.as_deref(), fields)
+        })
+        .map(MintRow::from)
+        .collect();
+    print_reference_table(rows, "mints")
+}
+
+async fn list_catalogues(
+    api_key: String,
+    filter: Option<String>,
+    lang: Option<String>,
+) -> Result<()> {
+    let reference = ReferenceCache::new(build_client(api_key, None, lang)?);
+    let rows: Vec<CatalogueRow> = reference
+        .catalogues()
+        .await?
+        .iter()
+        .filter(|c| {
+            let fields = [c.code.as_str(), c.title.as_str(), c.author.as_str()];
+            matches_filter(filter.as_deref(), fields)
+        })
+        .map(CatalogueRow::from)
+        .collect();
+    print_reference_table(rows, "catalogues")
+}
+
+fn print_reference_table<T: Tabled>(rows: Vec<T>, name: &str) -> Result<()> {
+    if rows.is_empty() {
+        println!("No {} found.", name);
+        return Ok(());
+    }
+    let count = rows.len();
+    table::print(rows)?;
+    println!("Total: {}", count);
+    Ok(())
+}
+
+async fn download_images(
+    api_key: String,
+    type_id: i64,
+    dest: PathBuf,
+    lang: Option<String>,
+) -> Result<()> {
+    let client = build_client(api_key, None, lang)?;
+    let type_ = client.get_type(type_id).await?;
+    let pictures = client.download_pictures(&type_, &dest).await?;
+
+    if pictures.is_empty() {
+        println!("No pictures found for type {}.", type_id);
+    }
+    for picture in pictures {
+        if picture.skipped {
+            println!("Skipped {} (already exists)", picture.path.display());
+        } else {
+            println!("Downloaded {}", picture.path.display());
+        }
+    }
+
+    Ok(())
+}
+
+async fn write_qr(
+    api_key: String,
+    type_id: i64,
+    output: PathBuf,
+    scale: u32,
+    lang: Option<String>,
+) -> Result<()> {
+    let client = build_client(api_key, None, lang)?;
+    let type_ = client.get_type(type_id).await?;
+    // The URL returned by the API points to the page in the language of the
+    // client.
+    let qr = match &type_.url {
+        Some(url) => QrCode::encode(url.as_str())?,
+        None => QrCode::for_type(&TypeLink::new(type_.id, type_.category)),
+    };
+    if output.extension().and_then(|e| e.to_str()) == Some("svg") {
+        std::fs::write(&output, qr.to_svg())?;
+    } else {
+        std::fs::write(&output, qr.to_png(scale)?)?;
+    }
+    println!("QR code written to {}", output.display());
+    Ok(())
+}
+
+async fn identify(
+    api_key: String,
+    obverse: PathBuf,
+    reverse: Option<PathBuf>,
+    max_results: i64,
+    thumbnails: Option<thumbnail::Protocol>,
+    lang: Option<String>,
+) -> Result<()> {
+    let thumbnails = thumbnails.map(thumbnail::Protocol::resolve).transpose()?;
+    let client = build_client(api_key, None, lang)?;
+    let mut builder = SearchByImageBuilder::new()
+        .max_results(max_results)
+        .photo(&std::fs::read(&obverse)?)?;
+    if let Some(reverse) = reverse {
+        builder = builder.photo(&std::fs::read(&reverse)?)?;
+    }
+
+    let response = builder.send(&client).await?.response;
+    if response.types.is_empty() {
+        println!("No matching types found.");
+        return Ok(());
+    }
+
+    let mut candidates = response.types;
+    candidates.sort_by_key(|t| (t.similarity_distance.is_none(), t.similarity_distance));
+    if let Some(protocol) = thumbnails {
+        for (i, t) in candidates.into_iter().enumerate() {
+            let urls = [t.obverse_thumbnail.clone(), t.reverse_thumbnail.clone()];
+            let row = CandidateRow::new(i + 1, t);
+            println!(
+                "{}. {}: {} ({}, similarity {})",
+                row.rank, row.id, row.title, row.issuer, row.similarity
+            );
+            thumbnail::print(&client, urls.each_ref().map(Option::as_ref), protocol).await?;
+        }
+    } else {
+        let rows: Vec<CandidateRow> = candidates
+            .into_iter()
+            .enumerate()
+            .map(|(i, t)| CandidateRow::new(i + 1, t))
+            .collect();
+        table::print(rows)?;
+    }
+
+    if let Some(year) = response.experimental_tentative_year {
+        println!("Tentative year: {}", year);
+    }
+    if let Some(grade) = response.experimental_tentative_grade {
+        println!("Tentative grade: {}", grade);
+    }
+
+    Ok(())
+}
+
+async fn import_collection(
+    api_key: String,
+    user_id: i64,
+    file: PathBuf,
+    mapping: ColumnMapping,
+    lang: Option<String>,
+    dry_run: bool,
+) -> Result<()> {
+    let reader = std::fs::File::open(&file)?;
+    let client = build_authorized_client(api_key, "edit_collection", lang, dry_run).await?;
+    let report = client
+        .import_collected_items(user_id, reader, &mapping)
+        .await?;
+
+    for row in &report.rows {
+        match &row.result {
+            Ok(WriteOutcome::Sent(confirmation)) => println!(
+                "Row {}: added item {} ({})",
+                row.row, confirmation.value.id, confirmation.value.type_info.title
+            ),
+            Ok(WriteOutcome::Planned(request)) => {
+                println!("Row {}: would send {}", row.row, request)
+            }
+            Err(e) => println!("Row {}: {}", row.row, e),
+        }
+    }
+    if dry_run {
+        println!(
+            "Dry run: {} of {} rows would be imported.",
+            report.planned().count(),
+            report.rows.len()
+        );
+    } else {
+        println!(
+            "Imported {} of {} rows.",
+            report.succeeded().count(),
+            report.rows.len()
+        );
+    }
+
+    Ok(())
+}
+
+// Fetches a stored type and its issues, or `None` if it is no longer in the
+// catalogue
+async fn fetch_live_type(
+    client: &Client,
+    type_id: i64,
+) -> Result<Option<(NumistaType, Vec<Issue>)>> {
+    let live = match client.get_type(type_id).await {
+        Ok(live) => live,
+        Err(e) if e.is_not_found() => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let issues = client.get_issues(type_id).await?;
+    Ok(Some((live, issues)))
+}
+
+async fn audit_store(api_key: String, path: PathBuf, lang: Option<String>) -> Result<()> {
+    if !path.is_file() {
+        bail!("No store at {}", path.display());
+    }
+    let store = SqliteStore::open(&path)
+        .with_context(|| format!("Failed to open the store at {}", path.display()))?;
+    let client = build_client(api_key, None, lang)?;
+    let types = store.types()?;
+
+    let live: Vec<_> = stream::iter(&types)
+        .map(|record| fetch_live_type(&client, record.id))
+        .buffered(HYDRATE_CONCURRENCY)
+        .try_collect()
+        .await?;
+    let mut rows = Vec::new();
+    let mut changed = 0;
+    for (record, live) in types.iter().zip(live) {
+        let changes = match live {
+            Some((live, issues)) => {
+                let stored_issues = store.issues_of_type(record.id)?;
+                record
+                    .discrepancies(&stored_issues, &live, &issues)
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect()
+            }
+            None => vec!["no longer in the catalogue".to_string()],
+        };
+        if !changes.is_empty() {
+            changed += 1;
+        }
+        rows.extend(changes.into_iter().map(|change| AuditRow {
+            id: record.id,
+            title: record.title.clone(),
+            change,
+        }));
+    }
+
+    if rows.is_empty() {
+        println!("No changes in {} types.", types.len());
+    } else {
+        table::print(rows)?;
+        println!("{} of {} types changed.", changed, types.len());
+    }
+    Ok(())
+}
+
 // Main entrypoint
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -437,15 +2599,156 @@ async fn main() -> Result<()> {
         .with_ansi(false)
         .init();
 
+    table::set_options(table::TableOptions {
+        columns: cli.table_columns,
+        style: cli.style,
+    });
+    if let Some(locale) = cli.locale {
+        locale::set_locale(locale);
+    }
+    let profile = config::select_profile(cli.profile.as_deref())?;
+    let api_key = cli
+        .api_key
+        .or(profile.api_key)
+        .context("No API key given: use --api-key, set NUMISTA_API_KEY, or configure a profile")?;
+    let lang = match cli.lang {
+        Some(lang) => Some(lang),
+        None => profile
+            .lang
+            .map(|lang| parse_lang(&lang))
+            .transpose()
+            .map_err(|e| anyhow::anyhow!("Invalid language of the profile: {}", e))?,
+    };
+    let user = |user_id: Option<i64>| {
+        user_id
+            .or(profile.user_id)
+            .context("No user ID given: use --user-id, or configure a profile with a user_id")
+    };
+
     match cli.command {
-        Commands::Dump { user_id } => dump_collection(cli.api_key, user_id, cli.lang).await?,
-        Commands::Summarize { user_id } => {
-            summarize_collection(cli.api_key, user_id, cli.lang).await?
+        Commands::Login {
+            client_id,
+            scope,
+            port,
+            no_browser,
+        } => login(api_key, client_id, scope, port, no_browser, lang).await?,
+        Commands::Dump {
+            user_id,
+            filters,
+            sort_by,
+            group_by,
+        } => dump_collection(api_key, user(user_id)?, filters, sort_by, group_by, lang).await?,
+        Commands::Summarize {
+            user_id,
+            flags,
+            group_by,
+        } => summarize_collection(api_key, user(user_id)?, flags, group_by, lang).await?,
+        Commands::Collections { user_id, detail } => {
+            list_collections(api_key, user(user_id)?, detail, lang).await?
+        }
+        Commands::Stats {
+            user_id,
+            hydrate,
+            json,
+        } => collection_stats(api_key, user(user_id)?, hydrate, json, lang).await?,
+        Commands::Report {
+            user_id,
+            output,
+            hydrate,
+            title,
+        } => {
+            let user_id = user(user_id)?;
+            write_report(api_key, user_id, output, hydrate, title, lang).await?
+        }
+        Commands::Export {
+            user_id,
+            item_ids,
+            format,
+            output,
+            title,
+            photos,
+            estimate,
+        } => {
+            let user_id = user(user_id)?;
+            let export = fetch_export(api_key, user_id, &item_ids, lang).await?;
+            match format {
+                ExportFormat::Pdf => {
+                    let title = title.unwrap_or_else(|| {
+                        format!("Inventory of the collection of user {}", user_id)
+                    });
+                    export_pdf(&export, output, title, photos, estimate).await?
+                }
+                ExportFormat::Labels => export_labels(&export, output)?,
+            }
+        }
+        Commands::Types {
+            query,
+            year,
+            all,
+            thumbnails,
+        } => search_types(api_key, query, year, all, thumbnails, lang).await?,
+        Commands::Type { id } => get_type(api_key, id, lang).await?,
+        Commands::Issues { type_id, sort } => list_issues(api_key, type_id, sort, lang).await?,
+        Commands::Verify {
+            type_id,
+            weight,
+            size,
+            thickness,
+            tolerance,
+        } => {
+            let measured = [weight, size, thickness];
+            verify_measurements(api_key, type_id, measured, tolerance, lang).await?
         }
-        Commands::Types { query, year, all } => {
-            search_types(cli.api_key, query, year, all, cli.lang).await?
+        Commands::Value {
+            type_id,
+            issue_id,
+            currency,
+            melt,
+        } => show_value(api_key, type_id, issue_id, currency, melt, lang).await?,
+        Commands::Watch { command } => match command {
+            WatchCommand::Add(watch) => add_watch(api_key, watch, lang).await?,
+            WatchCommand::List => list_watches()?,
+            WatchCommand::Remove { id } => remove_watch(id)?,
+            WatchCommand::Check { notify } => check_watches(api_key, &notify, lang).await?,
+        },
+        Commands::Mint {
+            id,
+            summary,
+            max_types,
+        } => show_mint(api_key, id, summary, max_types, lang).await?,
+        Commands::Issuers { filter } => list_issuers(api_key, filter, lang).await?,
+        Commands::Mints { filter } => list_mints(api_key, filter, lang).await?,
+        Commands::Catalogues { filter } => list_catalogues(api_key, filter, lang).await?,
+        Commands::Images { type_id, dest } => download_images(api_key, type_id, dest, lang).await?,
+        Commands::Qr {
+            type_id,
+            output,
+            scale,
+        } => write_qr(api_key, type_id, output, scale, lang).await?,
+        Commands::Identify {
+            obverse,
+            reverse,
+            max_results,
+            thumbnails,
+        } => identify(api_key, obverse, reverse, max_results, thumbnails, lang).await?,
+        Commands::Import {
+            user_id,
+            file,
+            columns,
+            resolve_references,
+            delay_ms,
+        } => {
+            let mapping = columns
+                .into_iter()
+                .fold(ColumnMapping::new(), |m, (field, column)| {
+                    m.column(field, column)
+                })
+                .resolve_references(resolve_references)
+                .delay(Duration::from_millis(delay_ms));
+            let user_id = user(user_id)?;
+            import_collection(api_key, user_id, file, mapping, lang, cli.dry_run).await?
         }
-        Commands::Type { id } => get_type(cli.api_key, id, cli.lang).await?,
+        Commands::Audit { store } => audit_store(api_key, store, lang).await?,
     }
 
     Ok(())
@@ -0,0 +1,316 @@
+//! Notifications of the results of commands, e.g. of `watch check` run from
+//! cron.
+//!
+//! Each target given with `--notify` is a [`Notifier`]: `stdout` prints the
+//! results, an `http` or `https` URL receives them as a JSON [`Notification`]
+//! posted to it, and an `smtp` or `smtps` URL sends them by email, with the
+//! `smtp` feature.
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use url::Url;
+
+/// The version of the schema of [`Notification`], increased on any change
+/// which is not an added field.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The results of a command, sent to notifiers.
+///
+/// This is the JSON payload posted to webhooks, e.g.:
+///
+/// ```json
+/// {
+///   "version": 1,
+///   "event": "watch.alert",
+///   "subject": "1 watched price crossed its threshold",
+///   "lines": ["5 Cents - Victoria (type 42, issue 7) in VF: 11.00 EUR, below 12.50 EUR"],
+///   "data": { "alerts": [...] }
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct Notification {
+    /// The version of the schema, [`SCHEMA_VERSION`].
+    pub version: u32,
+    /// The kind of results, e.g. `watch.alert`.
+    pub event: &'static str,
+    /// A summary of the results, the subject of emails.
+    pub subject: String,
+    /// The results as printed by the command, one per line.
+    pub lines: Vec<String>,
+    /// The results as data, in a structure specific to the event.
+    pub data: serde_json::Value,
+}
+
+impl Notification {
+    /// Creates a notification of the current schema.
+    pub fn new<S: Into<String>>(
+        event: &'static str,
+        subject: S,
+        lines: Vec<String>,
+        data: serde_json::Value,
+    ) -> Self {
+        Self {
+            version: SCHEMA_VERSION,
+            event,
+            subject: subject.into(),
+            lines,
+            data,
+        }
+    }
+}
+
+/// A destination of notifications.
+#[async_trait::async_trait]
+pub trait Notifier: Send + Sync {
+    /// Sends a notification.
+    async fn notify(&self, notification: &Notification) -> Result<()>;
+}
+
+/// Prints the lines of notifications.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Stdout;
+
+#[async_trait::async_trait]
+impl Notifier for Stdout {
+    async fn notify(&self, notification: &Notification) -> Result<()> {
+        for line in &notification.lines {
+            println!("{}", line);
+        }
+        Ok(())
+    }
+}
+
+/// Posts notifications as JSON to a URL.
+#[derive(Debug, Clone)]
+pub struct Webhook {
+    client: reqwest::Client,
+    url: Url,
+}
+
+impl Webhook {
+    /// Creates a webhook posting to `url`.
+    pub fn new(url: Url) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Notifier for Webhook {
+    async fn notify(&self, notification: &Notification) -> Result<()> {
+        self.client
+            .post(self.url.clone())
+            .json(notification)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .with_context(|| format!("Failed to post to {}", self.url))?;
+        Ok(())
+    }
+}
+
+/// A target of notifications given on the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    Stdout,
+    Webhook(Url),
+    Email(Url),
+}
+
+impl Target {
+    /// Creates the notifier of the target.
+    pub fn notifier(&self) -> Result<Box<dyn Notifier>> {
+        Ok(match self {
+            Target::Stdout => Box::new(Stdout),
+            Target::Webhook(url) => Box::new(Webhook::new(url.clone())),
+            Target::Email(url) => email(url)?,
+        })
+    }
+}
+
+#[cfg(feature = "smtp")]
+fn email(url: &Url) -> Result<Box<dyn Notifier>> {
+    Ok(Box::new(smtp::Smtp::new(url)?))
+}
+
+#[cfg(not(feature = "smtp"))]
+fn email(_url: &Url) -> Result<Box<dyn Notifier>> {
+    bail!("Email notifications need planchet-cli to be built with the `smtp` feature")
+}
+
+/// Parses a target of notifications given with `--notify`.
+pub fn parse_target(s: &str) -> Result<Target, String> {
+    if s == "stdout" {
+        return Ok(Target::Stdout);
+    }
+    let url = Url::parse(s).map_err(|e| format!("Invalid notification target '{}': {}", s, e))?;
+    match url.scheme() {
+        "http" | "https" => Ok(Target::Webhook(url)),
+        "smtp" | "smtps" => Ok(Target::Email(url)),
+        scheme => Err(format!(
+            "Unknown notification target '{}' (stdout, or an http, https, smtp, or smtps URL)",
+            scheme
+        )),
+    }
+}
+
+/// Sends a notification to every target, defaulting to `stdout` without any.
+///
+/// Every target is tried, and the failures reported, before returning an
+/// error if any failed.
+pub async fn send(targets: &[Target], notification: &Notification) -> Result<()> {
+    let default = [Target::Stdout];
+    let targets = if targets.is_empty() {
+        &default[..]
+    } else {
+        targets
+    };
+    let mut failed = 0;
+    for target in targets {
+        let sent = match target.notifier() {
+            Ok(notifier) => notifier.notify(notification).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = sent {
+            eprintln!("Failed to notify: {:#}", e);
+            failed += 1;
+        }
+    }
+    if failed > 0 {
+        bail!("{} of {} notifications failed", failed, targets.len());
+    }
+    Ok(())
+}
+
+#[cfg(feature = "smtp")]
+mod smtp {
+    use super::{Notification, Notifier};
+    use anyhow::{bail, Context, Result};
+    use lettre::message::header::ContentType;
+    use lettre::message::Mailbox;
+    use lettre::transport::smtp::authentication::Credentials;
+    use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+    use std::env;
+    use url::Url;
+
+    /// Sends notifications by email, through an SMTP server given as
+    /// `smtp[s]://[user[:password]@]host[:port]?from=ADDRESS&to=ADDRESS`.
+    ///
+    /// `smtps` connects with TLS, on port 465 by default. `smtp` connects on
+    /// port 25 by default, and requires the server to upgrade the connection
+    /// with STARTTLS if a user is given, so that credentials are never sent in
+    /// clear; without a user, e.g. to a local relay, the connection is not
+    /// encrypted. The password can also be given by `$PLANCHET_SMTP_PASSWORD`,
+    /// to keep it off the command line.
+    #[derive(Debug, Clone)]
+    pub struct Smtp {
+        host: String,
+        port: u16,
+        transport: AsyncSmtpTransport<Tokio1Executor>,
+        from: Mailbox,
+        to: Vec<Mailbox>,
+    }
+
+    impl Smtp {
+        pub fn new(url: &Url) -> Result<Self> {
+            let host = url.host_str().context("The SMTP URL has no host")?;
+            let query = |name: &'static str| {
+                url.query_pairs()
+                    .filter(move |(key, _)| key == name)
+                    .map(|(_, value)| value.into_owned())
+            };
+            let address = |address: String| {
+                address
+                    .parse::<Mailbox>()
+                    .with_context(|| format!("Invalid email address '{}'", address))
+            };
+            let from = query("from")
+                .next()
+                .context("The SMTP URL has no sender: add ?from=ADDRESS")
+                .and_then(address)?;
+            let to = query("to").map(address).collect::<Result<Vec<_>>>()?;
+            if to.is_empty() {
+                bail!("The SMTP URL has no recipient: add &to=ADDRESS");
+            }
+            let credentials = match url.username() {
+                "" => None,
+                user => {
+                    let password = match url.password() {
+                        Some(password) => password.to_string(),
+                        None => env::var("PLANCHET_SMTP_PASSWORD").unwrap_or_default(),
+                    };
+                    Some(Credentials::new(decode(user), decode(&password)))
+                }
+            };
+            let transport = match (url.scheme(), &credentials) {
+                ("smtps", _) => AsyncSmtpTransport::<Tokio1Executor>::relay(host)?,
+                (_, Some(_)) => {
+                    AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?.port(25)
+                }
+                (_, None) => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+            };
+            let transport = match url.port() {
+                Some(port) => transport.port(port),
+                None => transport,
+            };
+            let transport = match credentials {
+                Some(credentials) => transport.credentials(credentials),
+                None => transport,
+            };
+            let transport = transport.build();
+            Ok(Self {
+                host: host.to_string(),
+                port: url
+                    .port()
+                    .unwrap_or(if url.scheme() == "smtps" { 465 } else { 25 }),
+                transport,
+                from,
+                to,
+            })
+        }
+
+        /// Writes the message of a notification.
+        fn message(&self, notification: &Notification) -> Result<Message> {
+            if notification.subject.contains(['\r', '\n']) {
+                bail!("The subject of the email has a line break");
+            }
+            let builder = self
+                .to
+                .iter()
+                .cloned()
+                .fold(Message::builder().from(self.from.clone()), |builder, to| {
+                    builder.to(to)
+                })
+                .subject(&notification.subject)
+                .header(ContentType::TEXT_PLAIN);
+            let mut body = notification.lines.join("\r\n");
+            body.push_str("\r\n");
+            Ok(builder.body(body)?)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Notifier for Smtp {
+        async fn notify(&self, notification: &Notification) -> Result<()> {
+            self.transport
+                .send(self.message(notification)?)
+                .await
+                .with_context(|| {
+                    format!(
+                        "Failed to send the email through {}:{}",
+                        self.host, self.port
+                    )
+                })?;
+            Ok(())
+        }
+    }
+
+    /// Decodes a percent-encoded part of a URL.
+    fn decode(s: &str) -> String {
+        url::form_urlencoded::parse(format!("x={}", s.replace('+', "%2B")).as_bytes())
+            .next()
+            .map(|(_, value)| value.into_owned())
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,121 @@
+//! The rendering of the tables of the commands, in the style and with the
+//! columns given on the command line.
+//!
+//! The options are set once by `main`, and apply to every table printed.
+use anyhow::{bail, Result};
+use clap::ValueEnum;
+use std::sync::OnceLock;
+use tabled::builder::Builder;
+use tabled::settings::Style;
+use tabled::{Table, Tabled};
+
+/// The options of the tables, if set.
+static OPTIONS: OnceLock<TableOptions> = OnceLock::new();
+
+/// The styles of tables.
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum TableStyle {
+    /// Boxed with ASCII characters.
+    #[default]
+    Ascii,
+    /// A Markdown table, e.g. to paste into forums or documentation.
+    Markdown,
+    /// Comma-separated values, with a header row.
+    Csv,
+}
+
+/// The style and columns of the tables.
+#[derive(Default)]
+pub struct TableOptions {
+    /// The columns to show, in order, by name. All columns are shown if
+    /// empty.
+    pub columns: Vec<String>,
+    pub style: TableStyle,
+}
+
+/// Sets the options of the tables printed by the commands.
+pub fn set_options(options: TableOptions) {
+    let _ = OPTIONS.set(options);
+}
+
+/// Checks if columns were selected on the command line.
+pub fn has_columns() -> bool {
+    OPTIONS.get().is_some_and(|o| !o.columns.is_empty())
+}
+
+/// Renders rows as a table.
+pub fn render<T: Tabled>(rows: impl IntoIterator<Item = T>) -> Result<String> {
+    render_records(Table::builder(rows).into())
+}
+
+/// Renders records as a table, the first of which is the header.
+pub fn render_records(records: Vec<Vec<String>>) -> Result<String> {
+    let default = TableOptions::default();
+    let options = OPTIONS.get().unwrap_or(&default);
+    let records = select_columns(records, &options.columns)?;
+    Ok(match options.style {
+        TableStyle::Ascii => Builder::from(records).build().to_string(),
+        TableStyle::Markdown => Builder::from(records)
+            .build()
+            .with(Style::markdown())
+            .to_string(),
+        TableStyle::Csv => records
+            .iter()
+            .map(|record| {
+                record
+                    .iter()
+                    .map(|field| csv_field(field))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+    })
+}
+
+/// Prints rows as a table.
+pub fn print<T: Tabled>(rows: impl IntoIterator<Item = T>) -> Result<()> {
+    println!("{}", render(rows)?);
+    Ok(())
+}
+
+/// The name of a column on the command line: its header in lower case, with
+/// underscores for spaces, e.g. `total_items`.
+fn column_name(header: &str) -> String {
+    header.trim().to_lowercase().replace([' ', '-'], "_")
+}
+
+/// Keeps the columns selected, in the order given.
+fn select_columns(records: Vec<Vec<String>>, columns: &[String]) -> Result<Vec<Vec<String>>> {
+    if columns.is_empty() {
+        return Ok(records);
+    }
+    let Some(header) = records.first() else {
+        return Ok(records);
+    };
+    let names: Vec<String> = header.iter().map(|h| column_name(h)).collect();
+    let mut indices = Vec::new();
+    for column in columns {
+        match names.iter().position(|name| *name == column_name(column)) {
+            Some(index) => indices.push(index),
+            None => bail!(
+                "Unknown column '{}' (columns: {})",
+                column,
+                names.join(", ")
+            ),
+        }
+    }
+    Ok(records
+        .into_iter()
+        .map(|record| indices.iter().map(|&i| record[i].clone()).collect())
+        .collect())
+}
+
+/// Quotes a CSV field if it contains a separator, a quote, or a line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
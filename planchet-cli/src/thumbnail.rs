@@ -0,0 +1,225 @@
+//! Inline thumbnails of the obverse and reverse of types, in the terminals
+//! which can show pictures: kitty, iTerm2 and WezTerm, and those supporting
+//! sixel graphics.
+//!
+//! Showing them needs the `thumbnails` feature, which decodes the pictures to
+//! place both sides next to each other and encode them for the terminal.
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use planchet::Client;
+use std::env;
+use url::Url;
+
+/// The graphics protocols of terminals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Protocol {
+    /// The protocol of the terminal, detected from its environment.
+    Auto,
+    /// The graphics protocol of kitty.
+    Kitty,
+    /// The inline images of iTerm2, also shown by WezTerm.
+    Iterm,
+    /// Sixel graphics, e.g. in foot, mlterm, or xterm with `-ti vt340`.
+    Sixel,
+}
+
+impl Protocol {
+    /// Returns the protocol to use, detecting the one of the terminal if
+    /// `Auto`.
+    pub fn resolve(self) -> Result<Protocol> {
+        if !cfg!(feature = "thumbnails") {
+            bail!("Thumbnails need planchet-cli to be built with the `thumbnails` feature");
+        }
+        if self != Protocol::Auto {
+            return Ok(self);
+        }
+        detect().context(
+            "The terminal does not seem to show pictures: give its protocol, e.g. --thumbnails sixel",
+        )
+    }
+}
+
+/// Detects the graphics protocol of the terminal from its environment
+/// variables. Terminals supporting sixel graphics are only known by name, as
+/// asking them would need to read their answer from the terminal.
+fn detect() -> Option<Protocol> {
+    let var = |name: &str| env::var(name).unwrap_or_default();
+    let term = var("TERM");
+    if term == "xterm-kitty" || env::var_os("KITTY_WINDOW_ID").is_some() {
+        Some(Protocol::Kitty)
+    } else if matches!(var("TERM_PROGRAM").as_str(), "iTerm.app" | "WezTerm") {
+        Some(Protocol::Iterm)
+    } else if term.starts_with("foot") || term.starts_with("mlterm") || term.contains("sixel") {
+        Some(Protocol::Sixel)
+    } else {
+        None
+    }
+}
+
+/// Prints the obverse and reverse thumbnails of a type next to each other,
+/// followed by a line break. Thumbnails which cannot be fetched are reported
+/// and left out.
+pub async fn print(
+    client: &Client,
+    thumbnails: [Option<&Url>; 2],
+    protocol: Protocol,
+) -> Result<()> {
+    let mut pictures = Vec::new();
+    for url in thumbnails.into_iter().flatten() {
+        match client.fetch_picture(url).await {
+            Ok(picture) => pictures.push(picture),
+            Err(e) => eprintln!("Failed to fetch the thumbnail {}: {}", url, e),
+        }
+    }
+    if pictures.is_empty() {
+        return Ok(());
+    }
+    println!("{}", render::encode(&pictures, protocol)?);
+    Ok(())
+}
+
+#[cfg(feature = "thumbnails")]
+mod render {
+    use super::Protocol;
+    use anyhow::Result;
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    use image::{imageops, ImageFormat, Rgba, RgbaImage};
+    use std::collections::BTreeMap;
+    use std::fmt::Write;
+    use std::io::Cursor;
+
+    /// The height of the thumbnails shown, in pixels.
+    const HEIGHT: u32 = 120;
+
+    /// The space between the thumbnails, in pixels.
+    const GAP: u32 = 8;
+
+    /// The size of the chunks of the pictures sent to kitty.
+    const KITTY_CHUNK: usize = 4096;
+
+    /// Encodes pictures, placed next to each other, for a terminal.
+    pub fn encode(pictures: &[Vec<u8>], protocol: Protocol) -> Result<String> {
+        let canvas = compose(pictures)?;
+        Ok(match protocol {
+            Protocol::Sixel => sixel(&canvas),
+            Protocol::Kitty => kitty(&png(&canvas)?),
+            Protocol::Auto | Protocol::Iterm => iterm(&png(&canvas)?),
+        })
+    }
+
+    /// Scales pictures to the same height, and places them next to each other.
+    fn compose(pictures: &[Vec<u8>]) -> Result<RgbaImage> {
+        let mut thumbnails = Vec::new();
+        for picture in pictures {
+            let picture = image::load_from_memory(picture)?;
+            thumbnails.push(picture.thumbnail(u32::MAX, HEIGHT).to_rgba8());
+        }
+        let width = thumbnails.iter().map(|t| t.width() + GAP).sum::<u32>() - GAP;
+        let height = thumbnails.iter().map(RgbaImage::height).max().unwrap_or(0);
+        let mut canvas = RgbaImage::new(width, height);
+        let mut x = 0;
+        for thumbnail in &thumbnails {
+            imageops::overlay(&mut canvas, thumbnail, x.into(), 0);
+            x += thumbnail.width() + GAP;
+        }
+        Ok(canvas)
+    }
+
+    fn png(canvas: &RgbaImage) -> Result<Vec<u8>> {
+        let mut png = Vec::new();
+        canvas.write_to(&mut Cursor::new(&mut png), ImageFormat::Png)?;
+        Ok(png)
+    }
+
+    /// Encodes a PNG picture as an inline image of iTerm2.
+    fn iterm(png: &[u8]) -> String {
+        format!(
+            "\x1b]1337;File=inline=1;size={};preserveAspectRatio=1:{}\x07",
+            png.len(),
+            STANDARD.encode(png)
+        )
+    }
+
+    /// Encodes a PNG picture for the graphics protocol of kitty, which takes
+    /// it in chunks.
+    fn kitty(png: &[u8]) -> String {
+        let data = STANDARD.encode(png);
+        let mut out = String::new();
+        let mut start = 0;
+        while start < data.len() {
+            let end = (start + KITTY_CHUNK).min(data.len());
+            let more = u8::from(end < data.len());
+            let chunk = &data[start..end];
+            if start == 0 {
+                let _ = write!(out, "\x1b_Ga=T,f=100,m={};{}\x1b\\", more, chunk);
+            } else {
+                let _ = write!(out, "\x1b_Gm={};{}\x1b\\", more, chunk);
+            }
+            start = end;
+        }
+        out
+    }
+
+    /// Returns the color of a pixel in a palette of 6 levels of each of red,
+    /// green, and blue, or `None` if it is transparent.
+    fn palette_index(pixel: &Rgba<u8>) -> Option<usize> {
+        if pixel[3] < 128 {
+            return None;
+        }
+        let level = |c: u8| (usize::from(c) * 5 + 127) / 255;
+        Some(level(pixel[0]) * 36 + level(pixel[1]) * 6 + level(pixel[2]))
+    }
+
+    /// Encodes a picture as sixel graphics, with 216 colors. Transparent
+    /// pixels are left as they are.
+    fn sixel(canvas: &RgbaImage) -> String {
+        let (width, height) = canvas.dimensions();
+        let mut out = format!("\x1bP0;1q\"1;1;{};{}", width, height);
+        for i in 0..216 {
+            let (r, g, b) = (i / 36, i / 6 % 6, i % 6);
+            let _ = write!(out, "#{};2;{};{};{}", i, r * 20, g * 20, b * 20);
+        }
+        // Each band of 6 rows is drawn color by color, a sixel per column
+        // with a bit per row
+        for top in (0..height).step_by(6) {
+            let mut bands: BTreeMap<usize, Vec<u8>> = BTreeMap::new();
+            for x in 0..width {
+                for row in 0..6.min(height - top) {
+                    if let Some(i) = palette_index(canvas.get_pixel(x, top + row)) {
+                        bands.entry(i).or_insert_with(|| vec![0; width as usize])[x as usize] |=
+                            1 << row;
+                    }
+                }
+            }
+            for (i, sixels) in bands {
+                let _ = write!(out, "#{}", i);
+                let mut x = 0;
+                while x < sixels.len() {
+                    let run = sixels[x..].iter().take_while(|&&s| s == sixels[x]).count();
+                    let c = char::from(63 + sixels[x]);
+                    if run > 3 {
+                        let _ = write!(out, "!{}{}", run, c);
+                    } else {
+                        out.extend(std::iter::repeat_n(c, run));
+                    }
+                    x += run;
+                }
+                out.push('$');
+            }
+            out.push('-');
+        }
+        out.push_str("\x1b\\");
+        out
+    }
+}
+
+#[cfg(not(feature = "thumbnails"))]
+mod render {
+    use super::Protocol;
+    use anyhow::{bail, Result};
+
+    pub fn encode(_pictures: &[Vec<u8>], _protocol: Protocol) -> Result<String> {
+        bail!("Thumbnails need planchet-cli to be built with the `thumbnails` feature")
+    }
+}
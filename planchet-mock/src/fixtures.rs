@@ -0,0 +1,70 @@
+//! Realistic response bodies for every endpoint of the API.
+//!
+//! The fixtures describe one user and a small corner of the catalogue: the
+//! type with ID [`TYPE_ID`], its issues, the mint with ID [`MINT_ID`], and
+//! the publication with ID [`PUBLICATION_ID`]. They can also be used on their
+//! own, e.g. as bodies of hand-written mocks.
+
+/// The ID of the type in [`TYPE`].
+pub const TYPE_ID: i64 = 99700;
+
+/// The ID of the issue whose prices are in [`PRICES`].
+pub const ISSUE_ID: i64 = 98766;
+
+/// The ID of the mint in [`MINT`].
+pub const MINT_ID: i64 = 11;
+
+/// The ID of the publication in [`PUBLICATION`].
+pub const PUBLICATION_ID: &str = "L106610";
+
+/// The ID of the user whose collection is in [`COLLECTED_ITEMS`].
+pub const USER_ID: i64 = 1;
+
+/// The ID of the collected item in [`COLLECTED_ITEM`].
+pub const COLLECTED_ITEM_ID: i64 = 1;
+
+/// The response of `GET /types/{id}`.
+pub const TYPE: &str = include_str!("../fixtures/type.json");
+
+/// The response of `GET /types/{id}/issues`.
+pub const ISSUES: &str = include_str!("../fixtures/issues.json");
+
+/// The response of `GET /types/{id}/issues/{issue_id}/prices`.
+pub const PRICES: &str = include_str!("../fixtures/prices.json");
+
+/// The response of `GET /types`.
+pub const SEARCH_TYPES: &str = include_str!("../fixtures/search_types.json");
+
+/// The response of `GET /issuers`.
+pub const ISSUERS: &str = include_str!("../fixtures/issuers.json");
+
+/// The response of `GET /mints`.
+pub const MINTS: &str = include_str!("../fixtures/mints.json");
+
+/// The response of `GET /mints/{id}`.
+pub const MINT: &str = include_str!("../fixtures/mint.json");
+
+/// The response of `GET /catalogues`.
+pub const CATALOGUES: &str = include_str!("../fixtures/catalogues.json");
+
+/// The response of `GET /publications/{id}`.
+pub const PUBLICATION: &str = include_str!("../fixtures/publication.json");
+
+/// The response of `GET /users/{id}`.
+pub const USER: &str = include_str!("../fixtures/user.json");
+
+/// The response of `GET /users/{id}/collections`.
+pub const COLLECTIONS: &str = include_str!("../fixtures/collections.json");
+
+/// The response of `GET /users/{id}/collected_items`.
+pub const COLLECTED_ITEMS: &str = include_str!("../fixtures/collected_items.json");
+
+/// The response of `GET /users/{id}/collected_items/{item_id}`, and of adding
+/// or editing a collected item.
+pub const COLLECTED_ITEM: &str = include_str!("../fixtures/collected_item.json");
+
+/// The response of `GET /oauth_token`.
+pub const OAUTH_TOKEN: &str = include_str!("../fixtures/oauth_token.json");
+
+/// The response of `POST /search_by_image`.
+pub const SEARCH_BY_IMAGE: &str = include_str!("../fixtures/search_by_image.json");
@@ -0,0 +1,194 @@
+//! A fake Numista API server for testing applications built on `planchet`.
+//!
+//! [`MockNumista`] starts a local [`mockito`] server which answers every
+//! endpoint of the API with realistic responses, taken from the [`fixtures`].
+//! Applications can then test their use of `planchet` without writing JSON
+//! bodies by hand.
+//!
+//! The server knows a single user, whose collection holds a few items, and a
+//! small corner of the catalogue built around one type. Requests for other
+//! IDs get a `404 Not Found` error, like the real API. Responses can be
+//! overridden by adding mocks to [`MockNumista::server`], as mocks added later
+//! take precedence.
+//!
+//! # Examples
+//!
+//! ```
+//! use planchet::models::GetCollectedItemsParams;
+//! use planchet_mock::{fixtures, MockNumista};
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let numista = MockNumista::start().await;
+//! let client = numista.client();
+//!
+//! let t = client.get_type(fixtures::TYPE_ID).await.unwrap();
+//! assert_eq!(t.issuer.unwrap().code, "etats-unis");
+//!
+//! let items = client
+//!     .get_collected_items(fixtures::USER_ID, &GetCollectedItemsParams::new())
+//!     .await
+//!     .unwrap();
+//! assert_eq!(items.items.len(), 2);
+//! # }
+//! ```
+use mockito::{Matcher, Server, ServerGuard};
+use planchet::{Client, ClientBuilder};
+
+pub mod fixtures;
+pub mod schema;
+
+/// The API key of the clients returned by [`MockNumista::client`].
+pub const API_KEY: &str = "mock_api_key";
+
+/// A fake Numista API server, stopped when dropped.
+pub struct MockNumista {
+    server: ServerGuard,
+}
+
+impl MockNumista {
+    /// Starts a server answering every endpoint with the [`fixtures`].
+    pub async fn start() -> Self {
+        let mut server = Server::new_async().await;
+        let user = fixtures::USER_ID;
+        let item = fixtures::COLLECTED_ITEM_ID;
+        let type_id = fixtures::TYPE_ID;
+
+        // Mocks are matched last first, so these only answer requests which
+        // match no other mock.
+        for method in ["GET", "POST", "PATCH", "DELETE"] {
+            server
+                .mock(method, Matcher::Any)
+                .match_query(Matcher::Any)
+                .with_status(404)
+                .with_header("content-type", "application/json")
+                .with_body(r#"{"error_message": "Not found"}"#)
+                .expect_at_least(0)
+                .create_async()
+                .await;
+        }
+
+        let routes = [
+            ("GET", format!("/types/{}", type_id), 200, fixtures::TYPE),
+            (
+                "GET",
+                format!("/types/{}/issues", type_id),
+                200,
+                fixtures::ISSUES,
+            ),
+            (
+                "GET",
+                format!("/types/{}/issues/{}/prices", type_id, fixtures::ISSUE_ID),
+                200,
+                fixtures::PRICES,
+            ),
+            ("GET", "/types".to_string(), 200, fixtures::SEARCH_TYPES),
+            ("GET", "/issuers".to_string(), 200, fixtures::ISSUERS),
+            ("GET", "/mints".to_string(), 200, fixtures::MINTS),
+            (
+                "GET",
+                format!("/mints/{}", fixtures::MINT_ID),
+                200,
+                fixtures::MINT,
+            ),
+            ("GET", "/catalogues".to_string(), 200, fixtures::CATALOGUES),
+            (
+                "GET",
+                format!("/publications/{}", fixtures::PUBLICATION_ID),
+                200,
+                fixtures::PUBLICATION,
+            ),
+            ("GET", format!("/users/{}", user), 200, fixtures::USER),
+            (
+                "GET",
+                format!("/users/{}/collections", user),
+                200,
+                fixtures::COLLECTIONS,
+            ),
+            (
+                "GET",
+                format!("/users/{}/collected_items", user),
+                200,
+                fixtures::COLLECTED_ITEMS,
+            ),
+            (
+                "POST",
+                format!("/users/{}/collected_items", user),
+                201,
+                fixtures::COLLECTED_ITEM,
+            ),
+            (
+                "GET",
+                format!("/users/{}/collected_items/{}", user, item),
+                200,
+                fixtures::COLLECTED_ITEM,
+            ),
+            (
+                "PATCH",
+                format!("/users/{}/collected_items/{}", user, item),
+                200,
+                fixtures::COLLECTED_ITEM,
+            ),
+            (
+                "GET",
+                "/oauth_token".to_string(),
+                200,
+                fixtures::OAUTH_TOKEN,
+            ),
+            (
+                "POST",
+                "/search_by_image".to_string(),
+                200,
+                fixtures::SEARCH_BY_IMAGE,
+            ),
+        ];
+        for (method, path, status, body) in routes {
+            server
+                .mock(method, path.as_str())
+                .match_query(Matcher::Any)
+                .with_status(status)
+                .with_header("content-type", "application/json")
+                .with_body(body)
+                .expect_at_least(0)
+                .create_async()
+                .await;
+        }
+        server
+            .mock(
+                "DELETE",
+                format!("/users/{}/collected_items/{}", user, item).as_str(),
+            )
+            .with_status(204)
+            .expect_at_least(0)
+            .create_async()
+            .await;
+
+        Self { server }
+    }
+
+    /// Returns the base URL of the server.
+    pub fn url(&self) -> String {
+        self.server.url()
+    }
+
+    /// Returns a client builder configured to use the server, with the
+    /// [`API_KEY`] and a bearer token.
+    pub fn client_builder(&self) -> ClientBuilder {
+        ClientBuilder::new()
+            .api_key(API_KEY)
+            .bearer_token("mock_access_token")
+            .base_url(self.url())
+    }
+
+    /// Returns a client using the server.
+    pub fn client(&self) -> Client {
+        self.client_builder()
+            .build()
+            .expect("the mock client configuration is valid")
+    }
+
+    /// Returns the underlying server, to add or override mocks.
+    pub fn server(&mut self) -> &mut ServerGuard {
+        &mut self.server
+    }
+}
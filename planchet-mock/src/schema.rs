@@ -0,0 +1,231 @@
+//! The shapes of response bodies, to detect changes to the schema of the API.
+//!
+//! [`shape`] replaces every value of a body with the name of its type, and
+//! merges the elements of arrays, so that bodies of the same schema have the
+//! same shape whatever their values. [`diff`] lists the fields added, removed,
+//! or changed between two shapes.
+//!
+//! The `schema_watch` test fetches the objects of the [`fixtures`] from the
+//! live API and compares their shapes with those of the fixtures, failing with
+//! the differences when Numista adds or removes fields. It needs an API key,
+//! and only runs when asked to:
+//!
+//! ```bash
+//! PLANCHET_SCHEMA_WATCH=1 NUMISTA_API_KEY=... cargo test -p planchet-mock --test schema_watch
+//! ```
+//!
+//! # Examples
+//!
+//! ```
+//! use planchet_mock::schema::{diff, shape, SchemaChange};
+//! use serde_json::json;
+//!
+//! let snapshot = shape(&json!({ "id": 1, "tags": ["a"] }));
+//! let live = shape(&json!({ "id": 2, "tags": [], "url": "https://..." }));
+//! assert_eq!(diff(&snapshot, &live), [SchemaChange::Added("url".to_string())]);
+//! ```
+//!
+//! [`fixtures`]: crate::fixtures
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// A difference between two shapes, found by [`diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaChange {
+    /// A field is only in the new shape, at this path.
+    Added(String),
+    /// A field is only in the old shape, at this path.
+    Removed(String),
+    /// The type of a field changed.
+    Changed {
+        path: String,
+        from: String,
+        to: String,
+    },
+}
+
+impl fmt::Display for SchemaChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SchemaChange::Added(path) => write!(f, "added: {}", path),
+            SchemaChange::Removed(path) => write!(f, "removed: {}", path),
+            SchemaChange::Changed { path, from, to } => {
+                write!(f, "changed: {} from {} to {}", path, from, to)
+            }
+        }
+    }
+}
+
+/// Returns the shape of a body: its objects with the shapes of their fields,
+/// its arrays with the merged shape of their elements, if any, and its other
+/// values replaced with the names of their types, e.g. `"string"`.
+pub fn shape(value: &Value) -> Value {
+    match value {
+        Value::Null => Value::from("null"),
+        Value::Bool(_) => Value::from("boolean"),
+        Value::Number(_) => Value::from("number"),
+        Value::String(_) => Value::from("string"),
+        Value::Array(elements) => {
+            let merged = elements.iter().map(shape).reduce(|a, b| merge(&a, &b));
+            Value::Array(merged.into_iter().collect())
+        }
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(name, value)| (name.clone(), shape(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// Merges two shapes: the fields of both objects, or the names of both types,
+/// e.g. `"null|string"` for a field which is sometimes `null`.
+fn merge(a: &Value, b: &Value) -> Value {
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => {
+            let mut merged = a.clone();
+            for (name, shape) in b {
+                let field = match a.get(name) {
+                    Some(existing) => merge(existing, shape),
+                    None => shape.clone(),
+                };
+                merged.insert(name.clone(), field);
+            }
+            Value::Object(merged)
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            let merged = a.iter().chain(b).cloned().reduce(|a, b| merge(&a, &b));
+            Value::Array(merged.into_iter().collect())
+        }
+        _ => {
+            let names: BTreeSet<String> = [a, b].into_iter().flat_map(type_names).collect();
+            Value::from(names.into_iter().collect::<Vec<_>>().join("|"))
+        }
+    }
+}
+
+/// Returns the names of the types of a shape.
+fn type_names(shape: &Value) -> Vec<String> {
+    match shape {
+        Value::String(names) => names.split('|').map(str::to_string).collect(),
+        Value::Array(_) => vec!["array".to_string()],
+        Value::Object(_) => vec!["object".to_string()],
+        _ => Vec::new(),
+    }
+}
+
+/// Lists the differences between an old and a new shape, ordered by path.
+///
+/// `null` is compatible with any type, since the API returns it for most
+/// optional fields, and an empty array with any array.
+pub fn diff(old: &Value, new: &Value) -> Vec<SchemaChange> {
+    let mut changes = Vec::new();
+    diff_at("", old, new, &mut changes);
+    changes
+}
+
+fn diff_at(path: &str, old: &Value, new: &Value, changes: &mut Vec<SchemaChange>) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => diff_fields(path, old, new, changes),
+        (Value::Array(old), Value::Array(new)) => {
+            if let (Some(old), Some(new)) = (old.first(), new.first()) {
+                diff_at(&format!("{}[]", path), old, new, changes);
+            }
+        }
+        _ => {
+            let non_null = |shape: &Value| -> BTreeSet<String> {
+                type_names(shape)
+                    .into_iter()
+                    .filter(|name| name != "null")
+                    .collect()
+            };
+            let (from, to) = (non_null(old), non_null(new));
+            if !from.is_empty() && !to.is_empty() && from != to {
+                changes.push(SchemaChange::Changed {
+                    path: path.to_string(),
+                    from: from.into_iter().collect::<Vec<_>>().join("|"),
+                    to: to.into_iter().collect::<Vec<_>>().join("|"),
+                });
+            }
+        }
+    }
+}
+
+fn diff_fields(
+    path: &str,
+    old: &Map<String, Value>,
+    new: &Map<String, Value>,
+    changes: &mut Vec<SchemaChange>,
+) {
+    let names: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    for name in names {
+        let field = match path {
+            "" => name.clone(),
+            _ => format!("{}.{}", path, name),
+        };
+        match (old.get(name), new.get(name)) {
+            (Some(old), Some(new)) => diff_at(&field, old, new, changes),
+            (Some(_), None) => changes.push(SchemaChange::Removed(field)),
+            (None, Some(_)) => changes.push(SchemaChange::Added(field)),
+            (None, None) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_shape_and_diff() {
+        let old = shape(&json!({
+            "id": 1,
+            "title": "5 Cents",
+            "ruler": [{ "id": 1, "name": "Victoria" }, { "id": 2, "wikidata_id": null }],
+            "comments": null,
+            "weight": 1.2,
+        }));
+        assert_eq!(
+            old,
+            json!({
+                "id": "number",
+                "title": "string",
+                "ruler": [{ "id": "number", "name": "string", "wikidata_id": "null" }],
+                "comments": "null",
+                "weight": "number",
+            })
+        );
+        // The values do not matter, nor do nulls.
+        let same = shape(&json!({
+            "id": 2,
+            "title": "1 Cent",
+            "ruler": [{ "id": 3, "name": "Edward VII", "wikidata_id": "Q20875" }],
+            "comments": "<p>Rare</p>",
+            "weight": null,
+        }));
+        assert!(diff(&old, &same).is_empty());
+
+        let new = shape(&json!({
+            "id": "1",
+            "title": "5 Cents",
+            "ruler": [{ "id": 1, "name": "Victoria", "period": "1837-1901" }],
+            "comments": null,
+            "size": 15.5,
+        }));
+        assert_eq!(
+            diff(&old, &new)
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            [
+                "changed: id from number to string",
+                "added: ruler[].period",
+                "removed: ruler[].wikidata_id",
+                "added: size",
+                "removed: weight",
+            ]
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use planchet::models::{
+    AddCollectedItemParams, EditCollectedItemParams, GetCollectedItemsParams, GrantType,
+    OAuthTokenParams, SearchByImageParams, SearchTypesParams,
+};
+use planchet::Error;
+use planchet_mock::{fixtures, MockNumista};
+
+#[tokio::test]
+async fn every_endpoint_test() {
+    let numista = MockNumista::start().await;
+    let client = numista.client();
+    let user = fixtures::USER_ID;
+
+    let t = client.get_type(fixtures::TYPE_ID).await.unwrap();
+    assert_eq!(t.id, fixtures::TYPE_ID);
+    let issues = client.get_issues(fixtures::TYPE_ID).await.unwrap();
+    assert!(issues.iter().any(|i| i.id == fixtures::ISSUE_ID));
+    let prices = client
+        .get_prices(fixtures::TYPE_ID, fixtures::ISSUE_ID, Some("USD"))
+        .await
+        .unwrap();
+    assert!(!prices.prices.is_empty());
+    let results = client
+        .search_types(&SearchTypesParams::new().q("quarter"))
+        .await
+        .unwrap();
+    assert_eq!(results.types[0].id, fixtures::TYPE_ID);
+
+    assert!(!client.get_issuers().await.unwrap().issuers.is_empty());
+    assert!(!client.get_mints().await.unwrap().mints.is_empty());
+    let mint = client.get_mint(fixtures::MINT_ID).await.unwrap();
+    assert_eq!(mint.id, fixtures::MINT_ID);
+    assert!(!client.get_catalogues().await.unwrap().catalogues.is_empty());
+    let publication = client
+        .get_publication(fixtures::PUBLICATION_ID)
+        .await
+        .unwrap();
+    assert_eq!(publication.id, fixtures::PUBLICATION_ID);
+
+    assert_eq!(client.get_user(user).await.unwrap().username, "numismatist");
+    assert!(!client
+        .get_user_collections(user)
+        .await
+        .unwrap()
+        .collections
+        .is_empty());
+    let items = client
+        .get_collected_items(user, &GetCollectedItemsParams::new())
+        .await
+        .unwrap();
+    assert_eq!(items.items[0].id, fixtures::COLLECTED_ITEM_ID);
+    client
+        .get_collected_item(user, fixtures::COLLECTED_ITEM_ID)
+        .await
+        .unwrap();
+    client
+        .add_collected_item(user, &AddCollectedItemParams::new(fixtures::TYPE_ID))
+        .await
+        .unwrap();
+    client
+        .edit_collected_item(
+            user,
+            fixtures::COLLECTED_ITEM_ID,
+            &EditCollectedItemParams::new().quantity(3),
+        )
+        .await
+        .unwrap();
+    client
+        .delete_collected_item(user, fixtures::COLLECTED_ITEM_ID)
+        .await
+        .unwrap();
+
+    let token = client
+        .get_oauth_token(&OAuthTokenParams::new(GrantType::ClientCredentials))
+        .await
+        .unwrap();
+    assert_eq!(token.user_id, user);
+    let matches = client
+        .search_by_image(&SearchByImageParams {
+            category: None,
+            images: vec![planchet::models::request::Image {
+                mime_type: planchet::models::request::MimeType::Jpeg,
+                image_data: "aW1hZ2U=".to_string(),
+            }],
+            max_results: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(matches.types[0].id, fixtures::TYPE_ID);
+}
+
+#[tokio::test]
+async fn unknown_ids_and_overrides_test() {
+    let mut numista = MockNumista::start().await;
+    let client = numista.client();
+
+    assert!(matches!(
+        client.get_type(1).await,
+        Err(Error::ApiError(e)) if e.is_not_found()
+    ));
+
+    numista
+        .server()
+        .mock("GET", "/users/1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"username": "someone_else"}"#)
+        .create_async()
+        .await;
+    let user = client.get_user(fixtures::USER_ID).await.unwrap();
+    assert_eq!(user.username, "someone_else");
+}
+
+/// Deserializes a fixture into a model, and checks that serializing the model
+/// gives a body which deserializes into the same model.
+fn assert_round_trip<T: serde::de::DeserializeOwned + serde::Serialize>(fixture: &str) {
+    let model: T = serde_json::from_str(fixture).unwrap();
+    let serialized = serde_json::to_value(&model).unwrap();
+    let again: T = serde_json::from_value(serialized.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&again).unwrap(), serialized);
+}
+
+#[test]
+fn serialize_round_trip_test() {
+    use planchet::models::*;
+
+    assert_round_trip::<NumistaType>(fixtures::TYPE);
+    assert_round_trip::<Vec<Issue>>(fixtures::ISSUES);
+    assert_round_trip::<GradePrices>(fixtures::PRICES);
+    assert_round_trip::<SearchTypesResponse>(fixtures::SEARCH_TYPES);
+    assert_round_trip::<IssuersResponse>(fixtures::ISSUERS);
+    assert_round_trip::<MintsResponse>(fixtures::MINTS);
+    assert_round_trip::<MintDetail>(fixtures::MINT);
+    assert_round_trip::<CataloguesResponse>(fixtures::CATALOGUES);
+    assert_round_trip::<Publication>(fixtures::PUBLICATION);
+    assert_round_trip::<User>(fixtures::USER);
+    assert_round_trip::<CollectionsResponse>(fixtures::COLLECTIONS);
+    assert_round_trip::<CollectedItems>(fixtures::COLLECTED_ITEMS);
+    assert_round_trip::<CollectedItem>(fixtures::COLLECTED_ITEM);
+    assert_round_trip::<OAuthToken>(fixtures::OAUTH_TOKEN);
+    assert_round_trip::<SearchByImageResponse>(fixtures::SEARCH_BY_IMAGE);
+
+    // Fields are serialized under the names used by the API.
+    let t: NumistaType = serde_json::from_str(fixtures::TYPE).unwrap();
+    let serialized = serde_json::to_value(&t).unwrap();
+    assert_eq!(serialized["id"], fixtures::TYPE_ID);
+    assert_eq!(serialized["title"], t.title.as_str());
+}
@@ -0,0 +1,85 @@
+//! Compares the shapes of live responses with those of the fixtures, to alert
+//! maintainers when Numista adds or removes fields.
+//!
+//! This only runs with `PLANCHET_SCHEMA_WATCH=1`, and needs an API key in
+//! `NUMISTA_API_KEY`; see the `schema` module.
+use planchet::client::DEFAULT_API_KEY_HEADER;
+use planchet::ApiVersion;
+use planchet_mock::fixtures;
+use planchet_mock::schema::{diff, shape};
+use serde_json::Value;
+use std::env;
+
+/// The objects compared: a name, the path of the request, and the fixture.
+fn curated_objects() -> Vec<(&'static str, String, &'static str)> {
+    let type_id = fixtures::TYPE_ID;
+    vec![
+        ("type", format!("/types/{}", type_id), fixtures::TYPE),
+        (
+            "issues",
+            format!("/types/{}/issues", type_id),
+            fixtures::ISSUES,
+        ),
+        (
+            "prices",
+            format!("/types/{}/issues/{}/prices", type_id, fixtures::ISSUE_ID),
+            fixtures::PRICES,
+        ),
+        (
+            "search_types",
+            "/types?q=quarter".to_string(),
+            fixtures::SEARCH_TYPES,
+        ),
+        ("issuers", "/issuers".to_string(), fixtures::ISSUERS),
+        ("mints", "/mints".to_string(), fixtures::MINTS),
+        (
+            "mint",
+            format!("/mints/{}", fixtures::MINT_ID),
+            fixtures::MINT,
+        ),
+        (
+            "catalogues",
+            "/catalogues".to_string(),
+            fixtures::CATALOGUES,
+        ),
+        (
+            "publication",
+            format!("/publications/{}", fixtures::PUBLICATION_ID),
+            fixtures::PUBLICATION,
+        ),
+    ]
+}
+
+#[tokio::test]
+async fn schema_watch_test() {
+    if env::var("PLANCHET_SCHEMA_WATCH").as_deref() != Ok("1") {
+        eprintln!("Skipped: set PLANCHET_SCHEMA_WATCH=1 to compare with the live API");
+        return;
+    }
+    let api_key = env::var("NUMISTA_API_KEY").expect("NUMISTA_API_KEY is needed");
+    let base_url = ApiVersion::default().base_url();
+    let client = reqwest::Client::new();
+
+    let mut report = Vec::new();
+    for (name, path, fixture) in curated_objects() {
+        let live: Value = client
+            .get(format!("{}{}", base_url, path))
+            .header(DEFAULT_API_KEY_HEADER, &api_key)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .unwrap_or_else(|e| panic!("Failed to fetch {}: {}", path, e))
+            .json()
+            .await
+            .unwrap();
+        let fixture: Value = serde_json::from_str(fixture).unwrap();
+        for change in diff(&shape(&fixture), &shape(&live)) {
+            report.push(format!("{}: {}", name, change));
+        }
+    }
+    assert!(
+        report.is_empty(),
+        "The schema of the API changed; update the fixtures and the models:\n{}",
+        report.join("\n")
+    );
+}
@@ -0,0 +1,221 @@
+//! Recording API responses as named fixtures, and replaying them offline.
+//!
+//! A fixture is the raw body of a response, saved as `<name>.json` in the
+//! fixtures directory. The name starts with the client method which makes the
+//! request, so replaying knows which model to deserialize it into. The
+//! responses are recorded by the client itself, through a cassette of the
+//! `vcr` feature of planchet, which is saved in the directory too.
+use anyhow::{bail, Context, Result};
+use planchet::models::{
+    CataloguesResponse, CollectedItem, CollectedItems, CollectionsResponse, GradePrices, Issue,
+    IssuersResponse, MintDetail, MintsResponse, NumistaType, Publication, SearchTypesResponse,
+    User,
+};
+use planchet::vcr::{Cassette, Mode};
+use planchet::ClientBuilder;
+use serde::de::DeserializeOwned;
+use std::fmt::Debug;
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+/// The endpoints whose responses are checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endpoint {
+    GetIssuers,
+    GetMints,
+    GetMint,
+    GetCatalogues,
+    GetUserCollections,
+    GetCollectedItems,
+    GetCollectedItem,
+    GetType,
+    GetIssues,
+    GetPrices,
+    GetUser,
+    GetPublication,
+    SearchTypes,
+}
+
+impl Endpoint {
+    const ALL: &'static [Endpoint] = &[
+        Endpoint::GetIssuers,
+        Endpoint::GetMints,
+        Endpoint::GetMint,
+        Endpoint::GetCatalogues,
+        Endpoint::GetUserCollections,
+        Endpoint::GetCollectedItems,
+        Endpoint::GetCollectedItem,
+        Endpoint::GetType,
+        Endpoint::GetIssues,
+        Endpoint::GetPrices,
+        Endpoint::GetUser,
+        Endpoint::GetPublication,
+        Endpoint::SearchTypes,
+    ];
+
+    /// The name of the client method which makes the request.
+    pub fn name(self) -> &'static str {
+        match self {
+            Endpoint::GetIssuers => "get_issuers",
+            Endpoint::GetMints => "get_mints",
+            Endpoint::GetMint => "get_mint",
+            Endpoint::GetCatalogues => "get_catalogues",
+            Endpoint::GetUserCollections => "get_user_collections",
+            Endpoint::GetCollectedItems => "get_collected_items",
+            Endpoint::GetCollectedItem => "get_collected_item",
+            Endpoint::GetType => "get_type",
+            Endpoint::GetIssues => "get_issues",
+            Endpoint::GetPrices => "get_prices",
+            Endpoint::GetUser => "get_user",
+            Endpoint::GetPublication => "get_publication",
+            Endpoint::SearchTypes => "search_types",
+        }
+    }
+
+    /// Finds the endpoint of a fixture from its name, e.g. `get_mint-12`.
+    fn from_fixture_name(name: &str) -> Option<Endpoint> {
+        let method = name.split('-').next()?;
+        Endpoint::ALL.iter().copied().find(|e| e.name() == method)
+    }
+
+    /// Deserializes a response body into the model of the endpoint.
+    fn check(self, body: &[u8]) -> Result<()> {
+        match self {
+            Endpoint::GetIssuers => check_as::<IssuersResponse>(body),
+            Endpoint::GetMints => check_as::<MintsResponse>(body),
+            Endpoint::GetMint => check_as::<MintDetail>(body),
+            Endpoint::GetCatalogues => check_as::<CataloguesResponse>(body),
+            Endpoint::GetUserCollections => check_as::<CollectionsResponse>(body),
+            Endpoint::GetCollectedItems => check_as::<CollectedItems>(body),
+            Endpoint::GetCollectedItem => check_as::<CollectedItem>(body),
+            Endpoint::GetType => check_as::<NumistaType>(body),
+            Endpoint::GetIssues => check_as::<Vec<Issue>>(body),
+            Endpoint::GetPrices => check_as::<GradePrices>(body),
+            Endpoint::GetUser => check_as::<User>(body),
+            Endpoint::GetPublication => check_as::<Publication>(body),
+            Endpoint::SearchTypes => check_as::<SearchTypesResponse>(body),
+        }
+    }
+}
+
+fn check_as<T: DeserializeOwned>(body: &[u8]) -> Result<()> {
+    serde_json::from_slice::<T>(body)?;
+    Ok(())
+}
+
+/// The cassette the responses are recorded to, in the fixtures directory.
+///
+/// It is a YAML file, so that replaying the fixtures skips it, and can be
+/// replayed with [`planchet::vcr`] itself.
+const CASSETTE: &str = "cassette.yaml";
+
+/// Records the responses of a client as fixtures.
+///
+/// The requests are made by [`planchet::Client`], so the fixtures are the
+/// responses to the requests of the library under test, with its routes,
+/// authentication, and language, and with the credentials redacted by its
+/// cassettes.
+pub struct Recorder {
+    record_dir: Option<PathBuf>,
+    cassette: Option<Cassette>,
+}
+
+impl Recorder {
+    /// Creates a recorder saving fixtures in `record_dir`, if given.
+    pub fn new(record_dir: Option<PathBuf>) -> Result<Self> {
+        let cassette = match &record_dir {
+            Some(dir) => {
+                fs::create_dir_all(dir)
+                    .with_context(|| format!("Failed to create {}", dir.display()))?;
+                Some(Cassette::open(dir.join(CASSETTE), Mode::Record)?)
+            }
+            None => None,
+        };
+        Ok(Self {
+            record_dir,
+            cassette,
+        })
+    }
+
+    /// Sets the cassette recording the responses on a client builder.
+    pub fn client_builder(&self, builder: ClientBuilder) -> ClientBuilder {
+        match &self.cassette {
+            Some(cassette) => builder.cassette(cassette.clone()),
+            None => builder,
+        }
+    }
+
+    /// Awaits a request of a client built with [`Recorder::client_builder`],
+    /// saving its response as a fixture.
+    ///
+    /// The response is saved even if it does not deserialize, so that it can
+    /// be replayed once the models are fixed.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - The endpoint requested.
+    /// * `name` - The name of the fixture, which starts with the name of the
+    ///   endpoint.
+    /// * `request` - The request, e.g. `client.get_type(420)`.
+    pub async fn get<T, F>(&self, endpoint: Endpoint, name: &str, request: F) -> Result<T>
+    where
+        T: Debug,
+        F: Future<Output = planchet::Result<T>>,
+    {
+        println!("Calling {}()", endpoint.name());
+        let result = request.await;
+        if let (Some(dir), Some(cassette)) = (&self.record_dir, &self.cassette) {
+            if let Some(interaction) = cassette.interactions().pop() {
+                let file = dir.join(format!("{}.json", name));
+                fs::write(&file, interaction.response.body)
+                    .with_context(|| format!("Failed to write {}", file.display()))?;
+            }
+        }
+        let value = result.with_context(|| format!("Failed to get {}", name))?;
+        println!("{:#?}", value);
+        Ok(value)
+    }
+}
+
+/// Deserializes every fixture in a directory, reporting those which fail.
+///
+/// Returns an error if any fixture fails, or if there are none.
+pub fn replay(dir: &Path) -> Result<()> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::io::Result<_>>()?;
+    files.retain(|f| f.extension().is_some_and(|ext| ext == "json"));
+    files.sort();
+    if files.is_empty() {
+        bail!("No fixtures found in {}", dir.display());
+    }
+
+    let mut failures = 0;
+    for file in &files {
+        let name = file
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default();
+        let result = match Endpoint::from_fixture_name(name) {
+            Some(endpoint) => fs::read(file)
+                .map_err(anyhow::Error::from)
+                .and_then(|body| endpoint.check(&body)),
+            None => Err(anyhow::anyhow!("unknown endpoint")),
+        };
+        match result {
+            Ok(()) => println!("ok      {}", name),
+            Err(e) => {
+                failures += 1;
+                println!("FAILED  {}: {:#}", name, e);
+            }
+        }
+    }
+
+    if failures > 0 {
+        bail!("{} of {} fixtures failed", failures, files.len());
+    }
+    println!("All {} fixtures passed", files.len());
+    Ok(())
+}
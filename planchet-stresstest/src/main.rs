@@ -1,23 +1,68 @@
+mod fixtures;
+mod mutate;
+
 use anyhow::Result;
 use clap::Parser;
+use fixtures::{Endpoint, Recorder};
 use planchet::{
-    model::{GrantType, OAuthTokenParams, SearchTypesParams, GetCollectedItemsParams},
+    models::{
+        CataloguesResponse, CollectedItem, CollectedItems, CollectionsResponse,
+        GetCollectedItemsParams, GradePrices, GrantType, Issue, IssuersResponse, MintDetail,
+        MintsResponse, NumistaType, OAuthTokenParams, Publication, SearchTypesParams,
+        SearchTypesResponse, User,
+    },
     ClientBuilder,
 };
+use std::path::PathBuf;
 use tracing::Level;
 use tracing_subscriber::FmtSubscriber;
 
-/// A manual tool for verifying the deserialization of all read-only API responses.
+/// A manual tool for verifying the deserialization of all read-only API responses,
+/// and optionally the serialization of write requests.
+///
+/// With the `strict` feature, the library is built with the `strict-models`
+/// feature, so any field returned by the API that the models do not cover is
+/// reported as an error:
+///
+/// ```bash
+/// cargo run -p planchet-stresstest --features strict -- --user-id ...
+/// ```
+///
+/// With `--record`, every response is saved as a named fixture, building a
+/// corpus of real responses. With `--replay`, the fixtures are deserialized
+/// again offline, e.g. to check that changes to the models still accept them.
+/// Fixtures contain the data of the account used, including its collection.
+///
+/// With `--mutate`, the write endpoints are exercised too: collected items
+/// are added to the collection of the user, edited, and deleted, and every
+/// field sent is compared with the one returned. Use a disposable account, or
+/// at least a disposable collection with `--collection`.
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
     /// The user ID to use for authentication.
-    #[arg(long)]
-    user_id: i64,
+    #[arg(long, required_unless_present = "replay")]
+    user_id: Option<i64>,
 
     /// The Numista API key.
-    #[arg(long, env = "NUMISTA_API_KEY")]
-    api_key: String,
+    #[arg(long, env = "NUMISTA_API_KEY", required_unless_present = "replay")]
+    api_key: Option<String>,
+
+    /// Save every response as a fixture in this directory.
+    #[arg(long, value_name = "DIR", conflicts_with = "replay")]
+    record: Option<PathBuf>,
+
+    /// Deserialize the fixtures in this directory instead of calling the API.
+    #[arg(long, value_name = "DIR")]
+    replay: Option<PathBuf>,
+
+    /// Also add, edit, and delete collected items in the user's collection.
+    #[arg(long, conflicts_with = "replay")]
+    mutate: bool,
+
+    /// The ID of the collection to add items to with `--mutate`.
+    #[arg(long, requires = "mutate")]
+    collection: Option<i64>,
 
     /// Enable debug logging.
     #[arg(long)]
@@ -39,76 +84,139 @@ async fn main() -> Result<()> {
     tracing::subscriber::set_global_default(subscriber)
         .expect("Failed to set tracing subscriber");
 
+    if let Some(dir) = &cli.replay {
+        return fixtures::replay(dir);
+    }
+    // Both are required unless replaying.
+    let (Some(user_id), Some(api_key)) = (cli.user_id, cli.api_key) else {
+        unreachable!("clap requires --user-id and --api-key");
+    };
+
     println!("Calling get_oauth_token()");
-    let pre_auth_client = ClientBuilder::new().api_key(&cli.api_key).build()?;
-    let params = OAuthTokenParams::new(GrantType::ClientCredentials)
-        .scope("view_collection");
+    let pre_auth_client = ClientBuilder::new().api_key(&api_key).build()?;
+    let scope = if cli.mutate {
+        "view_collection,edit_collection"
+    } else {
+        "view_collection"
+    };
+    let params = OAuthTokenParams::new(GrantType::ClientCredentials).scope(scope);
     let token = pre_auth_client.get_oauth_token(&params).await?;
 
-    let client = ClientBuilder::new()
-        .api_key(&cli.api_key)
-        .bearer_token(&token.access_token)
-        .build()?;
-
     println!("Successfully authenticated!");
 
-    println!("Calling get_issuers()");
-    let issuers = client.get_issuers().await?;
-    println!("{:#?}", issuers);
+    let recorder = Recorder::new(cli.record)?;
+    let client = recorder
+        .client_builder(
+            ClientBuilder::new()
+                .api_key(&api_key)
+                .bearer_token(&token.access_token),
+        )
+        .build()?;
 
-    println!("Calling get_mints()");
-    let mints = client.get_mints().await?;
-    println!("{:#?}", mints);
+    let _: IssuersResponse = recorder
+        .get(Endpoint::GetIssuers, "get_issuers", client.get_issuers())
+        .await?;
+
+    let mints: MintsResponse = recorder
+        .get(Endpoint::GetMints, "get_mints", client.get_mints())
+        .await?;
     if let Some(mint) = mints.mints.first() {
-        println!("Calling get_mint()");
-        println!("{:#?}", client.get_mint(mint.id).await?);
+        let _: MintDetail = recorder
+            .get(
+                Endpoint::GetMint,
+                &format!("get_mint-{}", mint.id),
+                client.get_mint(mint.id),
+            )
+            .await?;
     }
 
-    println!("Calling get_catalogues()");
-    let catalogues = client.get_catalogues().await?;
-    println!("{:#?}", catalogues);
+    let _: CataloguesResponse = recorder
+        .get(
+            Endpoint::GetCatalogues,
+            "get_catalogues",
+            client.get_catalogues(),
+        )
+        .await?;
 
-    println!("Calling get_user_collections()");
-    let user_collections = client.get_user_collections(cli.user_id).await?;
-    println!("{:#?}", user_collections);
+    let _: CollectionsResponse = recorder
+        .get(
+            Endpoint::GetUserCollections,
+            "get_user_collections",
+            client.get_user_collections(user_id),
+        )
+        .await?;
 
-    println!("Calling get_collected_items()");
-    let collected_items = client
-        .get_collected_items(cli.user_id, &GetCollectedItemsParams::new())
+    let collected_items: CollectedItems = recorder
+        .get(
+            Endpoint::GetCollectedItems,
+            "get_collected_items",
+            client.get_collected_items(user_id, &GetCollectedItemsParams::new()),
+        )
         .await?;
-    println!("{:#?}", collected_items);
     if let Some(item) = collected_items.items.first() {
-        println!("Calling get_collected_item()");
-        println!(
-            "{:#?}",
-            client.get_collected_item(cli.user_id, item.id).await?
-        );
-        println!("Calling get_type()");
-        let r#type = client.get_type(item.type_info.id).await?;
-        println!("{:#?}", r#type);
-
-        println!("Calling get_issues()");
-        let issues = client.get_issues(item.type_info.id).await?;
-        println!("{:#?}", issues);
+        let type_id = item.type_info.id;
+        let _: CollectedItem = recorder
+            .get(
+                Endpoint::GetCollectedItem,
+                &format!("get_collected_item-{}", item.id),
+                client.get_collected_item(user_id, item.id),
+            )
+            .await?;
+
+        let _: NumistaType = recorder
+            .get(
+                Endpoint::GetType,
+                &format!("get_type-{}", type_id),
+                client.get_type(type_id),
+            )
+            .await?;
+
+        let _: Vec<Issue> = recorder
+            .get(
+                Endpoint::GetIssues,
+                &format!("get_issues-{}", type_id),
+                client.get_issues(type_id),
+            )
+            .await?;
 
         if let Some(issue) = &item.issue {
-            println!("Calling get_prices()");
-            println!(
-                "{:#?}",
-                client
-                    .get_prices(item.type_info.id, issue.id, None)
-                    .await?
-            );
+            let _: GradePrices = recorder
+                .get(
+                    Endpoint::GetPrices,
+                    &format!("get_prices-{}-{}", type_id, issue.id),
+                    client.get_prices(type_id, issue.id, None),
+                )
+                .await?;
         }
     }
 
-    println!("Calling get_user()");
-    println!("{:#?}", client.get_user(cli.user_id).await?);
-    println!("Calling get_publication()");
-    println!("{:#?}", client.get_publication("L106610").await?);
-    println!("Calling search_types()");
-    let params = SearchTypesParams::new().q("victoria");
-    println!("{:#?}", client.search_types(&params).await?);
+    let _: User = recorder
+        .get(Endpoint::GetUser, "get_user", client.get_user(user_id))
+        .await?;
+
+    let _: Publication = recorder
+        .get(
+            Endpoint::GetPublication,
+            "get_publication-L106610",
+            client.get_publication("L106610"),
+        )
+        .await?;
+
+    let _: SearchTypesResponse = recorder
+        .get(
+            Endpoint::SearchTypes,
+            "search_types",
+            client.search_types(&SearchTypesParams::new().q("victoria")),
+        )
+        .await?;
+
+    if cli.mutate {
+        let client = ClientBuilder::new()
+            .api_key(&api_key)
+            .bearer_token(&token.access_token)
+            .build()?;
+        mutate::run(&client, user_id, cli.collection).await?;
+    }
 
     Ok(())
 }
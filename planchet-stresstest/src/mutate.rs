@@ -0,0 +1,307 @@
+//! Exercising the write endpoints, by adding, editing, and deleting collected
+//! items.
+//!
+//! The items are added to the collection of the authenticated user, with
+//! [`MARKER`] at the start of their private comment, and optionally to one of
+//! its collections set aside for the purpose. Every field sent is compared
+//! with the item returned by the API, so a field which is serialized wrongly,
+//! or ignored by the API, is reported. The items are deleted at the end of the
+//! run, even if a check fails, and any marked item left over by an interrupted
+//! run is deleted before starting.
+use anyhow::{bail, Context, Result};
+use chrono::NaiveDate;
+use planchet::models::{
+    AddCollectedItemParams, CollectedItem, EditCollectedItemParams, GetCollectedItemsParams, Grade,
+    ItemPriceParams, SearchTypesParams,
+};
+use planchet::Client;
+use std::fmt::Debug;
+
+/// The start of the private comment of the items added by the stresstest.
+pub const MARKER: &str = "[planchet-stresstest]";
+
+/// Adds, edits, and deletes collected items, checking each response.
+///
+/// # Arguments
+///
+/// * `client` - A client authorized to edit the collection.
+/// * `user_id` - The ID of the authenticated user.
+/// * `collection` - The ID of the collection to add the items to, if any.
+pub async fn run(client: &Client, user_id: i64, collection: Option<i64>) -> Result<()> {
+    delete_leftovers(client, user_id).await?;
+
+    println!("Calling search_types()");
+    let types = client
+        .search_types(&SearchTypesParams::new().q("victoria"))
+        .await?;
+    let type_id = types.types.first().context("No type to add found")?.id;
+    println!("Calling get_issues()");
+    let issue_id = client.get_issues(type_id).await?.first().map(|i| i.id);
+
+    let mut added = Vec::new();
+    let result = exercise(client, user_id, type_id, issue_id, collection, &mut added).await;
+    for item_id in added {
+        println!("Cleaning up item {}", item_id);
+        if let Err(e) = client.delete_collected_item(user_id, item_id).await {
+            println!("Failed to delete item {}: {}", item_id, e);
+        }
+    }
+    result
+}
+
+/// Deletes the marked items left over by an interrupted run.
+async fn delete_leftovers(client: &Client, user_id: i64) -> Result<()> {
+    println!("Calling get_collected_items()");
+    let items = client
+        .get_collected_items(user_id, &GetCollectedItemsParams::new())
+        .await?;
+    let leftovers = items.items.iter().filter(|item| {
+        item.private_comment
+            .as_deref()
+            .unwrap_or_default()
+            .starts_with(MARKER)
+    });
+    for item in leftovers {
+        println!("Deleting item {} left over by a previous run", item.id);
+        client.delete_collected_item(user_id, item.id).await?;
+    }
+    Ok(())
+}
+
+/// Runs the add, edit, and delete flows, recording the IDs of the items
+/// added and not yet deleted in `added`.
+async fn exercise(
+    client: &Client,
+    user_id: i64,
+    type_id: i64,
+    issue_id: Option<i64>,
+    collection: Option<i64>,
+    added: &mut Vec<i64>,
+) -> Result<()> {
+    // An item with only the required fields, checking that unset fields are
+    // left out of the payload.
+    println!("Calling add_collected_item() with only a type");
+    let minimal = AddCollectedItemParams::new(type_id).private_comment(MARKER);
+    let item = client.add_collected_item(user_id, &minimal).await?;
+    added.push(item.id);
+    println!("{:#?}", item);
+    let mut mismatches = Vec::new();
+    compare("type", &type_id, &item.type_info.id, &mut mismatches);
+    compare("quantity", &1, &item.quantity, &mut mismatches);
+    compare("for_swap", &false, &item.for_swap, &mut mismatches);
+    report("add_collected_item", mismatches)?;
+
+    println!("Calling delete_collected_item()");
+    client.delete_collected_item(user_id, item.id).await?;
+    added.retain(|&id| id != item.id);
+    println!("Calling get_collected_item() on the deleted item");
+    match client.get_collected_item(user_id, item.id).await {
+        Err(e) if e.is_not_found() => {}
+        Err(e) => return Err(e.into()),
+        Ok(_) => bail!("Item {} still exists after being deleted", item.id),
+    }
+
+    // An item with every field set.
+    println!("Calling add_collected_item() with every field");
+    let mut full = AddCollectedItemParams::new(type_id)
+        .quantity(2)
+        .grade(Grade::Vf)
+        .for_swap(true)
+        .private_comment(format!("{} Private comment", MARKER))
+        .public_comment("Public comment, with “quotes” & accents: é")
+        .price(ItemPriceParams {
+            value: "12.50".parse()?,
+            currency: "EUR".to_string(),
+        })
+        .storage_location("Album 1, page 2")
+        .acquisition_place("Coin show")
+        .acquisition_date(NaiveDate::from_ymd_opt(2020, 2, 29).unwrap())
+        .serial_number("A 123456")
+        .internal_id("INV-1")
+        .weight("4.65".parse()?)
+        .size("21.2".parse()?)
+        .axis(12);
+    if let Some(issue_id) = issue_id {
+        full = full.issue(issue_id);
+    }
+    if let Some(collection) = collection {
+        full = full.collection(collection);
+    }
+    let item = client.add_collected_item(user_id, &full).await?;
+    added.push(item.id);
+    println!("{:#?}", item);
+    report("add_collected_item", check_added(&full, &item))?;
+
+    println!("Calling edit_collected_item()");
+    let edit = EditCollectedItemParams::new()
+        .quantity(3)
+        .grade(Grade::Xf)
+        .for_swap(false)
+        .public_comment("Edited public comment")
+        .price(ItemPriceParams {
+            value: "15".parse()?,
+            currency: "USD".to_string(),
+        });
+    let edited = client.edit_collected_item(user_id, item.id, &edit).await?;
+    println!("{:#?}", edited);
+    let mut mismatches = Vec::new();
+    compare("quantity", &3, &edited.quantity, &mut mismatches);
+    compare("grade", &Some(Grade::Xf), &edited.grade, &mut mismatches);
+    compare("for_swap", &false, &edited.for_swap, &mut mismatches);
+    compare(
+        "public_comment",
+        &edit.public_comment.clone().flatten(),
+        &edited.public_comment,
+        &mut mismatches,
+    );
+    compare_price(
+        edit.price.as_ref().and_then(Option::as_ref),
+        &edited,
+        &mut mismatches,
+    );
+    // The fields left out of the edit are unchanged.
+    compare(
+        "serial_number",
+        &item.serial_number,
+        &edited.serial_number,
+        &mut mismatches,
+    );
+    compare(
+        "storage_location",
+        &item.storage_location,
+        &edited.storage_location,
+        &mut mismatches,
+    );
+    report("edit_collected_item", mismatches)?;
+
+    println!("Calling get_collected_item()");
+    let fetched = client.get_collected_item(user_id, item.id).await?;
+    let mut mismatches = Vec::new();
+    compare("quantity", &3, &fetched.quantity, &mut mismatches);
+    compare("grade", &Some(Grade::Xf), &fetched.grade, &mut mismatches);
+    report("get_collected_item", mismatches)?;
+
+    println!("Calling delete_collected_item()");
+    client.delete_collected_item(user_id, item.id).await?;
+    added.retain(|&id| id != item.id);
+
+    println!("All write checks passed");
+    Ok(())
+}
+
+/// Compares the fields of an added item with those sent.
+fn check_added(sent: &AddCollectedItemParams, item: &CollectedItem) -> Vec<String> {
+    let mut mismatches = Vec::new();
+    compare("type", &sent.type_id, &item.type_info.id, &mut mismatches);
+    compare(
+        "issue",
+        &sent.issue,
+        &item.issue.as_ref().map(|i| i.id),
+        &mut mismatches,
+    );
+    compare(
+        "quantity",
+        &sent.quantity,
+        &Some(item.quantity),
+        &mut mismatches,
+    );
+    compare("grade", &sent.grade, &item.grade, &mut mismatches);
+    compare(
+        "for_swap",
+        &sent.for_swap,
+        &Some(item.for_swap),
+        &mut mismatches,
+    );
+    compare(
+        "private_comment",
+        &sent.private_comment,
+        &item.private_comment,
+        &mut mismatches,
+    );
+    compare(
+        "public_comment",
+        &sent.public_comment,
+        &item.public_comment,
+        &mut mismatches,
+    );
+    compare_price(sent.price.as_ref(), item, &mut mismatches);
+    if sent.collection.is_some() {
+        compare(
+            "collection",
+            &sent.collection,
+            &item.collection.as_ref().map(|c| c.id),
+            &mut mismatches,
+        );
+    }
+    compare(
+        "storage_location",
+        &sent.storage_location,
+        &item.storage_location,
+        &mut mismatches,
+    );
+    compare(
+        "acquisition_place",
+        &sent.acquisition_place,
+        &item.acquisition_place,
+        &mut mismatches,
+    );
+    compare(
+        "acquisition_date",
+        &sent.acquisition_date,
+        &item.acquisition_date,
+        &mut mismatches,
+    );
+    compare(
+        "serial_number",
+        &sent.serial_number,
+        &item.serial_number,
+        &mut mismatches,
+    );
+    compare(
+        "internal_id",
+        &sent.internal_id,
+        &item.internal_id,
+        &mut mismatches,
+    );
+    compare("weight", &sent.weight, &item.weight, &mut mismatches);
+    compare("size", &sent.size, &item.size, &mut mismatches);
+    compare("axis", &sent.axis, &item.axis, &mut mismatches);
+    mismatches
+}
+
+/// Compares the price of an item with the one sent.
+fn compare_price(
+    sent: Option<&ItemPriceParams>,
+    item: &CollectedItem,
+    mismatches: &mut Vec<String>,
+) {
+    let sent = sent.map(|p| (p.value, p.currency.clone()));
+    let got = item
+        .price
+        .as_ref()
+        .map(|p| (p.value, p.currency.code().to_string()));
+    compare("price", &sent, &got, mismatches);
+}
+
+/// Records a mismatch if a field returned differs from the one sent.
+fn compare<T: PartialEq + Debug>(field: &str, sent: &T, got: &T, mismatches: &mut Vec<String>) {
+    if sent != got {
+        mismatches.push(format!("{}: sent {:?}, got {:?}", field, sent, got));
+    }
+}
+
+/// Fails if any field of a response differs from the request.
+fn report(endpoint: &str, mismatches: Vec<String>) -> Result<()> {
+    if mismatches.is_empty() {
+        println!("ok      {}", endpoint);
+        return Ok(());
+    }
+    for mismatch in &mismatches {
+        println!("FAILED  {}: {}", endpoint, mismatch);
+    }
+    bail!(
+        "{} fields of {} differ from the request",
+        mismatches.len(),
+        endpoint
+    )
+}
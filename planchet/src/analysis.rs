@@ -0,0 +1,299 @@
+//! Helpers for analyzing catalogue data.
+use crate::models::{CollectedItem, Issue, NumistaType};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Mintage statistics computed over the issues of a type.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MintageStats {
+    /// The total mintage of all issues with a known mintage.
+    pub total: i64,
+    /// The total mintage per mint letter. Issues without a mint letter are
+    /// grouped under `None`.
+    pub by_mint_letter: BTreeMap<Option<String>, i64>,
+    /// The IDs of the issues with a known mintage, from the rarest (lowest
+    /// mintage) to the most common.
+    pub by_rarity: Vec<i64>,
+    /// The number of issues for which the mintage is unknown.
+    pub unknown_mintage_count: usize,
+}
+
+/// Computes mintage statistics for a set of issues, as returned by
+/// [`Client::get_issues`](crate::Client::get_issues).
+pub fn mintage_stats(issues: &[Issue]) -> MintageStats {
+    let mut stats = MintageStats::default();
+    let mut ranked = Vec::new();
+
+    for issue in issues {
+        match issue.mintage {
+            Some(mintage) => {
+                stats.total += mintage;
+                *stats
+                    .by_mint_letter
+                    .entry(issue.mint_letter.clone())
+                    .or_default() += mintage;
+                ranked.push((mintage, issue.id));
+            }
+            None => stats.unknown_mintage_count += 1,
+        }
+    }
+
+    ranked.sort();
+    stats.by_rarity = ranked.into_iter().map(|(_, id)| id).collect();
+    stats
+}
+
+/// Distributions of the items of a collection.
+///
+/// Items are counted by quantity, so an item held twice counts twice. Items
+/// for which a property is unknown are left out of its distribution.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CollectionStats {
+    /// The number of items, counting quantities.
+    pub total_items: i64,
+    /// The number of distinct types.
+    pub distinct_types: usize,
+    /// The number of items per issuer name.
+    pub by_issuer: BTreeMap<String, i64>,
+    /// The number of items per category, e.g. `coin`.
+    pub by_category: BTreeMap<String, i64>,
+    /// The number of items per decade, e.g. `1850` for 1850 to 1859.
+    pub by_decade: BTreeMap<i32, i64>,
+    /// The number of items per grade, e.g. `vf`.
+    pub by_grade: BTreeMap<String, i64>,
+    /// The number of items per composition, e.g. `Silver`. Only known for
+    /// the items whose type is hydrated.
+    pub by_composition: BTreeMap<String, i64>,
+    /// The number of items whose year is unknown.
+    pub unknown_year_count: i64,
+}
+
+/// Computes the distributions of the items of a collection, as returned by
+/// [`Client::get_collected_items`](crate::Client::get_collected_items).
+///
+/// Collected items only hold a summary of their type. The full types, as
+/// returned by [`Client::get_type`](crate::Client::get_type), can be passed
+/// in `types` by ID: they add the composition, and the year of items whose
+/// issue is unknown but whose type was only issued in one year.
+pub fn collection_stats(
+    items: &[CollectedItem],
+    types: &HashMap<i64, NumistaType>,
+) -> CollectionStats {
+    let mut stats = CollectionStats::default();
+    let mut type_ids = BTreeSet::new();
+
+    for item in items {
+        let quantity = item.quantity;
+        let full_type = types.get(&item.type_info.id);
+        stats.total_items += quantity;
+        type_ids.insert(item.type_info.id);
+
+        if let Some(issuer) = &item.type_info.issuer {
+            *stats.by_issuer.entry(issuer.name.clone()).or_default() += quantity;
+        }
+        if let Some(category) = label(&item.type_info.category) {
+            *stats.by_category.entry(category).or_default() += quantity;
+        }
+        if let Some(grade) = item.grade.as_ref().and_then(label) {
+            *stats.by_grade.entry(grade).or_default() += quantity;
+        }
+        let composition = full_type
+            .and_then(|t| t.composition.as_ref())
+            .and_then(|c| c.text.clone());
+        if let Some(composition) = composition {
+            *stats.by_composition.entry(composition).or_default() += quantity;
+        }
+
+        let year = item.issue.as_ref().and_then(|i| i.gregorian_year).or_else(|| {
+            full_type
+                .filter(|t| t.min_year == t.max_year)
+                .and_then(|t| t.min_year)
+        });
+        match year {
+            Some(year) => *stats.by_decade.entry(year.div_euclid(10) * 10).or_default() += quantity,
+            None => stats.unknown_year_count += quantity,
+        }
+    }
+
+    stats.distinct_types = type_ids.len();
+    stats
+}
+
+/// Counts the types carrying each tag, e.g. to show the tags of search
+/// results or of the hydrated types of a collection.
+///
+/// Tags are compared regardless of case, and reported as they first appear.
+/// The most frequent tags come first, and tags as frequent are sorted
+/// alphabetically.
+pub fn tag_cloud(types: &[NumistaType]) -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, (String, usize)> = HashMap::new();
+    for t in types {
+        // A type counts once per tag, even if the tag is repeated.
+        let mut seen = BTreeSet::new();
+        for tag in t.tags.iter().flatten() {
+            let key = tag.to_lowercase();
+            if seen.insert(key.clone()) {
+                counts.entry(key).or_insert_with(|| (tag.clone(), 0)).1 += 1;
+            }
+        }
+    }
+    let mut cloud: Vec<(String, usize)> = counts.into_values().collect();
+    cloud.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    cloud
+}
+
+/// Returns the types carrying a tag, compared regardless of case.
+///
+/// The API cannot search types by tag, so this filters types already
+/// fetched, e.g. with [`Client::hydrate`](crate::Client::hydrate).
+pub fn with_tag<'a>(types: &'a [NumistaType], tag: &str) -> Vec<&'a NumistaType> {
+    let tag = tag.to_lowercase();
+    types
+        .iter()
+        .filter(|t| {
+            t.tags
+                .iter()
+                .flatten()
+                .any(|other| other.to_lowercase() == tag)
+        })
+        .collect()
+}
+
+/// Returns the name a value is serialized as, e.g. `coin` for a category.
+fn label<T: Serialize>(value: &T) -> Option<String> {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(id: i64, mint_letter: Option<&str>, mintage: Option<i64>) -> Issue {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "mint_letter": mint_letter,
+            "mintage": mintage,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_mintage_stats() {
+        let issues = vec![
+            issue(1, Some("D"), Some(3000)),
+            issue(2, Some("P"), Some(1000)),
+            issue(3, Some("D"), Some(500)),
+            issue(4, None, Some(2000)),
+            issue(5, Some("S"), None),
+        ];
+
+        let stats = mintage_stats(&issues);
+        assert_eq!(stats.total, 6500);
+        assert_eq!(stats.by_mint_letter[&Some("D".to_string())], 3500);
+        assert_eq!(stats.by_mint_letter[&Some("P".to_string())], 1000);
+        assert_eq!(stats.by_mint_letter[&None], 2000);
+        assert!(!stats.by_mint_letter.contains_key(&Some("S".to_string())));
+        assert_eq!(stats.by_rarity, vec![3, 2, 4, 1]);
+        assert_eq!(stats.unknown_mintage_count, 1);
+    }
+
+    #[test]
+    fn test_mintage_stats_empty() {
+        assert_eq!(mintage_stats(&[]), MintageStats::default());
+    }
+
+    fn collected_item(
+        id: i64,
+        type_id: i64,
+        quantity: i64,
+        year: Option<i32>,
+        grade: Option<&str>,
+    ) -> CollectedItem {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "quantity": quantity,
+            "for_swap": false,
+            "type": {
+                "id": type_id,
+                "title": "Test",
+                "category": "coin",
+                "issuer": {"code": "canada", "name": "Canada"},
+            },
+            "issue": year.map(|y| serde_json::json!({"id": id, "gregorian_year": y})),
+            "grade": grade,
+        }))
+        .unwrap()
+    }
+
+    fn tagged_type(id: i64, tags: &[&str]) -> NumistaType {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "url": format!("https://en.numista.com/catalogue/pieces{}.html", id),
+            "title": "Test",
+            "category": "coin",
+            "tags": tags,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_tag_cloud() {
+        let types = vec![
+            tagged_type(1, &["Ships", "Birds"]),
+            tagged_type(2, &["ships", "Ships"]),
+            tagged_type(3, &["Crowns"]),
+            tagged_type(4, &[]),
+        ];
+        assert_eq!(
+            tag_cloud(&types),
+            vec![
+                ("Ships".to_string(), 2),
+                ("Birds".to_string(), 1),
+                ("Crowns".to_string(), 1),
+            ]
+        );
+
+        let ids: Vec<i64> = with_tag(&types, "SHIPS").iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![1, 2]);
+        assert!(with_tag(&types, "dogs").is_empty());
+    }
+
+    #[test]
+    fn test_collection_stats() {
+        let items = vec![
+            collected_item(1, 10, 2, Some(1858), Some("vf")),
+            collected_item(2, 10, 1, Some(1851), None),
+            collected_item(3, 11, 1, Some(1920), Some("unc")),
+            collected_item(4, 12, 1, None, Some("vf")),
+        ];
+        let full_type: NumistaType = serde_json::from_value(serde_json::json!({
+            "id": 12,
+            "url": "https://en.numista.com/catalogue/pieces12.html",
+            "title": "Test",
+            "category": "coin",
+            "min_year": 1967,
+            "max_year": 1967,
+            "composition": {"text": "Silver"},
+        }))
+        .unwrap();
+        let types = HashMap::from([(12, full_type)]);
+
+        let stats = collection_stats(&items, &HashMap::new());
+        assert_eq!(stats.total_items, 5);
+        assert_eq!(stats.distinct_types, 3);
+        assert_eq!(stats.by_issuer["Canada"], 5);
+        assert_eq!(stats.by_category["coin"], 5);
+        assert_eq!(stats.by_decade, BTreeMap::from([(1850, 3), (1920, 1)]));
+        assert_eq!(stats.by_grade["vf"], 3);
+        assert_eq!(stats.by_grade["unc"], 1);
+        assert!(stats.by_composition.is_empty());
+        assert_eq!(stats.unknown_year_count, 1);
+
+        let stats = collection_stats(&items, &types);
+        assert_eq!(stats.by_decade[&1960], 1);
+        assert_eq!(stats.by_composition["Silver"], 1);
+        assert_eq!(stats.unknown_year_count, 0);
+    }
+}
@@ -0,0 +1,212 @@
+//! The endpoints of the API, as a trait.
+//!
+//! [`NumistaApi`] has a method per endpoint of the API, and is implemented by
+//! [`Client`]. Code written against the trait rather than the client can be
+//! given a fake in unit tests or demos, or a decorator adding caching or
+//! metrics around another implementation.
+//!
+//! # Examples
+//!
+//! ```
+//! use planchet::api::NumistaApi;
+//! use planchet::models::GetCollectedItemsParams;
+//!
+//! /// Counts the items of a collection, through any implementation.
+//! async fn count_items(api: &impl NumistaApi, user_id: i64) -> planchet::Result<i64> {
+//!     let items = api
+//!         .get_collected_items(user_id, &GetCollectedItemsParams::new())
+//!         .await?;
+//!     Ok(items.items.iter().map(|item| item.quantity).sum())
+//! }
+//! ```
+use crate::client::{Client, DeleteOutcome};
+use crate::error::Result;
+use crate::models::{
+    self,
+    request::{
+        AddCollectedItemParams, EditCollectedItemParams, GetCollectedItemsParams, OAuthTokenParams,
+        SearchByImageParams, SearchTypesParams,
+    },
+    response::{
+        CataloguesResponse, CollectionsResponse, IssuersResponse, MintsResponse,
+        SearchByImageResponse, SearchTypesResponse,
+    },
+    CollectedItem, CollectedItems, GradePrices, MintDetail, NumistaType, OAuthToken, Publication,
+    User,
+};
+
+/// The endpoints of the Numista API.
+///
+/// See the methods of the same name on [`Client`] for their documentation.
+#[async_trait::async_trait]
+pub trait NumistaApi: Send + Sync {
+    /// Gets a single type. See [`Client::get_type`].
+    async fn get_type(&self, type_id: i64) -> Result<NumistaType>;
+
+    /// Gets the issues of a type. See [`Client::get_issues`].
+    async fn get_issues(&self, type_id: i64) -> Result<Vec<models::Issue>>;
+
+    /// Gets the prices for an issue. See [`Client::get_prices`].
+    async fn get_prices(
+        &self,
+        type_id: i64,
+        issue_id: i64,
+        currency: Option<&str>,
+    ) -> Result<GradePrices>;
+
+    /// Searches for types. See [`Client::search_types`].
+    async fn search_types(&self, params: &SearchTypesParams) -> Result<SearchTypesResponse>;
+
+    /// Gets the list of issuers. See [`Client::get_issuers`].
+    async fn get_issuers(&self) -> Result<IssuersResponse>;
+
+    /// Gets the list of mints. See [`Client::get_mints`].
+    async fn get_mints(&self) -> Result<MintsResponse>;
+
+    /// Gets a single mint. See [`Client::get_mint`].
+    async fn get_mint(&self, mint_id: i64) -> Result<MintDetail>;
+
+    /// Gets the list of catalogues. See [`Client::get_catalogues`].
+    async fn get_catalogues(&self) -> Result<CataloguesResponse>;
+
+    /// Gets a single publication. See [`Client::get_publication`].
+    async fn get_publication(&self, id: &str) -> Result<Publication>;
+
+    /// Gets a user. See [`Client::get_user`].
+    async fn get_user(&self, user_id: i64) -> Result<User>;
+
+    /// Gets the collections of a user. See [`Client::get_user_collections`].
+    async fn get_user_collections(&self, user_id: i64) -> Result<CollectionsResponse>;
+
+    /// Gets the collected items of a user. See
+    /// [`Client::get_collected_items`].
+    async fn get_collected_items(
+        &self,
+        user_id: i64,
+        params: &GetCollectedItemsParams,
+    ) -> Result<CollectedItems>;
+
+    /// Gets a single collected item. See [`Client::get_collected_item`].
+    async fn get_collected_item(&self, user_id: i64, item_id: i64) -> Result<CollectedItem>;
+
+    /// Adds a collected item. See [`Client::add_collected_item`].
+    async fn add_collected_item(
+        &self,
+        user_id: i64,
+        item: &AddCollectedItemParams,
+    ) -> Result<CollectedItem>;
+
+    /// Edits a collected item. See [`Client::edit_collected_item`].
+    async fn edit_collected_item(
+        &self,
+        user_id: i64,
+        item_id: i64,
+        item: &EditCollectedItemParams,
+    ) -> Result<CollectedItem>;
+
+    /// Deletes a collected item. See [`Client::delete_collected_item`].
+    async fn delete_collected_item(&self, user_id: i64, item_id: i64) -> Result<DeleteOutcome>;
+
+    /// Gets an OAuth token. See [`Client::get_oauth_token`].
+    async fn get_oauth_token(&self, params: &OAuthTokenParams) -> Result<OAuthToken>;
+
+    /// Searches for types by image. See [`Client::search_by_image`].
+    async fn search_by_image(&self, request: &SearchByImageParams)
+        -> Result<SearchByImageResponse>;
+}
+
+#[async_trait::async_trait]
+impl NumistaApi for Client {
+    async fn get_type(&self, type_id: i64) -> Result<NumistaType> {
+        Client::get_type(self, type_id).await
+    }
+
+    async fn get_issues(&self, type_id: i64) -> Result<Vec<models::Issue>> {
+        Client::get_issues(self, type_id).await
+    }
+
+    async fn get_prices(
+        &self,
+        type_id: i64,
+        issue_id: i64,
+        currency: Option<&str>,
+    ) -> Result<GradePrices> {
+        Client::get_prices(self, type_id, issue_id, currency).await
+    }
+
+    async fn search_types(&self, params: &SearchTypesParams) -> Result<SearchTypesResponse> {
+        Client::search_types(self, params).await
+    }
+
+    async fn get_issuers(&self) -> Result<IssuersResponse> {
+        Client::get_issuers(self).await
+    }
+
+    async fn get_mints(&self) -> Result<MintsResponse> {
+        Client::get_mints(self).await
+    }
+
+    async fn get_mint(&self, mint_id: i64) -> Result<MintDetail> {
+        Client::get_mint(self, mint_id).await
+    }
+
+    async fn get_catalogues(&self) -> Result<CataloguesResponse> {
+        Client::get_catalogues(self).await
+    }
+
+    async fn get_publication(&self, id: &str) -> Result<Publication> {
+        Client::get_publication(self, id).await
+    }
+
+    async fn get_user(&self, user_id: i64) -> Result<User> {
+        Client::get_user(self, user_id).await
+    }
+
+    async fn get_user_collections(&self, user_id: i64) -> Result<CollectionsResponse> {
+        Client::get_user_collections(self, user_id).await
+    }
+
+    async fn get_collected_items(
+        &self,
+        user_id: i64,
+        params: &GetCollectedItemsParams,
+    ) -> Result<CollectedItems> {
+        Client::get_collected_items(self, user_id, params).await
+    }
+
+    async fn get_collected_item(&self, user_id: i64, item_id: i64) -> Result<CollectedItem> {
+        Client::get_collected_item(self, user_id, item_id).await
+    }
+
+    async fn add_collected_item(
+        &self,
+        user_id: i64,
+        item: &AddCollectedItemParams,
+    ) -> Result<CollectedItem> {
+        Client::add_collected_item(self, user_id, item).await
+    }
+
+    async fn edit_collected_item(
+        &self,
+        user_id: i64,
+        item_id: i64,
+        item: &EditCollectedItemParams,
+    ) -> Result<CollectedItem> {
+        Client::edit_collected_item(self, user_id, item_id, item).await
+    }
+
+    async fn delete_collected_item(&self, user_id: i64, item_id: i64) -> Result<DeleteOutcome> {
+        Client::delete_collected_item(self, user_id, item_id).await
+    }
+
+    async fn get_oauth_token(&self, params: &OAuthTokenParams) -> Result<OAuthToken> {
+        Client::get_oauth_token(self, params).await
+    }
+
+    async fn search_by_image(
+        &self,
+        request: &SearchByImageParams,
+    ) -> Result<SearchByImageResponse> {
+        Client::search_by_image(self, request).await
+    }
+}
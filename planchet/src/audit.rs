@@ -0,0 +1,260 @@
+//! A tamper-evident log of the write requests sent to the API.
+//!
+//! When an [`AuditLog`] is set with [`ClientBuilder::audit_log`], every
+//! request which changes data (i.e. any request other than `GET` and `HEAD`)
+//! is recorded as an [`AuditEntry`]: its method, path, a hash of its payload,
+//! and its outcome. Entries are written as JSON lines to a file, or sent to a
+//! channel.
+//!
+//! Each entry holds the hash of the previous one, so editing or removing an
+//! entry of a log file breaks the chain, which [`verify`] detects.
+//!
+//! [`ClientBuilder::audit_log`]: crate::ClientBuilder::audit_log
+use crate::error::{Error, Result};
+use chrono::{DateTime, Utc};
+use http::Extensions;
+use reqwest_middleware::{Middleware, Next};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::warn;
+
+/// The hash preceding the first entry of a log.
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// A write request sent to the API, and its outcome.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// When the request was sent.
+    pub timestamp: DateTime<Utc>,
+    /// The HTTP method of the request, e.g. `POST`.
+    pub method: String,
+    /// The path of the request URL. The query is left out, as it may hold
+    /// credentials.
+    pub path: String,
+    /// The SHA-256 hash of the request body, in hexadecimal, if it had one.
+    pub payload_hash: Option<String>,
+    /// The ID generated for the request, also sent in the `X-Request-ID`
+    /// header.
+    pub request_id: Option<String>,
+    /// The HTTP status code returned by the API, if it responded.
+    pub status: Option<u16>,
+    /// Why no response was received, if the request failed.
+    pub error: Option<String>,
+    /// The hash of the previous entry of the log.
+    pub previous_hash: String,
+    /// The hash of this entry, covering all its other fields.
+    pub hash: String,
+}
+
+impl AuditEntry {
+    /// Computes the hash of the entry from its other fields.
+    fn compute_hash(&self) -> String {
+        let unhashed = AuditEntry {
+            hash: String::new(),
+            ..self.clone()
+        };
+        let json = serde_json::to_vec(&unhashed).expect("audit entries serialize to JSON");
+        format!("{:x}", Sha256::digest(json))
+    }
+}
+
+#[derive(Debug)]
+enum Sink {
+    File(File),
+    Channel(UnboundedSender<AuditEntry>),
+}
+
+#[derive(Debug)]
+struct State {
+    sink: Sink,
+    last_hash: String,
+}
+
+/// Where the entries of the audit log are recorded.
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    state: Arc<Mutex<State>>,
+}
+
+impl AuditLog {
+    /// Opens a log file, creating it if needed, and appends entries to it.
+    ///
+    /// The chain of hashes continues from the last entry of the file.
+    pub fn file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let mut last_hash = GENESIS_HASH.to_string();
+        if path.exists() {
+            for line in BufReader::new(File::open(path)?).lines() {
+                let line = line?;
+                if !line.trim().is_empty() {
+                    last_hash = serde_json::from_str::<AuditEntry>(&line)?.hash;
+                }
+            }
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self::new(Sink::File(file), last_hash))
+    }
+
+    /// Sends entries to a channel, e.g. to store them elsewhere.
+    pub fn channel(sender: UnboundedSender<AuditEntry>) -> Self {
+        Self::new(Sink::Channel(sender), GENESIS_HASH.to_string())
+    }
+
+    fn new(sink: Sink, last_hash: String) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State { sink, last_hash })),
+        }
+    }
+
+    /// Chains an entry to the previous one and records it.
+    fn record(&self, mut entry: AuditEntry) -> std::io::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        entry.previous_hash = state.last_hash.clone();
+        entry.hash = entry.compute_hash();
+        match &mut state.sink {
+            Sink::File(file) => {
+                let mut line = serde_json::to_vec(&entry)?;
+                line.push(b'\n');
+                file.write_all(&line)?;
+                file.flush()?;
+            }
+            Sink::Channel(sender) => {
+                // The receiver may have been dropped, e.g. at shutdown.
+                let _ = sender.send(entry.clone());
+            }
+        }
+        state.last_hash = entry.hash;
+        Ok(())
+    }
+}
+
+/// Checks the chain of hashes of a log file, returning its number of entries.
+///
+/// Returns [`Error::Audit`] at the first entry which was edited, or whose
+/// previous entry was removed.
+pub fn verify<P: AsRef<Path>>(path: P) -> Result<usize> {
+    let mut previous_hash = GENESIS_HASH.to_string();
+    let mut count = 0;
+    for (i, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry: AuditEntry = serde_json::from_str(&line)?;
+        if entry.previous_hash != previous_hash {
+            return Err(Error::Audit(format!(
+                "line {}: the previous entry is missing or was changed",
+                i + 1
+            )));
+        }
+        if entry.compute_hash() != entry.hash {
+            return Err(Error::Audit(format!(
+                "line {}: the entry was changed",
+                i + 1
+            )));
+        }
+        previous_hash = entry.hash;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Records the write requests passing through it in an [`AuditLog`].
+pub(crate) struct AuditMiddleware {
+    log: AuditLog,
+}
+
+impl AuditMiddleware {
+    pub(crate) fn new(log: AuditLog) -> Self {
+        Self { log }
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for AuditMiddleware {
+    async fn handle(
+        &self,
+        req: reqwest::Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<reqwest::Response> {
+        if matches!(*req.method(), http::Method::GET | http::Method::HEAD) {
+            return next.run(req, extensions).await;
+        }
+
+        let mut entry = AuditEntry {
+            timestamp: Utc::now(),
+            method: req.method().to_string(),
+            path: req.url().path().to_string(),
+            payload_hash: req
+                .body()
+                .and_then(|b| b.as_bytes())
+                .map(|b| format!("{:x}", Sha256::digest(b))),
+            request_id: req
+                .headers()
+                .get(crate::client::REQUEST_ID_HEADER)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string),
+            status: None,
+            error: None,
+            previous_hash: String::new(),
+            hash: String::new(),
+        };
+        let res = next.run(req, extensions).await;
+        match &res {
+            Ok(response) => entry.status = Some(response.status().as_u16()),
+            Err(e) => entry.error = Some(e.to_string()),
+        }
+        if let Err(e) = self.log.record(entry) {
+            warn!("Failed to record a request in the audit log: {}", e);
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str) -> AuditEntry {
+        AuditEntry {
+            timestamp: Utc::now(),
+            method: "DELETE".to_string(),
+            path: path.to_string(),
+            payload_hash: None,
+            request_id: None,
+            status: Some(204),
+            error: None,
+            previous_hash: String::new(),
+            hash: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_verify_detects_tampering() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.log");
+        AuditLog::file(&path)
+            .unwrap()
+            .record(entry("/users/1/collected_items/1"))
+            .unwrap();
+        // Reopening continues the chain.
+        let log = AuditLog::file(&path).unwrap();
+        log.record(entry("/users/1/collected_items/2")).unwrap();
+        log.record(entry("/users/1/collected_items/3")).unwrap();
+        assert_eq!(verify(&path).unwrap(), 3);
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, content.replace("items/2", "items/4")).unwrap();
+        assert!(matches!(verify(&path), Err(Error::Audit(_))));
+
+        let lines: Vec<&str> = content.lines().collect();
+        std::fs::write(&path, format!("{}\n{}\n", lines[0], lines[2])).unwrap();
+        assert!(matches!(verify(&path), Err(Error::Audit(_))));
+    }
+}
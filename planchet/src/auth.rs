@@ -0,0 +1,89 @@
+//! Custom authentication of the requests, e.g. for a gateway in front of the
+//! API.
+//!
+//! Some deployments send the requests to the API through an internal gateway,
+//! which needs its own credentials, such as a short-lived JWT. An
+//! [`AuthProvider`] set with [`ClientBuilder::auth_provider`] is asked for the
+//! headers to add to each request just before it is sent, so that it can fetch
+//! or refresh its credentials as needed. The headers it adds replace any of the
+//! same name, and are not logged.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use planchet::auth::AuthProvider;
+//! use planchet::ClientBuilder;
+//! use reqwest::header::{HeaderMap, HeaderValue};
+//!
+//! struct GatewayToken;
+//!
+//! #[async_trait::async_trait]
+//! impl AuthProvider for GatewayToken {
+//!     async fn headers(&self, _request: &reqwest::Request) -> planchet::Result<HeaderMap> {
+//!         let mut headers = HeaderMap::new();
+//!         headers.insert("X-Gateway-Token", HeaderValue::from_static("jwt"));
+//!         Ok(headers)
+//!     }
+//! }
+//!
+//! let client = ClientBuilder::new()
+//!     .api_key("YOUR_API_KEY")
+//!     .auth_provider(GatewayToken)
+//!     .build()
+//!     .unwrap();
+//! ```
+//!
+//! [`ClientBuilder::auth_provider`]: crate::ClientBuilder::auth_provider
+use crate::error::Result;
+use http::Extensions;
+use reqwest::header::HeaderMap;
+use reqwest_middleware::{Middleware, Next};
+use std::sync::Arc;
+
+/// A source of headers authenticating the requests.
+#[async_trait::async_trait]
+pub trait AuthProvider: Send + Sync + 'static {
+    /// Returns the headers to add to a request, e.g. an `Authorization`
+    /// header with a fresh token.
+    ///
+    /// An error fails the request, without sending it.
+    async fn headers(&self, request: &reqwest::Request) -> Result<HeaderMap>;
+}
+
+impl std::fmt::Debug for dyn AuthProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("AuthProvider")
+    }
+}
+
+/// Adds the headers of an [`AuthProvider`] to each request.
+pub(crate) struct AuthMiddleware {
+    provider: Arc<dyn AuthProvider>,
+}
+
+impl AuthMiddleware {
+    pub(crate) fn new(provider: Arc<dyn AuthProvider>) -> Self {
+        Self { provider }
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for AuthMiddleware {
+    async fn handle(
+        &self,
+        mut req: reqwest::Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<reqwest::Response> {
+        let headers = self
+            .provider
+            .headers(&req)
+            .await
+            .map_err(reqwest_middleware::Error::middleware)?;
+        for (name, mut value) in headers.into_iter().filter_map(|(n, v)| Some((n?, v))) {
+            value.set_sensitive(true);
+            req.headers_mut().insert(name, value);
+        }
+        next.run(req, extensions).await
+    }
+}
@@ -0,0 +1,343 @@
+//! Precious metal content and melt value.
+//!
+//! The precious metal content of a type is its weight times the fineness of
+//! its composition, read by the `composition` module. Given the spot prices
+//! of the precious metals in [`SpotPrices`], [`melt_value`] values a type,
+//! and [`collection_bullion`] a whole collection. The prices can be fetched
+//! from any [`SpotPriceProvider`], such as `GoldApiPrices` with the
+//! `spot-prices` feature.
+//!
+//! Clad and plated types, and types whose weight or fineness is unknown, have
+//! no known content.
+//!
+//! This module is only available with the `decimal` feature.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use planchet::bullion::{self, SpotPrices};
+//! use planchet::composition::Metal;
+//! use planchet::ClientBuilder;
+//! use rust_decimal::Decimal;
+//!
+//! # async fn run() -> planchet::Result<()> {
+//! let client = ClientBuilder::new().api_key("YOUR_API_KEY").build()?;
+//! let type_ = client.get_type(12345).await?;
+//!
+//! let prices = SpotPrices::new().price(Metal::Silver, Decimal::new(3050, 2));
+//! if let Some(value) = bullion::melt_value(&type_, &prices) {
+//!     println!("Melt value: {:.2}", value);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use crate::composition::Metal;
+use crate::error::{Error, Result};
+use crate::models::{CollectedItem, NumistaType};
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, HashMap};
+
+/// The weight of a troy ounce, in grams.
+pub const GRAMS_PER_TROY_OUNCE: Decimal = Decimal::from_parts(311_034_768, 0, 0, false, 7);
+
+/// A source of spot prices of precious metals.
+#[async_trait::async_trait]
+pub trait SpotPriceProvider: Send + Sync {
+    /// Returns the price of a troy ounce of a metal.
+    ///
+    /// Returns [`Error::SpotPrice`] if the price is unknown.
+    async fn spot_price(&self, metal: &Metal) -> Result<Decimal>;
+}
+
+/// The spot prices of precious metals, per troy ounce, in a single currency.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpotPrices {
+    prices: HashMap<Metal, Decimal>,
+}
+
+impl SpotPrices {
+    /// Creates an empty set of prices.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the price of a troy ounce of a metal.
+    pub fn price(mut self, metal: Metal, per_troy_ounce: Decimal) -> Self {
+        self.prices.insert(metal, per_troy_ounce);
+        self
+    }
+
+    /// Fetches the prices of the given metals from a provider.
+    pub async fn fetch<P: SpotPriceProvider + ?Sized>(
+        provider: &P,
+        metals: &[Metal],
+    ) -> Result<Self> {
+        let mut prices = Self::new();
+        for metal in metals {
+            let price = provider.spot_price(metal).await?;
+            prices = prices.price(metal.clone(), price);
+        }
+        Ok(prices)
+    }
+
+    /// Returns the price of a gram of a metal, if it is known.
+    pub fn per_gram(&self, metal: &Metal) -> Option<Decimal> {
+        self.prices
+            .get(metal)
+            .map(|price| price / GRAMS_PER_TROY_OUNCE)
+    }
+}
+
+#[async_trait::async_trait]
+impl SpotPriceProvider for SpotPrices {
+    async fn spot_price(&self, metal: &Metal) -> Result<Decimal> {
+        self.prices
+            .get(metal)
+            .copied()
+            .ok_or_else(|| Error::SpotPrice(format!("no price for {:?}", metal)))
+    }
+}
+
+/// How long [`GoldApiPrices`] keeps a price before fetching it again.
+#[cfg(feature = "spot-prices")]
+pub const DEFAULT_SPOT_PRICE_TTL: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// The spot prices of gold-api.com, in US dollars.
+///
+/// Each price is fetched on first use, and kept for
+/// [`DEFAULT_SPOT_PRICE_TTL`] unless set with [`GoldApiPrices::ttl`], since
+/// spot prices move during the day.
+///
+/// This is only available with the `spot-prices` feature.
+#[cfg(feature = "spot-prices")]
+#[derive(Debug)]
+pub struct GoldApiPrices {
+    client: reqwest::Client,
+    url: String,
+    ttl: std::time::Duration,
+    prices: std::sync::Mutex<HashMap<Metal, (Decimal, std::time::Instant)>>,
+}
+
+#[cfg(feature = "spot-prices")]
+impl Default for GoldApiPrices {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "spot-prices")]
+impl GoldApiPrices {
+    /// Creates a provider of the latest prices.
+    pub fn new() -> Self {
+        Self::with_url("https://api.gold-api.com")
+    }
+
+    /// Creates a provider reading the prices from `url`, in the format of
+    /// gold-api.com.
+    ///
+    /// This is useful for testing.
+    pub fn with_url<S: Into<String>>(url: S) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.into(),
+            ttl: DEFAULT_SPOT_PRICE_TTL,
+            prices: Default::default(),
+        }
+    }
+
+    /// Sets how long a price is kept before being fetched again.
+    pub fn ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+}
+
+#[cfg(feature = "spot-prices")]
+#[async_trait::async_trait]
+impl SpotPriceProvider for GoldApiPrices {
+    async fn spot_price(&self, metal: &Metal) -> Result<Decimal> {
+        #[derive(serde::Deserialize)]
+        struct Price {
+            price: Decimal,
+        }
+
+        let cached = self
+            .prices
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .get(metal)
+            .copied();
+        if let Some((price, fetched)) = cached {
+            if fetched.elapsed() < self.ttl {
+                return Ok(price);
+            }
+        }
+        let symbol = match metal {
+            Metal::Gold => "XAU",
+            Metal::Silver => "XAG",
+            Metal::Platinum => "XPT",
+            Metal::Palladium => "XPD",
+            _ => return Err(Error::SpotPrice(format!("no price for {:?}", metal))),
+        };
+        let url = format!("{}/price/{}", self.url.trim_end_matches('/'), symbol);
+        let response = self.client.get(url).send().await?;
+        if !response.status().is_success() {
+            return Err(Error::SpotPrice(format!(
+                "gold-api.com returned status {}",
+                response.status()
+            )));
+        }
+        let price = response.json::<Price>().await?.price;
+        self.prices
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(metal.clone(), (price, std::time::Instant::now()));
+        Ok(price)
+    }
+}
+
+/// The precious metal held in a piece.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetalContent {
+    /// The precious metal.
+    pub metal: Metal,
+    /// The weight of pure metal, in grams.
+    pub fine_weight: Decimal,
+}
+
+/// Returns the precious metal content of a piece of the given weight and
+/// composition.
+fn content(weight: Decimal, type_: &NumistaType) -> Option<MetalContent> {
+    let composition = type_.composition.as_ref()?.parsed()?;
+    if !composition.metal.is_precious() || composition.core.is_some() {
+        return None;
+    }
+    Some(MetalContent {
+        metal: composition.metal,
+        fine_weight: weight * composition.fineness?,
+    })
+}
+
+/// Returns the precious metal content of a type, if it is known.
+pub fn metal_content(type_: &NumistaType) -> Option<MetalContent> {
+    content(type_.weight?, type_)
+}
+
+/// Returns the melt value of a type, in the currency of the prices, if its
+/// content and the price of its metal are known.
+pub fn melt_value(type_: &NumistaType, prices: &SpotPrices) -> Option<Decimal> {
+    let content = metal_content(type_)?;
+    Some(content.fine_weight * prices.per_gram(&content.metal)?)
+}
+
+/// The precious metal held in a collection.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CollectionBullion {
+    /// The weight of pure metal, in grams, per precious metal.
+    pub fine_weight: BTreeMap<Metal, Decimal>,
+    /// The melt value of the metals with a price.
+    pub melt_value: Decimal,
+    /// The number of items, counting quantities, without a known precious
+    /// metal content: items of other metals, or whose type, weight, or
+    /// fineness is unknown.
+    pub other_items: i64,
+}
+
+/// Computes the precious metal content and melt value of the items of a
+/// collection.
+///
+/// Collected items only hold a summary of their type, so the full types are
+/// passed in `types` by ID, as for
+/// [`analysis::collection_stats`](crate::analysis::collection_stats). The
+/// weight recorded for an item, if any, is used over the weight of its type.
+pub fn collection_bullion(
+    items: &[CollectedItem],
+    types: &HashMap<i64, NumistaType>,
+    prices: &SpotPrices,
+) -> CollectionBullion {
+    let mut bullion = CollectionBullion::default();
+    for item in items {
+        let content = types.get(&item.type_info.id).and_then(|type_| {
+            let weight = item.weight.or(type_.weight)?;
+            content(weight, type_)
+        });
+        let Some(content) = content else {
+            bullion.other_items += item.quantity;
+            continue;
+        };
+        let fine_weight = content.fine_weight * Decimal::from(item.quantity);
+        if let Some(per_gram) = prices.per_gram(&content.metal) {
+            bullion.melt_value += fine_weight * per_gram;
+        }
+        *bullion.fine_weight.entry(content.metal).or_default() += fine_weight;
+    }
+    bullion
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn full_type(id: i64, composition: &str, weight: &str) -> NumistaType {
+        serde_json::from_value(json!({
+            "id": id,
+            "title": "Type",
+            "category": "coin",
+            "composition": {"text": composition},
+            "weight": weight,
+        }))
+        .unwrap()
+    }
+
+    fn item(type_id: i64, quantity: i64) -> CollectedItem {
+        serde_json::from_value(json!({
+            "id": type_id * 10,
+            "quantity": quantity,
+            "type": {"id": type_id, "title": "Type", "category": "coin"},
+            "for_swap": 0,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_melt_value() {
+        let prices = SpotPrices::new().price(Metal::Silver, GRAMS_PER_TROY_OUNCE);
+        let silver = full_type(1, "Silver (.900)", "25");
+        assert_eq!(melt_value(&silver, &prices), Some(Decimal::new(225, 1)));
+
+        let gold = full_type(2, "Gold (.900)", "10");
+        assert_eq!(melt_value(&gold, &prices), None);
+        let clad = full_type(3, "Silver clad copper", "10");
+        assert_eq!(metal_content(&clad), None);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_spot_prices() {
+        let provider = SpotPrices::new().price(Metal::Gold, Decimal::new(2000, 0));
+        let prices = SpotPrices::fetch(&provider, &[Metal::Gold]).await.unwrap();
+        assert_eq!(prices, provider);
+        assert!(matches!(
+            SpotPrices::fetch(&provider, &[Metal::Silver]).await,
+            Err(Error::SpotPrice(_))
+        ));
+    }
+
+    #[test]
+    fn test_collection_bullion() {
+        let prices = SpotPrices::new().price(Metal::Silver, GRAMS_PER_TROY_OUNCE);
+        let types: HashMap<i64, NumistaType> = [
+            (1, full_type(1, "Silver (.500)", "10")),
+            (2, full_type(2, "Gold (.900)", "10")),
+            (3, full_type(3, "Bronze", "5")),
+        ]
+        .into();
+        let items = [item(1, 2), item(2, 1), item(3, 4), item(4, 1)];
+
+        let bullion = collection_bullion(&items, &types, &prices);
+        assert_eq!(bullion.fine_weight[&Metal::Silver], Decimal::new(10, 0));
+        assert_eq!(bullion.fine_weight[&Metal::Gold], Decimal::new(9, 0));
+        assert_eq!(bullion.melt_value, Decimal::new(10, 0));
+        assert_eq!(bullion.other_items, 5);
+    }
+}
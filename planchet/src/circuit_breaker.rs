@@ -0,0 +1,187 @@
+//! Failing fast while the API is down.
+//!
+//! A batch job keeps sending requests when the API has an outage, each of
+//! them failing only after a timeout, and adding to the load of the API. A
+//! [`CircuitBreaker`] set with [`ClientBuilder::circuit_breaker`] counts the
+//! consecutive requests which failed with a server error (a `5xx` status) or
+//! a timeout. Once they reach a threshold, the circuit "opens": requests fail
+//! at once with [`Error::CircuitOpen`], without being sent, for a cool-down
+//! period. The next request after the cool-down is sent, and closes the
+//! circuit if it succeeds, or opens it again if it fails.
+//!
+//! # Examples
+//!
+//! ```
+//! use planchet::circuit_breaker::CircuitBreaker;
+//! use planchet::ClientBuilder;
+//! use std::time::Duration;
+//!
+//! let breaker = CircuitBreaker::new()
+//!     .failure_threshold(3)
+//!     .cool_down(Duration::from_secs(60));
+//! let client = ClientBuilder::new()
+//!     .api_key("YOUR_API_KEY")
+//!     .circuit_breaker(breaker.clone())
+//!     .build()
+//!     .unwrap();
+//! assert!(!breaker.is_open());
+//! ```
+//!
+//! [`ClientBuilder::circuit_breaker`]: crate::ClientBuilder::circuit_breaker
+//! [`Error::CircuitOpen`]: crate::Error::CircuitOpen
+use http::Extensions;
+use reqwest_middleware::{Middleware, Next};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// The default number of consecutive failures which opens the circuit.
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+
+/// The default time during which requests fail fast once the circuit is open.
+pub const DEFAULT_COOL_DOWN: Duration = Duration::from_secs(30);
+
+/// The state of a circuit, shared by the clones of a breaker.
+#[derive(Debug, Default)]
+struct State {
+    /// The number of requests which failed in a row.
+    consecutive_failures: u32,
+    /// When the circuit closes again, if it is open.
+    open_until: Option<Instant>,
+}
+
+/// Stops sending requests for a while after repeated failures.
+///
+/// Clones share their state, so a breaker can be inspected after being given
+/// to a [`ClientBuilder`](crate::ClientBuilder), or shared by several clients.
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    cool_down: Duration,
+    state: Arc<Mutex<State>>,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self {
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            cool_down: DEFAULT_COOL_DOWN,
+            state: Arc::default(),
+        }
+    }
+}
+
+impl CircuitBreaker {
+    /// Creates a breaker with the default threshold and cool-down.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of consecutive failures which opens the circuit.
+    /// Defaults to [`DEFAULT_FAILURE_THRESHOLD`].
+    pub fn failure_threshold(mut self, failure_threshold: u32) -> Self {
+        self.failure_threshold = failure_threshold.max(1);
+        self
+    }
+
+    /// Sets the time during which requests fail fast once the circuit is
+    /// open. Defaults to [`DEFAULT_COOL_DOWN`].
+    pub fn cool_down(mut self, cool_down: Duration) -> Self {
+        self.cool_down = cool_down;
+        self
+    }
+
+    /// Checks if requests currently fail without being sent.
+    pub fn is_open(&self) -> bool {
+        self.remaining_cool_down().is_some()
+    }
+
+    /// Returns the time until the circuit closes, if it is open.
+    fn remaining_cool_down(&self) -> Option<Duration> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let remaining = state.open_until?.checked_duration_since(Instant::now())?;
+        (!remaining.is_zero()).then_some(remaining)
+    }
+
+    /// Records the outcome of a request which was sent.
+    fn record(&self, failed: bool) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if !failed {
+            *state = State::default();
+            return;
+        }
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+        if state.consecutive_failures >= self.failure_threshold {
+            state.open_until = Some(Instant::now() + self.cool_down);
+        }
+    }
+}
+
+/// The error of a request which was not sent because the circuit is open.
+#[derive(Debug)]
+pub(crate) struct CircuitOpen {
+    pub(crate) retry_in: Duration,
+}
+
+impl fmt::Display for CircuitOpen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "circuit breaker open for {:?}", self.retry_in)
+    }
+}
+
+impl std::error::Error for CircuitOpen {}
+
+/// Fails requests fast while the circuit of its breaker is open.
+pub(crate) struct CircuitBreakerMiddleware {
+    breaker: CircuitBreaker,
+}
+
+impl CircuitBreakerMiddleware {
+    pub(crate) fn new(breaker: CircuitBreaker) -> Self {
+        Self { breaker }
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for CircuitBreakerMiddleware {
+    async fn handle(
+        &self,
+        req: reqwest::Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<reqwest::Response> {
+        if let Some(retry_in) = self.breaker.remaining_cool_down() {
+            return Err(reqwest_middleware::Error::middleware(CircuitOpen {
+                retry_in,
+            }));
+        }
+        let res = next.run(req, extensions).await;
+        let failed = match &res {
+            Ok(response) => response.status().is_server_error(),
+            Err(e) => e.is_timeout() || e.is_connect(),
+        };
+        self.breaker.record(failed);
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opens_after_threshold() {
+        let breaker = CircuitBreaker::new()
+            .failure_threshold(2)
+            .cool_down(Duration::from_secs(60));
+        breaker.record(true);
+        assert!(!breaker.is_open());
+        breaker.record(false);
+        breaker.record(true);
+        assert!(!breaker.is_open());
+        breaker.record(true);
+        assert!(breaker.is_open());
+        // Clones share the state of the circuit.
+        assert!(breaker.clone().is_open());
+    }
+}
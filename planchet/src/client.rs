@@ -1,5 +1,9 @@
-use crate::error::{ApiError, Error, Result};
-use crate::model::{
+use crate::auth::{AuthMiddleware, AuthProvider};
+use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerMiddleware};
+use crate::error::{ApiError, Error, FieldError, Result};
+use crate::hydrate::{self, TypeCache};
+use crate::lang::{MultilangType, SupportedLanguage};
+use crate::models::{
     self,
     request::{
         AddCollectedItemParams, EditCollectedItemParams, GetCollectedItemsParams, OAuthTokenParams,
@@ -9,43 +13,355 @@ use crate::model::{
         CataloguesResponse, CollectionsResponse, IssuersResponse, MintsResponse,
         SearchByImageResponse, SearchTypesResponse,
     },
-    CollectedItem, CollectedItems, GradePrices, MintDetail, NumistaType, OAuthToken, Publication,
-    User,
+    CollectedItem, CollectedItems, GradePrices, GrantType, IssuerDetail, MintDetail, NumistaType,
+    OAuthToken, Publication, User,
 };
-use futures::stream::{self, Stream};
+use crate::oauth::{Scope, Token};
+use crate::pagination::{self, CappedResults, StreamOptions, TypeStream};
+use crate::pictures::{self, DownloadedPicture, Pictures};
+use crate::routes::{ApiVersion, Route};
+use crate::session::Session;
+use crate::sort::{self, SortBy};
+use crate::validation;
+use crate::watch::{self, CollectionEvent};
+use futures::future;
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
 use http::Extensions;
+#[cfg(feature = "iso-codes")]
 use isolang::Language;
-use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use reqwest_middleware::{ClientBuilder as MiddlewareClientBuilder, ClientWithMiddleware, Middleware, Next};
+use secrecy::{ExposeSecret, SecretString};
 use serde::{de::DeserializeOwned, Serialize};
-use tracing::{info_span, trace, Instrument};
+use std::borrow::Borrow;
+#[cfg(feature = "logging")]
+use std::borrow::Cow;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+#[cfg(feature = "logging")]
+use std::time::Instant;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, instrument};
+#[cfg(feature = "logging")]
+use tracing::{info_span, trace, Instrument, Level};
 
 /// The main client for interacting with the Numista API.
 #[derive(Debug, Clone)]
 pub struct Client {
     client: ClientWithMiddleware,
+    /// A client without the API credentials, used to download pictures.
+    downloader: reqwest::Client,
     base_url: String,
-    lang: Option<String>,
+    version: ApiVersion,
+    lang: Option<SupportedLanguage>,
+    dry_run: bool,
+    /// The scopes of the bearer token, if they are known.
+    scopes: Option<Vec<Scope>>,
+    /// The `Authorization` header of a [`Session`], sent instead of the one
+    /// of the builder.
+    session_auth: Option<HeaderValue>,
+    /// Whether the builder was given a bearer token.
+    bearer_token: bool,
 }
 
-#[derive(Debug, Clone, serde::Deserialize)]
+/// A write request which was not sent because the client is in dry-run mode.
+///
+/// See [`ClientBuilder::dry_run`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedRequest {
+    /// The HTTP method of the request.
+    pub method: reqwest::Method,
+    /// The URL the request would have been sent to, including any query
+    /// parameters.
+    pub url: url::Url,
+    /// The JSON body of the request, if any.
+    pub body: Option<serde_json::Value>,
+}
+
+impl std::fmt::Display for PlannedRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.method, self.url)?;
+        if let Some(body) = &self.body {
+            write!(f, " {}", body)?;
+        }
+        Ok(())
+    }
+}
+
+/// The result of adding a collected item which may already be in the
+/// collection.
+///
+/// See [`Client::add_collected_item_if_absent`] and
+/// [`Client::add_or_merge_collected_item`].
+#[derive(Debug, Clone)]
+pub enum AddOutcome {
+    /// The item was added.
+    Added(CollectedItem),
+    /// A matching item was already in the collection, and was left unchanged.
+    Skipped(CollectedItem),
+    /// A matching item was already in the collection, and its quantity was
+    /// increased.
+    Merged(CollectedItem),
+}
+
+impl AddOutcome {
+    /// Returns the added, skipped, or merged item.
+    pub fn item(&self) -> &CollectedItem {
+        match self {
+            AddOutcome::Added(item) | AddOutcome::Skipped(item) | AddOutcome::Merged(item) => item,
+        }
+    }
+}
+
+/// The outcome of a write request: what the API confirmed if it was sent, or
+/// the request planned if the client is in dry-run mode.
+///
+/// See [`Client::delete_collected_item`],
+/// [`Client::add_collected_item_with_outcome`] and
+/// [`Client::edit_collected_item_with_outcome`].
+#[derive(Debug, Clone)]
+pub enum WriteOutcome<T = ()> {
+    /// The request was sent, and succeeded.
+    Sent(Confirmation<T>),
+    /// The request was not sent, because the client is in dry-run mode.
+    ///
+    /// See [`ClientBuilder::dry_run`].
+    Planned(PlannedRequest),
+}
+
+/// What the API confirmed about a successful write request.
+#[derive(Debug, Clone)]
+pub struct Confirmation<T = ()> {
+    /// The item returned by the API, if any.
+    pub value: T,
+    /// The HTTP status code returned by the API.
+    pub status: u16,
+    /// The ID generated for the request, also sent in the `X-Request-ID`
+    /// header.
+    pub request_id: Option<String>,
+    /// The values of the `Warning` headers returned by the API.
+    pub warnings: Vec<String>,
+}
+
+/// The result of deleting a collected item.
+pub type DeleteOutcome = WriteOutcome<()>;
+
+impl<T> WriteOutcome<T> {
+    /// Reads the outcome of a successful response, before its body is read.
+    fn from_response(response: &reqwest::Response, value: T) -> Self {
+        WriteOutcome::Sent(Confirmation::from_response(response, value))
+    }
+
+    /// Returns what the API confirmed, if the request was sent.
+    pub fn confirmation(&self) -> Option<&Confirmation<T>> {
+        match self {
+            WriteOutcome::Sent(confirmation) => Some(confirmation),
+            WriteOutcome::Planned(_) => None,
+        }
+    }
+
+    /// Returns what the API confirmed, if the request was sent.
+    pub fn into_confirmation(self) -> Option<Confirmation<T>> {
+        match self {
+            WriteOutcome::Sent(confirmation) => Some(confirmation),
+            WriteOutcome::Planned(_) => None,
+        }
+    }
+
+    /// Returns the request which would have been sent, if the client is in
+    /// dry-run mode.
+    pub fn planned(&self) -> Option<&PlannedRequest> {
+        match self {
+            WriteOutcome::Sent(_) => None,
+            WriteOutcome::Planned(planned) => Some(planned),
+        }
+    }
+
+    /// Returns the item returned by the API, if the request was sent.
+    pub fn into_value(self) -> Option<T> {
+        match self {
+            WriteOutcome::Sent(confirmation) => Some(confirmation.value),
+            WriteOutcome::Planned(_) => None,
+        }
+    }
+
+    /// Replaces the value of the outcome, keeping what the API confirmed.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> WriteOutcome<U> {
+        match self {
+            WriteOutcome::Sent(confirmation) => WriteOutcome::Sent(confirmation.map(f)),
+            WriteOutcome::Planned(planned) => WriteOutcome::Planned(planned),
+        }
+    }
+}
+
+impl<T> Confirmation<T> {
+    /// Reads the confirmation of a successful response, before its body is
+    /// read.
+    fn from_response(response: &reqwest::Response, value: T) -> Self {
+        Self {
+            value,
+            status: response.status().as_u16(),
+            request_id: response
+                .extensions()
+                .get::<RequestId>()
+                .map(|id| id.0.clone()),
+            warnings: response
+                .headers()
+                .get_all(reqwest::header::WARNING)
+                .iter()
+                .filter_map(|v| v.to_str().ok())
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+
+    /// Replaces the value, keeping what the API confirmed.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Confirmation<U> {
+        Confirmation {
+            value: f(self.value),
+            status: self.status,
+            request_id: self.request_id,
+            warnings: self.warnings,
+        }
+    }
+}
+
+/// The body of an error response.
+///
+/// Every field is optional, and the code and field errors may have any shape,
+/// so that an unexpected body never hides the status of the response.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
 struct ApiErrorResponse {
-    error_message: String,
+    error_message: Option<String>,
+    #[serde(alias = "code")]
+    error_code: Option<serde_json::Value>,
+    errors: Option<serde_json::Value>,
+}
+
+/// The per-field validation errors of an error response, either as a list
+/// or as a map from field names to one or more messages.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(untagged)]
+enum FieldErrorsResponse {
+    List(Vec<FieldErrorResponse>),
+    Map(std::collections::BTreeMap<String, OneOrMany>),
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+struct FieldErrorResponse {
+    field: String,
+    #[serde(alias = "error_message")]
+    message: String,
 }
 
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl FieldErrorsResponse {
+    fn into_field_errors(self) -> Vec<FieldError> {
+        match self {
+            FieldErrorsResponse::List(errors) => errors
+                .into_iter()
+                .map(|e| FieldError {
+                    field: e.field,
+                    message: e.message,
+                })
+                .collect(),
+            FieldErrorsResponse::Map(errors) => errors
+                .into_iter()
+                .flat_map(|(field, messages)| {
+                    let messages = match messages {
+                        OneOrMany::One(message) => vec![message],
+                        OneOrMany::Many(messages) => messages,
+                    };
+                    messages.into_iter().map(move |message| FieldError {
+                        field: field.clone(),
+                        message,
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Turns an error response into [`Error::ApiError`].
+///
+/// A body which is not the expected JSON object is kept as the message, or
+/// the reason of the status if it is empty.
 async fn parse_api_error(response: reqwest::Response) -> Error {
-    let status_code = response.status().as_u16();
-    let api_error_response = match response.json::<ApiErrorResponse>().await {
-        Ok(api_error) => api_error,
+    let status = response.status();
+    let request_id = response
+        .extensions()
+        .get::<RequestId>()
+        .map(|id| id.0.clone());
+    let text = match response.text().await {
+        Ok(text) => text,
         Err(e) => return e.into(),
     };
+    let body: ApiErrorResponse = serde_json::from_str(&text).unwrap_or_default();
+
+    let message = body.error_message.unwrap_or_else(|| match text.trim() {
+        "" => status.canonical_reason().unwrap_or_default().to_string(),
+        text => text.to_string(),
+    });
+    let code = body.error_code.and_then(|code| match code {
+        serde_json::Value::String(code) => Some(code),
+        serde_json::Value::Number(code) => Some(code.to_string()),
+        _ => None,
+    });
+    let field_errors = body
+        .errors
+        .and_then(|errors| serde_json::from_value::<FieldErrorsResponse>(errors).ok())
+        .map(FieldErrorsResponse::into_field_errors)
+        .unwrap_or_default();
 
     Error::ApiError(ApiError {
-        message: api_error_response.error_message,
-        status: status_code,
+        message,
+        status: status.as_u16(),
+        request_id,
+        code,
+        field_errors,
     })
 }
 
+/// Returns the `Authorization` header of a bearer token, marked as sensitive.
+fn bearer_header(token: &str) -> Result<HeaderValue> {
+    if token.trim().is_empty() {
+        return Err(Error::InvalidToken("the token is empty".to_string()));
+    }
+    let mut value = HeaderValue::from_str(&format!("Bearer {}", token)).map_err(|_| {
+        Error::InvalidToken("the token contains characters not allowed in a header".to_string())
+    })?;
+    value.set_sensitive(true);
+    Ok(value)
+}
+
+
+/// Ends a stream with [`Error::Cancelled`] once `token` is cancelled.
+fn with_cancellation<'a, T: 'a>(
+    stream: impl Stream<Item = Result<T>> + 'a,
+    token: CancellationToken,
+) -> impl Stream<Item = Result<T>> + 'a {
+    stream::unfold(
+        (Box::pin(stream), Some(token)),
+        |(mut stream, token)| async move {
+            let token = token?;
+            tokio::select! {
+                biased;
+                _ = token.cancelled() => Some((Err(Error::Cancelled), (stream, None))),
+                item = stream.next() => item.map(|item| (item, (stream, Some(token)))),
+            }
+        },
+    )
+}
+
 async fn process_response<T: DeserializeOwned>(
     response: reqwest::Response,
 ) -> Result<T> {
@@ -56,9 +372,229 @@ async fn process_response<T: DeserializeOwned>(
     Err(parse_api_error(response).await)
 }
 
-#[derive(Default)]
-struct LoggingMiddleware;
+async fn process_write_response<T: DeserializeOwned>(
+    response: reqwest::Response,
+) -> Result<WriteOutcome<T>> {
+    if response.status().is_success() {
+        let confirmation = Confirmation::from_response(&response, ());
+        let value = response.json::<T>().await?;
+        return Ok(WriteOutcome::Sent(confirmation.map(|()| value)));
+    }
+
+    Err(parse_api_error(response).await)
+}
+
+/// Returns the language of a response given in its `Content-Language`
+/// header, if it is a supported one, e.g. `fr` for `fr-FR`.
+fn response_language(response: &reqwest::Response) -> Option<SupportedLanguage> {
+    let value = response
+        .headers()
+        .get(reqwest::header::CONTENT_LANGUAGE)?
+        .to_str()
+        .ok()?;
+    let tag = value.split(',').next()?.trim();
+    SupportedLanguage::from_code(tag.split(['-', '_']).next()?)
+}
+
+/// The header in which the API key is sent by default.
+pub const DEFAULT_API_KEY_HEADER: &str = "Numista-API-Key";
+
+/// The header in which the generated request ID is sent.
+pub(crate) const REQUEST_ID_HEADER: &str = "X-Request-ID";
+
+/// Headers whose values are never logged.
+#[cfg(feature = "logging")]
+const REDACTED_HEADERS: &[&str] = &["numista-api-key", "authorization"];
+
+/// Query parameters whose values are never logged.
+#[cfg(feature = "logging")]
+const REDACTED_PARAMS: &[&str] = &["client_secret", "code"];
+
+/// Fields of JSON bodies whose values are never logged.
+#[cfg(feature = "logging")]
+const REDACTED_FIELDS: &[&str] = &["access_token", "refresh_token"];
+
+/// The text logged in place of a credential.
+#[cfg(feature = "logging")]
+const REDACTED: &str = "[REDACTED]";
+
+/// The ID generated for a request, stored in the extensions of its response.
+#[derive(Debug, Clone)]
+struct RequestId(String);
+
+impl RequestId {
+    /// Generates an ID which is unique within the process, and very likely
+    /// unique across processes.
+    fn generate() -> Self {
+        static PREFIX: OnceLock<u32> = OnceLock::new();
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+        let prefix = PREFIX.get_or_init(|| {
+            let nanos = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.subsec_nanos() ^ d.as_secs() as u32)
+                .unwrap_or_default();
+            nanos ^ std::process::id().rotate_left(16)
+        });
+        let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+        RequestId(format!("{:08x}-{:08x}", prefix, count))
+    }
+}
+
+/// Formats headers for logging, redacting credentials.
+#[cfg(feature = "logging")]
+fn redact_headers(headers: &HeaderMap) -> String {
+    let entries: Vec<String> = headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if REDACTED_HEADERS.contains(&name.as_str()) || value.is_sensitive() {
+                REDACTED
+            } else {
+                value.to_str().unwrap_or("[binary]")
+            };
+            format!("{}: {}", name, value)
+        })
+        .collect();
+    format!("{{{}}}", entries.join(", "))
+}
+
+/// Returns the URL with the values of sensitive query parameters redacted.
+#[cfg(feature = "logging")]
+fn redact_url(url: &url::Url) -> url::Url {
+    if !url
+        .query_pairs()
+        .any(|(name, _)| REDACTED_PARAMS.contains(&name.as_ref()))
+    {
+        return url.clone();
+    }
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(name, value)| {
+            let value = if REDACTED_PARAMS.contains(&name.as_ref()) {
+                REDACTED.to_string()
+            } else {
+                value.into_owned()
+            };
+            (name.into_owned(), value)
+        })
+        .collect();
+    let mut redacted = url.clone();
+    redacted.query_pairs_mut().clear().extend_pairs(pairs);
+    redacted
+}
+
+/// Returns the body with the values of sensitive fields of a JSON object
+/// redacted, such as the tokens of an OAuth response.
+#[cfg(feature = "logging")]
+fn redact_body(body: &str) -> Cow<'_, str> {
+    let Ok(serde_json::Value::Object(mut object)) = serde_json::from_str(body) else {
+        return Cow::Borrowed(body);
+    };
+    let mut redacted = false;
+    for field in REDACTED_FIELDS {
+        if let Some(value) = object.get_mut(*field) {
+            *value = REDACTED.into();
+            redacted = true;
+        }
+    }
+    if redacted {
+        Cow::Owned(serde_json::Value::Object(object).to_string())
+    } else {
+        Cow::Borrowed(body)
+    }
+}
+
+/// How much of request and response bodies is logged.
+#[cfg(feature = "logging")]
+#[derive(Debug, Clone, Copy)]
+struct BodyLogging {
+    /// Whether bodies are logged at all.
+    enabled: bool,
+    /// The maximum number of bytes of each body logged.
+    max_size: Option<usize>,
+    /// The fraction of requests whose bodies are logged, between 0 and 1.
+    sample_rate: f64,
+}
+
+#[cfg(feature = "logging")]
+impl Default for BodyLogging {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_size: None,
+            sample_rate: 1.0,
+        }
+    }
+}
+
+/// Truncates a body to at most `max_size` bytes, noting its full size.
+#[cfg(feature = "logging")]
+fn truncate_body(body: &str, max_size: Option<usize>) -> Cow<'_, str> {
+    match max_size {
+        Some(max_size) if body.len() > max_size => {
+            let mut end = max_size;
+            while !body.is_char_boundary(end) {
+                end -= 1;
+            }
+            Cow::Owned(format!("{}... ({} bytes total)", &body[..end], body.len()))
+        }
+        _ => Cow::Borrowed(body),
+    }
+}
+
+/// Logs each request and its response, within a span carrying the request ID.
+#[cfg(feature = "logging")]
+struct LoggingMiddleware {
+    /// The credentials of the client, scrubbed from everything logged.
+    secrets: Vec<SecretString>,
+    bodies: BodyLogging,
+    /// The number of requests seen, used to sample which bodies are logged.
+    requests: AtomicU64,
+}
+
+#[cfg(feature = "logging")]
+impl LoggingMiddleware {
+    fn new(secrets: Vec<SecretString>, bodies: BodyLogging) -> Self {
+        Self {
+            secrets,
+            bodies,
+            requests: AtomicU64::new(0),
+        }
+    }
+
+    /// Decides whether to log the bodies of the next request.
+    ///
+    /// Sampling is deterministic: with a rate of 0.25, the bodies of every
+    /// fourth request are logged.
+    fn sample(&self) -> bool {
+        if !self.bodies.enabled {
+            return false;
+        }
+        let rate = self.bodies.sample_rate;
+        let n = self.requests.fetch_add(1, Ordering::Relaxed) as f64;
+        ((n + 1.0) * rate).floor() > (n * rate).floor()
+    }
+
+    /// Formats a body for logging, or returns `None` if it is not text.
+    ///
+    /// The whole body is scrubbed before it is truncated, so that no prefix of
+    /// a credential cut by the truncation is left in the log.
+    fn body_text(&self, bytes: &[u8]) -> Option<String> {
+        let body = self.scrub(&redact_body(std::str::from_utf8(bytes).ok()?));
+        Some(truncate_body(&body, self.bodies.max_size).into_owned())
+    }
 
+    /// Replaces any credential of the client in `text` with a placeholder.
+    fn scrub(&self, text: &str) -> String {
+        self.secrets
+            .iter()
+            .map(|s| s.expose_secret())
+            .filter(|s| !s.is_empty())
+            .fold(text.to_string(), |text, secret| text.replace(secret, REDACTED))
+    }
+}
+
+#[cfg(feature = "logging")]
 #[async_trait::async_trait]
 impl Middleware for LoggingMiddleware {
     async fn handle(
@@ -67,26 +603,51 @@ impl Middleware for LoggingMiddleware {
         extensions: &mut Extensions,
         next: Next<'_>,
     ) -> reqwest_middleware::Result<reqwest::Response> {
+        let request_id = req
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
         let span = info_span!(
             "Request",
             method = %req.method(),
-            url = %req.url(),
+            url = %self.scrub(redact_url(req.url()).as_str()),
+            request_id = %request_id,
         );
 
+        // Bodies are only buffered when they will be logged.
+        let log_bodies = tracing::enabled!(Level::TRACE) && self.sample();
+
         async move {
-            trace!("Request headers: {:?}", req.headers());
-            if let Some(body) = req.body() {
-                if let Some(bytes) = body.as_bytes() {
-                    if let Ok(str_body) = std::str::from_utf8(bytes) {
-                        trace!("Request body: {}", str_body);
-                    }
+            trace!("Request headers: {}", self.scrub(&redact_headers(req.headers())));
+            if log_bodies {
+                if let Some(body) = req
+                    .body()
+                    .and_then(|b| b.as_bytes())
+                    .and_then(|b| self.body_text(b))
+                {
+                    trace!("Request body: {}", body);
                 }
             }
 
+            let start = Instant::now();
             let res = next.run(req, extensions).await;
 
             match res {
                 Ok(response) => {
+                    trace!(
+                        "Response status: {} in {} ms",
+                        response.status(),
+                        start.elapsed().as_millis()
+                    );
+                    trace!(
+                        "Response headers: {}",
+                        self.scrub(&redact_headers(response.headers()))
+                    );
+                    if !log_bodies {
+                        return Ok(response);
+                    }
+
                     let status = response.status();
                     let headers = response.headers().clone();
                     let body_bytes = match response.bytes().await {
@@ -96,24 +657,23 @@ impl Middleware for LoggingMiddleware {
                         }
                     };
 
-                    trace!("Response status: {}", status);
-                    trace!("Response headers: {:?}", headers);
-                    if let Ok(str_body) = std::str::from_utf8(&body_bytes) {
-                        if !str_body.is_empty() {
-                            trace!("Response body: {}", str_body);
-                        }
+                    if let Some(body) = self.body_text(&body_bytes).filter(|b| !b.is_empty()) {
+                        trace!("Response body: {}", body);
                     }
 
                     let new_body = reqwest::Body::from(body_bytes);
-                    let mut new_response_builder = http::Response::builder()
-                        .status(status);
+                    let mut new_response_builder = http::Response::builder().status(status);
                     *new_response_builder.headers_mut().unwrap() = headers;
                     let new_response = new_response_builder.body(new_body).unwrap();
 
                     Ok(reqwest::Response::from(new_response))
                 }
                 Err(e) => {
-                    trace!("Request failed: {:?}", e);
+                    trace!(
+                        "Request failed after {} ms: {}",
+                        start.elapsed().as_millis(),
+                        self.scrub(&format!("{:?}", e))
+                    );
                     Err(e)
                 }
             }
@@ -123,22 +683,194 @@ impl Middleware for LoggingMiddleware {
     }
 }
 
+/// Limits the number of requests in flight at once, across all the clones of
+/// a client.
+struct ConcurrencyLimitMiddleware {
+    permits: Arc<Semaphore>,
+}
+
+#[async_trait::async_trait]
+impl Middleware for ConcurrencyLimitMiddleware {
+    async fn handle(
+        &self,
+        req: reqwest::Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<reqwest::Response> {
+        // The semaphore is never closed.
+        let permit = self.permits.acquire().await.ok();
+        let response = next.run(req, extensions).await?;
+
+        // The body is read before releasing the permit, so that a slow
+        // response counts against the limit until it is fully received.
+        let status = response.status();
+        let version = response.version();
+        let headers = response.headers().clone();
+        let response_extensions = response.extensions().clone();
+        let body = response.bytes().await?;
+        drop(permit);
+
+        let mut builder = http::Response::builder().status(status).version(version);
+        *builder.headers_mut().unwrap() = headers;
+        *builder.extensions_mut().unwrap() = response_extensions;
+        Ok(reqwest::Response::from(
+            builder.body(reqwest::Body::from(body)).unwrap(),
+        ))
+    }
+}
+
+/// Sends a generated ID with each request, and stores it in the extensions of
+/// the response.
+struct RequestIdMiddleware;
+
+#[async_trait::async_trait]
+impl Middleware for RequestIdMiddleware {
+    async fn handle(
+        &self,
+        mut req: reqwest::Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<reqwest::Response> {
+        let request_id = RequestId::generate();
+        if let Ok(value) = HeaderValue::from_str(&request_id.0) {
+            req.headers_mut().insert(REQUEST_ID_HEADER, value);
+        }
+        let mut response = next.run(req, extensions).await?;
+        response.extensions_mut().insert(request_id);
+        Ok(response)
+    }
+}
+
 macro_rules! add_lang_param {
     ($self:expr, $req:expr) => {
         if let Some(ref l) = $self.lang {
-            $req = $req.query(&[("lang", l)]);
+            $req = $req.query(&[("lang", l.code())]);
         }
     };
 }
 
 impl Client {
-    async fn get_request<T, Q>(&self, path: &str, query: Option<&Q>) -> Result<T>
+    /// Returns a client sending requests with a token, e.g. for a
+    /// [`Session`].
+    pub(crate) fn with_token(&self, token: &Token) -> Result<Client> {
+        Ok(Client {
+            scopes: Some(token.granted_scopes()),
+            session_auth: Some(bearer_header(&token.access_token)?),
+            ..self.clone()
+        })
+    }
+
+    /// Checks if requests are sent with a bearer token, of the builder or of
+    /// a [`Session`].
+    fn has_bearer_token(&self) -> bool {
+        self.bearer_token || self.session_auth.is_some()
+    }
+
+    /// Turns the refusal of the API to read the collection of a user without
+    /// a bearer token into [`Error::AuthorizationRequired`].
+    ///
+    /// A 401 is a refusal of the credentials themselves, e.g. an invalid API
+    /// key, and any refusal with a bearer token one of the token, e.g. an
+    /// expired one: both are left as they are.
+    fn authorization_required(&self, error: Error, user_id: i64) -> Error {
+        match &error {
+            Error::ApiError(e) if e.status == 403 && !self.has_bearer_token() => {
+                Error::AuthorizationRequired(user_id)
+            }
+            _ => error,
+        }
+    }
+
+    /// Requests a token for the user of the API key, with the given scopes,
+    /// and returns a [`Session`] of that user.
+    ///
+    /// This is the `client_credentials` grant, so the API key must be
+    /// associated with a user. To start a session with a token from the
+    /// authorization code flow instead, use [`Session::new`].
+    ///
+    /// # Arguments
+    ///
+    /// * `scopes` - The scopes to request.
+    #[instrument(name = "numista.authenticate", skip_all)]
+    pub async fn authenticate(&self, scopes: &[Scope]) -> Result<Session> {
+        let scopes: Vec<String> = scopes.iter().map(|s| s.as_str().to_string()).collect();
+        let params = OAuthTokenParams::new(GrantType::ClientCredentials).scope(scopes.join(","));
+        let token = Token::new(&self.get_oauth_token(&params).await?, &scopes);
+        Session::new(self, token)
+    }
+
+    /// Starts a request, with the token of the session, if any.
+    fn request(&self, method: reqwest::Method, url: &str) -> reqwest_middleware::RequestBuilder {
+        let req = self.client.request(method, url);
+        match &self.session_auth {
+            Some(auth) => req.header(reqwest::header::AUTHORIZATION, auth.clone()),
+            None => req,
+        }
+    }
+
+    /// Checks that the bearer token was granted a scope, if its scopes are
+    /// known.
+    fn require_scope(&self, scope: Scope) -> Result<()> {
+        match &self.scopes {
+            Some(scopes) if !scopes.contains(&scope) => Err(Error::MissingScope(scope)),
+            _ => Ok(()),
+        }
+    }
+
+    fn plan(&self, req: &reqwest_middleware::RequestBuilder) -> Result<Option<PlannedRequest>> {
+        if !self.dry_run {
+            return Ok(None);
+        }
+        let req = req
+            .try_clone()
+            .ok_or_else(|| Error::Request("request cannot be planned".into()))?
+            .build()?;
+        let body = req
+            .body()
+            .and_then(|b| b.as_bytes())
+            .map(serde_json::from_slice)
+            .transpose()?;
+        let planned = PlannedRequest {
+            method: req.method().clone(),
+            url: req.url().clone(),
+            body,
+        };
+        info!("Dry run, not sending: {}", planned);
+        Ok(Some(planned))
+    }
+
+    /// Returns the URL of an endpoint.
+    fn url(&self, route: Route) -> String {
+        format!("{}{}", self.base_url, route.path(self.version))
+    }
+
+    /// Returns the version of the API the client uses.
+    pub fn api_version(&self) -> ApiVersion {
+        self.version
+    }
+
+    /// Returns the ISO 639-1 code of the language of the requests, if set.
+    pub fn lang(&self) -> Option<&str> {
+        self.lang.map(|lang| lang.code())
+    }
+
+    /// Returns the language of the requests, if set.
+    pub fn language(&self) -> Option<SupportedLanguage> {
+        self.lang
+    }
+
+    /// Returns the scopes of the bearer token, if they are known.
+    pub fn scopes(&self) -> Option<&[Scope]> {
+        self.scopes.as_deref()
+    }
+
+    async fn get_request<T, Q>(&self, route: Route<'_>, query: Option<&Q>) -> Result<T>
     where
         T: DeserializeOwned,
         Q: Serialize + ?Sized,
     {
-        let url = format!("{}{}", self.base_url, path);
-        let mut req = self.client.get(&url);
+        let url = self.url(route);
+        let mut req = self.request(reqwest::Method::GET, &url);
         add_lang_param!(self, req);
         if let Some(q) = query {
             req = req.query(q);
@@ -152,9 +884,56 @@ impl Client {
     /// # Arguments
     ///
     /// * `type_id` - The ID of the type to get.
+    #[instrument(name = "numista.get_type", skip_all)]
     pub async fn get_type(&self, type_id: i64) -> Result<NumistaType> {
-        self.get_request(&format!("/types/{}", type_id), None::<&()>)
-            .await
+        self.get_type_in(type_id, self.lang).await
+    }
+
+    /// Gets a type in several languages at once, e.g. for bilingual labels.
+    ///
+    /// The type is fetched concurrently in each language, regardless of the
+    /// language of the client, and fails if any request fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `type_id` - The ID of the type to get.
+    /// * `langs` - The languages to get the type in.
+    #[instrument(name = "numista.get_type_multilang", skip_all)]
+    pub async fn get_type_multilang(
+        &self,
+        type_id: i64,
+        langs: &[SupportedLanguage],
+    ) -> Result<MultilangType> {
+        let mut langs = langs.to_vec();
+        langs.sort();
+        langs.dedup();
+        let types = future::try_join_all(
+            langs
+                .iter()
+                .map(|&lang| self.get_type_in(type_id, Some(lang))),
+        )
+        .await?;
+        Ok(MultilangType::new(type_id, langs.into_iter().zip(types)))
+    }
+
+    /// Gets a type in a language, or in the default one of the API.
+    async fn get_type_in(
+        &self,
+        type_id: i64,
+        lang: Option<SupportedLanguage>,
+    ) -> Result<NumistaType> {
+        let url = self.url(Route::Type(type_id));
+        let mut req = self.request(reqwest::Method::GET, &url);
+        if let Some(lang) = lang {
+            req = req.query(&[("lang", lang.code())]);
+        }
+        let response = req.send().await?;
+        let lang = response_language(&response)
+            .or(lang)
+            .unwrap_or(SupportedLanguage::English);
+        let mut numista_type: NumistaType = process_response(response).await?;
+        numista_type.lang = Some(lang);
+        Ok(numista_type)
     }
 
     /// Gets the issues of a type.
@@ -162,9 +941,41 @@ impl Client {
     /// # Arguments
     ///
     /// * `type_id` - The ID of the type to get the issues for.
-    pub async fn get_issues(&self, type_id: i64) -> Result<Vec<model::Issue>> {
-        self.get_request(&format!("/types/{}/issues", type_id), None::<&()>)
-            .await
+    #[instrument(name = "numista.get_issues", skip_all)]
+    pub async fn get_issues(&self, type_id: i64) -> Result<Vec<models::Issue>> {
+        self.get_request(Route::Issues(type_id), None::<&()>).await
+    }
+
+    /// Finds the issue of a type which best matches a year and mint letter.
+    ///
+    /// All issues of the type are fetched and filtered with [`models::Issue::matches`].
+    /// When several issues match, issues dated with exactly `year` are
+    /// preferred over undated issues whose year range contains it, and, if no
+    /// `mint_letter` is given, issues without a mint letter are preferred.
+    ///
+    /// Returns `None` if no issue matches.
+    ///
+    /// # Arguments
+    ///
+    /// * `type_id` - The ID of the type to search the issues of.
+    /// * `year` - The year on the item.
+    /// * `mint_letter` - The mint letter on the item, if any.
+    #[instrument(name = "numista.find_issue", skip_all)]
+    pub async fn find_issue(
+        &self,
+        type_id: i64,
+        year: Option<i32>,
+        mint_letter: Option<&str>,
+    ) -> Result<Option<models::Issue>> {
+        let issues = self.get_issues(type_id).await?;
+        Ok(issues
+            .into_iter()
+            .filter(|i| i.matches(year, mint_letter, &[]))
+            .min_by_key(|i| {
+                let inexact_year = year.is_some() && i.year != year && i.gregorian_year != year;
+                let extra_letter = mint_letter.is_none() && i.mint_letter.is_some();
+                (inexact_year, extra_letter)
+            }))
     }
 
     /// Gets the prices for an issue.
@@ -174,6 +985,7 @@ impl Client {
     /// * `type_id` - The ID of the type.
     /// * `issue_id` - The ID of the issue.
     /// * `currency` - The currency to get the prices in.
+    #[instrument(name = "numista.get_prices", skip_all)]
     pub async fn get_prices(
         &self,
         type_id: i64,
@@ -187,11 +999,8 @@ impl Client {
 
         let params = GetPricesParams { currency };
 
-        self.get_request(
-            &format!("/types/{}/issues/{}/prices", type_id, issue_id),
-            Some(&params),
-        )
-        .await
+        self.get_request(Route::Prices { type_id, issue_id }, Some(&params))
+            .await
     }
 
     /// Searches for types in the Numista catalogue.
@@ -199,11 +1008,36 @@ impl Client {
     /// # Arguments
     ///
     /// * `params` - The search parameters.
+    #[instrument(name = "numista.search_types", skip_all)]
     pub async fn search_types(
         &self,
         params: &SearchTypesParams,
     ) -> Result<SearchTypesResponse> {
-        self.get_request("/types", Some(params)).await
+        validation::check(params)?;
+        self.get_request(Route::Types, Some(params)).await
+    }
+
+    /// Searches for types, returning the raw response body.
+    ///
+    /// The body can be parsed with [`models::SearchTypesResponseRef::from_slice`],
+    /// which borrows strings from the body instead of allocating them.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - The search parameters.
+    #[instrument(name = "numista.search_types_raw", skip_all)]
+    pub async fn search_types_raw(&self, params: &SearchTypesParams) -> Result<Vec<u8>> {
+        validation::check(params)?;
+        let url = self.url(Route::Types);
+        let mut req = self.request(reqwest::Method::GET, &url);
+        add_lang_param!(self, req);
+        let response = req.query(params).send().await?;
+
+        if response.status().is_success() {
+            return Ok(response.bytes().await?.to_vec());
+        }
+
+        Err(parse_api_error(response).await)
     }
 
     /// Returns a stream of all types matching the search parameters.
@@ -216,84 +1050,146 @@ impl Client {
     pub fn stream_all_types<'a>(
         &self,
         params: SearchTypesParams,
-    ) -> impl Stream<Item = Result<model::SearchTypeResult>> + 'a {
-        struct State {
-            client: Client,
-            params: SearchTypesParams,
-            current_page: i64,
-            buffer: std::vec::IntoIter<model::SearchTypeResult>,
-            items_fetched: i64,
-            total_items: Option<i64>,
-        }
-
-        let initial_state = State {
-            client: self.clone(),
-            params,
-            current_page: 1,
-            buffer: Vec::new().into_iter(),
-            items_fetched: 0,
-            total_items: None,
-        };
-
-        stream::unfold(initial_state, |mut state| async move {
-            // Stop if we have fetched all items OR if the last page was empty.
-            if let Some(total) = state.total_items {
-                if state.items_fetched >= total {
-                    return None;
-                }
-            }
+    ) -> impl Stream<Item = Result<models::SearchTypeResult>> + 'a {
+        pagination::stream_types(self, params, StreamOptions::default())
+    }
 
-            // If we have items in the buffer, return the next one
-            if let Some(item) = state.buffer.next() {
-                state.items_fetched += 1;
-                return Some((Ok(item), state));
-            }
+    /// Returns a stream of all types matching the search parameters, with
+    /// options to harden long pulls.
+    ///
+    /// The returned [`TypeStream`] reports what it has done through
+    /// [`TypeStream::stats`], which can be checked once the stream completes.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - The search parameters.
+    /// * `options` - The options of the pull.
+    pub fn stream_types<'a>(
+        &self,
+        params: SearchTypesParams,
+        options: StreamOptions,
+    ) -> TypeStream<'a> {
+        pagination::stream_types(self, params, options)
+    }
 
-            // Buffer is empty, fetch the next page
-            let mut params = state.params.clone();
-            params = params.page(state.current_page);
+    /// Collects the types matching the search parameters, up to
+    /// `max_items`.
+    ///
+    /// The pages are fetched as with [`Client::stream_all_types`], and
+    /// fetching stops once `max_items` types are collected, so that memory
+    /// use is bounded however many types match. Whether more types matched is
+    /// reported by [`CappedResults::truncated`].
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - The search parameters.
+    /// * `max_items` - The maximum number of types to collect.
+    #[instrument(name = "numista.search_types_all", skip_all)]
+    pub async fn search_types_all(
+        &self,
+        params: SearchTypesParams,
+        max_items: usize,
+    ) -> Result<CappedResults<models::SearchTypeResult>> {
+        pagination::collect_types(self, params, max_items).await
+    }
 
-            match state.client.search_types(&params).await {
-                Ok(response) => {
-                    if state.total_items.is_none() {
-                        state.total_items = Some(response.count);
-                    }
+    /// Fetches the full types of search results, in the same order.
+    ///
+    /// At most [`hydrate::HYDRATE_CONCURRENCY`] types are fetched at the same
+    /// time, and a type appearing in several results is only fetched once.
+    ///
+    /// # Arguments
+    ///
+    /// * `results` - The search results to fetch the types of.
+    pub fn hydrate<'a, I>(&self, results: I) -> impl Stream<Item = Result<NumistaType>> + 'a
+    where
+        I: IntoIterator,
+        I::Item: Borrow<models::SearchTypeResult>,
+    {
+        self.hydrate_with_cache(results, &TypeCache::new())
+    }
 
-                    // If the page is empty, we're done for good.
-                    if response.types.is_empty() {
-                        state.total_items = Some(state.items_fetched); // Prevent any further calls
-                        return None;
-                    }
+    /// Fetches the full types of search results, like [`Client::hydrate`],
+    /// using types from `cache` rather than fetching them again, and adding
+    /// the types fetched to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `results` - The search results to fetch the types of.
+    /// * `cache` - The types already fetched.
+    pub fn hydrate_with_cache<'a, I>(
+        &self,
+        results: I,
+        cache: &TypeCache,
+    ) -> impl Stream<Item = Result<NumistaType>> + 'a
+    where
+        I: IntoIterator,
+        I::Item: Borrow<models::SearchTypeResult>,
+    {
+        let type_ids = results.into_iter().map(|r| r.borrow().id).collect();
+        hydrate::hydrate(self, type_ids, cache.clone())
+    }
 
-                    // Increment page number and refill buffer
-                    state.current_page += 1;
-                    state.buffer = response.types.into_iter();
+    /// Streams all types matching the search parameters, like
+    /// [`Client::stream_all_types`], until `token` is cancelled.
+    ///
+    /// Once the token is cancelled, any request in flight is dropped and the
+    /// stream yields a single [`Error::Cancelled`] before ending.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - The search parameters.
+    /// * `token` - The token which cancels the stream.
+    pub fn stream_all_types_with_cancellation<'a>(
+        &self,
+        params: SearchTypesParams,
+        token: CancellationToken,
+    ) -> impl Stream<Item = Result<models::SearchTypeResult>> + 'a {
+        with_cancellation(self.stream_all_types(params), token)
+    }
 
-                    // Return the first item from the new buffer
-                    if let Some(item) = state.buffer.next() {
-                        state.items_fetched += 1;
-                        Some((Ok(item), state))
-                    } else {
-                        None
+    /// Streams all types matching the search parameters in a deterministic
+    /// order.
+    ///
+    /// Unlike [`Client::stream_all_types`], every page is fetched before the
+    /// first type is yielded, so the order does not depend on how the API
+    /// orders results between pages. See [`SortBy`] for the orders available.
+    /// If a page cannot be fetched, the stream only yields the error.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - The search parameters.
+    /// * `by` - The order in which to yield the types.
+    pub fn stream_all_types_sorted<'a>(
+        &self,
+        params: SearchTypesParams,
+        by: SortBy,
+    ) -> impl Stream<Item = Result<models::SearchTypeResult>> + 'a {
+        let all = self.stream_all_types(params);
+        stream::once(async move { all.try_collect::<Vec<_>>().await })
+            .map(move |result| {
+                let results = match result {
+                    Ok(mut types) => {
+                        sort::sort_types(&mut types, by);
+                        types.into_iter().map(Ok).collect()
                     }
-                }
-                Err(e) => {
-                    // On error, stop streaming and return the error
-                    state.total_items = Some(state.items_fetched); // Prevent further calls
-                    Some((Err(e), state))
-                }
-            }
-        })
+                    Err(e) => vec![Err(e)],
+                };
+                stream::iter(results)
+            })
+            .flatten()
     }
 
     /// Gets the list of issuers.
+    #[instrument(name = "numista.get_issuers", skip_all)]
     pub async fn get_issuers(&self) -> Result<IssuersResponse> {
-        self.get_request("/issuers", None::<&()>).await
+        self.get_request(Route::Issuers, None::<&()>).await
     }
 
     /// Gets the list of mints.
+    #[instrument(name = "numista.get_mints", skip_all)]
     pub async fn get_mints(&self) -> Result<MintsResponse> {
-        self.get_request("/mints", None::<&()>).await
+        self.get_request(Route::Mints, None::<&()>).await
     }
 
     /// Gets a single mint.
@@ -301,24 +1197,72 @@ impl Client {
     /// # Arguments
     ///
     /// * `mint_id` - The ID of the mint to get.
+    #[instrument(name = "numista.get_mint", skip_all)]
     pub async fn get_mint(&self, mint_id: i64) -> Result<MintDetail> {
-        self.get_request(&format!("/mints/{}", mint_id), None::<&()>)
-            .await
+        self.get_request(Route::Mint(mint_id), None::<&()>).await
+    }
+
+    /// Gets the types struck at a mint.
+    ///
+    /// The API cannot search types by mint, so this searches the types of
+    /// the country of the mint, fetches them with [`Client::hydrate`], and
+    /// keeps those listing the mint. At most `max_candidates` types of the
+    /// country are considered: [`CappedResults::truncated`] is set if there
+    /// were more. No types are returned if the country of the mint is
+    /// unknown.
+    ///
+    /// # Arguments
+    ///
+    /// * `mint_id` - The ID of the mint.
+    /// * `max_candidates` - The maximum number of types of the country of the
+    ///   mint to fetch.
+    #[instrument(name = "numista.get_types_by_mint", skip_all)]
+    pub async fn get_types_by_mint(
+        &self,
+        mint_id: i64,
+        max_candidates: usize,
+    ) -> Result<CappedResults<NumistaType>> {
+        let mint = self.get_mint(mint_id).await?;
+        let Some(country) = mint.country else {
+            return Ok(CappedResults {
+                items: Vec::new(),
+                truncated: false,
+                total: None,
+            });
+        };
+        let params = SearchTypesParams::new().issuer(country.code);
+        let candidates = self.search_types_all(params, max_candidates).await?;
+        let types: Vec<NumistaType> = self.hydrate(&candidates.items).try_collect().await?;
+        Ok(CappedResults {
+            items: types
+                .into_iter()
+                .filter(|t| t.mints.iter().flatten().any(|m| m.id == mint_id))
+                .collect(),
+            truncated: candidates.truncated,
+            // The API reports the number of types of the country, not of the
+            // mint.
+            total: None,
+        })
     }
 
     /// Gets the list of catalogues.
+    #[instrument(name = "numista.get_catalogues", skip_all)]
     pub async fn get_catalogues(&self) -> Result<CataloguesResponse> {
-        self.get_request("/catalogues", None::<&()>).await
+        self.get_request(Route::Catalogues, None::<&()>).await
     }
 
     /// Gets a single publication.
     ///
+    /// The API cannot search publications. Their IDs appear in the URLs of
+    /// bibliography pages on the Numista website, e.g. `L106610`, and in the
+    /// [`part_of`](models::Publication::part_of) of other publications.
+    ///
     /// # Arguments
     ///
     /// * `id` - The ID of the publication to get.
+    #[instrument(name = "numista.get_publication", skip_all)]
     pub async fn get_publication(&self, id: &str) -> Result<Publication> {
-        self.get_request(&format!("/publications/{}", id), None::<&()>)
-            .await
+        self.get_request(Route::Publication(id), None::<&()>).await
     }
 
     /// Gets a user.
@@ -326,37 +1270,76 @@ impl Client {
     /// # Arguments
     ///
     /// * `user_id` - The ID of the user to get.
+    #[instrument(name = "numista.get_user", skip_all)]
     pub async fn get_user(&self, user_id: i64) -> Result<User> {
-        self.get_request(&format!("/users/{}", user_id), None::<&()>)
-            .await
+        self.get_request(Route::User(user_id), None::<&()>).await
     }
 
     /// Gets the collections of a user.
     ///
+    /// The API can only list collections. They are created, renamed, and
+    /// deleted on the Numista website; items can then be assigned to them with
+    /// [`AddCollectedItemParams::collection`] and
+    /// [`EditCollectedItemParams::collection`].
+    ///
+    /// As with [`Client::get_collected_items`], public collections can be
+    /// read without a bearer token.
+    ///
     /// # Arguments
     ///
     /// * `user_id` - The ID of the user to get the collections for.
+    #[instrument(name = "numista.get_user_collections", skip_all)]
     pub async fn get_user_collections(&self, user_id: i64) -> Result<CollectionsResponse> {
-        self.get_request(&format!("/users/{}/collections", user_id), None::<&()>)
+        self.get_request(Route::UserCollections(user_id), None::<&()>)
             .await
+            .map_err(|e| self.authorization_required(e, user_id))
     }
 
     /// Gets the collected items of a user.
     ///
+    /// The collection of any user who made it public can be read with the
+    /// API key alone. Reading a collection which is not public needs a bearer
+    /// token of its owner with the `view_collection` scope, and fails with
+    /// [`Error::AuthorizationRequired`] without one.
+    ///
     /// # Arguments
     ///
     /// * `user_id` - The ID of the user to get the collected items for.
     /// * `params` - The search parameters.
+    #[instrument(name = "numista.get_collected_items", skip_all)]
     pub async fn get_collected_items(
         &self,
         user_id: i64,
         params: &GetCollectedItemsParams,
     ) -> Result<CollectedItems> {
         self.get_request(
-            &format!("/users/{}/collected_items", user_id),
+            Route::CollectedItems(user_id),
             Some(params),
         )
         .await
+        .map_err(|e| self.authorization_required(e, user_id))
+    }
+
+    /// Watches the collection of a user, yielding the items added, edited,
+    /// or removed since the watch started.
+    ///
+    /// The collection is fetched every `interval`, starting immediately. A
+    /// failed fetch yields the error, and the watch continues with the next
+    /// one. Changes made between two fetches are only seen as their net
+    /// effect. See the [`watch`] module.
+    ///
+    /// Returns [`Error::Validation`] if `interval` is zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user whose collection to watch.
+    /// * `interval` - The time between two fetches of the collection.
+    pub fn watch_collection<'a>(
+        &self,
+        user_id: i64,
+        interval: Duration,
+    ) -> Result<impl Stream<Item = Result<CollectionEvent>> + 'a> {
+        watch::watch_collection(self, user_id, interval)
     }
 
     /// Adds a collected item to a user's collection.
@@ -365,20 +1348,111 @@ impl Client {
     ///
     /// * `user_id` - The ID of the user to add the collected item to.
     /// * `item` - The item to add.
+    #[instrument(name = "numista.add_collected_item", skip_all)]
     pub async fn add_collected_item(
         &self,
         user_id: i64,
         item: &AddCollectedItemParams,
     ) -> Result<CollectedItem> {
-        let url = format!("{}/users/{}/collected_items", self.base_url, user_id);
-        let mut req = self.client.post(&url);
+        self.add_collected_item_with_outcome(user_id, item)
+            .await?
+            .into_value()
+            .ok_or(Error::DryRunWithoutOutcome)
+    }
+
+    /// Adds a collected item to a user's collection, returning what the API
+    /// confirmed along with the added item, or the request planned in dry-run
+    /// mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user to add the collected item to.
+    /// * `item` - The item to add.
+    #[instrument(name = "numista.add_collected_item_with_outcome", skip_all)]
+    pub async fn add_collected_item_with_outcome(
+        &self,
+        user_id: i64,
+        item: &AddCollectedItemParams,
+    ) -> Result<WriteOutcome<CollectedItem>> {
+        validation::check(item)?;
+        self.require_scope(Scope::EditCollection)?;
+        let url = self.url(Route::CollectedItems(user_id));
+        let mut req = self.request(reqwest::Method::POST, &url);
         add_lang_param!(self, req);
-        let response = req
+        let req = req
             .header("Content-Type", "application/json")
-            .body(serde_json::to_string(item)?)
-            .send()
-            .await?;
-        process_response(response).await
+            .body(serde_json::to_string(item)?);
+        if let Some(planned) = self.plan(&req)? {
+            return Ok(WriteOutcome::Planned(planned));
+        }
+        let response = req.send().await?;
+        process_write_response(response).await
+    }
+
+    /// Adds a collected item to a user's collection, unless an item with the
+    /// same type, issue, and serial number is already in it.
+    ///
+    /// This makes it safe to re-run a script which adds items after it
+    /// failed part way.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user to add the collected item to.
+    /// * `item` - The item to add.
+    #[instrument(name = "numista.add_collected_item_if_absent", skip_all)]
+    pub async fn add_collected_item_if_absent(
+        &self,
+        user_id: i64,
+        item: &AddCollectedItemParams,
+    ) -> Result<AddOutcome> {
+        match self.find_matching_item(user_id, item).await? {
+            Some(existing) => Ok(AddOutcome::Skipped(existing)),
+            None => Ok(AddOutcome::Added(
+                self.add_collected_item(user_id, item).await?,
+            )),
+        }
+    }
+
+    /// Adds a collected item to a user's collection, or if an item with the
+    /// same type, issue, and serial number is already in it, increases its
+    /// quantity instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user to add the collected item to.
+    /// * `item` - The item to add.
+    #[instrument(name = "numista.add_or_merge_collected_item", skip_all)]
+    pub async fn add_or_merge_collected_item(
+        &self,
+        user_id: i64,
+        item: &AddCollectedItemParams,
+    ) -> Result<AddOutcome> {
+        match self.find_matching_item(user_id, item).await? {
+            Some(existing) => {
+                let quantity = existing.quantity + item.quantity.unwrap_or(1);
+                let edit = EditCollectedItemParams::new().quantity(quantity);
+                Ok(AddOutcome::Merged(
+                    self.edit_collected_item(user_id, existing.id, &edit).await?,
+                ))
+            }
+            None => Ok(AddOutcome::Added(
+                self.add_collected_item(user_id, item).await?,
+            )),
+        }
+    }
+
+    async fn find_matching_item(
+        &self,
+        user_id: i64,
+        item: &AddCollectedItemParams,
+    ) -> Result<Option<CollectedItem>> {
+        let params = GetCollectedItemsParams::new().type_id(item.type_id);
+        let existing = self.get_collected_items(user_id, &params).await?;
+        Ok(existing.items.into_iter().find(|existing| {
+            existing.type_info.id == item.type_id
+                && existing.issue.as_ref().map(|i| i.id) == item.issue
+                && existing.serial_number == item.serial_number
+        }))
     }
 
     /// Gets a single collected item from a user's collection.
@@ -387,9 +1461,10 @@ impl Client {
     ///
     /// * `user_id` - The ID of the user.
     /// * `item_id` - The ID of the item to get.
+    #[instrument(name = "numista.get_collected_item", skip_all)]
     pub async fn get_collected_item(&self, user_id: i64, item_id: i64) -> Result<CollectedItem> {
         self.get_request(
-            &format!("/users/{}/collected_items/{}", user_id, item_id),
+            Route::CollectedItem { user_id, item_id },
             None::<&()>,
         )
         .await
@@ -402,43 +1477,70 @@ impl Client {
     /// * `user_id` - The ID of the user.
     /// * `item_id` - The ID of the item to edit.
     /// * `item` - The fields to edit.
+    #[instrument(name = "numista.edit_collected_item", skip_all)]
     pub async fn edit_collected_item(
         &self,
         user_id: i64,
         item_id: i64,
         item: &EditCollectedItemParams,
     ) -> Result<CollectedItem> {
-        let url = format!(
-            "{}/users/{}/collected_items/{}",
-            self.base_url, user_id, item_id
-        );
-        let mut req = self.client.patch(&url);
+        self.edit_collected_item_with_outcome(user_id, item_id, item)
+            .await?
+            .into_value()
+            .ok_or(Error::DryRunWithoutOutcome)
+    }
+
+    /// Edits a collected item in a user's collection, returning what the API
+    /// confirmed along with the edited item, or the request planned in dry-run
+    /// mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `item_id` - The ID of the item to edit.
+    /// * `item` - The fields to edit.
+    #[instrument(name = "numista.edit_collected_item_with_outcome", skip_all)]
+    pub async fn edit_collected_item_with_outcome(
+        &self,
+        user_id: i64,
+        item_id: i64,
+        item: &EditCollectedItemParams,
+    ) -> Result<WriteOutcome<CollectedItem>> {
+        validation::check(item)?;
+        self.require_scope(Scope::EditCollection)?;
+        let url = self.url(Route::CollectedItem { user_id, item_id });
+        let mut req = self.request(reqwest::Method::PATCH, &url);
         add_lang_param!(self, req);
-        let response = req
+        let req = req
             .header("Content-Type", "application/json")
-            .body(serde_json::to_string(item)?)
-            .send()
-            .await?;
-        process_response(response).await
+            .body(serde_json::to_string(item)?);
+        if let Some(planned) = self.plan(&req)? {
+            return Ok(WriteOutcome::Planned(planned));
+        }
+        let response = req.send().await?;
+        process_write_response(response).await
     }
 
-    /// Deletes a collected item from a user's collection.
+    /// Deletes a collected item from a user's collection, returning what the
+    /// API confirmed, or the request planned in dry-run mode.
     ///
     /// # Arguments
     ///
     /// * `user_id` - The ID of the user.
     /// * `item_id` - The ID of the item to delete.
-    pub async fn delete_collected_item(&self, user_id: i64, item_id: i64) -> Result<()> {
-        let url = format!(
-            "{}/users/{}/collected_items/{}",
-            self.base_url, user_id, item_id
-        );
-        let mut req = self.client.delete(&url);
+    #[instrument(name = "numista.delete_collected_item", skip_all)]
+    pub async fn delete_collected_item(&self, user_id: i64, item_id: i64) -> Result<DeleteOutcome> {
+        self.require_scope(Scope::EditCollection)?;
+        let url = self.url(Route::CollectedItem { user_id, item_id });
+        let mut req = self.request(reqwest::Method::DELETE, &url);
         add_lang_param!(self, req);
+        if let Some(planned) = self.plan(&req)? {
+            return Ok(WriteOutcome::Planned(planned));
+        }
         let response = req.send().await?;
 
         if response.status().is_success() {
-            return Ok(());
+            return Ok(WriteOutcome::from_response(&response, ()));
         }
 
         Err(parse_api_error(response).await)
@@ -449,8 +1551,9 @@ impl Client {
     /// # Arguments
     ///
     /// * `params` - The parameters for getting the token.
+    #[instrument(name = "numista.get_oauth_token", skip_all)]
     pub async fn get_oauth_token(&self, params: &OAuthTokenParams) -> Result<OAuthToken> {
-        self.get_request("/oauth_token", Some(params)).await
+        self.get_request(Route::OAuthToken, Some(params)).await
     }
 
     /// Searches for types by image.
@@ -458,12 +1561,14 @@ impl Client {
     /// # Arguments
     ///
     /// * `request` - The request body.
+    #[instrument(name = "numista.search_by_image", skip_all)]
     pub async fn search_by_image(
         &self,
         request: &SearchByImageParams,
     ) -> Result<SearchByImageResponse> {
-        let url = format!("{}/search_by_image", self.base_url);
-        let mut req = self.client.post(&url);
+        validation::check(request)?;
+        let url = self.url(Route::SearchByImage);
+        let mut req = self.request(reqwest::Method::POST, &url);
         add_lang_param!(self, req);
         let response = req
             .header("Content-Type", "application/json")
@@ -472,15 +1577,133 @@ impl Client {
             .await?;
         process_response(response).await
     }
+
+    /// Downloads the pictures of a type or collected item.
+    ///
+    /// Pictures are saved in a subdirectory of `dest_dir` named after the type
+    /// or item, e.g. `type-420/obverse.jpg` or `item-12/1.png`. The file
+    /// extension is chosen from the content type of the response. Pictures
+    /// for which a file already exists, with any extension, are skipped.
+    ///
+    /// At most [`pictures::DOWNLOAD_CONCURRENCY`] pictures are downloaded at
+    /// the same time.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The type or collected item to download the pictures of.
+    /// * `dest_dir` - The directory to save the pictures in.
+    #[instrument(name = "numista.download_pictures", skip_all)]
+    pub async fn download_pictures<P: Pictures>(
+        &self,
+        source: &P,
+        dest_dir: impl AsRef<Path>,
+    ) -> Result<Vec<DownloadedPicture>> {
+        let dir = dest_dir.as_ref().join(source.picture_dir());
+        tokio::fs::create_dir_all(&dir).await?;
+
+        stream::iter(source.picture_refs())
+            .map(|picture| {
+                let dir = dir.clone();
+                async move {
+                    if let Some(path) = find_existing(&dir, &picture.name).await? {
+                        return Ok(DownloadedPicture {
+                            path,
+                            skipped: true,
+                        });
+                    }
+
+                    let response = self.downloader.get(picture.url.clone()).send().await?;
+                    let response = response.error_for_status()?;
+                    let content_type = response
+                        .headers()
+                        .get(reqwest::header::CONTENT_TYPE)
+                        .and_then(|v| v.to_str().ok())
+                        .map(str::to_string);
+                    let bytes = response.bytes().await?;
+
+                    let extension = pictures::extension_for(content_type.as_deref(), &picture.url);
+                    let path = dir.join(format!("{}.{}", picture.name, extension));
+                    tokio::fs::write(&path, &bytes).await?;
+                    Ok(DownloadedPicture {
+                        path,
+                        skipped: false,
+                    })
+                }
+            })
+            .buffered(pictures::DOWNLOAD_CONCURRENCY)
+            .try_collect()
+            .await
+    }
+
+    /// Downloads the flag of an issuer into a cache directory.
+    ///
+    /// The flag is saved as `issuer-<code>/flag.<ext>` under `cache_dir`, in
+    /// the format served by Numista, and is only downloaded if not already
+    /// cached. Returns `None` if the issuer has no flag.
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer` - The issuer, as returned by [`Client::get_issuers`].
+    /// * `cache_dir` - The directory in which flags are cached.
+    #[instrument(name = "numista.download_flag", skip_all)]
+    pub async fn download_flag(
+        &self,
+        issuer: &IssuerDetail,
+        cache_dir: impl AsRef<Path>,
+    ) -> Result<Option<DownloadedPicture>> {
+        if issuer.flag.is_none() {
+            return Ok(None);
+        }
+        Ok(self.download_pictures(issuer, cache_dir).await?.pop())
+    }
+
+    /// Fetches a picture into memory, e.g. to embed it in a document.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL of the picture, such as [`models::Picture::thumbnail_url`].
+    #[instrument(name = "numista.fetch_picture", skip_all)]
+    pub async fn fetch_picture(&self, url: &url::Url) -> Result<Vec<u8>> {
+        let response = self.downloader.get(url.clone()).send().await?;
+        let bytes = response.error_for_status()?.bytes().await?;
+        Ok(bytes.to_vec())
+    }
+}
+
+async fn find_existing(dir: &Path, name: &str) -> Result<Option<std::path::PathBuf>> {
+    let mut entries = tokio::fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if path.file_stem().and_then(|s| s.to_str()) == Some(name) {
+            return Ok(Some(path));
+        }
+    }
+    Ok(None)
 }
 
 /// A builder for creating a `Client`.
 #[derive(Debug, Default)]
 pub struct ClientBuilder {
-    api_key: Option<String>,
+    api_key: Option<SecretString>,
+    api_key_header: Option<String>,
+    title_case_headers: bool,
     base_url: Option<String>,
-    bearer_token: Option<String>,
-    lang: Option<Language>,
+    bearer_token: Option<SecretString>,
+    scopes: Option<Vec<Scope>>,
+    version: ApiVersion,
+    /// The ISO 639-1 code of the language.
+    lang: Option<String>,
+    dry_run: bool,
+    allow_missing_key: bool,
+    #[cfg(feature = "logging")]
+    body_logging: BodyLogging,
+    circuit_breaker: Option<CircuitBreaker>,
+    max_concurrent_requests: Option<usize>,
+    #[cfg(feature = "audit")]
+    audit_log: Option<crate::audit::AuditLog>,
+    #[cfg(feature = "vcr")]
+    cassette: Option<crate::vcr::Cassette>,
+    auth_provider: Option<Arc<dyn AuthProvider>>,
 }
 
 impl ClientBuilder {
@@ -490,75 +1713,299 @@ impl ClientBuilder {
     }
 
     /// Sets the API key to use for requests.
+    ///
+    /// The key is kept in memory as a secret, and is never logged or printed
+    /// by `Debug`.
     pub fn api_key<S: Into<String>>(mut self, api_key: S) -> Self {
-        self.api_key = Some(api_key.into());
+        self.api_key = Some(SecretString::from(api_key.into()));
+        self
+    }
+
+    /// Sets the name of the header in which the API key is sent. Defaults to
+    /// [`DEFAULT_API_KEY_HEADER`], `Numista-API-Key`.
+    ///
+    /// This is only useful behind a proxy which expects the key in a header
+    /// of its own. [`ClientBuilder::build`] fails with
+    /// [`Error::InvalidApiKey`] if the name is not a valid header name.
+    pub fn api_key_header<S: Into<String>>(mut self, name: S) -> Self {
+        self.api_key_header = Some(name.into());
+        self
+    }
+
+    /// Sends the names of headers in title case, e.g. `Numista-Api-Key`,
+    /// instead of in lower case.
+    ///
+    /// Header names are case-insensitive, but some proxies compare them
+    /// case-sensitively. This only applies to HTTP/1 connections: HTTP/2
+    /// requires lower case.
+    pub fn title_case_headers(mut self) -> Self {
+        self.title_case_headers = true;
         self
     }
 
-    /// Sets the base URL to use for requests.
+    /// Sets the base URL to use for requests, including the API version, e.g.
+    /// `https://api.numista.com/v3`.
     ///
-    /// This is useful for testing.
+    /// This is useful for testing. To choose the API version, use
+    /// [`ClientBuilder::api_version`] instead.
     pub fn base_url<S: Into<String>>(mut self, base_url: S) -> Self {
         self.base_url = Some(base_url.into());
         self
     }
 
+    /// Sets the version of the API to use. Defaults to [`ApiVersion::V3`].
+    ///
+    /// Unless a base URL is set, requests are sent to the official API host
+    /// for this version.
+    pub fn api_version(mut self, version: ApiVersion) -> Self {
+        self.version = version;
+        self
+    }
+
     /// Sets the bearer token to use for requests.
+    ///
+    /// Like the API key, the token is never logged or printed by `Debug`.
     pub fn bearer_token<S: Into<String>>(mut self, bearer_token: S) -> Self {
-        self.bearer_token = Some(bearer_token.into());
+        self.bearer_token = Some(SecretString::from(bearer_token.into()));
+        self
+    }
+
+    /// Sets the scopes granted to the bearer token.
+    ///
+    /// Requests needing a scope not granted then fail with
+    /// [`Error::MissingScope`] without being sent. Without this, the scopes
+    /// are not checked, and the API refuses such requests itself.
+    pub fn scopes<I: IntoIterator<Item = Scope>>(mut self, scopes: I) -> Self {
+        self.scopes = Some(scopes.into_iter().collect());
+        self
+    }
+
+    /// Sets the bearer token to use for requests, and the scopes it was
+    /// granted.
+    pub fn token(self, token: &Token) -> Self {
+        self.bearer_token(token.access_token.as_str())
+            .scopes(token.granted_scopes())
+    }
+
+    /// Sets the language to use for requests.
+    pub fn language(mut self, lang: SupportedLanguage) -> Self {
+        self.lang = Some(lang.code().to_string());
         self
     }
 
     /// Sets the language to use for requests.
+    ///
+    /// [`ClientBuilder::build`] fails with [`Error::UnsupportedLanguage`] if
+    /// the API does not support the language. This is only available with the
+    /// `iso-codes` feature.
+    #[cfg(feature = "iso-codes")]
     pub fn lang(mut self, lang: Language) -> Self {
-        self.lang = Some(lang);
+        let code = lang.to_639_1().unwrap_or_else(|| lang.to_639_3());
+        self.lang = Some(code.to_string());
         self
     }
 
-    /// Sets the language code to use for requests.
+    /// Sets the language code to use for requests, e.g. `"fr"`.
+    ///
+    /// [`ClientBuilder::build`] fails with [`Error::UnsupportedLanguage`] if
+    /// the code is not the ISO 639-1 code of a [`SupportedLanguage`].
     pub fn lang_code<S: Into<String>>(mut self, lang_code: S) -> Self {
-        if let Some(l) = Language::from_639_1(&lang_code.into().to_lowercase()) {
-            self.lang = Some(l);
-        }
+        self.lang = Some(lang_code.into());
+        self
+    }
+
+    /// Allows building the client without an API key, which is then not sent.
+    ///
+    /// Every endpoint of the Numista API needs a key, so this is only useful
+    /// behind a gateway or proxy which adds the key, or its own credentials,
+    /// to the requests it forwards. Without this, [`ClientBuilder::build`]
+    /// fails with [`Error::ApiKeyMissing`] when no key was given.
+    pub fn allow_missing_key(mut self) -> Self {
+        self.allow_missing_key = true;
+        self
+    }
+
+    /// Sets whether the client is in dry-run mode.
+    ///
+    /// In dry-run mode, [`Client::add_collected_item_with_outcome`],
+    /// [`Client::edit_collected_item_with_outcome`], and
+    /// [`Client::delete_collected_item`] log the request they would send and
+    /// return it as [`WriteOutcome::Planned`] instead of sending it.
+    /// [`Client::add_collected_item`] and [`Client::edit_collected_item`],
+    /// which have no item to return, fail with
+    /// [`Error::DryRunWithoutOutcome`]. Other requests are sent as usual.
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Sets whether request and response bodies are logged at the `TRACE`
+    /// level. Defaults to `true`.
+    ///
+    /// Status codes, latencies, and headers are logged either way. Bodies
+    /// which are not logged are not buffered, which saves memory on large
+    /// responses.
+    #[cfg(feature = "logging")]
+    pub fn log_bodies(mut self, log_bodies: bool) -> Self {
+        self.body_logging.enabled = log_bodies;
+        self
+    }
+
+    /// Sets the maximum number of bytes of each body logged. Longer bodies
+    /// are truncated. By default, bodies are logged in full.
+    #[cfg(feature = "logging")]
+    pub fn max_logged_body_size(mut self, max_size: usize) -> Self {
+        self.body_logging.max_size = Some(max_size);
+        self
+    }
+
+    /// Sets the fraction of requests whose bodies are logged, between 0 and
+    /// 1. Defaults to 1, logging every body.
+    #[cfg(feature = "logging")]
+    pub fn body_log_sample_rate(mut self, sample_rate: f64) -> Self {
+        self.body_logging.sample_rate = sample_rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets a circuit breaker, which fails requests fast with
+    /// [`Error::CircuitOpen`] after repeated server errors or timeouts.
+    ///
+    /// See the [`circuit_breaker`](crate::circuit_breaker) module.
+    pub fn circuit_breaker(mut self, breaker: CircuitBreaker) -> Self {
+        self.circuit_breaker = Some(breaker);
+        self
+    }
+
+    /// Sets the maximum number of requests the client sends at once. By
+    /// default, there is no limit.
+    ///
+    /// The limit is shared by all the clones of the client, e.g. those given
+    /// to spawned tasks, so that it holds across the whole program. Further
+    /// requests wait until the body of a response is fully received.
+    /// Downloads of pictures are not limited.
+    ///
+    /// A limit of zero is raised to one, and a limit above
+    /// [`Semaphore::MAX_PERMITS`](tokio::sync::Semaphore::MAX_PERMITS) is
+    /// lowered to it.
+    pub fn max_concurrent_requests(mut self, max: usize) -> Self {
+        self.max_concurrent_requests = Some(max.clamp(1, Semaphore::MAX_PERMITS));
+        self
+    }
+
+    /// Records every write request sent by the client in an audit log.
+    ///
+    /// See the [`audit`](crate::audit) module.
+    #[cfg(feature = "audit")]
+    pub fn audit_log(mut self, log: crate::audit::AuditLog) -> Self {
+        self.audit_log = Some(log);
+        self
+    }
+
+    /// Records the responses to the requests of the client in a cassette, or
+    /// replays them from it.
+    ///
+    /// See the [`vcr`](crate::vcr) module.
+    #[cfg(feature = "vcr")]
+    pub fn cassette(mut self, cassette: crate::vcr::Cassette) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    /// Sets a provider of headers added to each request, e.g. the credentials
+    /// of a gateway in front of the API.
+    ///
+    /// See the [`auth`](crate::auth) module.
+    pub fn auth_provider<P: AuthProvider>(mut self, provider: P) -> Self {
+        self.auth_provider = Some(Arc::new(provider));
         self
     }
 
     /// Builds the `Client`.
     pub fn build(self) -> Result<Client> {
+        let lang = self.lang.as_deref().map(str::parse).transpose()?;
         let mut headers = HeaderMap::new();
+        let mut secrets = Vec::new();
         if let Some(api_key) = self.api_key {
-            let mut auth_value = HeaderValue::from_str(&api_key).unwrap();
+            if api_key.expose_secret().trim().is_empty() {
+                return Err(Error::InvalidApiKey("the key is empty".to_string()));
+            }
+            let mut auth_value = HeaderValue::from_str(api_key.expose_secret()).map_err(|_| {
+                Error::InvalidApiKey("the key contains characters not allowed in a header".to_string())
+            })?;
             auth_value.set_sensitive(true);
-            headers.insert("Numista-API-Key", auth_value);
-        } else {
+            let name = self.api_key_header.as_deref().unwrap_or(DEFAULT_API_KEY_HEADER);
+            let name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| {
+                Error::InvalidApiKey(format!("'{}' is not a valid header name", name))
+            })?;
+            headers.insert(name, auth_value);
+            secrets.push(api_key);
+        } else if !self.allow_missing_key {
             return Err(Error::ApiKeyMissing);
         }
 
+        let bearer_token = self.bearer_token.is_some();
         if let Some(bearer_token) = self.bearer_token {
-            let mut auth_value =
-                HeaderValue::from_str(&format!("Bearer {}", bearer_token)).unwrap();
-            auth_value.set_sensitive(true);
-            headers.insert("Authorization", auth_value);
+            headers.insert(
+                "Authorization",
+                bearer_header(bearer_token.expose_secret())?,
+            );
+            secrets.push(bearer_token);
         }
 
-        let reqwest_client = reqwest::Client::builder()
-            .default_headers(headers)
-            .build()?;
-
-        let client = MiddlewareClientBuilder::new(reqwest_client)
-            .with(LoggingMiddleware)
-            .build();
-
-        let base_url = self
-            .base_url
-            .unwrap_or_else(|| "https://api.numista.com/v3".to_string());
+        let mut reqwest_client = reqwest::Client::builder().default_headers(headers);
+        if self.title_case_headers {
+            reqwest_client = reqwest_client.http1_title_case_headers();
+        }
+        let reqwest_client = reqwest_client.build()?;
+        let downloader = reqwest::Client::builder().build()?;
+
+        let client = MiddlewareClientBuilder::new(reqwest_client);
+        // Added first, so that no ID is generated for requests not sent.
+        let client = match self.circuit_breaker {
+            Some(breaker) => client.with(CircuitBreakerMiddleware::new(breaker)),
+            None => client,
+        };
+        let client = match self.max_concurrent_requests {
+            Some(max) => client.with(ConcurrencyLimitMiddleware {
+                permits: Arc::new(Semaphore::new(max)),
+            }),
+            None => client,
+        };
+        let client = client.with(RequestIdMiddleware);
+        #[cfg(feature = "logging")]
+        let client = client.with(LoggingMiddleware::new(secrets, self.body_logging));
+        // Added after the request ID middleware, to see the request ID header.
+        #[cfg(feature = "audit")]
+        let client = match self.audit_log {
+            Some(log) => client.with(crate::audit::AuditMiddleware::new(log)),
+            None => client,
+        };
+        // Added after the logging and audit middlewares, so that the headers
+        // it adds are not logged.
+        let client = match self.auth_provider {
+            Some(provider) => client.with(AuthMiddleware::new(provider)),
+            None => client,
+        };
+        // Added last, so that replayed requests still go through the others.
+        #[cfg(feature = "vcr")]
+        let client = match self.cassette {
+            Some(cassette) => client.with(crate::vcr::VcrMiddleware::new(cassette)),
+            None => client,
+        };
+        let client = client.build();
 
-        let lang = self.lang.and_then(|l| l.to_639_1().map(|s| s.to_string()));
+        let base_url = self.base_url.unwrap_or_else(|| self.version.base_url());
 
         Ok(Client {
             client,
+            downloader,
             base_url,
+            version: self.version,
             lang,
+            dry_run: self.dry_run,
+            scopes: self.scopes,
+            session_auth: None,
+            bearer_token,
         })
     }
 }
@@ -575,6 +2022,137 @@ mod tests {
         assert!(client.is_ok());
     }
 
+    #[test]
+    fn build_client_without_key_test() {
+        let client = ClientBuilder::new().allow_missing_key().build();
+        assert!(client.is_ok());
+    }
+
+    #[test]
+    fn build_client_invalid_key_header_test() {
+        let client = ClientBuilder::new()
+            .api_key("key")
+            .api_key_header("X-Api Key")
+            .build();
+        assert!(matches!(client, Err(Error::InvalidApiKey(_))));
+    }
+
+    #[cfg(feature = "logging")]
+    #[test]
+    fn redact_headers_test() {
+        let mut headers = HeaderMap::new();
+        headers.insert("Numista-API-Key", HeaderValue::from_static("secret_key"));
+        headers.insert("Authorization", HeaderValue::from_static("Bearer secret_token"));
+        headers.insert("Content-Type", HeaderValue::from_static("application/json"));
+
+        let redacted = redact_headers(&headers);
+        assert!(!redacted.contains("secret"));
+        assert!(redacted.contains("numista-api-key: [REDACTED]"));
+        assert!(redacted.contains("content-type: application/json"));
+    }
+
+    #[test]
+    fn build_client_invalid_credentials_test() {
+        for api_key in ["", "  ", "bad\nkey"] {
+            let client = ClientBuilder::new().api_key(api_key).build();
+            assert!(matches!(client, Err(Error::InvalidApiKey(_))), "{:?}", api_key);
+        }
+        for token in ["", "bad\ntoken"] {
+            let client = ClientBuilder::new()
+                .api_key("test_key")
+                .bearer_token(token)
+                .build();
+            assert!(matches!(client, Err(Error::InvalidToken(_))), "{:?}", token);
+        }
+    }
+
+    #[test]
+    fn debug_hides_credentials_test() {
+        let builder = ClientBuilder::new()
+            .api_key("secret_key")
+            .bearer_token("secret_token");
+        assert!(!format!("{:?}", builder).contains("secret"));
+
+        let client = builder.build().unwrap();
+        assert!(!format!("{:?}", client).contains("secret"));
+    }
+
+    #[cfg(feature = "logging")]
+    #[test]
+    fn scrub_test() {
+        let middleware = LoggingMiddleware::new(
+            vec![SecretString::from("secret_key")],
+            BodyLogging::default(),
+        );
+        assert_eq!(
+            middleware.scrub(r#"{"key": "secret_key"}"#),
+            r#"{"key": "[REDACTED]"}"#
+        );
+
+        let url = url::Url::parse(
+            "https://api.numista.com/v3/oauth_token?grant_type=authorization_code&code=abc&client_secret=xyz",
+        )
+        .unwrap();
+        assert_eq!(
+            redact_url(&url).as_str(),
+            "https://api.numista.com/v3/oauth_token?grant_type=authorization_code&code=%5BREDACTED%5D&client_secret=%5BREDACTED%5D"
+        );
+
+        let body = middleware
+            .body_text(br#"{"access_token": "abc", "refresh_token": "def", "user_id": 1}"#)
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["access_token"], "[REDACTED]");
+        assert_eq!(body["refresh_token"], "[REDACTED]");
+        assert_eq!(body["user_id"], 1);
+        assert_eq!(redact_body("not json"), "not json");
+
+        let middleware = LoggingMiddleware::new(
+            vec![SecretString::from("secret_key")],
+            BodyLogging {
+                max_size: Some(12),
+                ..BodyLogging::default()
+            },
+        );
+        assert_eq!(
+            middleware.body_text(b"key=secret_key").unwrap(),
+            "key=[REDACTE... (14 bytes total)"
+        );
+    }
+
+    #[cfg(feature = "logging")]
+    #[test]
+    fn body_logging_test() {
+        assert_eq!(truncate_body("short", Some(10)), "short");
+        assert_eq!(
+            truncate_body("a longer body", Some(8)),
+            "a longer... (13 bytes total)"
+        );
+        assert_eq!(truncate_body("été", Some(2)), "é... (5 bytes total)");
+
+        let bodies = BodyLogging {
+            sample_rate: 0.25,
+            ..BodyLogging::default()
+        };
+        let middleware = LoggingMiddleware::new(Vec::new(), bodies);
+        let sampled: Vec<_> = (0..8).map(|_| middleware.sample()).collect();
+        assert_eq!(sampled.iter().filter(|&&s| s).count(), 2);
+
+        let bodies = BodyLogging {
+            enabled: false,
+            ..BodyLogging::default()
+        };
+        assert!(!LoggingMiddleware::new(Vec::new(), bodies).sample());
+    }
+
+    #[test]
+    fn request_id_generate_test() {
+        let first = RequestId::generate();
+        let second = RequestId::generate();
+        assert_ne!(first.0, second.0);
+        assert_eq!(first.0.len(), 17);
+    }
+
     #[test]
     fn build_client_missing_api_key_test() {
         let client = ClientBuilder::new().build();
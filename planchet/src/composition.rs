@@ -0,0 +1,283 @@
+//! Parsing of compositions into metals and fineness.
+//!
+//! [`Composition::text`] is written for collectors, e.g. `Silver (.925)` or
+//! `Copper-nickel clad copper`. [`parse`] reads such a text into a
+//! [`ParsedComposition`], with the metal, its fineness if given, and the
+//! metal of the core of clad and plated pieces. The `bullion` module uses it
+//! to compute the precious metal content of types.
+//!
+//! This module is only available with the `decimal` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use planchet::composition::{self, Metal};
+//! use rust_decimal::Decimal;
+//!
+//! let composition = composition::parse("Silver (.925)").unwrap();
+//! assert_eq!(composition.metal, Metal::Silver);
+//! assert_eq!(composition.fineness, Some(Decimal::new(925, 3)));
+//!
+//! let composition = composition::parse("Copper-nickel clad copper").unwrap();
+//! assert_eq!(composition.metal, Metal::CopperNickel);
+//! assert_eq!(composition.core, Some(Metal::Copper));
+//! ```
+use crate::models::Composition;
+use rust_decimal::Decimal;
+
+/// A metal or alloy.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Metal {
+    Gold,
+    Silver,
+    Platinum,
+    Palladium,
+    Copper,
+    Nickel,
+    CopperNickel,
+    Bronze,
+    Brass,
+    NickelBrass,
+    AluminiumBronze,
+    Aluminium,
+    Zinc,
+    Tin,
+    Iron,
+    Steel,
+    Billon,
+    /// Any other metal or alloy, as written.
+    Other(String),
+}
+
+impl Metal {
+    /// Checks if the metal is a precious metal, with a spot price.
+    pub fn is_precious(&self) -> bool {
+        matches!(
+            self,
+            Metal::Gold | Metal::Silver | Metal::Platinum | Metal::Palladium
+        )
+    }
+
+    fn from_name(name: &str) -> Metal {
+        let normalized = name.to_lowercase().replace('-', " ");
+        match normalized
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .as_str()
+        {
+            "gold" => Metal::Gold,
+            "silver" => Metal::Silver,
+            "platinum" => Metal::Platinum,
+            "palladium" => Metal::Palladium,
+            "copper" => Metal::Copper,
+            "nickel" => Metal::Nickel,
+            "copper nickel" | "cupronickel" | "cupro nickel" => Metal::CopperNickel,
+            "bronze" => Metal::Bronze,
+            "brass" => Metal::Brass,
+            "nickel brass" => Metal::NickelBrass,
+            "aluminium bronze" | "aluminum bronze" => Metal::AluminiumBronze,
+            "aluminium" | "aluminum" => Metal::Aluminium,
+            "zinc" => Metal::Zinc,
+            "tin" => Metal::Tin,
+            "iron" => Metal::Iron,
+            "steel" => Metal::Steel,
+            "billon" => Metal::Billon,
+            _ => Metal::Other(name.trim().to_string()),
+        }
+    }
+}
+
+/// A composition, read from its text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedComposition {
+    /// The metal, or the metal of the surface of clad and plated pieces.
+    pub metal: Metal,
+    /// The share of the metal in the alloy, between 0 and 1, if given.
+    pub fineness: Option<Decimal>,
+    /// The metal of the core of clad and plated pieces.
+    pub core: Option<Metal>,
+}
+
+/// The words joining the metal of the surface to the metal of the core.
+const CORE_SEPARATORS: &[&str] = &[" clad ", " plated ", " coated ", " on "];
+
+/// Parses the text of a composition.
+///
+/// The fineness can follow the metal, in brackets or not, as a fraction
+/// (`.925`), in thousandths (`925`), or in carats (`22 ct`, `22K`). Returns
+/// `None` if the text is empty.
+pub fn parse(text: &str) -> Option<ParsedComposition> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+
+    let (rest, mut fineness) = match (text.find('('), text.rfind(')')) {
+        (Some(open), Some(close)) if open < close => {
+            let fineness = parse_fineness(&text[open + 1..close]);
+            let rest = format!("{}{}", &text[..open], &text[close + 1..]);
+            (rest, fineness)
+        }
+        _ => (text.to_string(), None),
+    };
+    let mut rest = rest.trim().to_string();
+    if fineness.is_none() {
+        // A fineness not in brackets ends the text, e.g. `Gold 22 ct`.
+        let words: Vec<&str> = rest.split_whitespace().collect();
+        for start in (1..words.len()).rev().take(2) {
+            if let Some(value) = parse_fineness(&words[start..].join(" ")) {
+                fineness = Some(value);
+                rest = words[..start].join(" ");
+                break;
+            }
+        }
+    }
+
+    let (metal, core) = match CORE_SEPARATORS
+        .iter()
+        .find_map(|separator| find_ignore_case(&rest, separator).map(|i| (i, separator.len())))
+    {
+        Some((i, len)) => (
+            Metal::from_name(&rest[..i]),
+            Some(Metal::from_name(&rest[i + len..])),
+        ),
+        None => (Metal::from_name(&rest), None),
+    };
+    Some(ParsedComposition {
+        metal,
+        fineness,
+        core,
+    })
+}
+
+/// Returns the byte index of the first occurrence of an ASCII `needle` in
+/// `text`, ignoring case.
+///
+/// This searches `text` itself rather than a lowercased copy, whose indices
+/// can differ from those of `text` when lowercasing changes the length of a
+/// character, e.g. `İ`.
+fn find_ignore_case(text: &str, needle: &str) -> Option<usize> {
+    let needle = needle.as_bytes();
+    text.char_indices().map(|(i, _)| i).find(|&i| {
+        text.as_bytes()
+            .get(i..i + needle.len())
+            .is_some_and(|bytes| bytes.eq_ignore_ascii_case(needle))
+    })
+}
+
+/// Parses a fineness, returning `None` if it is not between 0 and 1.
+fn parse_fineness(text: &str) -> Option<Decimal> {
+    let text = text.trim().trim_end_matches('‰').to_lowercase();
+    let text = text.strip_suffix("fine").unwrap_or(&text).trim();
+    let carats = ["carats", "carat", "karats", "karat", "ct", "kt", "k"]
+        .iter()
+        .find_map(|suffix| text.strip_suffix(suffix));
+    let fineness = match carats {
+        Some(carats) => carats.trim().parse::<Decimal>().ok()? / Decimal::from(24),
+        None => {
+            let value: Decimal = text.parse().ok()?;
+            if value > Decimal::ONE {
+                value / Decimal::from(1000)
+            } else {
+                value
+            }
+        }
+    };
+    (fineness > Decimal::ZERO && fineness <= Decimal::ONE).then_some(fineness)
+}
+
+impl Composition {
+    /// Parses the text of the composition. See [`parse`].
+    pub fn parsed(&self) -> Option<ParsedComposition> {
+        parse(self.text.as_deref()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let cases = [
+            (
+                "Silver (.925)",
+                Metal::Silver,
+                Some(Decimal::new(925, 3)),
+                None,
+            ),
+            ("Gold (900)", Metal::Gold, Some(Decimal::new(9, 1)), None),
+            (
+                "Gold 22 ct",
+                Metal::Gold,
+                Some(Decimal::new(22, 0) / Decimal::new(24, 0)),
+                None,
+            ),
+            (
+                "Platinum .9995",
+                Metal::Platinum,
+                Some(Decimal::new(9995, 4)),
+                None,
+            ),
+            ("Bronze", Metal::Bronze, None, None),
+            ("Nickel-brass", Metal::NickelBrass, None, None),
+            (
+                "Copper-nickel clad copper",
+                Metal::CopperNickel,
+                None,
+                Some(Metal::Copper),
+            ),
+            (
+                "Copper plated steel",
+                Metal::Copper,
+                None,
+                Some(Metal::Steel),
+            ),
+            (
+                "Orichalcum",
+                Metal::Other("Orichalcum".to_string()),
+                None,
+                None,
+            ),
+        ];
+        for (text, metal, fineness, core) in cases {
+            assert_eq!(
+                parse(text),
+                Some(ParsedComposition {
+                    metal,
+                    fineness,
+                    core
+                }),
+                "{}",
+                text
+            );
+        }
+        assert_eq!(parse(" "), None);
+    }
+
+    #[test]
+    fn test_parse_non_ascii() {
+        // `İ` is 2 bytes long, and 3 once lowercased.
+        assert_eq!(
+            parse("İ clad Über"),
+            Some(ParsedComposition {
+                metal: Metal::Other("İ".to_string()),
+                fineness: None,
+                core: Some(Metal::Other("Über".to_string())),
+            })
+        );
+        assert_eq!(
+            parse("Argent CLAD Kupfer-Nickel"),
+            Some(ParsedComposition {
+                metal: Metal::Other("Argent".to_string()),
+                fineness: None,
+                core: Some(Metal::Other("Kupfer-Nickel".to_string())),
+            })
+        );
+        assert_eq!(
+            parse("Серебро (.900)").unwrap().fineness,
+            Some(Decimal::new(9, 1))
+        );
+    }
+}
@@ -0,0 +1,243 @@
+//! Mapping of Numista issuer codes to ISO 3166-1 country codes.
+//!
+//! Numista identifies issuers by codes derived from their French names, such
+//! as `etats-unis` for the United States. Issuers which correspond to a
+//! current country are mapped to its ISO 3166-1 alpha-2 code. Historical
+//! states, territories without a code, and issuers such as international
+//! organizations are not mapped.
+//!
+//! # Examples
+//!
+//! ```
+//! use planchet::country;
+//!
+//! assert_eq!(country::iso_country_for_issuer("etats-unis"), Some("US"));
+//! assert_eq!(country::issuer_for_iso_country("us"), Some("etats-unis"));
+//! ```
+use crate::models::{Issuer, IssuerDetail};
+
+/// Numista issuer codes and the ISO 3166-1 alpha-2 codes of the countries
+/// they correspond to, sorted by issuer code.
+///
+/// Each country appears at most once.
+pub const ISSUER_COUNTRIES: &[(&str, &str)] = &[
+    ("afghanistan", "AF"),
+    ("afrique_du_sud", "ZA"),
+    ("albanie", "AL"),
+    ("algerie", "DZ"),
+    ("allemagne", "DE"),
+    ("andorre", "AD"),
+    ("angola", "AO"),
+    ("arabie_saoudite", "SA"),
+    ("argentine", "AR"),
+    ("armenie", "AM"),
+    ("australie", "AU"),
+    ("autriche", "AT"),
+    ("azerbaidjan", "AZ"),
+    ("bahamas", "BS"),
+    ("bahrein", "BH"),
+    ("bangladesh", "BD"),
+    ("barbade", "BB"),
+    ("belgique", "BE"),
+    ("belize", "BZ"),
+    ("bermudes", "BM"),
+    ("bielorussie", "BY"),
+    ("bolivie", "BO"),
+    ("bosnie-herzegovine", "BA"),
+    ("botswana", "BW"),
+    ("bresil", "BR"),
+    ("bulgarie", "BG"),
+    ("cambodge", "KH"),
+    ("cameroun", "CM"),
+    ("canada", "CA"),
+    ("chili", "CL"),
+    ("chine", "CN"),
+    ("chypre", "CY"),
+    ("colombie", "CO"),
+    ("coree_du_nord", "KP"),
+    ("coree_du_sud", "KR"),
+    ("costa_rica", "CR"),
+    ("croatie", "HR"),
+    ("cuba", "CU"),
+    ("danemark", "DK"),
+    ("egypte", "EG"),
+    ("emirats_arabes_unis", "AE"),
+    ("equateur", "EC"),
+    ("espagne", "ES"),
+    ("estonie", "EE"),
+    ("etats-unis", "US"),
+    ("ethiopie", "ET"),
+    ("fidji", "FJ"),
+    ("finlande", "FI"),
+    ("france", "FR"),
+    ("georgie", "GE"),
+    ("ghana", "GH"),
+    ("gibraltar", "GI"),
+    ("grece", "GR"),
+    ("guatemala", "GT"),
+    ("guernesey", "GG"),
+    ("haiti", "HT"),
+    ("honduras", "HN"),
+    ("hong-kong", "HK"),
+    ("hongrie", "HU"),
+    ("ile_de_man", "IM"),
+    ("inde", "IN"),
+    ("indonesie", "ID"),
+    ("irak", "IQ"),
+    ("iran", "IR"),
+    ("irlande", "IE"),
+    ("islande", "IS"),
+    ("israel", "IL"),
+    ("italie", "IT"),
+    ("jamaique", "JM"),
+    ("japon", "JP"),
+    ("jersey", "JE"),
+    ("jordanie", "JO"),
+    ("kazakhstan", "KZ"),
+    ("kenya", "KE"),
+    ("koweit", "KW"),
+    ("lettonie", "LV"),
+    ("liban", "LB"),
+    ("liechtenstein", "LI"),
+    ("lituanie", "LT"),
+    ("luxembourg", "LU"),
+    ("macao", "MO"),
+    ("macedoine_du_nord", "MK"),
+    ("malaisie", "MY"),
+    ("malte", "MT"),
+    ("maroc", "MA"),
+    ("maurice", "MU"),
+    ("mexique", "MX"),
+    ("moldavie", "MD"),
+    ("monaco", "MC"),
+    ("mongolie", "MN"),
+    ("montenegro", "ME"),
+    ("namibie", "NA"),
+    ("nepal", "NP"),
+    ("nicaragua", "NI"),
+    ("nigeria", "NG"),
+    ("norvege", "NO"),
+    ("nouvelle-zelande", "NZ"),
+    ("oman", "OM"),
+    ("ouganda", "UG"),
+    ("ouzbekistan", "UZ"),
+    ("pakistan", "PK"),
+    ("panama", "PA"),
+    ("paraguay", "PY"),
+    ("pays-bas", "NL"),
+    ("perou", "PE"),
+    ("philippines", "PH"),
+    ("pologne", "PL"),
+    ("portugal", "PT"),
+    ("qatar", "QA"),
+    ("republique_dominicaine", "DO"),
+    ("republique_tcheque", "CZ"),
+    ("roumanie", "RO"),
+    ("royaume-uni", "GB"),
+    ("russie", "RU"),
+    ("saint-marin", "SM"),
+    ("salvador", "SV"),
+    ("serbie", "RS"),
+    ("singapour", "SG"),
+    ("slovaquie", "SK"),
+    ("slovenie", "SI"),
+    ("sri_lanka", "LK"),
+    ("suede", "SE"),
+    ("suisse", "CH"),
+    ("syrie", "SY"),
+    ("taiwan", "TW"),
+    ("tanzanie", "TZ"),
+    ("thailande", "TH"),
+    ("trinite-et-tobago", "TT"),
+    ("tunisie", "TN"),
+    ("turquie", "TR"),
+    ("ukraine", "UA"),
+    ("uruguay", "UY"),
+    ("vatican", "VA"),
+    ("venezuela", "VE"),
+    ("vietnam", "VN"),
+    ("zambie", "ZM"),
+    ("zimbabwe", "ZW"),
+];
+
+/// Returns the ISO 3166-1 alpha-2 code of the country corresponding to a
+/// Numista issuer code, e.g. `US` for `etats-unis`.
+pub fn iso_country_for_issuer(issuer_code: &str) -> Option<&'static str> {
+    ISSUER_COUNTRIES
+        .binary_search_by_key(&issuer_code, |&(code, _)| code)
+        .ok()
+        .map(|i| ISSUER_COUNTRIES[i].1)
+}
+
+/// Returns the Numista issuer code corresponding to a country, given its
+/// ISO 3166-1 alpha-2 code in any case, e.g. `etats-unis` for `US`.
+pub fn issuer_for_iso_country(alpha2: &str) -> Option<&'static str> {
+    ISSUER_COUNTRIES
+        .iter()
+        .find(|(_, iso)| iso.eq_ignore_ascii_case(alpha2))
+        .map(|&(code, _)| code)
+}
+
+impl Issuer {
+    /// Returns the ISO 3166-1 alpha-2 code of the country corresponding to
+    /// the issuer, if it is a current country.
+    pub fn iso_country(&self) -> Option<&'static str> {
+        iso_country_for_issuer(&self.code)
+    }
+}
+
+impl IssuerDetail {
+    /// Returns the ISO 3166-1 alpha-2 code of the country corresponding to
+    /// the issuer, if it is a current country.
+    pub fn iso_country(&self) -> Option<&'static str> {
+        iso_country_for_issuer(&self.code)
+    }
+}
+
+/// Returns the flag emoji of a country, given its ISO 3166-1 alpha-2 code.
+///
+/// Returns `None` if the code is not two ASCII letters.
+pub fn flag_emoji(alpha2: &str) -> Option<String> {
+    if alpha2.len() != 2 || !alpha2.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    // Flags are written as pairs of regional indicator symbols.
+    alpha2
+        .chars()
+        .map(|c| char::from_u32(0x1F1E6 + (c.to_ascii_uppercase() as u32 - 'A' as u32)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_is_sorted() {
+        assert!(ISSUER_COUNTRIES.windows(2).all(|w| w[0].0 < w[1].0));
+        let mut countries: Vec<_> = ISSUER_COUNTRIES.iter().map(|&(_, iso)| iso).collect();
+        countries.sort_unstable();
+        countries.dedup();
+        assert_eq!(countries.len(), ISSUER_COUNTRIES.len());
+    }
+
+    #[test]
+    fn test_iso_country_for_issuer() {
+        assert_eq!(iso_country_for_issuer("etats-unis"), Some("US"));
+        assert_eq!(iso_country_for_issuer("canada"), Some("CA"));
+        assert_eq!(iso_country_for_issuer("prusse"), None);
+        assert_eq!(issuer_for_iso_country("gb"), Some("royaume-uni"));
+        assert_eq!(issuer_for_iso_country("XX"), None);
+
+        let issuer: IssuerDetail =
+            serde_json::from_str(r#"{"code": "allemagne", "name": "Germany"}"#).unwrap();
+        assert_eq!(issuer.iso_country(), Some("DE"));
+    }
+
+    #[test]
+    fn test_flag_emoji() {
+        assert_eq!(flag_emoji("CA").as_deref(), Some("🇨🇦"));
+        assert_eq!(flag_emoji("gb").as_deref(), Some("🇬🇧"));
+        assert_eq!(flag_emoji("GBR"), None);
+    }
+}
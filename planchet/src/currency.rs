@@ -0,0 +1,326 @@
+//! Conversion of prices into another currency.
+//!
+//! Prices returned by the API are in the currency requested, or the one the
+//! collector entered, so a collection usually holds values in several
+//! currencies. A [`CurrencyConverter`] converts them into a single currency,
+//! using the rates of an [`ExchangeRates`] provider: [`FixedRates`] for rates
+//! known in advance, or `EcbRates` for the reference rates of the European
+//! Central Bank, with the `ecb` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use iso_currency::Currency;
+//! use planchet::currency::{CurrencyConverter, FixedRates};
+//! use rust_decimal::Decimal;
+//!
+//! # async fn run() -> planchet::Result<()> {
+//! let rates = FixedRates::new(Currency::EUR).rate(Currency::USD, Decimal::new(125, 2));
+//! let converter = CurrencyConverter::new(rates, Currency::EUR);
+//!
+//! let value = converter.convert(Decimal::new(10, 0), Currency::USD).await?;
+//! assert_eq!(value, Decimal::new(8, 0));
+//! # Ok(())
+//! # }
+//! ```
+use crate::error::{Error, Result};
+use crate::models::{GradePrices, ItemPrice};
+use iso_currency::Currency as IsoCurrency;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// A source of exchange rates.
+#[async_trait::async_trait]
+pub trait ExchangeRates: Send + Sync {
+    /// Returns how many units of `to` one unit of `from` is worth.
+    ///
+    /// Returns [`Error::ExchangeRate`] if the rate is unknown.
+    async fn exchange_rate(&self, from: IsoCurrency, to: IsoCurrency) -> Result<Decimal>;
+}
+
+/// Exchange rates known in advance, relative to a base currency.
+#[derive(Debug, Clone)]
+pub struct FixedRates {
+    base: IsoCurrency,
+    rates: HashMap<IsoCurrency, Decimal>,
+}
+
+impl FixedRates {
+    /// Creates a table of rates relative to `base`, with no rates yet.
+    pub fn new(base: IsoCurrency) -> Self {
+        Self {
+            base,
+            rates: HashMap::new(),
+        }
+    }
+
+    /// Sets how many units of `currency` one unit of the base currency is
+    /// worth.
+    pub fn rate(mut self, currency: IsoCurrency, per_base: Decimal) -> Self {
+        self.rates.insert(currency, per_base);
+        self
+    }
+
+    fn per_base(&self, currency: IsoCurrency) -> Result<Decimal> {
+        if currency == self.base {
+            return Ok(Decimal::ONE);
+        }
+        match self.rates.get(&currency) {
+            Some(rate) if !rate.is_zero() => Ok(*rate),
+            _ => Err(Error::ExchangeRate(format!(
+                "no rate from {} to {}",
+                self.base.code(),
+                currency.code()
+            ))),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ExchangeRates for FixedRates {
+    async fn exchange_rate(&self, from: IsoCurrency, to: IsoCurrency) -> Result<Decimal> {
+        self.per_base(to)?
+            .checked_div(self.per_base(from)?)
+            .ok_or_else(|| {
+                Error::ExchangeRate(format!(
+                    "the rate from {} to {} is out of range",
+                    from.code(),
+                    to.code()
+                ))
+            })
+    }
+}
+
+/// Converts prices into a target currency.
+#[derive(Debug, Clone)]
+pub struct CurrencyConverter<R> {
+    rates: R,
+    target: IsoCurrency,
+}
+
+impl<R: ExchangeRates> CurrencyConverter<R> {
+    /// Creates a converter into `target`, using `rates`.
+    pub fn new(rates: R, target: IsoCurrency) -> Self {
+        Self { rates, target }
+    }
+
+    /// Returns the currency prices are converted into.
+    pub fn target(&self) -> IsoCurrency {
+        self.target
+    }
+
+    /// Converts a value in `currency` into the target currency, rounded to
+    /// its minor unit.
+    ///
+    /// Returns [`Error::ExchangeRate`] if the converted value is too large to
+    /// be held in a `Decimal`.
+    pub async fn convert(&self, value: Decimal, currency: IsoCurrency) -> Result<Decimal> {
+        if currency == self.target {
+            return Ok(value);
+        }
+        let rate = self.rates.exchange_rate(currency, self.target).await?;
+        let minor_digits = self.target.exponent().unwrap_or(2);
+        let converted = value.checked_mul(rate).ok_or_else(|| {
+            Error::ExchangeRate(format!(
+                "{} {} is too large to convert into {}",
+                value,
+                currency.code(),
+                self.target.code()
+            ))
+        })?;
+        Ok(converted.round_dp(minor_digits.into()))
+    }
+
+    /// Converts the price of a collected item into the target currency.
+    pub async fn convert_item_price(&self, price: &ItemPrice) -> Result<ItemPrice> {
+        let mut converted = price.clone();
+        converted.value = self.convert(price.value, price.currency).await?;
+        converted.currency = self.target;
+        Ok(converted)
+    }
+
+    /// Converts the estimated prices of an issue into the target currency.
+    pub async fn convert_grade_prices(&self, prices: &GradePrices) -> Result<GradePrices> {
+        let mut converted = prices.clone();
+        for grade_price in &mut converted.prices {
+            grade_price.price = self.convert(grade_price.price, prices.currency).await?;
+        }
+        converted.currency = self.target;
+        Ok(converted)
+    }
+}
+
+/// The reference exchange rates of the European Central Bank.
+///
+/// The rates are published once a day, relative to the euro. They are
+/// fetched on first use, and kept for the lifetime of the provider.
+///
+/// This is only available with the `ecb` feature.
+#[cfg(feature = "ecb")]
+#[derive(Debug)]
+pub struct EcbRates {
+    client: reqwest::Client,
+    url: String,
+    rates: tokio::sync::OnceCell<FixedRates>,
+}
+
+#[cfg(feature = "ecb")]
+impl Default for EcbRates {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "ecb")]
+impl EcbRates {
+    /// Creates a provider of the latest daily rates.
+    pub fn new() -> Self {
+        Self::with_url("https://www.ecb.europa.eu/stats/eurofxref/eurofxref-daily.xml")
+    }
+
+    /// Creates a provider reading the rates from `url`, in the format of the
+    /// daily reference rates.
+    ///
+    /// This is useful for testing, or to use historical rates.
+    pub fn with_url<S: Into<String>>(url: S) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.into(),
+            rates: tokio::sync::OnceCell::new(),
+        }
+    }
+
+    async fn rates(&self) -> Result<&FixedRates> {
+        self.rates
+            .get_or_try_init(|| async {
+                let response = self.client.get(&self.url).send().await?;
+                if !response.status().is_success() {
+                    return Err(Error::ExchangeRate(format!(
+                        "the ECB returned status {}",
+                        response.status()
+                    )));
+                }
+                parse_ecb_rates(&response.text().await?)
+            })
+            .await
+    }
+}
+
+#[cfg(feature = "ecb")]
+#[async_trait::async_trait]
+impl ExchangeRates for EcbRates {
+    async fn exchange_rate(&self, from: IsoCurrency, to: IsoCurrency) -> Result<Decimal> {
+        self.rates().await?.exchange_rate(from, to).await
+    }
+}
+
+/// Parses the `<Cube currency='USD' rate='1.0812'/>` elements of the ECB
+/// reference rates.
+#[cfg(feature = "ecb")]
+fn parse_ecb_rates(xml: &str) -> Result<FixedRates> {
+    fn attribute<'a>(element: &'a str, name: &str) -> Option<&'a str> {
+        let start = element.find(&format!("{}=", name))? + name.len() + 1;
+        let quote = element[start..].chars().next()?;
+        let value = &element[start + 1..];
+        Some(&value[..value.find(quote)?])
+    }
+
+    let mut rates = FixedRates::new(IsoCurrency::EUR);
+    for element in xml.split('<').filter(|e| e.starts_with("Cube ")) {
+        let (Some(code), Some(rate)) = (attribute(element, "currency"), attribute(element, "rate"))
+        else {
+            continue;
+        };
+        // Currencies unknown to `iso_currency` cannot be requested anyway.
+        if let (Some(currency), Ok(rate)) = (IsoCurrency::from_code(code), rate.parse()) {
+            rates = rates.rate(currency, rate);
+        }
+    }
+    if rates.rates.is_empty() {
+        return Err(Error::ExchangeRate(
+            "no rates found in the ECB response".to_string(),
+        ));
+    }
+    Ok(rates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rates() -> FixedRates {
+        FixedRates::new(IsoCurrency::EUR)
+            .rate(IsoCurrency::USD, Decimal::new(125, 2))
+            .rate(IsoCurrency::JPY, Decimal::new(160, 0))
+    }
+
+    #[tokio::test]
+    async fn test_fixed_rates() {
+        let rates = rates();
+        let rate = |from, to| rates.exchange_rate(from, to);
+        assert_eq!(
+            rate(IsoCurrency::EUR, IsoCurrency::USD).await.unwrap(),
+            Decimal::new(125, 2)
+        );
+        assert_eq!(
+            rate(IsoCurrency::USD, IsoCurrency::JPY).await.unwrap(),
+            Decimal::new(128, 0)
+        );
+        assert!(matches!(
+            rate(IsoCurrency::EUR, IsoCurrency::GBP).await,
+            Err(Error::ExchangeRate(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_convert_rounds_to_minor_unit() {
+        let converter = CurrencyConverter::new(rates(), IsoCurrency::JPY);
+        let value = converter
+            .convert(Decimal::new(1001, 2), IsoCurrency::USD)
+            .await
+            .unwrap();
+        // 10.01 USD is 1281.28 JPY, which has no minor unit.
+        assert_eq!(value, Decimal::new(1281, 0));
+    }
+
+    #[tokio::test]
+    async fn test_convert_overflow() {
+        let converter = CurrencyConverter::new(rates(), IsoCurrency::JPY);
+        assert!(matches!(
+            converter.convert(Decimal::MAX, IsoCurrency::EUR).await,
+            Err(Error::ExchangeRate(_))
+        ));
+
+        // A rate too large to be held in a `Decimal`.
+        let rates = rates().rate(IsoCurrency::USD, Decimal::new(1, 28));
+        let rate = rates
+            .exchange_rate(IsoCurrency::USD, IsoCurrency::JPY)
+            .await;
+        assert!(
+            matches!(&rate, Err(Error::ExchangeRate(e)) if e.contains("out of range")),
+            "{:?}",
+            rate
+        );
+    }
+
+    #[cfg(feature = "ecb")]
+    #[test]
+    fn test_parse_ecb_rates() {
+        let xml = r#"<gesmes:Envelope>
+            <Cube><Cube time='2024-05-02'>
+                <Cube currency='USD' rate='1.0712'/>
+                <Cube currency="GBP" rate="0.8561"/>
+                <Cube currency='XXX' rate='1'/>
+            </Cube></Cube></gesmes:Envelope>"#;
+        let rates = parse_ecb_rates(xml).unwrap();
+        assert_eq!(
+            rates.per_base(IsoCurrency::USD).unwrap(),
+            Decimal::new(10712, 4)
+        );
+        assert_eq!(
+            rates.per_base(IsoCurrency::GBP).unwrap(),
+            Decimal::new(8561, 4)
+        );
+        assert!(parse_ecb_rates("<Cube/>").is_err());
+    }
+}
@@ -42,6 +42,74 @@ where
     }
 }
 
+/// Deserializes an `i64` which the API may return as either a string or an
+/// integer.
+pub fn string_or_i64<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    de_from_str_or_int(deserializer)
+}
+
+/// Deserializes an optional value which the API may return as a string,
+/// treating `null`, missing, and empty or blank strings as `None`.
+///
+/// Non-empty strings are parsed with [`FromStr`], while any other value is
+/// deserialized directly. Use together with `#[serde(default)]`.
+pub fn empty_string_as_none<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + Deserialize<'de>,
+    <T as FromStr>::Err: Display,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrValue<T> {
+        String(String),
+        Value(T),
+    }
+
+    match Option::<StringOrValue<T>>::deserialize(deserializer)? {
+        Some(StringOrValue::String(s)) if s.trim().is_empty() => Ok(None),
+        Some(StringOrValue::String(s)) => s.trim().parse::<T>().map(Some).map_err(serde::de::Error::custom),
+        Some(StringOrValue::Value(v)) => Ok(Some(v)),
+        None => Ok(None),
+    }
+}
+
+/// Deserializes a `bool` which the API may return as a boolean, as `0` or `1`,
+/// or as a string of either.
+pub fn bool_from_int<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum BoolOrInt {
+        Bool(bool),
+        Int(i64),
+        String(String),
+    }
+
+    match BoolOrInt::deserialize(deserializer)? {
+        BoolOrInt::Bool(b) => Ok(b),
+        BoolOrInt::Int(0) => Ok(false),
+        BoolOrInt::Int(1) => Ok(true),
+        BoolOrInt::Int(i) => Err(serde::de::Error::custom(format!(
+            "invalid boolean value: {}",
+            i
+        ))),
+        BoolOrInt::String(s) => match s.as_str() {
+            "0" | "false" => Ok(false),
+            "1" | "true" => Ok(true),
+            _ => Err(serde::de::Error::custom(format!(
+                "invalid boolean value: {}",
+                s
+            ))),
+        },
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -136,4 +204,71 @@ mod tests {
         let res: TestStructOptionalI64 = from_str(json).unwrap();
         assert_eq!(res.val, Some(51));
     }
+
+    #[test]
+    fn test_string_or_i64() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(deserialize_with = "string_or_i64")]
+            val: i64,
+        }
+
+        let res: TestStruct = from_str(r#"{"val": 10}"#).unwrap();
+        assert_eq!(res.val, 10);
+
+        let res: TestStruct = from_str(r#"{"val": "10"}"#).unwrap();
+        assert_eq!(res.val, 10);
+
+        assert!(from_str::<TestStruct>(r#"{"val": ""}"#).is_err());
+    }
+
+    #[test]
+    fn test_empty_string_as_none() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(deserialize_with = "empty_string_as_none", default)]
+            val: Option<i64>,
+        }
+
+        let res: TestStruct = from_str(r#"{"val": 2643743}"#).unwrap();
+        assert_eq!(res.val, Some(2643743));
+
+        let res: TestStruct = from_str(r#"{"val": "2643743"}"#).unwrap();
+        assert_eq!(res.val, Some(2643743));
+
+        let res: TestStruct = from_str(r#"{"val": ""}"#).unwrap();
+        assert_eq!(res.val, None);
+
+        let res: TestStruct = from_str(r#"{"val": null}"#).unwrap();
+        assert_eq!(res.val, None);
+
+        let res: TestStruct = from_str(r#"{}"#).unwrap();
+        assert_eq!(res.val, None);
+
+        assert!(from_str::<TestStruct>(r#"{"val": "text"}"#).is_err());
+    }
+
+    #[test]
+    fn test_bool_from_int() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(deserialize_with = "bool_from_int")]
+            val: bool,
+        }
+
+        for (json, expected) in [
+            (r#"{"val": true}"#, true),
+            (r#"{"val": false}"#, false),
+            (r#"{"val": 1}"#, true),
+            (r#"{"val": 0}"#, false),
+            (r#"{"val": "1"}"#, true),
+            (r#"{"val": "0"}"#, false),
+        ] {
+            let res: TestStruct = from_str(json).unwrap();
+            assert_eq!(res.val, expected, "for {}", json);
+        }
+
+        assert!(from_str::<TestStruct>(r#"{"val": 2}"#).is_err());
+        assert!(from_str::<TestStruct>(r#"{"val": "yes"}"#).is_err());
+    }
 }
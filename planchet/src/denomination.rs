@@ -0,0 +1,368 @@
+//! Parsing of face values into structured amounts.
+//!
+//! [`Value::text`] is written for collectors, e.g. `5 Cents`, `¼ Dollar`, or
+//! `2 Shillings 6 Pence`. [`parse`] reads such a text into a [`ParsedValue`],
+//! with the amount as a number and the unit in the singular, so types can be
+//! sorted and grouped by face value. Amounts written in several units of a
+//! non-decimal system, such as pounds, shillings, and pence, are converted
+//! into the smallest of them.
+//!
+//! This module is only available with the `decimal` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use planchet::denomination;
+//! use rust_decimal::Decimal;
+//!
+//! let value = denomination::parse("¼ Dollar").unwrap();
+//! assert_eq!(value.amount, Decimal::new(25, 2));
+//! assert_eq!(value.unit, "Dollar");
+//!
+//! let value = denomination::parse("2 Shillings 6 Pence").unwrap();
+//! assert_eq!(value.amount, Decimal::new(30, 0));
+//! assert_eq!(value.unit, "Penny");
+//! ```
+use crate::models::Value;
+use rust_decimal::Decimal;
+
+/// A face value, read from its text.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParsedValue {
+    /// The amount, in `unit`.
+    pub amount: Decimal,
+    /// The unit of the amount, in the singular, e.g. `Cent` for `5 Cents`.
+    pub unit: String,
+    /// The ID of the currency, if the value came with one.
+    pub currency_id: Option<i64>,
+}
+
+/// The units of the non-decimal systems, in lowercase, and their worth in the
+/// smallest unit of their system.
+const NON_DECIMAL_SYSTEMS: &[&[(&str, i64)]] = &[
+    // Pounds, shillings, and pence, in farthings.
+    &[
+        ("guinea", 1008),
+        ("pound", 960),
+        ("sovereign", 960),
+        ("crown", 240),
+        ("florin", 96),
+        ("shilling", 48),
+        ("groat", 16),
+        ("penny", 4),
+        ("farthing", 1),
+    ],
+    // Rupees, annas, and pice, in pies.
+    &[("rupee", 192), ("anna", 12), ("pice", 3), ("pie", 1)],
+    // Thalers, groschen, and pfennige, in pfennige.
+    &[("thaler", 360), ("groschen", 12), ("pfennig", 1)],
+];
+
+/// Plurals which are not formed by adding an `s`, in lowercase.
+const IRREGULAR_PLURALS: &[(&str, &str)] = &[
+    ("pence", "penny"),
+    ("lire", "lira"),
+    ("pfennige", "pfennig"),
+    ("kroner", "krone"),
+    ("kronor", "krona"),
+    ("kronur", "krona"),
+    ("centesimi", "centesimo"),
+    ("reis", "real"),
+    ("lei", "leu"),
+    ("leva", "lev"),
+    ("bani", "ban"),
+    ("stotinki", "stotinka"),
+];
+
+/// Units which hold their amount, as in `Halfpenny`, in lowercase.
+const UNITS_WITH_AMOUNT: &[(&str, i64, i64, &str)] = &[
+    ("halfpenny", 1, 2, "penny"),
+    ("halfpence", 1, 2, "penny"),
+    ("halfcrown", 1, 2, "crown"),
+];
+
+/// Parses the text of a face value.
+///
+/// The amount comes first, as digits (`5`, `2.5`, `1,000`), a fraction
+/// (`1/2`, `½`, `1 ½`), or a word (`Half`, `Quarter`). Anything in brackets
+/// is ignored. Returns `None` if the text has no amount or no unit, if it
+/// mixes units which are not of the same non-decimal system, or if the amount
+/// does not fit in a `Decimal`.
+pub fn parse(text: &str) -> Option<ParsedValue> {
+    let text = text.split(['(', '[']).next().unwrap_or_default().trim();
+    if let Some((_, numerator, denominator, unit)) = UNITS_WITH_AMOUNT
+        .iter()
+        .find(|(word, ..)| text.eq_ignore_ascii_case(word))
+    {
+        return Some(ParsedValue {
+            amount: Decimal::from(*numerator) / Decimal::from(*denominator),
+            unit: with_case_of(unit, text),
+            currency_id: None,
+        });
+    }
+
+    // The amounts of the text, each with the words of its unit.
+    let mut groups: Vec<(Decimal, Vec<&str>)> = Vec::new();
+    for token in tokens(text) {
+        let amount = parse_amount(token).or_else(|| {
+            // Words are only amounts before a unit.
+            match groups.last() {
+                Some((_, unit)) if unit.is_empty() => None,
+                _ => parse_amount_word(token),
+            }
+        });
+        match (amount, groups.last_mut()) {
+            (Some(amount), Some((total, unit))) if unit.is_empty() => {
+                *total = total.checked_add(amount)?;
+            }
+            (Some(amount), _) => groups.push((amount, Vec::new())),
+            (None, Some((_, unit))) => unit.push(token),
+            (None, None) => return None,
+        }
+    }
+
+    match groups.as_slice() {
+        [] => None,
+        [(amount, unit)] => Some(ParsedValue {
+            amount: *amount,
+            unit: singular(&unit_name(unit)?),
+            currency_id: None,
+        }),
+        _ => combine(&groups),
+    }
+}
+
+/// Splits a text into words, with fraction characters as words of their own,
+/// so `1½` reads as `1 ½`.
+fn tokens(text: &str) -> impl Iterator<Item = &str> {
+    text.split_whitespace().flat_map(|word| {
+        let split = word
+            .char_indices()
+            .find(|(i, c)| *i > 0 && vulgar_fraction(*c).is_some())
+            .map(|(i, _)| i);
+        match split {
+            Some(i) => vec![&word[..i], &word[i..]],
+            None => vec![word],
+        }
+    })
+}
+
+fn parse_amount(token: &str) -> Option<Decimal> {
+    let mut chars = token.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        if let Some((numerator, denominator)) = vulgar_fraction(c) {
+            return Some(Decimal::from(numerator) / Decimal::from(denominator));
+        }
+    }
+    if let Some((numerator, denominator)) = token.split_once('/') {
+        let numerator: Decimal = numerator.parse().ok()?;
+        let denominator: Decimal = denominator.parse().ok()?;
+        // This is `None` for a zero denominator too.
+        return numerator.checked_div(denominator);
+    }
+    if !token.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    // A comma followed by three digits separates thousands, any other comma
+    // is a decimal separator.
+    let mut normalized = String::with_capacity(token.len());
+    let mut rest = token;
+    while let Some(i) = rest.find(',') {
+        normalized.push_str(&rest[..i]);
+        let after = &rest[i + 1..];
+        let digits = after.bytes().take_while(u8::is_ascii_digit).count();
+        if digits != 3 {
+            normalized.push('.');
+        }
+        rest = after;
+    }
+    normalized.push_str(rest);
+    normalized.parse().ok()
+}
+
+fn parse_amount_word(token: &str) -> Option<Decimal> {
+    match token.to_lowercase().as_str() {
+        "half" => Some(Decimal::new(5, 1)),
+        "quarter" => Some(Decimal::new(25, 2)),
+        _ => None,
+    }
+}
+
+fn vulgar_fraction(c: char) -> Option<(i64, i64)> {
+    Some(match c {
+        '½' => (1, 2),
+        '⅓' => (1, 3),
+        '⅔' => (2, 3),
+        '¼' => (1, 4),
+        '¾' => (3, 4),
+        '⅕' => (1, 5),
+        '⅙' => (1, 6),
+        '⅛' => (1, 8),
+        '⅜' => (3, 8),
+        '⅝' => (5, 8),
+        '⅞' => (7, 8),
+        '⅒' => (1, 10),
+        _ => return None,
+    })
+}
+
+/// Joins the words of a unit, if there are any.
+fn unit_name(words: &[&str]) -> Option<String> {
+    (!words.is_empty()).then(|| words.join(" "))
+}
+
+/// Converts an amount written in several units of a non-decimal system into
+/// the smallest of them.
+fn combine(groups: &[(Decimal, Vec<&str>)]) -> Option<ParsedValue> {
+    let mut units = Vec::with_capacity(groups.len());
+    for (amount, words) in groups {
+        let unit = singular(&unit_name(words)?);
+        units.push((*amount, unit));
+    }
+    let system = NON_DECIMAL_SYSTEMS
+        .iter()
+        .find(|system| units.iter().all(|(_, unit)| worth(system, unit).is_some()))?;
+
+    let mut total = Decimal::ZERO;
+    let mut smallest: Option<(i64, &str)> = None;
+    for (amount, unit) in &units {
+        let worth = worth(system, unit)?;
+        total = total.checked_add(amount.checked_mul(Decimal::from(worth))?)?;
+        if smallest.is_none_or(|(w, _)| worth < w) {
+            smallest = Some((worth, unit));
+        }
+    }
+    let (worth, unit) = smallest?;
+    Some(ParsedValue {
+        amount: total / Decimal::from(worth),
+        unit: unit.to_string(),
+        currency_id: None,
+    })
+}
+
+fn worth(system: &[(&str, i64)], unit: &str) -> Option<i64> {
+    system
+        .iter()
+        .find(|(name, _)| unit.eq_ignore_ascii_case(name))
+        .map(|(_, worth)| *worth)
+}
+
+/// Returns a unit in the singular. Only the last word changes, so `Euro
+/// Cents` becomes `Euro Cent`.
+fn singular(unit: &str) -> String {
+    let (head, last) = match unit.rsplit_once(' ') {
+        Some((head, last)) => (Some(head), last),
+        None => (None, unit),
+    };
+    let lower = last.to_lowercase();
+    let singular = match IRREGULAR_PLURALS
+        .iter()
+        .find(|(plural, _)| *plural == lower)
+    {
+        Some((_, singular)) => with_case_of(singular, last),
+        None if lower.len() > 3 && lower.ends_with('s') && !lower.ends_with("ss") => {
+            last[..last.len() - 1].to_string()
+        }
+        None => last.to_string(),
+    };
+    match head {
+        Some(head) => format!("{} {}", head, singular),
+        None => singular,
+    }
+}
+
+/// Capitalizes a lowercase word if `like` is capitalized.
+fn with_case_of(word: &str, like: &str) -> String {
+    if !like.starts_with(char::is_uppercase) {
+        return word.to_string();
+    }
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+impl Value {
+    /// Parses the text of the value. See [`parse`].
+    pub fn parsed(&self) -> Option<ParsedValue> {
+        let mut parsed = parse(self.text.as_deref()?)?;
+        parsed.currency_id = self.currency.as_ref().map(|c| c.id);
+        Some(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(text: &str) -> Option<(Decimal, String)> {
+        parse(text).map(|v| (v.amount, v.unit))
+    }
+
+    fn value(amount: Decimal, unit: &str) -> Option<(Decimal, String)> {
+        Some((amount, unit.to_string()))
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parsed("5 Cents"), value(Decimal::new(5, 0), "Cent"));
+        assert_eq!(parsed("1 Dollar"), value(Decimal::ONE, "Dollar"));
+        assert_eq!(parsed("¼ Dollar"), value(Decimal::new(25, 2), "Dollar"));
+        assert_eq!(parsed("1/2 Penny"), value(Decimal::new(5, 1), "Penny"));
+        assert_eq!(parsed("1½ Pence"), value(Decimal::new(15, 1), "Penny"));
+        assert_eq!(parsed("2 ½ Annas"), value(Decimal::new(25, 1), "Anna"));
+        assert_eq!(parsed("Half Crown"), value(Decimal::new(5, 1), "Crown"));
+        assert_eq!(parsed("Halfpenny"), value(Decimal::new(5, 1), "Penny"));
+        assert_eq!(parsed("2,5 Francs"), value(Decimal::new(25, 1), "Franc"));
+        assert_eq!(parsed("1,000 Lire"), value(Decimal::new(1000, 0), "Lira"));
+        assert_eq!(
+            parsed("10 Euro Cents"),
+            value(Decimal::new(10, 0), "Euro Cent")
+        );
+        assert_eq!(parsed("1 Peso (Silver)"), value(Decimal::ONE, "Peso"));
+    }
+
+    #[test]
+    fn test_parse_non_decimal() {
+        assert_eq!(
+            parsed("2 Shillings 6 Pence"),
+            value(Decimal::new(30, 0), "Penny")
+        );
+        assert_eq!(
+            parsed("1 Pound 1 Shilling"),
+            value(Decimal::new(21, 0), "Shilling")
+        );
+        assert_eq!(
+            parsed("1 Rupee 2 Annas"),
+            value(Decimal::new(18, 0), "Anna")
+        );
+        // Units of different systems do not add up.
+        assert_eq!(parsed("1 Dollar 5 Cents"), None);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for text in ["", "Dollar", "5", "Trade token", "1/0 Dollar"] {
+            assert_eq!(parse(text), None, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_parse_overflow() {
+        let max = Decimal::MAX.to_string();
+        for text in [
+            format!("{}/0.001 Dollar", max),
+            format!("{} {} Dollar", max, max),
+            format!("{} ½ Dollar", max),
+            format!("{} Pounds {} Shillings", max, max),
+            format!("{} Guineas 1 Penny", max),
+        ] {
+            assert_eq!(parse(&text), None, "{}", text);
+        }
+        assert_eq!(
+            parsed(&format!("{} Dollars", max)),
+            value(Decimal::MAX, "Dollar")
+        );
+    }
+}
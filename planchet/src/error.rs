@@ -1,15 +1,117 @@
+use crate::circuit_breaker::CircuitOpen;
+use crate::oauth::Scope;
+use crate::pagination::PaginationError;
+use crate::validation::ValidationIssue;
 use thiserror::Error;
 
 /// An error returned by the Numista API.
 #[derive(Debug)]
+#[non_exhaustive]
 pub struct ApiError {
     /// The error message returned by the API.
     pub message: String,
     /// The HTTP status code returned by the API.
     pub status: u16,
+    /// The ID generated for the request, also sent in the `X-Request-ID`
+    /// header. Include it when reporting a problem.
+    pub request_id: Option<String>,
+    /// The machine-readable error code returned by the API, if any.
+    pub code: Option<String>,
+    /// The validation errors of individual fields, returned with some
+    /// `400 Bad Request` responses.
+    pub field_errors: Vec<FieldError>,
+}
+
+/// A validation error of one field of a request, part of an [`ApiError`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FieldError {
+    /// The name of the field, e.g. `grade`.
+    pub field: String,
+    /// Why the value of the field is invalid.
+    pub message: String,
+}
+
+impl FieldError {
+    /// Creates the validation error of a field.
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        FieldError {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
 }
 
 impl ApiError {
+    /// Creates an error with a status code and a message, e.g. to return from
+    /// a mock [`NumistaApi`](crate::NumistaApi).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use planchet::{ApiError, ErrorKind};
+    ///
+    /// let error = ApiError::new(400, "Invalid grade")
+    ///     .with_code("invalid_parameter")
+    ///     .with_field_error("grade", "Unknown grade 'mint'");
+    /// assert_eq!(error.kind(), ErrorKind::ClientBug);
+    /// assert_eq!(error.errors_for("grade").count(), 1);
+    /// ```
+    pub fn new(status: u16, message: impl Into<String>) -> Self {
+        ApiError {
+            message: message.into(),
+            status,
+            request_id: None,
+            code: None,
+            field_errors: Vec::new(),
+        }
+    }
+
+    /// Sets the machine-readable error code.
+    pub fn with_code(mut self, code: impl Into<String>) -> Self {
+        self.code = Some(code.into());
+        self
+    }
+
+    /// Sets the ID of the request.
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id = Some(request_id.into());
+        self
+    }
+
+    /// Adds a validation error of a field.
+    pub fn with_field_error(
+        mut self,
+        field: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        self.field_errors.push(FieldError::new(field, message));
+        self
+    }
+
+    /// Returns the validation errors of a field.
+    pub fn errors_for<'a>(&'a self, field: &'a str) -> impl Iterator<Item = &'a FieldError> {
+        self.field_errors.iter().filter(move |e| e.field == field)
+    }
+
+    /// Returns the category of the error.
+    ///
+    /// The error code is used when it is a known one, as it is more precise
+    /// than the HTTP status code, which is used otherwise.
+    pub fn kind(&self) -> ErrorKind {
+        if let Some(kind) = self.code.as_deref().and_then(kind_of_code) {
+            return kind;
+        }
+        match self.status {
+            401 | 403 | 501 => ErrorKind::Auth,
+            404 => ErrorKind::NotFound,
+            429 => ErrorKind::RateLimited,
+            400..=499 => ErrorKind::ClientBug,
+            500..=599 => ErrorKind::Server,
+            _ => ErrorKind::Other,
+        }
+    }
+
     /// Checks if the error is due to an invalid or missing parameter (HTTP 400).
     ///
     /// See <https://numista.com/api/doc> for more details.
@@ -48,23 +150,201 @@ impl ApiError {
     }
 }
 
+/// Maps the error codes of the API to the category of the error.
+fn kind_of_code(code: &str) -> Option<ErrorKind> {
+    match code {
+        "invalid_parameter" | "missing_parameter" | "invalid_request" => Some(ErrorKind::ClientBug),
+        "invalid_api_key"
+        | "unauthorized"
+        | "invalid_token"
+        | "expired_token"
+        | "insufficient_scope"
+        | "forbidden"
+        | "no_user_associated_with_api_key" => Some(ErrorKind::Auth),
+        "not_found" => Some(ErrorKind::NotFound),
+        "rate_limit_exceeded" | "quota_exceeded" => Some(ErrorKind::RateLimited),
+        _ => None,
+    }
+}
+
+/// Checks if an error of the HTTP client is a failure to decode a response
+/// body, rather than to send the request or receive the response.
+fn is_decode_error(err: &(dyn std::error::Error + 'static)) -> bool {
+    if let Some(err) = err.downcast_ref::<reqwest::Error>() {
+        return err.is_decode();
+    }
+    matches!(
+        err.downcast_ref::<reqwest_middleware::Error>(),
+        Some(reqwest_middleware::Error::Reqwest(err)) if err.is_decode()
+    )
+}
+
+/// A broad category of [`Error`](enum@Error), for deciding how to react to a failure.
+///
+/// Returned by [`Error::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The request could not be sent, or no response was received.
+    Network,
+    /// The API rate limit has been exceeded.
+    RateLimited,
+    /// The API failed to handle a valid request.
+    Server,
+    /// The credentials are missing, invalid, expired, or not allowed to
+    /// access the resource.
+    Auth,
+    /// The requested resource does not exist.
+    NotFound,
+    /// The request was invalid, so sending it again will fail the same way.
+    ClientBug,
+    /// The operation was cancelled.
+    Cancelled,
+    /// Any other error, e.g. an I/O error.
+    Other,
+}
+
 /// The error type for this crate.
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum Error {
-    /// The API key was not provided in the `ClientBuilder`.
+    /// The API key was not provided in the `ClientBuilder`, nor allowed to be
+    /// missing with `ClientBuilder::allow_missing_key`.
     #[error("Numista API key is required")]
     ApiKeyMissing,
 
+    /// The API key provided in the `ClientBuilder` cannot be used.
+    #[error("Invalid Numista API key: {0}")]
+    InvalidApiKey(String),
+
+    /// The bearer token provided in the `ClientBuilder` cannot be used.
+    #[error("Invalid bearer token: {0}")]
+    InvalidToken(String),
+
+    /// The API refused to read the collection of a user, which is not public,
+    /// without a bearer token of the user with the `view_collection` scope.
+    #[error("Authorization required: the collection of user {0} is not public, and can only be read with a token of its owner")]
+    AuthorizationRequired(i64),
+
+    /// The bearer token of the client was not granted a scope needed by a
+    /// request, so the request was not sent.
+    #[error("The token was not granted the {0} scope")]
+    MissingScope(Scope),
+
+    /// The user did not grant access in the OAuth authorization flow.
+    ///
+    /// See the [`oauth`](crate::oauth) module.
+    #[error("OAuth authorization failed: {0}")]
+    OAuth(String),
+
     /// An error related to the underlying HTTP client or middleware stack.
     #[error("Request error: {0}")]
     Request(#[from] Box<dyn std::error::Error + Send + Sync>),
 
+    /// The language given to the `ClientBuilder` is not supported by the API.
+    ///
+    /// See [`SupportedLanguage`](crate::SupportedLanguage).
+    #[error("Language not supported by the Numista API: '{0}'")]
+    UnsupportedLanguage(String),
+
+    /// An image could not be used to search by image.
+    #[error("Invalid image: {0}")]
+    InvalidImage(String),
+
+    /// An I/O error, e.g. while writing downloaded pictures to disk.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
     /// An error from `serde_json`.
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
 
+    /// An error from the SQLite store.
+    #[cfg(feature = "store")]
+    #[error("Store error: {0}")]
+    Store(#[from] rusqlite::Error),
+
+    /// An error reading a CSV file to import.
+    #[cfg(feature = "import")]
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+
+    /// A row of a CSV file could not be imported.
+    #[cfg(feature = "import")]
+    #[error("Import error: {0}")]
+    Import(String),
+
+    /// The GeoNames web services returned an error.
+    #[cfg(feature = "geonames")]
+    #[error("GeoNames error: {0}")]
+    GeoNames(String),
+
+    /// A report could not be rendered.
+    #[cfg(feature = "report")]
+    #[error("Report error: {0}")]
+    Report(String),
+
+    /// An exchange rate is unknown, or could not be fetched.
+    #[error("Exchange rate error: {0}")]
+    ExchangeRate(String),
+
+    /// A spot price is unknown, or could not be fetched.
+    #[error("Spot price error: {0}")]
+    SpotPrice(String),
+
+    /// A QR code could not be generated, e.g. from a too long text.
+    #[cfg(feature = "qr")]
+    #[error("QR code error: {0}")]
+    Qr(String),
+
+    /// A cassette could not be read or written, or has no response recorded
+    /// for a request.
+    #[cfg(feature = "vcr")]
+    #[error("Cassette error: {0}")]
+    Vcr(String),
+
+    /// An audit log could not be verified.
+    #[cfg(feature = "audit")]
+    #[error("Audit log error: {0}")]
+    Audit(String),
+
+    /// The request parameters are invalid, so the request was not sent.
+    #[error("Invalid parameters: {}", .0.iter().map(|i| i.to_string()).collect::<Vec<_>>().join("; "))]
+    Validation(Vec<ValidationIssue>),
+
+    /// The operation was cancelled through its `CancellationToken`.
+    #[error("Operation cancelled")]
+    Cancelled,
+
+    /// A paginated pull was stopped because the API paginated inconsistently.
+    #[error("Pagination error: {0}")]
+    Pagination(PaginationError),
+
+    /// The request was not sent because the circuit breaker of the client is
+    /// open, after repeated failures. It may be sent again after the given
+    /// time.
+    ///
+    /// See the [`circuit_breaker`](crate::circuit_breaker) module.
+    #[error("Circuit breaker open, retry in {0:?}")]
+    CircuitOpen(std::time::Duration),
+
+    /// A method returning the item written was called on a client in dry-run
+    /// mode, which has no item to return since it does not send the request.
+    /// The `_with_outcome` variants of these methods return the request
+    /// planned instead.
+    ///
+    /// See [`ClientBuilder::dry_run`](crate::ClientBuilder::dry_run).
+    #[error("No item is written in dry-run mode: use the _with_outcome variant of the method")]
+    DryRunWithoutOutcome,
+
     /// An error returned by the Numista API.
-    #[error("API error (status {}): {}", .0.status, .0.message)]
+    #[error(
+        "API error (status {}{}): {}{}",
+        .0.status,
+        .0.code.as_ref().map(|code| format!(", code {}", code)).unwrap_or_default(),
+        .0.message,
+        .0.request_id.as_ref().map(|id| format!(" (request ID {})", id)).unwrap_or_default()
+    )]
     ApiError(ApiError),
 }
 
@@ -76,9 +356,138 @@ impl From<reqwest::Error> for Error {
 
 impl From<reqwest_middleware::Error> for Error {
     fn from(err: reqwest_middleware::Error) -> Self {
-        Error::Request(Box::new(err))
+        match err {
+            reqwest_middleware::Error::Middleware(e) => {
+                let e = match e.downcast::<CircuitOpen>() {
+                    Ok(open) => return Error::CircuitOpen(open.retry_in),
+                    Err(e) => e,
+                };
+                #[cfg(feature = "vcr")]
+                let e = match e.downcast::<crate::vcr::CassetteError>() {
+                    Ok(cassette) => return Error::Vcr(cassette.0),
+                    Err(e) => e,
+                };
+                // The errors of an `AuthProvider`.
+                let e = match e.downcast::<Error>() {
+                    Ok(err) => return err,
+                    Err(e) => e,
+                };
+                Error::Request(Box::new(reqwest_middleware::Error::Middleware(e)))
+            }
+            err => Error::Request(Box::new(err)),
+        }
+    }
+}
+
+impl Error {
+    /// Returns the ID generated for the request which failed, if the API
+    /// responded.
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            Error::ApiError(e) => e.request_id.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Returns the category of the error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::ApiKeyMissing
+            | Error::InvalidApiKey(_)
+            | Error::InvalidToken(_)
+            | Error::AuthorizationRequired(_)
+            | Error::MissingScope(_)
+            | Error::OAuth(_) => ErrorKind::Auth,
+            // A response which does not decode would fail the same way again.
+            Error::Request(e) if is_decode_error(e.as_ref()) => ErrorKind::Other,
+            Error::Request(_) => ErrorKind::Network,
+            Error::CircuitOpen(_) => ErrorKind::Server,
+            Error::InvalidImage(_)
+            | Error::UnsupportedLanguage(_)
+            | Error::Validation(_)
+            | Error::DryRunWithoutOutcome => ErrorKind::ClientBug,
+            Error::Cancelled => ErrorKind::Cancelled,
+            Error::ApiError(e) => e.kind(),
+            _ => ErrorKind::Other,
+        }
+    }
+
+    /// Checks if the request may succeed if sent again later: a network
+    /// failure, an exceeded rate limit or a server error.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self.kind(),
+            ErrorKind::Network | ErrorKind::RateLimited | ErrorKind::Server
+        )
+    }
+
+    /// Checks if the error is due to missing or invalid credentials.
+    pub fn is_auth(&self) -> bool {
+        self.kind() == ErrorKind::Auth
+    }
+
+    /// Checks if the requested resource does not exist.
+    pub fn is_not_found(&self) -> bool {
+        self.kind() == ErrorKind::NotFound
+    }
+
+    /// Checks if the request was invalid, which usually means a bug in the
+    /// calling code.
+    pub fn is_client_bug(&self) -> bool {
+        self.kind() == ErrorKind::ClientBug
+    }
+
+    /// Checks if the API failed to handle the request.
+    pub fn is_server(&self) -> bool {
+        self.kind() == ErrorKind::Server
     }
 }
 
 /// A `Result` type alias for this crate's `Error` type.
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn api_error(status: u16) -> Error {
+        Error::ApiError(ApiError::new(status, "error"))
+    }
+
+    #[test]
+    fn test_kind_of_api_errors() {
+        assert_eq!(api_error(400).kind(), ErrorKind::ClientBug);
+        assert_eq!(api_error(401).kind(), ErrorKind::Auth);
+        assert_eq!(api_error(404).kind(), ErrorKind::NotFound);
+        assert_eq!(api_error(429).kind(), ErrorKind::RateLimited);
+        assert_eq!(api_error(501).kind(), ErrorKind::Auth);
+        assert_eq!(api_error(503).kind(), ErrorKind::Server);
+    }
+
+    #[test]
+    fn test_kind_of_error_codes() {
+        let error = |status, code: &str| ApiError::new(status, "error").with_code(code);
+        assert_eq!(error(400, "invalid_token").kind(), ErrorKind::Auth);
+        assert_eq!(error(400, "not_found").kind(), ErrorKind::NotFound);
+        assert_eq!(error(400, "quota_exceeded").kind(), ErrorKind::RateLimited);
+        assert_eq!(error(401, "invalid_parameter").kind(), ErrorKind::ClientBug);
+        // Unknown codes fall back to the status.
+        assert_eq!(error(400, "unknown_code").kind(), ErrorKind::ClientBug);
+        assert_eq!(error(503, "unknown_code").kind(), ErrorKind::Server);
+    }
+
+    #[test]
+    fn test_predicates() {
+        assert!(api_error(429).is_retryable());
+        assert!(api_error(502).is_retryable());
+        assert!(!api_error(400).is_retryable());
+        assert!(api_error(401).is_auth());
+        assert!(Error::ApiKeyMissing.is_auth());
+        assert!(Error::AuthorizationRequired(1).is_auth());
+        assert!(api_error(404).is_not_found());
+        assert!(Error::Validation(Vec::new()).is_client_bug());
+        assert!(api_error(500).is_server());
+        assert!(!Error::Cancelled.is_retryable());
+        assert_eq!(Error::Cancelled.kind(), ErrorKind::Cancelled);
+    }
+}
@@ -0,0 +1,379 @@
+//! Labels for 2x2 flips.
+//!
+//! Collectors keep their coins in 2x2 inch flips, with a label describing the
+//! piece. [`Labels`] prints a label per collected item, with the title of its
+//! type, its year and mint, its grade, its catalogue references, and a QR code
+//! linking to the page of the type on Numista. The labels are laid out on A4
+//! sheets, 15 per sheet, with their outline as a cutting line, in a PDF
+//! document or in SVG pictures.
+//!
+//! This module is only available with the `labels` feature.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use planchet::export::labels::Labels;
+//! use planchet::models::GetCollectedItemsParams;
+//! use planchet::ClientBuilder;
+//!
+//! # async fn run() -> planchet::Result<()> {
+//! let client = ClientBuilder::new().api_key("YOUR_API_KEY").build()?;
+//! let items: Vec<_> = client
+//!     .get_collected_items(123, &GetCollectedItemsParams::new())
+//!     .await?
+//!     .items
+//!     .into_iter()
+//!     .filter(|item| item.storage_location.as_deref() == Some("Album 3"))
+//!     .collect();
+//!
+//! std::fs::write("labels.pdf", Labels::new(&items).pdf())?;
+//! # Ok(())
+//! # }
+//! ```
+use super::pdf::{self, Document, Font, A4_HEIGHT, A4_WIDTH};
+use super::{issue_year, reference};
+use crate::models::{CollectedItem, NumistaType};
+use crate::qr::QrCode;
+use crate::urls::TypeLink;
+use isolang::Language;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// The size of a label, 2 inches, in points.
+const LABEL_SIZE: f32 = 144.0;
+const COLUMNS: usize = 3;
+const ROWS: usize = 5;
+const PADDING: f32 = 8.0;
+const QR_SIZE: f32 = 48.0;
+const TITLE_SIZE: f32 = 9.0;
+const TITLE_LINES: usize = 3;
+const DETAIL_SIZE: f32 = 8.0;
+
+/// Labels for the 2x2 flips of collected items.
+#[derive(Debug, Clone)]
+pub struct Labels<'a> {
+    items: &'a [CollectedItem],
+    types: Option<&'a HashMap<i64, NumistaType>>,
+    lang: Option<Language>,
+}
+
+impl<'a> Labels<'a> {
+    /// Creates a label for each of the given items, in the same order.
+    pub fn new(items: &'a [CollectedItem]) -> Self {
+        Self {
+            items,
+            types: None,
+            lang: None,
+        }
+    }
+
+    /// Sets the full types of the items, by ID, to add the catalogue
+    /// references of their type to the labels.
+    pub fn types(mut self, types: &'a HashMap<i64, NumistaType>) -> Self {
+        self.types = Some(types);
+        self
+    }
+
+    /// Sets the language of the pages the QR codes link to.
+    pub fn lang(mut self, lang: Language) -> Self {
+        self.lang = Some(lang);
+        self
+    }
+
+    /// Renders the labels as a PDF document, with a page per sheet.
+    pub fn pdf(&self) -> Vec<u8> {
+        let mut doc = Document::new(A4_WIDTH, A4_HEIGHT);
+        for (i, item) in self.items.iter().enumerate() {
+            if i % (COLUMNS * ROWS) == 0 {
+                doc.add_page();
+            }
+            let (x, y) = position(i);
+            self.draw(&mut PdfCanvas(&mut doc), item, x, y);
+        }
+        doc.finish()
+    }
+
+    /// Renders the labels as SVG pictures, one per sheet.
+    pub fn svg(&self) -> Vec<String> {
+        self.items
+            .chunks(COLUMNS * ROWS)
+            .map(|items| {
+                let mut canvas = SvgCanvas::new();
+                for (i, item) in items.iter().enumerate() {
+                    let (x, y) = position(i);
+                    self.draw(&mut canvas, item, x, y);
+                }
+                canvas.finish()
+            })
+            .collect()
+    }
+
+    /// Draws the label of an item, from its top left corner.
+    fn draw(&self, canvas: &mut impl Canvas, item: &CollectedItem, x: f32, y: f32) {
+        canvas.stroke_rect(x, y, LABEL_SIZE, LABEL_SIZE);
+        let width = LABEL_SIZE - 2.0 * PADDING;
+        let mut title = pdf::wrap(&item.type_info.title, Font::Bold, TITLE_SIZE, width);
+        if title.len() > TITLE_LINES {
+            let overflow = format!("{} {}", title[TITLE_LINES - 1], title[TITLE_LINES]);
+            title.truncate(TITLE_LINES - 1);
+            title.push(fit(&overflow, Font::Bold, TITLE_SIZE, width, true));
+        }
+        let mut top = y + PADDING;
+        for line in &title {
+            top += TITLE_SIZE + 1.5;
+            canvas.text(x + PADDING, top, TITLE_SIZE, Font::Bold, line);
+        }
+        top += 4.0;
+        for line in self.details(item) {
+            top += DETAIL_SIZE + 2.0;
+            let line = fit(&line, Font::Regular, DETAIL_SIZE, width, false);
+            canvas.text(x + PADDING, top, DETAIL_SIZE, Font::Regular, &line);
+        }
+
+        let mut link = TypeLink::new(item.type_info.id, item.type_info.category.clone());
+        if let Some(lang) = self.lang {
+            link = link.lang(lang);
+        }
+        let qr = QrCode::for_type(&link);
+        let module = QR_SIZE / qr.size() as f32;
+        let left = x + LABEL_SIZE - PADDING - QR_SIZE;
+        let top = y + LABEL_SIZE - PADDING - QR_SIZE;
+        for row in 0..qr.size() {
+            for column in (0..qr.size()).filter(|&c| qr.is_dark(c, row)) {
+                let (x, y) = (left + column as f32 * module, top + row as f32 * module);
+                canvas.fill_rect(x, y, module, module);
+            }
+        }
+    }
+
+    /// Returns the lines under the title of the label of an item.
+    fn details(&self, item: &CollectedItem) -> Vec<String> {
+        let type_ = self.types.and_then(|types| types.get(&item.type_info.id));
+        let mut issue = Vec::new();
+        if let Some(item_issue) = &item.issue {
+            issue.extend(issue_year(item_issue));
+            issue.extend(item_issue.mint_letter.clone());
+        }
+        let mints = type_.and_then(|t| t.mints.as_deref()).unwrap_or_default();
+        if let ([mint], None) = (
+            mints,
+            item.issue.as_ref().and_then(|i| i.mint_letter.as_ref()),
+        ) {
+            issue.push(mint.name.clone());
+        }
+
+        let mut grade: Vec<String> = item.grade.iter().map(|g| g.to_string()).collect();
+        if let Some(details) = &item.grading_details {
+            grade.extend(details.grading_company.as_ref().map(|c| c.name.clone()));
+            grade.extend(details.slab_grade.as_ref().map(|g| g.value.clone()));
+        }
+
+        let references: Vec<String> = type_
+            .and_then(|t| t.references.as_ref())
+            .into_iter()
+            .flatten()
+            .map(reference)
+            .collect();
+        [issue.join(" "), grade.join(" "), references.join(", ")]
+            .into_iter()
+            .filter(|line| !line.is_empty())
+            .collect()
+    }
+}
+
+/// Returns the top left corner of the label at an index, on its sheet.
+fn position(index: usize) -> (f32, f32) {
+    let index = index % (COLUMNS * ROWS);
+    let left = (A4_WIDTH - COLUMNS as f32 * LABEL_SIZE) / 2.0;
+    let top = (A4_HEIGHT - ROWS as f32 * LABEL_SIZE) / 2.0;
+    let (row, column) = (index / COLUMNS, index % COLUMNS);
+    (
+        left + column as f32 * LABEL_SIZE,
+        top + row as f32 * LABEL_SIZE,
+    )
+}
+
+/// Shortens a text with an ellipsis until it is no wider than `width`. If
+/// `truncated` is set, the ellipsis is added even if the text fits.
+fn fit(text: &str, font: Font, size: f32, width: f32, truncated: bool) -> String {
+    if !truncated && pdf::text_width(text, font, size) <= width {
+        return text.to_string();
+    }
+    let mut text = text.to_string();
+    loop {
+        let candidate = format!("{}…", text.trim_end());
+        if text.is_empty() || pdf::text_width(&candidate, font, size) <= width {
+            return candidate;
+        }
+        text.pop();
+    }
+}
+
+/// A sheet on which labels are drawn, with coordinates from its top left
+/// corner.
+trait Canvas {
+    /// Draws a line of text, from the left end of its baseline.
+    fn text(&mut self, x: f32, y: f32, size: f32, font: Font, text: &str);
+
+    /// Draws a black rectangle.
+    fn fill_rect(&mut self, x: f32, y: f32, width: f32, height: f32);
+
+    /// Draws the outline of a rectangle, as a cutting line.
+    fn stroke_rect(&mut self, x: f32, y: f32, width: f32, height: f32);
+}
+
+/// The current page of a PDF document, whose coordinates are from the
+/// bottom left corner.
+struct PdfCanvas<'a>(&'a mut Document);
+
+impl Canvas for PdfCanvas<'_> {
+    fn text(&mut self, x: f32, y: f32, size: f32, font: Font, text: &str) {
+        let height = self.0.height();
+        self.0.text(x, height - y, size, font, text);
+    }
+
+    fn fill_rect(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        let bottom = self.0.height() - y - height;
+        self.0.fill_rect(x, bottom, width, height);
+    }
+
+    fn stroke_rect(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        let bottom = self.0.height() - y - height;
+        self.0.stroke_rect(x, bottom, width, height, 0.25);
+    }
+}
+
+/// An SVG picture of an A4 sheet.
+struct SvgCanvas {
+    out: String,
+}
+
+impl SvgCanvas {
+    fn new() -> Self {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"210mm\" height=\"297mm\" \
+             viewBox=\"0 0 {} {}\">",
+            A4_WIDTH, A4_HEIGHT
+        );
+        Self { out }
+    }
+
+    fn finish(mut self) -> String {
+        self.out.push_str("</svg>\n");
+        self.out
+    }
+}
+
+impl Canvas for SvgCanvas {
+    fn text(&mut self, x: f32, y: f32, size: f32, font: Font, text: &str) {
+        let weight = match font {
+            Font::Regular => "normal",
+            Font::Bold => "bold",
+        };
+        let _ = writeln!(
+            self.out,
+            "<text x=\"{}\" y=\"{}\" \
+             font-family=\"DejaVu Sans, Helvetica, Arial, sans-serif\" \
+             font-size=\"{}\" font-weight=\"{}\">{}</text>",
+            x,
+            y,
+            size,
+            weight,
+            escape(text)
+        );
+    }
+
+    fn fill_rect(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        let _ = writeln!(
+            self.out,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/>",
+            x, y, width, height
+        );
+    }
+
+    fn stroke_rect(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        let _ = writeln!(
+            self.out,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" \
+             stroke=\"black\" stroke-width=\"0.25\"/>",
+            x, y, width, height
+        );
+    }
+}
+
+/// Escapes the characters with a special meaning in SVG text.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn item(id: i64, title: &str) -> CollectedItem {
+        serde_json::from_value(json!({
+            "id": id,
+            "quantity": 1,
+            "type": {"id": 420, "title": title, "category": "coin"},
+            "issue": {"id": 1, "year": 1858, "mint_letter": "H"},
+            "for_swap": 0,
+            "grade": "vf",
+        }))
+        .unwrap()
+    }
+
+    fn types() -> HashMap<i64, NumistaType> {
+        let type_ = serde_json::from_value(json!({
+            "id": 420,
+            "title": "5 Cents - Victoria",
+            "category": "coin",
+            "references": [
+                {"catalogue": {"id": 3, "code": "KM"}, "number": "2"},
+                {"catalogue": {"id": 9, "code": "Charlton"}, "number": "1"},
+            ],
+        }))
+        .unwrap();
+        [(420, type_)].into()
+    }
+
+    #[test]
+    fn test_pdf_labels() {
+        let items: Vec<CollectedItem> = (0..16).map(|i| item(i, "5 Cents - Victoria")).collect();
+        let types = types();
+        let pdf = Labels::new(&items).types(&types).pdf();
+        let text = String::from_utf8_lossy(&pdf);
+
+        assert!(text.contains("/Count 2"));
+        let title = pdf::shown("5 Cents - Victoria", Font::Bold);
+        assert_eq!(text.matches(&title).count(), 16);
+        assert!(text.contains(&pdf::shown("1858 H", Font::Regular)));
+        assert!(text.contains(&pdf::shown("VF", Font::Regular)));
+        assert!(text.contains(&pdf::shown("KM# 2, Charlton# 1", Font::Regular)));
+        assert_eq!(text.matches(" re\nS").count(), 16);
+        assert!(text.contains(" re\nf"));
+    }
+
+    #[test]
+    fn test_svg_labels() {
+        let title = "1 Dollar - Elizabeth II & the Loon, with a title far too long for the three lines of a flip label";
+        let items = [item(1, title)];
+        let sheets = Labels::new(&items).svg();
+        assert_eq!(sheets.len(), 1);
+
+        let svg = &sheets[0];
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(svg.contains("&amp;"));
+        assert!(!svg.contains(" & "));
+        assert_eq!(svg.matches("font-weight=\"bold\"").count(), TITLE_LINES);
+        assert!(svg.contains("…</text>"));
+        assert!(svg.matches("<rect").count() > 100);
+
+        assert!(Labels::new(&[]).svg().is_empty());
+    }
+}
@@ -0,0 +1,35 @@
+//! Printable documents made from a collection.
+//!
+//! The documents are written without any external tool or font: PDF
+//! documents embed the DejaVu Sans fonts, which cover the Latin, Greek, and
+//! Cyrillic scripts among others. Characters missing from the fonts, e.g.
+//! Chinese or Japanese, are left out. The fonts are embedded whole, so a
+//! document weighs at least 1.5 MB.
+//!
+//! * [`pdf`]: an inventory of the items of a collection, for insurance
+//!   purposes.
+//! * `labels`: labels for 2x2 flips, with a QR code linking to the page of
+//!   the type on Numista, with the `labels` feature.
+//!
+//! This module is only available with the `pdf` feature.
+use crate::models::{Issue, Reference};
+
+#[cfg(feature = "labels")]
+pub mod labels;
+pub mod pdf;
+
+/// Returns the year of an issue as shown on the pieces, or its range of
+/// years if it is undated.
+pub(crate) fn issue_year(issue: &Issue) -> Option<String> {
+    match (issue.year, issue.min_year, issue.max_year) {
+        (Some(year), _, _) => Some(year.to_string()),
+        (None, Some(min), Some(max)) if min != max => Some(format!("{}–{}", min, max)),
+        (None, Some(year), _) | (None, None, Some(year)) => Some(year.to_string()),
+        (None, None, None) => None,
+    }
+}
+
+/// Returns a catalogue reference as written by collectors, e.g. `KM# 7`.
+pub(crate) fn reference(reference: &Reference) -> String {
+    format!("{}# {}", reference.catalogue.code, reference.number)
+}
@@ -0,0 +1,720 @@
+//! PDF inventories of a collection.
+//!
+//! An [`Inventory`] lists the items of a collection with their photo,
+//! description, serial number, acquisition, and value, as a PDF document to
+//! keep with an insurance policy. Collected items only hold a summary of
+//! their type, so the full types can be given to describe each item with its
+//! composition, weight, size, and catalogue references.
+//!
+//! This module is only available with the `pdf` feature.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use planchet::export::pdf::Inventory;
+//! use planchet::models::GetCollectedItemsParams;
+//! use planchet::ClientBuilder;
+//!
+//! # async fn run() -> planchet::Result<()> {
+//! let client = ClientBuilder::new().api_key("YOUR_API_KEY").build()?;
+//! let items = client
+//!     .get_collected_items(123, &GetCollectedItemsParams::new())
+//!     .await?
+//!     .items;
+//!
+//! let mut inventory = Inventory::new("My collection", &items);
+//! for item in &items {
+//!     if let Some(picture) = item.pictures.iter().flatten().next() {
+//!         let photo = client.fetch_picture(&picture.thumbnail_url).await?;
+//!         inventory = inventory.photo(item.id, photo);
+//!     }
+//! }
+//! std::fs::write("inventory.pdf", inventory.render())?;
+//! # Ok(())
+//! # }
+//! ```
+use super::{issue_year, reference};
+use crate::models::{CollectedItem, CurrencyCode, Number, NumistaType};
+#[cfg(feature = "labels")]
+use printpdf::{path::PaintMode, Rect};
+use printpdf::{
+    ColorBits, ColorSpace, Image, ImageFilter, ImageTransform, ImageXObject, IndirectFontRef, Line,
+    PdfConformance, PdfDocument, PdfDocumentReference, PdfLayerReference, Point, Pt, Px,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::OnceLock;
+use ttf_parser::Face;
+
+/// The width of an A4 page, in points.
+pub(crate) const A4_WIDTH: f32 = 595.0;
+/// The height of an A4 page, in points.
+pub(crate) const A4_HEIGHT: f32 = 842.0;
+
+const MARGIN: f32 = 50.0;
+const PHOTO_SIZE: f32 = 90.0;
+const PHOTO_GAP: f32 = 15.0;
+const TEXT_SIZE: f32 = 10.0;
+const LINE_HEIGHT: f32 = 13.0;
+const ITEM_GAP: f32 = 12.0;
+
+/// An inventory of the items of a collection.
+#[derive(Debug, Clone)]
+pub struct Inventory<'a> {
+    title: String,
+    items: &'a [CollectedItem],
+    types: Option<&'a HashMap<i64, NumistaType>>,
+    photos: HashMap<i64, Jpeg>,
+    valuations: HashMap<i64, (Number, CurrencyCode)>,
+}
+
+impl<'a> Inventory<'a> {
+    /// Creates an inventory of the given items.
+    pub fn new<S: Into<String>>(title: S, items: &'a [CollectedItem]) -> Self {
+        Self {
+            title: title.into(),
+            items,
+            types: None,
+            photos: HashMap::new(),
+            valuations: HashMap::new(),
+        }
+    }
+
+    /// Sets the full types of the items, by ID, to describe each item with
+    /// the composition, weight, size, and references of its type.
+    pub fn types(mut self, types: &'a HashMap<i64, NumistaType>) -> Self {
+        self.types = Some(types);
+        self
+    }
+
+    /// Sets the photo of an item, as the content of a JPEG file.
+    ///
+    /// Photos in other formats are left out of the document.
+    pub fn photo(mut self, item_id: i64, jpeg: Vec<u8>) -> Self {
+        if let Some(jpeg) = Jpeg::parse(jpeg) {
+            self.photos.insert(item_id, jpeg);
+        }
+        self
+    }
+
+    /// Sets the estimated value of an item, for all its pieces.
+    pub fn valuation(mut self, item_id: i64, value: Number, currency: CurrencyCode) -> Self {
+        self.valuations.insert(item_id, (value, currency));
+        self
+    }
+
+    /// Renders the inventory as a PDF document of A4 pages.
+    pub fn render(&self) -> Vec<u8> {
+        let mut layout = Layout::new(Document::new(A4_WIDTH, A4_HEIGHT));
+        layout.line(Font::Bold, 18.0, 24.0, MARGIN, &self.title);
+        let pieces: i64 = self.items.iter().map(|i| i.quantity).sum();
+        let count = format!("{} items, {} pieces.", self.items.len(), pieces);
+        layout.line(
+            Font::Regular,
+            TEXT_SIZE,
+            LINE_HEIGHT + ITEM_GAP,
+            MARGIN,
+            &count,
+        );
+
+        let text_x = if self.photos.is_empty() {
+            MARGIN
+        } else {
+            MARGIN + PHOTO_SIZE + PHOTO_GAP
+        };
+        let text_width = A4_WIDTH - MARGIN - text_x;
+        for item in self.items {
+            let lines: Vec<(Font, String)> = self
+                .describe(item)
+                .into_iter()
+                .flat_map(|(font, text)| {
+                    wrap(&text, font, TEXT_SIZE, text_width)
+                        .into_iter()
+                        .map(move |line| (font, line))
+                })
+                .collect();
+            let photo = self.photos.get(&item.id);
+            let text_height = lines.len() as f32 * LINE_HEIGHT;
+            let height = match photo {
+                Some(_) => text_height.max(PHOTO_SIZE),
+                None => text_height,
+            };
+            layout.ensure(height + ITEM_GAP);
+
+            let top = layout.y;
+            layout.doc.line(MARGIN, top, A4_WIDTH - MARGIN, top, 0.5);
+            layout.y -= ITEM_GAP / 2.0;
+            if let Some(photo) = photo {
+                let scale = PHOTO_SIZE / photo.width.max(photo.height) as f32;
+                let (width, height) = (photo.width as f32 * scale, photo.height as f32 * scale);
+                let image = layout.doc.add_image(photo.clone());
+                let x = MARGIN + (PHOTO_SIZE - width) / 2.0;
+                let y = layout.y - PHOTO_SIZE + (PHOTO_SIZE - height) / 2.0;
+                layout.doc.draw_image(image, x, y, width, height);
+            }
+            let y = layout.y;
+            for (font, text) in &lines {
+                layout.line(*font, TEXT_SIZE, LINE_HEIGHT, text_x, text);
+            }
+            layout.y = y - height - ITEM_GAP / 2.0;
+        }
+
+        let mut totals = vec![(Font::Bold, "Total".to_string())];
+        totals.push((Font::Regular, count));
+        let paid = self
+            .items
+            .iter()
+            .filter_map(|i| i.price.as_ref().map(|p| (p.value, &p.currency)));
+        if let Some(paid) = sum_by_currency(paid) {
+            totals.push((Font::Regular, format!("Price paid: {}", paid)));
+        }
+        let estimated = self.valuations.values().map(|(v, c)| (*v, c));
+        if let Some(estimated) = sum_by_currency(estimated) {
+            totals.push((Font::Regular, format!("Estimated value: {}", estimated)));
+        }
+        layout.ensure(totals.len() as f32 * LINE_HEIGHT + ITEM_GAP);
+        layout
+            .doc
+            .line(MARGIN, layout.y, A4_WIDTH - MARGIN, layout.y, 0.5);
+        layout.y -= ITEM_GAP / 2.0;
+        for (font, text) in &totals {
+            layout.line(*font, TEXT_SIZE, LINE_HEIGHT, MARGIN, text);
+        }
+        layout.doc.finish()
+    }
+
+    /// Returns the lines describing an item, before wrapping.
+    fn describe(&self, item: &CollectedItem) -> Vec<(Font, String)> {
+        let type_ = self.types.and_then(|types| types.get(&item.type_info.id));
+        let mut lines = Vec::new();
+        let title = match item.quantity {
+            1 => item.type_info.title.clone(),
+            quantity => format!("{} × {}", quantity, item.type_info.title),
+        };
+        lines.push((Font::Bold, title));
+
+        let mut origin = Vec::new();
+        let issuer = item.type_info.issuer.as_ref();
+        if let Some(issuer) = issuer.or(type_.and_then(|t| t.issuer.as_ref())) {
+            origin.push(issuer.name.clone());
+        }
+        if let Some(issue) = &item.issue {
+            origin.extend(issue_year(issue));
+            if let Some(mint_letter) = &issue.mint_letter {
+                origin.push(format!("mint mark {}", mint_letter));
+            }
+        }
+        let mut specs = Vec::new();
+        if let Some(composition) = type_.and_then(|t| t.composition.as_ref()?.text.clone()) {
+            specs.push(composition);
+        }
+        if let Some(weight) = item.weight.or(type_.and_then(|t| t.weight)) {
+            specs.push(format!("{} g", weight));
+        }
+        if let Some(size) = item.size.or(type_.and_then(|t| t.size)) {
+            specs.push(format!("{} mm", size));
+        }
+        let references: Vec<String> = type_
+            .and_then(|t| t.references.as_ref())
+            .into_iter()
+            .flatten()
+            .map(reference)
+            .collect();
+        let mut fields = vec![
+            (None, origin.join(", ")),
+            (None, specs.join(", ")),
+            (Some("References"), references.join(", ")),
+        ];
+
+        let mut grade: Vec<String> = item.grade.iter().map(|g| g.to_string()).collect();
+        if let Some(details) = &item.grading_details {
+            if let Some(company) = &details.grading_company {
+                grade.push(format!("slabbed by {}", company.name));
+            }
+            if let Some(slab_grade) = &details.slab_grade {
+                grade.push(slab_grade.value.clone());
+            }
+            if let Some(slab_number) = &details.slab_number {
+                grade.push(format!("slab number {}", slab_number));
+            }
+        }
+        fields.push((Some("Grade"), grade.join(", ")));
+        let serial_number = item.serial_number.clone().unwrap_or_default();
+        fields.push((Some("Serial number"), serial_number));
+        let acquisition = [
+            item.acquisition_date.map(|d| d.to_string()),
+            item.acquisition_place.clone(),
+        ];
+        let acquisition: Vec<String> = acquisition.into_iter().flatten().collect();
+        fields.push((Some("Acquired"), acquisition.join(", ")));
+        let storage_location = item.storage_location.clone().unwrap_or_default();
+        fields.push((Some("Storage location"), storage_location));
+        let internal_id = item.internal_id.clone().unwrap_or_default();
+        fields.push((Some("Inventory number"), internal_id));
+        let price = item.price.as_ref();
+        let price = price.map(|p| format_amount(p.value, &p.currency));
+        fields.push((Some("Price paid"), price.unwrap_or_default()));
+        let valuation = self.valuations.get(&item.id);
+        let valuation = valuation.map(|(value, currency)| format_amount(*value, currency));
+        fields.push((Some("Estimated value"), valuation.unwrap_or_default()));
+
+        lines.extend(
+            fields
+                .into_iter()
+                .filter(|(_, value)| !value.is_empty())
+                .map(|(label, value)| match label {
+                    Some(label) => (Font::Regular, format!("{}: {}", label, value)),
+                    None => (Font::Regular, value),
+                }),
+        );
+        lines
+    }
+}
+
+/// Returns the total of amounts in several currencies, e.g. `12.50 EUR +
+/// 3.00 USD`, or `None` if there is none.
+fn sum_by_currency<'c>(
+    amounts: impl Iterator<Item = (Number, &'c CurrencyCode)>,
+) -> Option<String> {
+    let mut totals: BTreeMap<String, (Number, &CurrencyCode)> = BTreeMap::new();
+    for (value, currency) in amounts {
+        totals
+            .entry(currency_code(currency))
+            .or_insert((Number::default(), currency))
+            .0 += value;
+    }
+    let totals: Vec<String> = totals
+        .values()
+        .map(|(value, currency)| format_amount(*value, currency))
+        .collect();
+    (!totals.is_empty()).then(|| totals.join(" + "))
+}
+
+/// Formats an amount with two decimals and its currency code.
+fn format_amount(value: Number, currency: &CurrencyCode) -> String {
+    #[cfg(feature = "decimal")]
+    let value = value.round_dp(2);
+    format!("{:.2} {}", value, currency_code(currency))
+}
+
+#[cfg(feature = "iso-codes")]
+fn currency_code(currency: &CurrencyCode) -> String {
+    currency.code().to_string()
+}
+
+#[cfg(not(feature = "iso-codes"))]
+fn currency_code(currency: &CurrencyCode) -> String {
+    currency.clone()
+}
+
+/// Lays out lines of text from the top of the pages, adding pages as needed.
+struct Layout {
+    doc: Document,
+    /// The position of the top of the next line.
+    y: f32,
+}
+
+impl Layout {
+    fn new(mut doc: Document) -> Self {
+        doc.add_page();
+        Self {
+            y: doc.height - MARGIN,
+            doc,
+        }
+    }
+
+    /// Starts a new page if there is less than `height` left on this one.
+    fn ensure(&mut self, height: f32) {
+        if self.y - height < MARGIN && self.y < self.doc.height - MARGIN {
+            self.doc.add_page();
+            self.y = self.doc.height - MARGIN;
+        }
+    }
+
+    fn line(&mut self, font: Font, size: f32, height: f32, x: f32, text: &str) {
+        self.ensure(height);
+        self.doc.text(x, self.y - size, size, font, text);
+        self.y -= height;
+    }
+}
+
+/// A font of the DejaVu Sans family, embedded in the documents so that text
+/// in any script it covers, e.g. Latin, Greek, or Cyrillic, is printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Font {
+    Regular,
+    Bold,
+}
+
+static DEJAVU_SANS: &[u8] = include_bytes!("../../fonts/DejaVuSans.ttf");
+static DEJAVU_SANS_BOLD: &[u8] = include_bytes!("../../fonts/DejaVuSans-Bold.ttf");
+
+impl Font {
+    fn data(self) -> &'static [u8] {
+        match self {
+            Font::Regular => DEJAVU_SANS,
+            Font::Bold => DEJAVU_SANS_BOLD,
+        }
+    }
+
+    /// Returns the parsed font, to measure text.
+    fn face(self) -> &'static Face<'static> {
+        static FACES: OnceLock<[Face<'static>; 2]> = OnceLock::new();
+        let faces = FACES.get_or_init(|| {
+            [Font::Regular, Font::Bold]
+                .map(|font| Face::parse(font.data(), 0).expect("the embedded fonts are valid"))
+        });
+        &faces[self as usize]
+    }
+}
+
+/// Returns the width of a text, in points.
+///
+/// Characters missing from the font are left out of the documents, and
+/// have no width.
+pub(crate) fn text_width(text: &str, font: Font, size: f32) -> f32 {
+    let face = font.face();
+    let width: u32 = text
+        .chars()
+        .filter_map(|c| face.glyph_index(c))
+        .filter_map(|glyph| face.glyph_hor_advance(glyph))
+        .map(u32::from)
+        .sum();
+    width as f32 * size / face.units_per_em() as f32
+}
+
+/// Splits a text into lines no wider than `width`, between words. Words
+/// wider than a line are put on a line of their own.
+pub(crate) fn wrap(text: &str, font: Font, size: f32, width: f32) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if current.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", current, word)
+        };
+        if text_width(&candidate, font, size) <= width || current.is_empty() {
+            current = candidate;
+        } else {
+            lines.push(std::mem::replace(&mut current, word.to_string()));
+        }
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+/// A JPEG picture, embedded as is in documents.
+#[derive(Debug, Clone)]
+pub(crate) struct Jpeg {
+    data: Vec<u8>,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    /// The number of color components: 1 for grayscale, 3 for color.
+    components: u8,
+}
+
+impl Jpeg {
+    /// Reads the size of a JPEG picture from its frame header, returning
+    /// `None` if it is not a grayscale or color JPEG.
+    pub(crate) fn parse(data: Vec<u8>) -> Option<Self> {
+        if !data.starts_with(&[0xFF, 0xD8]) {
+            return None;
+        }
+        let mut i = 2;
+        while i + 4 <= data.len() {
+            if data[i] != 0xFF {
+                return None;
+            }
+            let marker = data[i + 1];
+            if marker == 0xFF {
+                // Fill byte.
+                i += 1;
+                continue;
+            }
+            let length = u16::from_be_bytes([data[i + 2], data[i + 3]]) as usize;
+            let is_frame = (0xC0..=0xCF).contains(&marker) && ![0xC4, 0xC8, 0xCC].contains(&marker);
+            if is_frame {
+                let header = data.get(i + 4..i + 10)?;
+                let height = u16::from_be_bytes([header[1], header[2]]) as u32;
+                let width = u16::from_be_bytes([header[3], header[4]]) as u32;
+                let components = header[5];
+                let valid = width > 0 && height > 0 && matches!(components, 1 | 3);
+                return valid.then_some(Self {
+                    data,
+                    width,
+                    height,
+                    components,
+                });
+            }
+            i += 2 + length;
+        }
+        None
+    }
+}
+
+/// A PDF document being drawn, page by page.
+///
+/// Coordinates are in points, from the bottom left corner of the page.
+pub(crate) struct Document {
+    doc: PdfDocumentReference,
+    width: f32,
+    height: f32,
+    /// The layer of the current page, if a page was added.
+    layer: Option<PdfLayerReference>,
+    fonts: [IndirectFontRef; 2],
+    images: Vec<Jpeg>,
+}
+
+impl Document {
+    /// Creates a document with pages of the given size, in points.
+    pub(crate) fn new(width: f32, height: f32) -> Self {
+        // Without the CMYK color profile and the metadata of PDF/X-3, the
+        // default conformance of printpdf, which would weigh more than the
+        // fonts.
+        let doc =
+            PdfDocument::empty("").with_conformance(PdfConformance::Custom(Default::default()));
+        let fonts = [Font::Regular, Font::Bold].map(|font| {
+            doc.add_external_font(font.data())
+                .expect("the embedded fonts are valid")
+        });
+        Self {
+            doc,
+            width,
+            height,
+            layer: None,
+            fonts,
+            images: Vec::new(),
+        }
+    }
+
+    /// Starts a new page, on which the next drawings are made.
+    pub(crate) fn add_page(&mut self) {
+        let (page, layer) =
+            self.doc
+                .add_page(Pt(self.width).into(), Pt(self.height).into(), "Content");
+        self.layer = Some(self.doc.get_page(page).get_layer(layer));
+    }
+
+    fn layer(&mut self) -> &PdfLayerReference {
+        if self.layer.is_none() {
+            self.add_page();
+        }
+        self.layer.as_ref().unwrap()
+    }
+
+    /// Draws a line of text, from the left end of its baseline.
+    pub(crate) fn text(&mut self, x: f32, y: f32, size: f32, font: Font, text: &str) {
+        let font = self.fonts[font as usize].clone();
+        self.layer()
+            .use_text(text, size, Pt(x).into(), Pt(y).into(), &font);
+    }
+
+    /// Returns the height of the pages.
+    #[cfg(feature = "labels")]
+    pub(crate) fn height(&self) -> f32 {
+        self.height
+    }
+
+    /// Draws a straight line.
+    pub(crate) fn line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, width: f32) {
+        let layer = self.layer();
+        layer.set_outline_thickness(width);
+        layer.add_line(Line {
+            points: vec![(point(x1, y1), false), (point(x2, y2), false)],
+            is_closed: false,
+        });
+    }
+
+    /// Draws the outline of a rectangle.
+    #[cfg(feature = "labels")]
+    pub(crate) fn stroke_rect(&mut self, x: f32, y: f32, width: f32, height: f32, line: f32) {
+        let layer = self.layer();
+        layer.set_outline_thickness(line);
+        layer.add_rect(rect(x, y, width, height).with_mode(PaintMode::Stroke));
+    }
+
+    /// Draws a black rectangle.
+    #[cfg(feature = "labels")]
+    pub(crate) fn fill_rect(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        self.layer()
+            .add_rect(rect(x, y, width, height).with_mode(PaintMode::Fill));
+    }
+
+    /// Adds a picture to the document, returning its index.
+    pub(crate) fn add_image(&mut self, image: Jpeg) -> usize {
+        self.images.push(image);
+        self.images.len() - 1
+    }
+
+    /// Draws a picture added with [`Document::add_image`], stretched to the
+    /// given rectangle.
+    pub(crate) fn draw_image(&mut self, image: usize, x: f32, y: f32, width: f32, height: f32) {
+        let jpeg = &self.images[image];
+        let transform = ImageTransform {
+            translate_x: Some(Pt(x).into()),
+            translate_y: Some(Pt(y).into()),
+            // At 72 DPI, a pixel is a point.
+            dpi: Some(72.0),
+            scale_x: Some(width / jpeg.width as f32),
+            scale_y: Some(height / jpeg.height as f32),
+            ..ImageTransform::default()
+        };
+        let image = Image::from(ImageXObject {
+            width: Px(jpeg.width as usize),
+            height: Px(jpeg.height as usize),
+            color_space: match jpeg.components {
+                1 => ColorSpace::Greyscale,
+                _ => ColorSpace::Rgb,
+            },
+            bits_per_component: ColorBits::Bit8,
+            interpolate: true,
+            image_data: jpeg.data.clone(),
+            image_filter: Some(ImageFilter::DCT),
+            smask: None,
+            clipping_bbox: None,
+        });
+        image.add_to_layer(self.layer().clone(), transform);
+    }
+
+    /// Writes the document.
+    pub(crate) fn finish(mut self) -> Vec<u8> {
+        self.layer();
+        self.doc
+            .save_to_bytes()
+            .expect("writing a document to memory does not fail")
+    }
+}
+
+fn point(x: f32, y: f32) -> Point {
+    Point::new(Pt(x).into(), Pt(y).into())
+}
+
+#[cfg(feature = "labels")]
+fn rect(x: f32, y: f32, width: f32, height: f32) -> Rect {
+    Rect::new(
+        Pt(x).into(),
+        Pt(y).into(),
+        Pt(x + width).into(),
+        Pt(y + height).into(),
+    )
+}
+
+#[cfg(test)]
+/// Returns how a line of text is shown in a content stream: the IDs of
+/// its glyphs in the font, in hexadecimal.
+pub(crate) fn shown(text: &str, font: Font) -> String {
+    let face = font.face();
+    let glyphs: String = text
+        .chars()
+        .filter_map(|c| face.glyph_index(c))
+        .map(|glyph| format!("{:04X}", glyph.0))
+        .collect();
+    format!("<{}> Tj", glyphs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// The smallest JPEG header giving a size: SOI, then a SOF0 frame.
+    const JPEG: &[u8] = &[
+        0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00, 0xFF, 0xC0, 0x00, 0x0B, 0x08, 0x00, 0x20,
+        0x00, 0x40, 0x03, 0x01, 0x22, 0x00, 0xFF, 0xD9,
+    ];
+
+    fn item(id: i64) -> CollectedItem {
+        serde_json::from_value(json!({
+            "id": id,
+            "quantity": 2,
+            "type": {
+                "id": 420,
+                "title": "5 Cents - Victoria (Café)",
+                "category": "coin",
+                "issuer": {"code": "canada", "name": "Canada"},
+            },
+            "issue": {"id": 1, "year": 1858, "mint_letter": "H"},
+            "for_swap": 0,
+            "grade": "xf",
+            "serial_number": "AB 123",
+            "acquisition_date": "2020-05-01",
+            "acquisition_place": "Coin show",
+            "price": {"value": 12.5, "currency": "EUR"},
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_jpeg_size() {
+        let jpeg = Jpeg::parse(JPEG.to_vec()).unwrap();
+        assert_eq!((jpeg.width, jpeg.height, jpeg.components), (64, 32, 3));
+        assert!(Jpeg::parse(b"\x89PNG\r\n".to_vec()).is_none());
+    }
+
+    #[test]
+    fn test_render_inventory() {
+        let items = [item(1), item(2)];
+        let pdf = Inventory::new("Insurance (2024)", &items)
+            .photo(1, JPEG.to_vec())
+            .photo(2, b"not a picture".to_vec())
+            .valuation(
+                1,
+                Number::from(40),
+                items[0].price.clone().unwrap().currency,
+            )
+            .render();
+        let text = String::from_utf8_lossy(&pdf);
+
+        assert!(text.starts_with("%PDF-"));
+        assert!(text.trim_end().ends_with("%%EOF"));
+        assert!(text.contains(&shown("Insurance (2024)", Font::Bold)));
+        assert!(text.contains("/Count 1"));
+        assert_eq!(text.matches("/Subtype/Image").count(), 1);
+        assert!(text.contains("/Width 64/Height 32"));
+        assert!(text.contains("/FontFile2"));
+        assert!(text.contains(&shown("2 × 5 Cents - Victoria (Café)", Font::Bold)));
+        assert!(text.contains(&shown("Canada, 1858, mint mark H", Font::Regular)));
+        assert!(text.contains(&shown("Serial number: AB 123", Font::Regular)));
+        assert!(text.contains(&shown("Acquired: 2020-05-01, Coin show", Font::Regular)));
+        assert!(text.contains(&shown("Price paid: 25.00 EUR", Font::Regular)));
+        assert!(text.contains(&shown("Estimated value: 40.00 EUR", Font::Regular)));
+    }
+
+    #[test]
+    fn test_non_latin_text() {
+        let mut item = item(1);
+        item.type_info.title = "1 Рубль - Николай II".to_string();
+        item.acquisition_place = Some("Αθήνα".to_string());
+        let title = "Коллекция";
+        for text in [title, item.type_info.title.as_str(), "Αθήνα"] {
+            let face = Font::Regular.face();
+            assert!(text.chars().all(|c| face.glyph_index(c).is_some()));
+        }
+
+        let items = [item];
+        let pdf = Inventory::new(title, &items).render();
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.contains(&shown(title, Font::Bold)));
+        assert!(text.contains(&shown("2 × 1 Рубль - Николай II", Font::Bold)));
+        assert!(text.contains(&shown("Acquired: 2020-05-01, Αθήνα", Font::Regular)));
+    }
+
+    #[test]
+    fn test_page_breaks() {
+        let items: Vec<CollectedItem> = (0..40).map(item).collect();
+        let pdf = Inventory::new("Inventory", &items).render();
+        let text = String::from_utf8_lossy(&pdf);
+        let pages = text.matches("/Type/Page/").count();
+        assert!(pages > 1);
+        assert!(text.contains(&format!("/Count {}", pages)));
+    }
+
+    #[test]
+    fn test_wrap() {
+        let lines = wrap("one two three four", Font::Regular, 10.0, 45.0);
+        assert_eq!(lines, ["one two", "three", "four"]);
+        assert_eq!(wrap("", Font::Regular, 10.0, 40.0), Vec::<String>::new());
+    }
+}
@@ -0,0 +1,296 @@
+//! Coordinates of mints and publication places from GeoNames.
+//!
+//! Publication places carry a GeoNames ID, and mints the name of the place
+//! where they are located. [`GeoNamesClient`] resolves either into
+//! [`Coordinates`], for plotting them on a map.
+//!
+//! The GeoNames web services require a free account, whose username is passed
+//! to [`GeoNamesClient::new`].
+//!
+//! This module is only available with the `geonames` feature.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use planchet::geonames::GeoNamesClient;
+//! use planchet::ClientBuilder;
+//!
+//! # async fn run() -> planchet::Result<()> {
+//! let client = ClientBuilder::new().api_key("YOUR_API_KEY").build()?;
+//! let geonames = GeoNamesClient::new("YOUR_GEONAMES_USERNAME");
+//!
+//! let mint = client.get_mint(1).await?;
+//! if let Some(coordinates) = mint.coordinates(&geonames).await? {
+//!     println!("{}", coordinates);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use crate::error::{Error, Result};
+use crate::models::{MintDetail, PublicationPlace};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::fmt;
+
+/// The status code GeoNames returns when no place matches a request.
+const NO_RESULT_FOUND: i64 = 15;
+
+/// A point on the Earth, in decimal degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coordinates {
+    /// The latitude, positive north of the equator.
+    pub latitude: f64,
+    /// The longitude, positive east of the prime meridian.
+    pub longitude: f64,
+}
+
+impl fmt::Display for Coordinates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}, {}", self.latitude, self.longitude)
+    }
+}
+
+#[derive(Deserialize)]
+struct Place {
+    lat: String,
+    lng: String,
+}
+
+impl Place {
+    fn coordinates(&self) -> Option<Coordinates> {
+        Some(Coordinates {
+            latitude: self.lat.parse().ok()?,
+            longitude: self.lng.parse().ok()?,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct SearchResponse {
+    #[serde(default)]
+    geonames: Vec<Place>,
+}
+
+#[derive(Deserialize)]
+struct StatusResponse {
+    status: Status,
+}
+
+#[derive(Deserialize)]
+struct Status {
+    message: String,
+    value: i64,
+}
+
+/// A client for the GeoNames web services.
+#[derive(Debug, Clone)]
+pub struct GeoNamesClient {
+    client: reqwest::Client,
+    base_url: String,
+    username: String,
+}
+
+impl GeoNamesClient {
+    /// Creates a client for the public GeoNames web services.
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The username of the GeoNames account.
+    pub fn new<S: Into<String>>(username: S) -> Self {
+        Self::with_base_url("https://secure.geonames.org", username)
+    }
+
+    /// Creates a client for the GeoNames web services at `base_url`.
+    ///
+    /// This is useful for testing.
+    pub fn with_base_url<B: Into<String>, S: Into<String>>(base_url: B, username: S) -> Self {
+        let client = reqwest::Client::builder()
+            .user_agent(concat!("planchet/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            base_url: base_url.into(),
+            username: username.into(),
+        }
+    }
+
+    /// Gets the coordinates of a place by its GeoNames ID.
+    ///
+    /// Returns `None` if there is no such place.
+    ///
+    /// # Arguments
+    ///
+    /// * `geonames_id` - The GeoNames ID of the place.
+    pub async fn coordinates_of(&self, geonames_id: i64) -> Result<Option<Coordinates>> {
+        let place: Option<Place> = self
+            .get("getJSON", &[("geonameId", &geonames_id.to_string())])
+            .await?;
+        Ok(place.as_ref().and_then(Place::coordinates))
+    }
+
+    /// Gets the coordinates of the place which best matches a name, such as
+    /// `London, United Kingdom`.
+    ///
+    /// Returns `None` if no place matches.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the place.
+    pub async fn search(&self, name: &str) -> Result<Option<Coordinates>> {
+        let response: Option<SearchResponse> = self
+            .get("searchJSON", &[("q", name), ("maxRows", "1")])
+            .await?;
+        Ok(response
+            .and_then(|r| r.geonames.into_iter().next())
+            .and_then(|place| place.coordinates()))
+    }
+
+    /// Sends a request, returning `None` if GeoNames found no result.
+    async fn get<T: DeserializeOwned>(
+        &self,
+        service: &str,
+        query: &[(&str, &str)],
+    ) -> Result<Option<T>> {
+        let body: serde_json::Value = self
+            .client
+            .get(format!("{}/{}", self.base_url, service))
+            .query(query)
+            .query(&[("username", &self.username)])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        // Errors are returned with a success status and a `status` object.
+        if let Ok(StatusResponse { status }) = StatusResponse::deserialize(&body) {
+            if status.value == NO_RESULT_FOUND {
+                return Ok(None);
+            }
+            return Err(Error::GeoNames(status.message));
+        }
+        Ok(Some(serde_json::from_value(body)?))
+    }
+}
+
+impl MintDetail {
+    /// Gets the coordinates of the place where the mint is located.
+    ///
+    /// The place is looked up by name, qualified by the name of the country
+    /// when known. Returns `None` if the mint has no place or it cannot be
+    /// found.
+    ///
+    /// This is only available with the `geonames` feature.
+    pub async fn coordinates(&self, geonames: &GeoNamesClient) -> Result<Option<Coordinates>> {
+        let Some(place) = &self.place else {
+            return Ok(None);
+        };
+        let name = match &self.country {
+            Some(country) => format!("{}, {}", place, country.name),
+            None => place.clone(),
+        };
+        geonames.search(&name).await
+    }
+}
+
+impl PublicationPlace {
+    /// Gets the coordinates of the place, by its GeoNames ID if known and by
+    /// name otherwise.
+    ///
+    /// This is only available with the `geonames` feature.
+    pub async fn coordinates(&self, geonames: &GeoNamesClient) -> Result<Option<Coordinates>> {
+        match self.geonames_id {
+            Some(id) => geonames.coordinates_of(id).await,
+            None => geonames.search(&self.name).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_coordinates() {
+        let mut server = mockito::Server::new_async().await;
+        let get_mock = server
+            .mock("GET", "/getJSON")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("geonameId".into(), "2643743".into()),
+                mockito::Matcher::UrlEncoded("username".into(), "demo".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"geonameId": 2643743, "name": "London", "lat": "51.50853", "lng": "-0.12574"}"#,
+            )
+            .create_async()
+            .await;
+        let search_mock = server
+            .mock("GET", "/searchJSON")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "q".into(),
+                "Philadelphia, United States".into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"totalResultsCount": 1, "geonames": [{"lat": "39.95238", "lng": "-75.16362"}]}"#)
+            .create_async()
+            .await;
+        let geonames = GeoNamesClient::with_base_url(server.url(), "demo");
+
+        let place: PublicationPlace =
+            serde_json::from_str(r#"{"name": "London, United Kingdom", "geonames_id": "2643743"}"#)
+                .unwrap();
+        assert_eq!(
+            place.coordinates(&geonames).await.unwrap(),
+            Some(Coordinates {
+                latitude: 51.50853,
+                longitude: -0.12574
+            })
+        );
+
+        let mint: MintDetail = serde_json::from_str(
+            r#"{"id": 2, "place": "Philadelphia", "country": {"code": "united-states", "name": "United States"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            mint.coordinates(&geonames).await.unwrap(),
+            Some(Coordinates {
+                latitude: 39.95238,
+                longitude: -75.16362
+            })
+        );
+
+        get_mock.assert_async().await;
+        search_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_status_errors() {
+        let mut server = mockito::Server::new_async().await;
+        let _missing = server
+            .mock("GET", "/getJSON")
+            .match_query(mockito::Matcher::UrlEncoded("geonameId".into(), "1".into()))
+            .with_status(200)
+            .with_body(r#"{"status": {"message": "geonameId not found", "value": 15}}"#)
+            .create_async()
+            .await;
+        let _limit = server
+            .mock("GET", "/getJSON")
+            .match_query(mockito::Matcher::UrlEncoded("geonameId".into(), "2".into()))
+            .with_status(200)
+            .with_body(
+                r#"{"status": {"message": "the hourly limit has been exceeded", "value": 19}}"#,
+            )
+            .create_async()
+            .await;
+        let geonames = GeoNamesClient::with_base_url(server.url(), "demo");
+
+        assert_eq!(geonames.coordinates_of(1).await.unwrap(), None);
+        assert!(matches!(
+            geonames.coordinates_of(2).await,
+            Err(Error::GeoNames(message)) if message.contains("hourly limit")
+        ));
+    }
+}
@@ -0,0 +1,66 @@
+//! Conversion of HTML-formatted fields to plain text and Markdown.
+//!
+//! Some fields returned by the API, such as [`NumistaType::comments`] and
+//! [`Publication::bibliographical_notice`], contain HTML. This module is only
+//! available with the `html` feature.
+use crate::models::{NumistaType, Publication};
+
+/// Converts an HTML fragment to plain text, without any markup.
+///
+/// Lines are not wrapped. If the fragment cannot be parsed, it is returned
+/// unchanged.
+pub fn to_plain_text(html: &str) -> String {
+    html2text::config::plain_no_decorate()
+        .string_from_read(html.as_bytes(), usize::MAX / 2)
+        .map(|text| text.trim_end().to_string())
+        .unwrap_or_else(|_| html.to_string())
+}
+
+/// Converts an HTML fragment to Markdown.
+pub fn to_markdown(html: &str) -> String {
+    html2md::parse_html(html).trim_end().to_string()
+}
+
+impl NumistaType {
+    /// Returns the comments as plain text.
+    pub fn comments_plain(&self) -> Option<String> {
+        self.comments.as_deref().map(to_plain_text)
+    }
+
+    /// Returns the comments as Markdown.
+    pub fn comments_markdown(&self) -> Option<String> {
+        self.comments.as_deref().map(to_markdown)
+    }
+}
+
+impl Publication {
+    /// Returns the bibliographical notice as plain text.
+    pub fn bibliographical_notice_plain(&self) -> Option<String> {
+        self.bibliographical_notice.as_deref().map(to_plain_text)
+    }
+
+    /// Returns the bibliographical notice as Markdown.
+    pub fn bibliographical_notice_markdown(&self) -> Option<String> {
+        self.bibliographical_notice.as_deref().map(to_markdown)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_plain_text() {
+        let html = "David Hartill; 2017. <em>Cast Chinese Coins</em>. Self-published, London.";
+        assert_eq!(
+            to_plain_text(html),
+            "David Hartill; 2017. Cast Chinese Coins. Self-published, London."
+        );
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        let html = "<p>Struck in <strong>silver</strong>.</p>";
+        assert_eq!(to_markdown(html), "Struck in **silver**.");
+    }
+}
@@ -0,0 +1,109 @@
+//! Fetching the full types of search results.
+//!
+//! Search results only hold a summary of each type. [`Client::hydrate`]
+//! fetches the full [`NumistaType`] of each result, a few at a time, fetching
+//! each type only once even if it appears several times. A [`TypeCache`]
+//! given to [`Client::hydrate_with_cache`] keeps the types fetched across
+//! several searches.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use futures::TryStreamExt;
+//! use planchet::models::SearchTypesParams;
+//! use planchet::ClientBuilder;
+//!
+//! # async fn run() -> planchet::Result<()> {
+//! let client = ClientBuilder::new().api_key("YOUR_API_KEY").build()?;
+//! let results = client.search_types(&SearchTypesParams::new().q("victoria")).await?;
+//!
+//! let types: Vec<_> = client.hydrate(&results.types).try_collect().await?;
+//! for t in types {
+//!     println!("{}: {:?}", t.title, t.weight);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use crate::error::Result;
+use crate::models::NumistaType;
+use crate::Client;
+use futures::stream::{self, Stream, StreamExt};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+use tokio::sync::OnceCell;
+
+/// The maximum number of types fetched at the same time.
+pub const HYDRATE_CONCURRENCY: usize = 4;
+
+/// Full types already fetched, by ID.
+///
+/// Clones share their content, so a cache can be used by several tasks.
+#[derive(Debug, Clone, Default)]
+pub struct TypeCache {
+    types: Arc<Mutex<HashMap<i64, Arc<OnceCell<NumistaType>>>>>,
+}
+
+impl TypeCache {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the type with the given ID, if it was fetched.
+    pub fn get(&self, type_id: i64) -> Option<NumistaType> {
+        self.types
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&type_id)
+            .and_then(|cell| cell.get().cloned())
+    }
+
+    /// Returns the number of types fetched.
+    pub fn len(&self) -> usize {
+        let types = self.types.lock().unwrap_or_else(PoisonError::into_inner);
+        types.values().filter(|cell| cell.initialized()).count()
+    }
+
+    /// Checks if no type was fetched.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes every type from the cache.
+    pub fn clear(&self) {
+        self.types
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
+    /// Returns the cached type, or fetches it. Concurrent calls for the same
+    /// type only fetch it once.
+    async fn get_or_fetch(&self, client: &Client, type_id: i64) -> Result<NumistaType> {
+        let cell = self
+            .types
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(type_id)
+            .or_default()
+            .clone();
+        cell.get_or_try_init(|| client.get_type(type_id))
+            .await
+            .cloned()
+    }
+}
+
+pub(crate) fn hydrate<'a>(
+    client: &Client,
+    type_ids: Vec<i64>,
+    cache: TypeCache,
+) -> impl Stream<Item = Result<NumistaType>> + 'a {
+    let client = client.clone();
+    stream::iter(type_ids)
+        .map(move |type_id| {
+            let client = client.clone();
+            let cache = cache.clone();
+            async move { cache.get_or_fetch(&client, type_id).await }
+        })
+        .buffered(HYDRATE_CONCURRENCY)
+}
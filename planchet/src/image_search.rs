@@ -0,0 +1,227 @@
+//! A higher-level interface for searching types by image.
+use crate::error::{Error, Result};
+use crate::hydrate::{self, TypeCache};
+use crate::models::{
+    request::{Image, MimeType, SearchByImageParams},
+    response::SearchByImageResponse,
+    Category, NumistaType,
+};
+use crate::Client;
+use base64::Engine;
+use futures::TryStreamExt;
+
+/// The maximum width and height, in pixels, of a photo sent to the API.
+///
+/// With the `image` feature, larger photos are downscaled to fit before being
+/// sent.
+pub const MAX_IMAGE_DIMENSION: u32 = 1024;
+
+impl MimeType {
+    /// Detects the MIME type of an image from its leading bytes.
+    pub fn detect(bytes: &[u8]) -> Option<MimeType> {
+        if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some(MimeType::Jpeg)
+        } else if bytes.starts_with(&[0x89, b'P', b'N', b'G']) {
+            Some(MimeType::Png)
+        } else {
+            None
+        }
+    }
+}
+
+impl Image {
+    /// Creates an image from raw (not encoded) image data.
+    pub fn from_bytes(mime_type: MimeType, bytes: &[u8]) -> Self {
+        Self {
+            mime_type,
+            image_data: base64::engine::general_purpose::STANDARD.encode(bytes),
+        }
+    }
+}
+
+/// The results of a search by image, with the top matches optionally
+/// hydrated into full types.
+#[derive(Debug, Clone)]
+pub struct ImageSearchResults {
+    /// The raw response from the API.
+    pub response: SearchByImageResponse,
+    /// The full types of the top matches, in the same order as in the
+    /// response. This is empty unless [`SearchByImageBuilder::hydrate_top`]
+    /// was set.
+    pub types: Vec<NumistaType>,
+}
+
+/// A builder for searching types by one or more photos.
+///
+/// # Examples
+///
+/// ```no_run
+/// use planchet::image_search::SearchByImageBuilder;
+/// use planchet::ClientBuilder;
+///
+/// # async fn run() -> planchet::Result<()> {
+/// let client = ClientBuilder::new().api_key("YOUR_API_KEY").build()?;
+/// let results = SearchByImageBuilder::new()
+///     .photo(&std::fs::read("obverse.jpg")?)?
+///     .photo(&std::fs::read("reverse.jpg")?)?
+///     .max_results(10)
+///     .hydrate_top(3)
+///     .send(&client)
+///     .await?;
+/// for t in results.types {
+///     println!("{}", t.title);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SearchByImageBuilder {
+    category: Option<Category>,
+    images: Vec<Image>,
+    max_results: Option<i64>,
+    hydrate_top: usize,
+}
+
+impl SearchByImageBuilder {
+    /// Creates a new `SearchByImageBuilder`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the category to search in.
+    pub fn category(mut self, category: Category) -> Self {
+        self.category = Some(category);
+        self
+    }
+
+    /// Adds an already encoded image.
+    pub fn image(mut self, image: Image) -> Self {
+        self.images.push(image);
+        self
+    }
+
+    /// Adds a JPEG or PNG photo from its raw bytes.
+    ///
+    /// With the `image` feature, photos larger than [`MAX_IMAGE_DIMENSION`]
+    /// are downscaled and re-encoded as JPEG.
+    pub fn photo(mut self, bytes: &[u8]) -> Result<Self> {
+        let mime_type = MimeType::detect(bytes).ok_or_else(|| {
+            Error::InvalidImage("only JPEG and PNG photos are supported".to_string())
+        })?;
+        self.images.push(encode_photo(mime_type, bytes)?);
+        Ok(self)
+    }
+
+    /// Sets the maximum number of results to return.
+    pub fn max_results(mut self, max_results: i64) -> Self {
+        self.max_results = Some(max_results);
+        self
+    }
+
+    /// Fetches the full type of the top `n` matches after searching, like
+    /// [`Client::hydrate`].
+    pub fn hydrate_top(mut self, n: usize) -> Self {
+        self.hydrate_top = n;
+        self
+    }
+
+    /// Returns the request parameters built so far.
+    pub fn params(&self) -> SearchByImageParams {
+        SearchByImageParams {
+            category: self.category.clone(),
+            images: self.images.clone(),
+            max_results: self.max_results,
+        }
+    }
+
+    /// Sends the search, then fetches the top matches if requested.
+    pub async fn send(self, client: &Client) -> Result<ImageSearchResults> {
+        if self.images.is_empty() {
+            return Err(Error::InvalidImage("at least one photo is required".to_string()));
+        }
+
+        let response = client.search_by_image(&self.params()).await?;
+        let type_ids = response
+            .types
+            .iter()
+            .take(self.hydrate_top)
+            .map(|t| t.id)
+            .collect();
+        let types = hydrate::hydrate(client, type_ids, TypeCache::new())
+            .try_collect()
+            .await?;
+
+        Ok(ImageSearchResults { response, types })
+    }
+}
+
+#[cfg(feature = "image")]
+fn encode_photo(mime_type: MimeType, bytes: &[u8]) -> Result<Image> {
+    use image::codecs::jpeg::JpegEncoder;
+    use image::imageops::FilterType;
+
+    let photo = image::load_from_memory(bytes).map_err(|e| Error::InvalidImage(e.to_string()))?;
+    if photo.width() <= MAX_IMAGE_DIMENSION && photo.height() <= MAX_IMAGE_DIMENSION {
+        return Ok(Image::from_bytes(mime_type, bytes));
+    }
+
+    let resized = photo
+        .resize(MAX_IMAGE_DIMENSION, MAX_IMAGE_DIMENSION, FilterType::Triangle)
+        .to_rgb8();
+    let mut encoded = Vec::new();
+    JpegEncoder::new_with_quality(&mut encoded, 90)
+        .encode_image(&resized)
+        .map_err(|e| Error::InvalidImage(e.to_string()))?;
+    Ok(Image::from_bytes(MimeType::Jpeg, &encoded))
+}
+
+#[cfg(not(feature = "image"))]
+fn encode_photo(mime_type: MimeType, bytes: &[u8]) -> Result<Image> {
+    Ok(Image::from_bytes(mime_type, bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mime_type_detect() {
+        assert!(matches!(
+            MimeType::detect(&[0xFF, 0xD8, 0xFF, 0xE0]),
+            Some(MimeType::Jpeg)
+        ));
+        assert!(matches!(
+            MimeType::detect(b"\x89PNG\r\n\x1a\n"),
+            Some(MimeType::Png)
+        ));
+        assert!(MimeType::detect(b"GIF89a").is_none());
+    }
+
+    #[test]
+    fn test_photo_rejects_unknown_format() {
+        let result = SearchByImageBuilder::new().photo(b"GIF89a");
+        assert!(matches!(result, Err(Error::InvalidImage(_))));
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_photo_downscales_large_images() {
+        let photo = image::RgbImage::new(MAX_IMAGE_DIMENSION * 2, MAX_IMAGE_DIMENSION);
+        let mut png = std::io::Cursor::new(Vec::new());
+        photo.write_to(&mut png, image::ImageFormat::Png).unwrap();
+
+        let params = SearchByImageBuilder::new()
+            .photo(png.get_ref())
+            .unwrap()
+            .params();
+        let image = &params.images[0];
+        assert!(matches!(image.mime_type, MimeType::Jpeg));
+
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(&image.image_data)
+            .unwrap();
+        let resized = image::load_from_memory(&bytes).unwrap();
+        assert_eq!(resized.width(), MAX_IMAGE_DIMENSION);
+        assert_eq!(resized.height(), MAX_IMAGE_DIMENSION / 2);
+    }
+}
@@ -0,0 +1,578 @@
+//! Bulk import of collected items from CSV files.
+//!
+//! This module is only available with the `import` feature.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use planchet::import::{ColumnMapping, ImportField};
+//! use planchet::ClientBuilder;
+//!
+//! # async fn run() -> planchet::Result<()> {
+//! let client = ClientBuilder::new().bearer_token("YOUR_TOKEN").build()?;
+//! let mapping = ColumnMapping::new()
+//!     .column(ImportField::Quantity, "Qty")
+//!     .resolve_references(true);
+//!
+//! let file = std::fs::File::open("collection.csv")?;
+//! let report = client.import_collected_items(123, file, &mapping).await?;
+//! for row in report.failed() {
+//!     eprintln!("Row {}: {}", row.row, row.result.as_ref().unwrap_err());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use crate::error::{Error, Result};
+use crate::models::{
+    request::{AddCollectedItemParams, ItemPriceParams, SearchTypesParams},
+    CollectedItem, Grade, Number,
+};
+use crate::{Client, WriteOutcome};
+use chrono::NaiveDate;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// The default delay between two items being added.
+pub const DEFAULT_DELAY: Duration = Duration::from_millis(250);
+
+/// A field of a collected item which can be read from a CSV column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImportField {
+    /// The ID of the type.
+    TypeId,
+    /// The ID or code of a catalogue, e.g. "KM", used with
+    /// [`ImportField::Number`] to resolve the type.
+    Catalogue,
+    /// The number of the type in [`ImportField::Catalogue`].
+    Number,
+    /// The issuer code, used to narrow down the type when resolving it from
+    /// a catalogue reference.
+    Issuer,
+    /// The ID of the issue.
+    Issue,
+    Quantity,
+    /// The grade, as an abbreviation or full name, e.g. "VF".
+    Grade,
+    /// Whether the item is for swap: "true", "yes", or "1", or their
+    /// opposites.
+    ForSwap,
+    PrivateComment,
+    PublicComment,
+    /// The price paid, with [`ImportField::Currency`].
+    Price,
+    /// The ISO 4217 code of the currency of [`ImportField::Price`].
+    Currency,
+    /// The ID of the collection.
+    Collection,
+    StorageLocation,
+    AcquisitionPlace,
+    /// The acquisition date, as `YYYY-MM-DD`.
+    AcquisitionDate,
+    SerialNumber,
+    InternalId,
+}
+
+impl ImportField {
+    /// All fields, in the order of the default columns.
+    pub const ALL: [ImportField; 18] = [
+        ImportField::TypeId,
+        ImportField::Catalogue,
+        ImportField::Number,
+        ImportField::Issuer,
+        ImportField::Issue,
+        ImportField::Quantity,
+        ImportField::Grade,
+        ImportField::ForSwap,
+        ImportField::PrivateComment,
+        ImportField::PublicComment,
+        ImportField::Price,
+        ImportField::Currency,
+        ImportField::Collection,
+        ImportField::StorageLocation,
+        ImportField::AcquisitionPlace,
+        ImportField::AcquisitionDate,
+        ImportField::SerialNumber,
+        ImportField::InternalId,
+    ];
+
+    /// Returns the name of the column the field is read from by default.
+    pub fn default_column(&self) -> &'static str {
+        match self {
+            ImportField::TypeId => "type",
+            ImportField::Catalogue => "catalogue",
+            ImportField::Number => "number",
+            ImportField::Issuer => "issuer",
+            ImportField::Issue => "issue",
+            ImportField::Quantity => "quantity",
+            ImportField::Grade => "grade",
+            ImportField::ForSwap => "for_swap",
+            ImportField::PrivateComment => "private_comment",
+            ImportField::PublicComment => "public_comment",
+            ImportField::Price => "price",
+            ImportField::Currency => "currency",
+            ImportField::Collection => "collection",
+            ImportField::StorageLocation => "storage_location",
+            ImportField::AcquisitionPlace => "acquisition_place",
+            ImportField::AcquisitionDate => "acquisition_date",
+            ImportField::SerialNumber => "serial_number",
+            ImportField::InternalId => "internal_id",
+        }
+    }
+}
+
+impl fmt::Display for ImportField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.default_column())
+    }
+}
+
+impl FromStr for ImportField {
+    type Err = Error;
+
+    /// Parses a field from its default column name.
+    fn from_str(s: &str) -> Result<Self> {
+        ImportField::ALL
+            .into_iter()
+            .find(|f| f.default_column() == s)
+            .ok_or_else(|| Error::Import(format!("unknown field: {}", s)))
+    }
+}
+
+/// How the columns of a CSV file map to the fields of collected items.
+///
+/// By default, each field is read from the column named after it, e.g.
+/// `quantity` or `acquisition_date`. Fields without a column are left unset.
+#[derive(Debug, Clone)]
+pub struct ColumnMapping {
+    columns: HashMap<ImportField, String>,
+    resolve_references: bool,
+    delay: Duration,
+    cancellation: Option<CancellationToken>,
+}
+
+impl Default for ColumnMapping {
+    fn default() -> Self {
+        Self {
+            columns: ImportField::ALL
+                .into_iter()
+                .map(|f| (f, f.default_column().to_string()))
+                .collect(),
+            resolve_references: false,
+            delay: DEFAULT_DELAY,
+            cancellation: None,
+        }
+    }
+}
+
+impl ColumnMapping {
+    /// Creates a new `ColumnMapping` with the default columns.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the column a field is read from.
+    pub fn column<S: Into<String>>(mut self, field: ImportField, column: S) -> Self {
+        self.columns.insert(field, column.into());
+        self
+    }
+
+    /// Sets whether rows without a type ID have it resolved by searching the
+    /// catalogue for their catalogue reference.
+    pub fn resolve_references(mut self, resolve_references: bool) -> Self {
+        self.resolve_references = resolve_references;
+        self
+    }
+
+    /// Sets the delay between two items being added, to stay within the API
+    /// rate limit. Defaults to [`DEFAULT_DELAY`].
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Sets a token which stops the import when cancelled.
+    ///
+    /// Rows not yet started when the token is cancelled are reported with
+    /// [`Error::Cancelled`]. A row being added at that moment is finished
+    /// first, so its result is reported as usual.
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+}
+
+/// The result of importing a single row.
+#[derive(Debug)]
+pub struct ImportedRow {
+    /// The 1-based number of the row, not counting the header.
+    pub row: usize,
+    /// The added item, or the request planned if the client is in dry-run
+    /// mode, or why the row could not be imported.
+    pub result: Result<WriteOutcome<CollectedItem>>,
+}
+
+/// The per-row results of an import.
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub rows: Vec<ImportedRow>,
+}
+
+impl ImportReport {
+    /// Returns the rows which were imported.
+    pub fn succeeded(&self) -> impl Iterator<Item = &ImportedRow> {
+        self.rows
+            .iter()
+            .filter(|r| matches!(r.result, Ok(WriteOutcome::Sent(_))))
+    }
+
+    /// Returns the rows which would have been imported, if the client is in
+    /// dry-run mode.
+    pub fn planned(&self) -> impl Iterator<Item = &ImportedRow> {
+        self.rows
+            .iter()
+            .filter(|r| matches!(r.result, Ok(WriteOutcome::Planned(_))))
+    }
+
+    /// Returns the rows which could not be imported.
+    pub fn failed(&self) -> impl Iterator<Item = &ImportedRow> {
+        self.rows.iter().filter(|r| r.result.is_err())
+    }
+}
+
+/// A row which has been read, but whose type may not be resolved yet.
+struct ParsedRow {
+    type_id: Option<i64>,
+    reference: Option<(String, String)>,
+    issuer: Option<String>,
+    params: AddCollectedItemParams,
+}
+
+/// The values of a single CSV record, by field.
+struct Record<'a> {
+    values: HashMap<ImportField, &'a str>,
+}
+
+impl Record<'_> {
+    fn text(&self, field: ImportField) -> Option<String> {
+        self.values.get(&field).map(|v| v.to_string())
+    }
+
+    fn parse<T: FromStr>(&self, field: ImportField) -> Result<Option<T>>
+    where
+        T::Err: fmt::Display,
+    {
+        self.values
+            .get(&field)
+            .map(|v| {
+                v.parse()
+                    .map_err(|e| Error::Import(format!("invalid {} {:?}: {}", field, v, e)))
+            })
+            .transpose()
+    }
+
+    fn bool(&self, field: ImportField) -> Result<Option<bool>> {
+        self.values
+            .get(&field)
+            .map(|v| match v.to_lowercase().as_str() {
+                "true" | "yes" | "y" | "1" => Ok(true),
+                "false" | "no" | "n" | "0" => Ok(false),
+                _ => Err(Error::Import(format!("invalid {} {:?}", field, v))),
+            })
+            .transpose()
+    }
+}
+
+fn parse_row(record: &Record) -> Result<ParsedRow> {
+    let type_id = record.parse(ImportField::TypeId)?;
+    let reference = match (
+        record.text(ImportField::Catalogue),
+        record.text(ImportField::Number),
+    ) {
+        (Some(catalogue), Some(number)) => Some((catalogue, number)),
+        _ => None,
+    };
+    if type_id.is_none() && reference.is_none() {
+        return Err(Error::Import(
+            "missing type, or catalogue and number".to_string(),
+        ));
+    }
+
+    let price = match (
+        record.parse::<Number>(ImportField::Price)?,
+        record.text(ImportField::Currency),
+    ) {
+        (Some(value), Some(currency)) => Some(ItemPriceParams { value, currency }),
+        (Some(_), None) => return Err(Error::Import("price without currency".to_string())),
+        (None, _) => None,
+    };
+
+    let mut params = AddCollectedItemParams::new(type_id.unwrap_or_default());
+    params.issue = record.parse(ImportField::Issue)?;
+    params.quantity = record.parse(ImportField::Quantity)?;
+    params.grade = record.parse::<Grade>(ImportField::Grade)?;
+    params.for_swap = record.bool(ImportField::ForSwap)?;
+    params.private_comment = record.text(ImportField::PrivateComment);
+    params.public_comment = record.text(ImportField::PublicComment);
+    params.price = price;
+    params.collection = record.parse(ImportField::Collection)?;
+    params.storage_location = record.text(ImportField::StorageLocation);
+    params.acquisition_place = record.text(ImportField::AcquisitionPlace);
+    params.acquisition_date = record.parse::<NaiveDate>(ImportField::AcquisitionDate)?;
+    params.serial_number = record.text(ImportField::SerialNumber);
+    params.internal_id = record.text(ImportField::InternalId);
+
+    Ok(ParsedRow {
+        type_id,
+        reference,
+        issuer: record.text(ImportField::Issuer),
+        params,
+    })
+}
+
+/// Reads all rows of a CSV file. Rows which cannot be read or parsed are
+/// returned as errors.
+fn read_rows<R: Read>(reader: R, mapping: &ColumnMapping) -> Result<Vec<Result<ParsedRow>>> {
+    let mut reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_reader(reader);
+    let headers = reader.headers()?.clone();
+    let indices: Vec<(ImportField, usize)> = mapping
+        .columns
+        .iter()
+        .filter_map(|(field, column)| {
+            headers
+                .iter()
+                .position(|h| h == column)
+                .map(|i| (*field, i))
+        })
+        .collect();
+
+    Ok(reader
+        .records()
+        .map(|record| {
+            let record = record?;
+            let values = indices
+                .iter()
+                .filter_map(|(field, i)| {
+                    record
+                        .get(*i)
+                        .filter(|v| !v.is_empty())
+                        .map(|v| (*field, v))
+                })
+                .collect();
+            parse_row(&Record { values })
+        })
+        .collect())
+}
+
+impl Client {
+    /// Imports collected items from a CSV file into a user's collection.
+    ///
+    /// Each row is added with [`Client::add_collected_item_with_outcome`],
+    /// waiting [`ColumnMapping::delay`] between rows. A row which cannot be
+    /// imported does not stop the import; its error is recorded in the report
+    /// instead. In dry-run mode, the rows are planned rather than added: see
+    /// [`ImportReport::planned`].
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user to add the items to.
+    /// * `reader` - The CSV file, with a header row.
+    /// * `mapping` - How the columns map to the fields of the items.
+    #[tracing::instrument(name = "numista.import_collected_items", skip_all)]
+    pub async fn import_collected_items<R: Read>(
+        &self,
+        user_id: i64,
+        reader: R,
+        mapping: &ColumnMapping,
+    ) -> Result<ImportReport> {
+        let rows = read_rows(reader, mapping)?;
+        let mut catalogues: Option<HashMap<String, i64>> = None;
+        let mut report = ImportReport::default();
+
+        let cancellation = mapping.cancellation.clone().unwrap_or_default();
+        for (i, row) in rows.into_iter().enumerate() {
+            if i > 0 && !mapping.delay.is_zero() {
+                tokio::select! {
+                    biased;
+                    _ = cancellation.cancelled() => {}
+                    _ = tokio::time::sleep(mapping.delay) => {}
+                }
+            }
+            // A row already being added is not interrupted, so that its result
+            // is known.
+            let result = if cancellation.is_cancelled() {
+                Err(Error::Cancelled)
+            } else {
+                match row {
+                    Ok(row) => {
+                        self.import_row(user_id, row, mapping, &mut catalogues)
+                            .await
+                    }
+                    Err(e) => Err(e),
+                }
+            };
+            report.rows.push(ImportedRow { row: i + 1, result });
+        }
+
+        Ok(report)
+    }
+
+    async fn import_row(
+        &self,
+        user_id: i64,
+        mut row: ParsedRow,
+        mapping: &ColumnMapping,
+        catalogues: &mut Option<HashMap<String, i64>>,
+    ) -> Result<WriteOutcome<CollectedItem>> {
+        row.params.type_id = match (row.type_id, row.reference) {
+            (Some(type_id), _) => type_id,
+            (None, Some((catalogue, number))) if mapping.resolve_references => {
+                if catalogues.is_none() {
+                    let response = self.get_catalogues().await?;
+                    *catalogues = Some(
+                        response
+                            .catalogues
+                            .into_iter()
+                            .map(|c| (c.code.to_lowercase(), c.id))
+                            .collect(),
+                    );
+                }
+                let catalogue_id = match catalogue.parse::<i64>() {
+                    Ok(id) => id,
+                    Err(_) => *catalogues
+                        .as_ref()
+                        .and_then(|c| c.get(&catalogue.to_lowercase()))
+                        .ok_or_else(|| {
+                            Error::Import(format!("unknown catalogue: {}", catalogue))
+                        })?,
+                };
+                self.resolve_reference(catalogue_id, &catalogue, &number, row.issuer)
+                    .await?
+            }
+            (None, _) => {
+                return Err(Error::Import(
+                    "missing type, and resolving catalogue references is disabled".to_string(),
+                ))
+            }
+        };
+        self.add_collected_item_with_outcome(user_id, &row.params)
+            .await
+    }
+
+    async fn resolve_reference(
+        &self,
+        catalogue_id: i64,
+        catalogue: &str,
+        number: &str,
+        issuer: Option<String>,
+    ) -> Result<i64> {
+        let mut params = SearchTypesParams::new()
+            .catalogue(catalogue_id)
+            .number(number);
+        if let Some(issuer) = issuer {
+            params = params.issuer(issuer);
+        }
+        let response = self.search_types(&params).await?;
+        match response.types.as_slice() {
+            [t] => Ok(t.id),
+            [] => Err(Error::Import(format!(
+                "no type found for {} {}",
+                catalogue, number
+            ))),
+            types => Err(Error::Import(format!(
+                "{} types found for {} {}",
+                types.len(),
+                catalogue,
+                number
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_import_cancelled() {
+        let client = crate::ClientBuilder::new()
+            .api_key("test_key")
+            .base_url("http://127.0.0.1:9")
+            .build()
+            .unwrap();
+        let token = CancellationToken::new();
+        token.cancel();
+        let mapping = ColumnMapping::new().cancellation(token);
+
+        let report = client
+            .import_collected_items(1, "type\n420\n421\n".as_bytes(), &mapping)
+            .await
+            .unwrap();
+        assert_eq!(report.rows.len(), 2);
+        assert!(report
+            .rows
+            .iter()
+            .all(|r| matches!(r.result, Err(Error::Cancelled))));
+    }
+
+    #[tokio::test]
+    async fn test_import_dry_run() {
+        let client = crate::ClientBuilder::new()
+            .api_key("test_key")
+            .base_url("http://127.0.0.1:9")
+            .dry_run(true)
+            .build()
+            .unwrap();
+        let mapping = ColumnMapping::new().delay(Duration::ZERO);
+
+        let report = client
+            .import_collected_items(1, "type\n420\nx\n".as_bytes(), &mapping)
+            .await
+            .unwrap();
+        // The planned rows are not failures.
+        assert_eq!(report.planned().count(), 1);
+        assert_eq!(report.succeeded().count(), 0);
+        assert_eq!(report.failed().count(), 1);
+        let planned = report.rows[0].result.as_ref().unwrap().planned().unwrap();
+        assert_eq!(planned.body.as_ref().unwrap()["type"], 420);
+    }
+
+    #[test]
+    fn test_read_rows() {
+        let csv = "Numista ID,quantity,grade,for_swap,price,currency,acquisition_date\n\
+                   420,2,VF,yes,1.50,EUR,2024-05-01\n\
+                   ,1,,,,,\n\
+                   421,x,,,,,\n\
+                   422,1,,,3,,\n";
+        let mapping = ColumnMapping::new().column(ImportField::TypeId, "Numista ID");
+        let rows = read_rows(csv.as_bytes(), &mapping).unwrap();
+        assert_eq!(rows.len(), 4);
+
+        let row = rows[0].as_ref().unwrap();
+        assert_eq!(row.type_id, Some(420));
+        assert_eq!(row.params.type_id, 420);
+        assert_eq!(row.params.quantity, Some(2));
+        assert_eq!(row.params.grade, Some(Grade::Vf));
+        assert_eq!(row.params.for_swap, Some(true));
+        assert_eq!(row.params.price.as_ref().unwrap().currency, "EUR");
+        assert_eq!(
+            row.params.acquisition_date,
+            NaiveDate::from_ymd_opt(2024, 5, 1)
+        );
+
+        assert!(matches!(rows[1], Err(Error::Import(_))));
+        assert!(matches!(rows[2], Err(Error::Import(_))));
+        assert!(matches!(rows[3], Err(Error::Import(_))));
+    }
+
+    #[test]
+    fn test_import_field_from_str() {
+        for field in ImportField::ALL {
+            assert_eq!(field.to_string().parse::<ImportField>().unwrap(), field);
+        }
+        assert!("nope".parse::<ImportField>().is_err());
+    }
+}
@@ -0,0 +1,179 @@
+//! The languages of the Numista API.
+//!
+//! The translatable fields of the responses, such as titles, are available in
+//! the languages of the website. [`SupportedLanguage`] lists them, so that the
+//! language of a client is checked at compile time. Codes given at runtime, e.g.
+//! from the command line, are checked when the client is built.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use planchet::{ClientBuilder, SupportedLanguage};
+//!
+//! let client = ClientBuilder::new()
+//!     .api_key("YOUR_API_KEY")
+//!     .language(SupportedLanguage::French)
+//!     .build()
+//!     .unwrap();
+//!
+//! let lang: SupportedLanguage = "es".parse().unwrap();
+//! assert_eq!(lang, SupportedLanguage::Spanish);
+//! ```
+use crate::error::{Error, Result};
+use crate::models::NumistaType;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// A language in which the API returns its translatable fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum SupportedLanguage {
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "fr")]
+    French,
+    #[serde(rename = "es")]
+    Spanish,
+    #[serde(rename = "de")]
+    German,
+    #[serde(rename = "it")]
+    Italian,
+    #[serde(rename = "nl")]
+    Dutch,
+    #[serde(rename = "pt")]
+    Portuguese,
+}
+
+impl SupportedLanguage {
+    /// All the supported languages.
+    pub const ALL: [SupportedLanguage; 7] = [
+        SupportedLanguage::English,
+        SupportedLanguage::French,
+        SupportedLanguage::Spanish,
+        SupportedLanguage::German,
+        SupportedLanguage::Italian,
+        SupportedLanguage::Dutch,
+        SupportedLanguage::Portuguese,
+    ];
+
+    /// Returns the ISO 639-1 code of the language, e.g. `fr`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            SupportedLanguage::English => "en",
+            SupportedLanguage::French => "fr",
+            SupportedLanguage::Spanish => "es",
+            SupportedLanguage::German => "de",
+            SupportedLanguage::Italian => "it",
+            SupportedLanguage::Dutch => "nl",
+            SupportedLanguage::Portuguese => "pt",
+        }
+    }
+
+    /// Returns the language of an ISO 639-1 code, in any case, if it is
+    /// supported.
+    pub fn from_code(code: &str) -> Option<Self> {
+        let code = code.trim();
+        Self::ALL
+            .into_iter()
+            .find(|lang| lang.code().eq_ignore_ascii_case(code))
+    }
+
+    /// Returns the supported language of an ISO 639 language, if any.
+    #[cfg(feature = "iso-codes")]
+    pub fn from_language(language: isolang::Language) -> Option<Self> {
+        language.to_639_1().and_then(Self::from_code)
+    }
+}
+
+impl fmt::Display for SupportedLanguage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl FromStr for SupportedLanguage {
+    type Err = Error;
+
+    /// Parses an ISO 639-1 code, failing with
+    /// [`Error::UnsupportedLanguage`] if the language is not supported.
+    fn from_str(s: &str) -> Result<Self> {
+        Self::from_code(s).ok_or_else(|| Error::UnsupportedLanguage(s.to_string()))
+    }
+}
+
+/// A type in several languages, fetched with
+/// [`Client::get_type_multilang`](crate::Client::get_type_multilang).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MultilangType {
+    id: i64,
+    types: BTreeMap<SupportedLanguage, NumistaType>,
+}
+
+impl MultilangType {
+    pub(crate) fn new<I>(id: i64, types: I) -> Self
+    where
+        I: IntoIterator<Item = (SupportedLanguage, NumistaType)>,
+    {
+        Self {
+            id,
+            types: types.into_iter().collect(),
+        }
+    }
+
+    /// Returns the ID of the type.
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    /// Returns the type in a language, if it was fetched in it.
+    pub fn get(&self, lang: SupportedLanguage) -> Option<&NumistaType> {
+        self.types.get(&lang)
+    }
+
+    /// Returns the title of the type in a language, if it was fetched in it.
+    pub fn title(&self, lang: SupportedLanguage) -> Option<&str> {
+        self.get(lang)
+            .map(|numista_type| numista_type.title.as_str())
+    }
+
+    /// Returns the languages the type was fetched in, in the order of
+    /// [`SupportedLanguage`].
+    pub fn languages(&self) -> impl Iterator<Item = SupportedLanguage> + '_ {
+        self.types.keys().copied()
+    }
+
+    /// Returns the type in each language it was fetched in.
+    pub fn iter(&self) -> impl Iterator<Item = (SupportedLanguage, &NumistaType)> {
+        self.types
+            .iter()
+            .map(|(lang, numista_type)| (*lang, numista_type))
+    }
+
+    /// Returns the type in each language it was fetched in, by language.
+    pub fn into_map(self) -> BTreeMap<SupportedLanguage, NumistaType> {
+        self.types
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_language_test() {
+        for lang in SupportedLanguage::ALL {
+            assert_eq!(lang.code().parse::<SupportedLanguage>().unwrap(), lang);
+        }
+        assert_eq!(
+            "FR".parse::<SupportedLanguage>().unwrap(),
+            SupportedLanguage::French
+        );
+        for code in ["", "xx", "ja", "fra"] {
+            assert!(matches!(
+                code.parse::<SupportedLanguage>(),
+                Err(Error::UnsupportedLanguage(_))
+            ));
+        }
+    }
+}
@@ -5,7 +5,7 @@
 //! ## Basic Search
 //!
 //! ```no_run
-//! use planchet::model::SearchTypesParams;
+//! use planchet::models::SearchTypesParams;
 //! use planchet::ClientBuilder;
 //!
 //! #[tokio::main]
@@ -25,7 +25,7 @@
 //! ## Adding a Collected Item
 //!
 //! ```no_run
-//! use planchet::model::{AddCollectedItemParams, Grade};
+//! use planchet::models::{AddCollectedItemParams, Grade};
 //! use planchet::ClientBuilder;
 //!
 //! #[tokio::main]
@@ -55,7 +55,7 @@
 //! for a search and how to handle specific API errors.
 //!
 //! ```no_run
-//! use planchet::model::SearchTypesParams;
+//! use planchet::models::SearchTypesParams;
 //! use planchet::{ClientBuilder, Error};
 //! use futures::stream::TryStreamExt;
 //!
@@ -85,11 +85,147 @@
 //!     }
 //! }
 //! ```
+//!
+//! # Stability
+//!
+//! The response models, [`Error`], [`ApiError`], [`ErrorKind`], and the enums
+//! of values returned by the API, such as [`models::Category`], are
+//! `#[non_exhaustive]`, so that the fields and values Numista adds can be
+//! supported in minor releases. Match them with a wildcard arm, and create
+//! models by deserializing them, e.g. with `serde_json::from_value`, rather
+//! than with struct expressions.
+//!
+//! # Feature Flags
+//!
+//! The `logging`, `decimal`, and `iso-codes` features are enabled by default.
+//! Disabling them gives a leaner client, e.g. for embedded or serverless
+//! targets.
+//!
+//! * `logging`: Logs every request and response, with credentials redacted,
+//!   through `tracing`. This only gates the logging middleware: spans for
+//!   each API call are emitted either way, so `tracing` remains a
+//!   dependency.
+//! * `decimal`: Represents the prices, weights, and other non-integer numbers
+//!   of the models as exact `rust_decimal::Decimal`s, rather than `f64`s. See
+//!   [`models::Number`]. Also adds the parsing of face values and
+//!   compositions, and the precious metal content of types. See the
+//!   `denomination`, `composition`, and `bullion` modules.
+//! * `iso-codes`: Represents currencies and languages in the models as
+//!   `iso_currency` and `isolang` enums, rather than their codes as strings.
+//!   See [`models::CurrencyCode`] and [`models::LanguageCode`].
+//! * `lenient-models`: Adds an `extra` map to every response model which
+//!   collects any fields the API returns that this crate does not know about.
+//!   See [`models::Extra`].
+//! * `strict-models`: Rejects any response containing a field that this crate
+//!   does not know about, turning schema drift into a deserialization error.
+//!   It takes precedence over `lenient-models` when both are enabled, e.g.
+//!   by different crates of a dependency graph: the `extra` maps are then
+//!   always empty.
+//! * `html`: Adds plain text and Markdown accessors for HTML-formatted fields,
+//!   such as `NumistaType::comments_plain`. See the [`html`] module.
+//! * `image`: Downscales large photos before searching by image. See
+//!   [`image_search::SearchByImageBuilder::photo`].
+//! * `store`: Adds a SQLite store for types, issues, and collected items,
+//!   for incremental sync jobs and offline analysis. See the `store` and
+//!   `sync` modules.
+//! * `import`: Adds bulk import of collected items from CSV files. See the
+//!   `import` module.
+//! * `wikidata`: Adds label lookups of the Wikidata entities linked from
+//!   models. See the [`linked_data`] module.
+//! * `geonames`: Adds lookups of the coordinates of mints and publication
+//!   places from GeoNames. See the `geonames` module.
+//! * `report`: Adds HTML and Markdown reports of collection statistics, with
+//!   SVG charts. See the `report` module.
+//! * `ecb`: Adds the reference exchange rates of the European Central Bank,
+//!   for converting prices. See the `currency` module, which requires the
+//!   `decimal` and `iso-codes` features.
+//! * `spot-prices`: Adds the spot prices of precious metals from
+//!   gold-api.com, for the melt value of types. See the `bullion` module.
+//! * `pdf`: Adds PDF inventories of a collection, with a photo and the
+//!   acquisition and value of each item. See the `export` module.
+//! * `qr`: Adds QR codes, as SVG or PNG pictures, linking to the pages of
+//!   types and collected items. See the `qr` module.
+//! * `labels`: Adds labels for the 2x2 flips of collected items, with a QR
+//!   code linking to the page of their type. See the `export::labels`
+//!   module, which requires the `pdf` and `qr` features.
+//! * `vcr`: Adds cassettes recording the responses of the API on a first
+//!   run, and replaying them on the following ones, for deterministic tests.
+//!   See the `vcr` module.
+//! * `audit`: Adds a tamper-evident log of the write requests sent to the
+//!   API. See the `audit` module.
+//! * `clap`: Implements `clap::ValueEnum` for the enums of the models, such
+//!   as [`models::Category`] and [`models::Grade`], to take them as
+//!   command-line arguments.
+//! * `test-util`: Adds generators of unusual but valid API payloads, also as
+//!   proptest strategies, for property-based testing of the models, and
+//!   builders of the models, e.g. `NumistaType::builder()`, for tests. See
+//!   the `test_util` module.
+
+pub mod analysis;
+pub mod api;
+#[cfg(feature = "audit")]
+pub mod audit;
+pub mod auth;
+#[cfg(feature = "decimal")]
+pub mod bullion;
+pub mod circuit_breaker;
 pub mod client;
+#[cfg(feature = "decimal")]
+pub mod composition;
+pub mod country;
+#[cfg(all(feature = "decimal", feature = "iso-codes"))]
+pub mod currency;
 pub mod de;
+#[cfg(feature = "decimal")]
+pub mod denomination;
 pub mod error;
+#[cfg(feature = "pdf")]
+pub mod export;
+#[cfg(feature = "geonames")]
+pub mod geonames;
+#[cfg(feature = "html")]
+pub mod html;
+pub mod hydrate;
+pub mod image_search;
+#[cfg(feature = "import")]
+pub mod import;
+pub mod lang;
+pub mod linked_data;
+pub mod measurements;
+#[deprecated(since = "0.1.0", note = "use `planchet::models` instead")]
 pub mod model;
+pub mod models;
+pub mod oauth;
+pub mod pagination;
+pub mod pictures;
+#[cfg(feature = "qr")]
+pub mod qr;
+pub mod reference;
+#[cfg(feature = "report")]
+pub mod report;
+pub mod routes;
+pub mod session;
+pub mod sort;
+#[cfg(feature = "store")]
+pub mod store;
+#[cfg(feature = "store")]
+pub mod sync;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+#[cfg(feature = "iso-codes")]
+pub mod urls;
+pub mod validation;
+#[cfg(feature = "vcr")]
+pub mod vcr;
+pub mod watch;
+pub mod watchlist;
 
 // Re-export public API
-pub use client::{Client, ClientBuilder};
-pub use error::{ApiError, Error, Result};
+pub use client::{
+    AddOutcome, Client, ClientBuilder, Confirmation, DeleteOutcome, PlannedRequest, WriteOutcome,
+};
+pub use api::NumistaApi;
+pub use error::{ApiError, Error, ErrorKind, FieldError, Result};
+pub use lang::{MultilangType, SupportedLanguage};
+pub use routes::ApiVersion;
+pub use session::Session;
@@ -0,0 +1,311 @@
+//! Links to the Wikidata and Nomisma linked-data vocabularies.
+//!
+//! Issuers, mints, ruling authorities, and issuing entities carry the IDs of
+//! the same entities in Wikidata and Nomisma. The [`LinkedData`] trait turns
+//! them into resolvable URIs, for joining planchet data with other datasets.
+//!
+//! With the `wikidata` feature, [`WikidataClient`] looks up the labels of
+//! Wikidata entities.
+//!
+//! # Examples
+//!
+//! ```
+//! use planchet::linked_data::LinkedData;
+//! use planchet::models::RulingAuthority;
+//!
+//! let authority: RulingAuthority = serde_json::from_str(
+//!     r#"{"id": 1, "name": "Victoria", "wikidata_id": "Q9439", "nomisma_id": "victoria"}"#,
+//! )
+//! .unwrap();
+//! assert_eq!(
+//!     authority.wikidata_uri().unwrap().as_str(),
+//!     "http://www.wikidata.org/entity/Q9439"
+//! );
+//! assert_eq!(
+//!     authority.nomisma_uri().unwrap().as_str(),
+//!     "http://nomisma.org/id/victoria"
+//! );
+//! ```
+use crate::models::{IssuerDetail, IssuingEntity, MintDetail, RulingAuthority};
+use url::Url;
+
+/// The prefix of the URIs of Wikidata entities.
+pub const WIKIDATA_ENTITY_PREFIX: &str = "http://www.wikidata.org/entity/";
+
+/// The prefix of the URIs of Nomisma concepts.
+pub const NOMISMA_PREFIX: &str = "http://nomisma.org/id/";
+
+/// Returns whether `id` is a Wikidata item ID, such as `Q30`.
+pub fn is_wikidata_id(id: &str) -> bool {
+    id.strip_prefix('Q').is_some_and(|n| {
+        !n.is_empty() && !n.starts_with('0') && n.chars().all(|c| c.is_ascii_digit())
+    })
+}
+
+/// Returns the URI of a Wikidata entity, or `None` if `id` is not a Wikidata
+/// item ID.
+pub fn wikidata_uri(id: &str) -> Option<Url> {
+    if !is_wikidata_id(id) {
+        return None;
+    }
+    Url::parse(&format!("{}{}", WIKIDATA_ENTITY_PREFIX, id)).ok()
+}
+
+/// Returns the URI of a Nomisma concept, or `None` if `id` is not a Nomisma
+/// ID, such as `london`.
+pub fn nomisma_uri(id: &str) -> Option<Url> {
+    let valid = !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return None;
+    }
+    Url::parse(&format!("{}{}", NOMISMA_PREFIX, id)).ok()
+}
+
+/// A model which refers to entities in linked-data vocabularies.
+pub trait LinkedData {
+    /// Returns the ID of the Wikidata entity, if known.
+    fn wikidata_id(&self) -> Option<&str>;
+
+    /// Returns the ID of the Nomisma concept, if known.
+    fn nomisma_id(&self) -> Option<&str> {
+        None
+    }
+
+    /// Returns the URI of the Wikidata entity, if known.
+    fn wikidata_uri(&self) -> Option<Url> {
+        self.wikidata_id().and_then(wikidata_uri)
+    }
+
+    /// Returns the URI of the Nomisma concept, if known.
+    fn nomisma_uri(&self) -> Option<Url> {
+        self.nomisma_id().and_then(nomisma_uri)
+    }
+}
+
+impl LinkedData for IssuerDetail {
+    fn wikidata_id(&self) -> Option<&str> {
+        self.wikidata_id.as_deref()
+    }
+}
+
+impl LinkedData for IssuingEntity {
+    fn wikidata_id(&self) -> Option<&str> {
+        self.wikidata_id.as_deref()
+    }
+}
+
+impl LinkedData for MintDetail {
+    fn wikidata_id(&self) -> Option<&str> {
+        self.wikidata_id.as_deref()
+    }
+
+    fn nomisma_id(&self) -> Option<&str> {
+        self.nomisma_id.as_deref()
+    }
+}
+
+impl LinkedData for RulingAuthority {
+    fn wikidata_id(&self) -> Option<&str> {
+        self.wikidata_id.as_deref()
+    }
+
+    fn nomisma_id(&self) -> Option<&str> {
+        self.nomisma_id.as_deref()
+    }
+}
+
+#[cfg(feature = "wikidata")]
+pub use self::wikidata::WikidataClient;
+
+#[cfg(feature = "wikidata")]
+mod wikidata {
+    use super::is_wikidata_id;
+    use crate::error::Result;
+    use serde::Deserialize;
+    use std::collections::HashMap;
+
+    /// The maximum number of entities the Wikidata API returns per request.
+    const MAX_IDS_PER_REQUEST: usize = 50;
+
+    #[derive(Deserialize)]
+    struct EntitiesResponse {
+        #[serde(default)]
+        entities: HashMap<String, Entity>,
+    }
+
+    #[derive(Deserialize)]
+    struct Entity {
+        #[serde(default)]
+        labels: HashMap<String, Label>,
+    }
+
+    #[derive(Deserialize)]
+    struct Label {
+        value: String,
+    }
+
+    /// A client for looking up Wikidata entities.
+    ///
+    /// This is only available with the `wikidata` feature.
+    #[derive(Debug, Clone)]
+    pub struct WikidataClient {
+        client: reqwest::Client,
+        base_url: String,
+    }
+
+    impl Default for WikidataClient {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl WikidataClient {
+        /// Creates a client for the public Wikidata API.
+        pub fn new() -> Self {
+            Self::with_base_url("https://www.wikidata.org/w/api.php")
+        }
+
+        /// Creates a client for the Wikidata API at `base_url`.
+        ///
+        /// This is useful for testing.
+        pub fn with_base_url<S: Into<String>>(base_url: S) -> Self {
+            let client = reqwest::Client::builder()
+                .user_agent(concat!("planchet/", env!("CARGO_PKG_VERSION")))
+                .build()
+                .unwrap_or_default();
+            Self {
+                client,
+                base_url: base_url.into(),
+            }
+        }
+
+        /// Gets the label of an entity.
+        ///
+        /// Returns `None` if the entity does not exist, has no label in the
+        /// language, or `id` is not a Wikidata item ID.
+        ///
+        /// # Arguments
+        ///
+        /// * `id` - The ID of the entity, e.g. `Q30`.
+        /// * `lang` - The language code of the label, e.g. `en`.
+        pub async fn label(&self, id: &str, lang: &str) -> Result<Option<String>> {
+            Ok(self.labels(&[id], lang).await?.remove(id))
+        }
+
+        /// Gets the labels of several entities, keyed by ID.
+        ///
+        /// Entities which do not exist or have no label in the language, and
+        /// IDs which are not Wikidata item IDs, are left out.
+        ///
+        /// # Arguments
+        ///
+        /// * `ids` - The IDs of the entities.
+        /// * `lang` - The language code of the labels, e.g. `en`.
+        pub async fn labels(&self, ids: &[&str], lang: &str) -> Result<HashMap<String, String>> {
+            let ids: Vec<&str> = ids
+                .iter()
+                .copied()
+                .filter(|id| is_wikidata_id(id))
+                .collect();
+            let mut labels = HashMap::new();
+            for chunk in ids.chunks(MAX_IDS_PER_REQUEST) {
+                let response: EntitiesResponse = self
+                    .client
+                    .get(&self.base_url)
+                    .query(&[
+                        ("action", "wbgetentities"),
+                        ("format", "json"),
+                        ("props", "labels"),
+                        ("ids", &chunk.join("|")),
+                        ("languages", lang),
+                    ])
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?;
+                labels.extend(
+                    response
+                        .entities
+                        .into_iter()
+                        .filter_map(|(id, mut entity)| {
+                            entity.labels.remove(lang).map(|label| (id, label.value))
+                        }),
+                );
+            }
+            Ok(labels)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uris() {
+        assert_eq!(
+            wikidata_uri("Q30").unwrap().as_str(),
+            "http://www.wikidata.org/entity/Q30"
+        );
+        assert_eq!(wikidata_uri("Q"), None);
+        assert_eq!(wikidata_uri("Q030"), None);
+        assert_eq!(wikidata_uri("P31"), None);
+        assert_eq!(
+            nomisma_uri("royal_mint").unwrap().as_str(),
+            "http://nomisma.org/id/royal_mint"
+        );
+        assert_eq!(nomisma_uri("not an id"), None);
+    }
+
+    #[test]
+    fn test_mint_detail() {
+        let mint: MintDetail =
+            serde_json::from_str(r#"{"id": "1", "nomisma_id": "london", "wikidata_id": "Q84"}"#)
+                .unwrap();
+        assert_eq!(
+            mint.wikidata_uri().unwrap().as_str(),
+            "http://www.wikidata.org/entity/Q84"
+        );
+        assert_eq!(
+            mint.nomisma_uri().unwrap().as_str(),
+            "http://nomisma.org/id/london"
+        );
+    }
+
+    #[cfg(feature = "wikidata")]
+    #[tokio::test]
+    async fn test_wikidata_labels() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("action".into(), "wbgetentities".into()),
+                mockito::Matcher::UrlEncoded("ids".into(), "Q30|Q999999999".into()),
+                mockito::Matcher::UrlEncoded("languages".into(), "en".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"entities": {
+                    "Q30": {"id": "Q30", "labels": {"en": {"language": "en", "value": "United States"}}},
+                    "Q999999999": {"id": "Q999999999", "missing": ""}
+                }, "success": 1}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = WikidataClient::with_base_url(server.url());
+        let labels = client
+            .labels(&["Q30", "not-an-id", "Q999999999"], "en")
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(labels.len(), 1);
+        assert_eq!(labels["Q30"], "United States");
+    }
+}
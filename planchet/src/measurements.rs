@@ -0,0 +1,271 @@
+//! Checking the measurements of a piece against the catalogue.
+//!
+//! Counterfeits are often a little too light, too wide, or too thin. With
+//! [`NumistaType::check_measurements`], collectors compare the weight, size,
+//! and thickness they measured on a piece with those of its type, and get a
+//! [`Verdict`] flagging pieces which are off by more than some
+//! [`Tolerances`].
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use planchet::measurements::{Tolerances, Verdict};
+//! use planchet::models::Number;
+//! use planchet::ClientBuilder;
+//!
+//! # async fn run(weight: Number) -> planchet::Result<()> {
+//! let client = ClientBuilder::new().api_key("YOUR_API_KEY").build()?;
+//! let type_ = client.get_type(12345).await?;
+//!
+//! let report = type_.check_measurements(Some(weight), None, None, &Tolerances::new());
+//! if report.verdict() == Verdict::Suspicious {
+//!     println!("This piece does not match its type.");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use crate::models::{Number, NumistaType};
+
+/// How far measurements may be from the catalogue, in percent of the
+/// catalogue value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tolerances {
+    /// The tolerance on the weight. Defaults to 3%.
+    pub weight: Number,
+    /// The tolerance on the size. Defaults to 2%.
+    pub size: Number,
+    /// The tolerance on the thickness. Defaults to 10%, as thicknesses are
+    /// hard to measure precisely.
+    pub thickness: Number,
+}
+
+impl Default for Tolerances {
+    fn default() -> Self {
+        Self {
+            weight: Number::from(3),
+            size: Number::from(2),
+            thickness: Number::from(10),
+        }
+    }
+}
+
+impl Tolerances {
+    /// Creates the default tolerances.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the tolerance on the weight, in percent.
+    pub fn weight(mut self, percent: Number) -> Self {
+        self.weight = percent;
+        self
+    }
+
+    /// Sets the tolerance on the size, in percent.
+    pub fn size(mut self, percent: Number) -> Self {
+        self.size = percent;
+        self
+    }
+
+    /// Sets the tolerance on the thickness, in percent.
+    pub fn thickness(mut self, percent: Number) -> Self {
+        self.thickness = percent;
+        self
+    }
+}
+
+/// A measurement of a piece.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Measurement {
+    /// The weight, in grams.
+    Weight,
+    /// The size (diameter), in millimetres.
+    Size,
+    /// The thickness, in millimetres.
+    Thickness,
+}
+
+/// A measurement of a piece, compared with the catalogue.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeasurementCheck {
+    /// What was measured.
+    pub measurement: Measurement,
+    /// The value measured on the piece.
+    pub measured: Number,
+    /// The value of the type, if the catalogue has it.
+    pub expected: Option<Number>,
+    /// How far the measured value is from the expected one, in percent of
+    /// the expected one. Negative if the piece is lighter or smaller.
+    pub deviation: Option<Number>,
+    /// Whether the deviation is within the tolerance, if it is known.
+    pub within_tolerance: Option<bool>,
+}
+
+/// The overall result of the checks of a piece.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Verdict {
+    /// Every measurement is within its tolerance.
+    Consistent,
+    /// At least one measurement is off by more than its tolerance.
+    Suspicious,
+    /// No measurement could be compared with the catalogue.
+    Unknown,
+}
+
+/// The measurements of a piece, compared with the catalogue.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeasurementReport {
+    /// A check per measurement given, in the order weight, size, thickness.
+    pub checks: Vec<MeasurementCheck>,
+}
+
+impl MeasurementReport {
+    /// Returns the overall result of the checks.
+    pub fn verdict(&self) -> Verdict {
+        let results = self.checks.iter().filter_map(|c| c.within_tolerance);
+        let mut verdict = Verdict::Unknown;
+        for within in results {
+            if !within {
+                return Verdict::Suspicious;
+            }
+            verdict = Verdict::Consistent;
+        }
+        verdict
+    }
+}
+
+fn check(
+    measurement: Measurement,
+    measured: Number,
+    expected: Option<Number>,
+    tolerance: Number,
+) -> MeasurementCheck {
+    let zero = Number::from(0);
+    let deviation = expected
+        .filter(|e| *e > zero)
+        .map(|e| (measured - e) / e * Number::from(100));
+    MeasurementCheck {
+        measurement,
+        measured,
+        expected,
+        deviation,
+        within_tolerance: deviation.map(|d| within(d, tolerance)),
+    }
+}
+
+/// Checks if a deviation is within a tolerance.
+#[cfg(feature = "decimal")]
+fn within(deviation: Number, tolerance: Number) -> bool {
+    deviation.abs() <= tolerance
+}
+
+/// Checks if a deviation is within a tolerance.
+///
+/// The deviation of a measurement exactly at the tolerance, e.g. 4.9 g for
+/// 5 g at 2%, is off by a rounding error with `f64`s, so it is compared with
+/// a small margin.
+#[cfg(not(feature = "decimal"))]
+fn within(deviation: Number, tolerance: Number) -> bool {
+    deviation.abs() <= tolerance + 1e-9
+}
+
+impl NumistaType {
+    /// Compares measurements of a piece with those of the type.
+    ///
+    /// # Arguments
+    ///
+    /// * `weight` - The weight of the piece, in grams, if it was measured.
+    /// * `size` - The size of the piece, in millimetres, if it was measured.
+    /// * `thickness` - The thickness of the piece, in millimetres, if it was
+    ///   measured.
+    /// * `tolerances` - How far the measurements may be from the catalogue.
+    pub fn check_measurements(
+        &self,
+        weight: Option<Number>,
+        size: Option<Number>,
+        thickness: Option<Number>,
+        tolerances: &Tolerances,
+    ) -> MeasurementReport {
+        let measured = [
+            (Measurement::Weight, weight, self.weight, tolerances.weight),
+            (Measurement::Size, size, self.size, tolerances.size),
+            (
+                Measurement::Thickness,
+                thickness,
+                self.thickness,
+                tolerances.thickness,
+            ),
+        ];
+        MeasurementReport {
+            checks: measured
+                .into_iter()
+                .filter_map(|(measurement, measured, expected, tolerance)| {
+                    Some(check(measurement, measured?, expected, tolerance))
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn number(value: i32, tenths: i32) -> Number {
+        Number::from(value) + Number::from(tenths) / Number::from(10)
+    }
+
+    /// Checks that a deviation is the expected one, up to the rounding errors
+    /// of `f64`s.
+    fn assert_deviation(check: &MeasurementCheck, expected: Number) {
+        let deviation = check.deviation.expect("a deviation");
+        let margin = Number::from(1) / Number::from(1_000_000);
+        assert!(
+            (deviation - expected).abs() <= margin,
+            "{} is not {}",
+            deviation,
+            expected
+        );
+    }
+
+    fn full_type() -> NumistaType {
+        serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "title": "5 Cents",
+            "category": "coin",
+            "weight": 5,
+            "size": 21,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_check_measurements() {
+        let type_ = full_type();
+        let tolerances = Tolerances::new();
+
+        let report = type_.check_measurements(Some(number(4, 9)), None, None, &tolerances);
+        assert_eq!(report.checks.len(), 1);
+        // Exactly at the tolerance.
+        assert_deviation(&report.checks[0], Number::from(-2));
+        assert_eq!(report.verdict(), Verdict::Consistent);
+
+        let report = type_.check_measurements(None, Some(number(21, 5)), None, &tolerances);
+        assert_deviation(&report.checks[0], Number::from(50) / Number::from(21));
+        assert_eq!(report.verdict(), Verdict::Suspicious);
+
+        let report = type_.check_measurements(
+            Some(number(5, 0)),
+            Some(number(22, 5)),
+            Some(number(1, 5)),
+            &tolerances,
+        );
+        assert_eq!(report.verdict(), Verdict::Suspicious);
+        assert_eq!(report.checks[1].within_tolerance, Some(false));
+        // The catalogue has no thickness to compare with.
+        assert_eq!(report.checks[2].within_tolerance, None);
+
+        let report = type_.check_measurements(None, None, Some(number(1, 5)), &tolerances);
+        assert_eq!(report.verdict(), Verdict::Unknown);
+    }
+}
@@ -0,0 +1,6 @@
+//! The models of the API, before they moved to [`crate::models`].
+//!
+//! Every item is still available here, under the same name, so that code
+//! written against earlier versions keeps compiling. See the
+//! [migration guide](crate::models#migrating-from-model).
+pub use crate::models::*;
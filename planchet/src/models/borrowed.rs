@@ -0,0 +1,116 @@
+//! Borrowing variants of the search result models.
+//!
+//! These models borrow their strings from the response body where possible,
+//! avoiding an allocation per field when ingesting large numbers of search
+//! results. Use [`Client::search_types_raw`](crate::Client::search_types_raw)
+//! to fetch the body, parse it with [`SearchTypesResponseRef::from_slice`], and
+//! call `to_owned()` on the results that need to outlive the body.
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use super::{Category, Issuer, SearchTypeResult, SearchTypesResponse};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct IssuerRef<'a> {
+    #[serde(borrow)]
+    pub code: Cow<'a, str>,
+    #[serde(borrow)]
+    pub name: Cow<'a, str>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: super::Extra,
+}
+
+impl IssuerRef<'_> {
+    /// Converts the issuer into its owned equivalent.
+    pub fn to_owned(&self) -> Issuer {
+        Issuer {
+            code: self.code.to_string(),
+            name: self.name.to_string(),
+            #[cfg(feature = "lenient-models")]
+            extra: self.extra.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct SearchTypeResultRef<'a> {
+    pub id: i64,
+    #[serde(borrow)]
+    pub title: Cow<'a, str>,
+    pub category: Option<Category>,
+    #[serde(borrow)]
+    pub issuer: Option<IssuerRef<'a>>,
+    pub min_year: Option<i32>,
+    pub max_year: Option<i32>,
+    /// The URL of the obverse thumbnail, unparsed.
+    #[serde(borrow)]
+    pub obverse_thumbnail: Option<Cow<'a, str>>,
+    /// The URL of the reverse thumbnail, unparsed.
+    #[serde(borrow)]
+    pub reverse_thumbnail: Option<Cow<'a, str>>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: super::Extra,
+}
+
+impl SearchTypeResultRef<'_> {
+    /// Converts the result into its owned equivalent.
+    ///
+    /// Thumbnail URLs which cannot be parsed are dropped.
+    pub fn to_owned(&self) -> SearchTypeResult {
+        SearchTypeResult {
+            id: self.id,
+            title: self.title.to_string(),
+            category: self.category.clone(),
+            issuer: self.issuer.as_ref().map(IssuerRef::to_owned),
+            min_year: self.min_year,
+            max_year: self.max_year,
+            obverse_thumbnail: self.obverse_thumbnail.as_deref().and_then(|u| Url::parse(u).ok()),
+            reverse_thumbnail: self.reverse_thumbnail.as_deref().and_then(|u| Url::parse(u).ok()),
+            #[cfg(feature = "lenient-models")]
+            extra: self.extra.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct SearchTypesResponseRef<'a> {
+    pub count: i64,
+    #[serde(borrow)]
+    pub types: Vec<SearchTypeResultRef<'a>>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: super::Extra,
+}
+
+impl<'a> SearchTypesResponseRef<'a> {
+    /// Parses a response body, borrowing strings from it where possible.
+    pub fn from_slice(body: &'a [u8]) -> serde_json::Result<Self> {
+        serde_json::from_slice(body)
+    }
+
+    /// Converts the response into its owned equivalent.
+    pub fn to_owned(&self) -> SearchTypesResponse {
+        SearchTypesResponse {
+            count: self.count,
+            types: self.types.iter().map(SearchTypeResultRef::to_owned).collect(),
+            #[cfg(feature = "lenient-models")]
+            extra: self.extra.clone(),
+        }
+    }
+}
@@ -0,0 +1,814 @@
+//! Types shared by the requests and the responses of the API.
+use crate::validation::MAX_AXIS;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Unrecognized fields captured from an API response.
+///
+/// Every response model carries an `extra` map of this type when the
+/// `lenient-models` feature is enabled, so that fields added to the API after
+/// this crate was released can still be inspected at runtime. The map is
+/// always empty when the `strict-models` feature is enabled too, since such
+/// fields are then rejected.
+pub type Extra = std::collections::HashMap<String, serde_json::Value>;
+
+/// A non-integer number returned by the API, such as a price or a weight.
+///
+/// This is an exact `rust_decimal::Decimal` with the `decimal` feature, and an
+/// `f64` otherwise.
+#[cfg(feature = "decimal")]
+pub type Number = rust_decimal::Decimal;
+#[cfg(not(feature = "decimal"))]
+pub type Number = f64;
+
+/// An ISO 4217 currency code.
+///
+/// This is an `iso_currency::Currency` with the `iso-codes` feature, and the
+/// code as a `String` otherwise.
+#[cfg(feature = "iso-codes")]
+pub type CurrencyCode = iso_currency::Currency;
+#[cfg(not(feature = "iso-codes"))]
+pub type CurrencyCode = String;
+
+/// An ISO 639 language code.
+///
+/// This is an `isolang::Language` with the `iso-codes` feature, and the code
+/// as a `String` otherwise.
+#[cfg(feature = "iso-codes")]
+pub type LanguageCode = isolang::Language;
+#[cfg(not(feature = "iso-codes"))]
+pub type LanguageCode = String;
+
+/// Implements `clap::ValueEnum` for an enum with an `as_str` method, with the
+/// given values, those known to this crate, as the possible values.
+#[cfg(feature = "clap")]
+macro_rules! value_enum {
+    ($name:ident, [$($value:expr),+ $(,)?]) => {
+        impl clap::ValueEnum for $name {
+            fn value_variants<'a>() -> &'a [Self] {
+                static VALUES: &[$name] = &[$($value),+];
+                VALUES
+            }
+
+            fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
+                let known = Self::value_variants().iter().find(|value| *value == self)?;
+                Some(clap::builder::PossibleValue::new(known.as_str()))
+            }
+        }
+    };
+}
+
+#[cfg(feature = "clap")]
+pub(crate) use value_enum;
+
+/// Declares an enum of well-known string values with an `Other` fallback for
+/// values this crate does not know about, so that new values returned by the
+/// API never cause a deserialization error.
+macro_rules! open_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($(#[$vmeta:meta])* $variant:ident => $text:literal,)+
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
+        #[serde(from = "String", into = "String")]
+        #[non_exhaustive]
+        pub enum $name {
+            $($(#[$vmeta])* $variant,)+
+            /// A value not known to this crate.
+            Other(String),
+        }
+
+        impl $name {
+            /// Returns the name of the value, as used by the API.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $text,)+
+                    $name::Other(s) => s,
+                }
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(s: String) -> Self {
+                $(if s.eq_ignore_ascii_case($text) {
+                    return $name::$variant;
+                })+
+                $name::Other(s)
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(value: $name) -> Self {
+                match value {
+                    $name::Other(s) => s,
+                    known => known.as_str().to_string(),
+                }
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = std::convert::Infallible;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Ok($name::from(s.to_string()))
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "{}", self.as_str())
+            }
+        }
+
+        #[cfg(feature = "clap")]
+        $crate::models::common::value_enum!($name, [$($name::$variant),+]);
+    };
+}
+
+pub(crate) use open_enum;
+
+open_enum! {
+    /// A writing script used in the lettering of an item.
+    pub enum Script {
+        Latin => "Latin",
+        Cyrillic => "Cyrillic",
+        Greek => "Greek",
+        Arabic => "Arabic",
+        Hebrew => "Hebrew",
+        Chinese => "Chinese",
+        Japanese => "Japanese",
+        Korean => "Korean",
+        Devanagari => "Devanagari",
+        Bengali => "Bengali",
+        Tamil => "Tamil",
+        Thai => "Thai",
+        Georgian => "Georgian",
+        Armenian => "Armenian",
+        Mongolian => "Mongolian",
+        Tibetan => "Tibetan",
+        Ethiopic => "Ge'ez",
+        Burmese => "Burmese",
+        Khmer => "Khmer",
+    }
+}
+
+open_enum! {
+    /// A calendar in which the year of an issue may be expressed.
+    pub enum Calendar {
+        Gregorian => "Gregorian",
+        Julian => "Julian",
+        Islamic => "Islamic",
+        SolarHijri => "Solar Hijri",
+        Hebrew => "Hebrew",
+        Buddhist => "Buddhist",
+        Japanese => "Japanese",
+        Chinese => "Chinese",
+        Minguo => "Minguo",
+        Ethiopian => "Ethiopian",
+        VikramSamvat => "Vikram Samvat",
+        Saka => "Saka",
+    }
+}
+
+/// The relative orientation (die axis) of the obverse and reverse of an item.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(from = "String", into = "String")]
+#[non_exhaustive]
+pub enum Orientation {
+    /// Coin alignment, with the reverse upside down (6 o'clock, 180°).
+    Coin,
+    /// Medal alignment, with both sides upright (12 o'clock, 0°).
+    Medal,
+    /// The alignment varies from one item to another.
+    Variable,
+    /// The reverse is rotated a quarter turn clockwise (3 o'clock, 90°).
+    Three,
+    /// The reverse is rotated a quarter turn anticlockwise (9 o'clock, 270°).
+    Nine,
+    /// An orientation returned by the API that this crate does not know about.
+    Other(String),
+}
+
+impl Orientation {
+    /// Creates an orientation from a clock-hour axis, as used by
+    /// [`CollectedItem::axis`](super::CollectedItem::axis).
+    ///
+    /// Axes go from 1 to 12, with `12` for medal alignment, as checked by
+    /// the [`validation`](crate::validation) of requests. Returns `None` for
+    /// other axes, and for those that do not correspond to a named
+    /// orientation.
+    pub fn from_axis(axis: i64) -> Option<Orientation> {
+        match axis {
+            12 => Some(Orientation::Medal),
+            3 => Some(Orientation::Three),
+            6 => Some(Orientation::Coin),
+            9 => Some(Orientation::Nine),
+            _ => None,
+        }
+    }
+
+    /// Creates an orientation from a rotation in degrees, rounded to the
+    /// nearest clock hour. Returns `None` if the rotation is not a finite
+    /// number.
+    pub fn from_degrees(degrees: f64) -> Option<Orientation> {
+        axis_from_degrees(degrees).and_then(Orientation::from_axis)
+    }
+
+    /// Returns the clock-hour axis of the orientation, from 1 to 12.
+    ///
+    /// Returns `None` for [`Orientation::Variable`] and [`Orientation::Other`].
+    pub fn to_axis(&self) -> Option<i64> {
+        match self {
+            Orientation::Medal => Some(12),
+            Orientation::Three => Some(3),
+            Orientation::Coin => Some(6),
+            Orientation::Nine => Some(9),
+            Orientation::Variable | Orientation::Other(_) => None,
+        }
+    }
+
+    /// Returns the clockwise rotation of the reverse in degrees, from 0 to 270.
+    ///
+    /// Returns `None` for [`Orientation::Variable`] and [`Orientation::Other`].
+    pub fn to_degrees(&self) -> Option<u16> {
+        self.to_axis().and_then(axis_to_degrees)
+    }
+
+    /// Returns the name of the orientation, as used by the API.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Orientation::Coin => "coin",
+            Orientation::Medal => "medal",
+            Orientation::Variable => "variable",
+            Orientation::Three => "three",
+            Orientation::Nine => "nine",
+            Orientation::Other(s) => s,
+        }
+    }
+}
+
+impl From<String> for Orientation {
+    fn from(s: String) -> Self {
+        match s.to_ascii_lowercase().as_str() {
+            "coin" => Orientation::Coin,
+            "medal" => Orientation::Medal,
+            "variable" => Orientation::Variable,
+            "three" => Orientation::Three,
+            "nine" => Orientation::Nine,
+            _ => Orientation::Other(s),
+        }
+    }
+}
+
+impl From<Orientation> for String {
+    fn from(orientation: Orientation) -> Self {
+        match orientation {
+            Orientation::Other(s) => s,
+            known => known.as_str().to_string(),
+        }
+    }
+}
+
+/// Converts a rotation in degrees into a clock-hour axis, from 1 to 12,
+/// rounded to the nearest hour. Medal alignment (0°) is 12. Returns `None`
+/// if the rotation is not a finite number.
+pub(crate) fn axis_from_degrees(degrees: f64) -> Option<i64> {
+    if !degrees.is_finite() {
+        return None;
+    }
+    match (degrees.rem_euclid(360.0) / 30.0).round() as i64 % 12 {
+        0 => Some(12),
+        hours => Some(hours),
+    }
+}
+
+/// Converts a clock-hour axis into a rotation in degrees, from 0 to 330.
+/// Returns `None` if the axis is not between 1 and 12.
+pub(crate) fn axis_to_degrees(axis: i64) -> Option<u16> {
+    (1..=MAX_AXIS)
+        .contains(&axis)
+        .then(|| (axis % 12) as u16 * 30)
+}
+
+impl fmt::Display for Orientation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Orientation::Coin => write!(f, "Coin"),
+            Orientation::Medal => write!(f, "Medal"),
+            Orientation::Variable => write!(f, "Variable"),
+            Orientation::Three => write!(f, "3 o'clock"),
+            Orientation::Nine => write!(f, "9 o'clock"),
+            Orientation::Other(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl FromStr for Orientation {
+    type Err = std::convert::Infallible;
+
+    /// Parses an orientation from its name, as used by the API or displayed,
+    /// or from a clock-hour axis, ignoring case, e.g. "coin", "3 o'clock" or
+    /// "6". Other values give [`Orientation::Other`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hours = s.trim().trim_end_matches(" o'clock");
+        if let Some(orientation) = hours.parse().ok().and_then(Orientation::from_axis) {
+            return Ok(orientation);
+        }
+        Ok(Orientation::from(s.trim().to_string()))
+    }
+}
+
+#[cfg(feature = "clap")]
+value_enum!(
+    Orientation,
+    [
+        Orientation::Coin,
+        Orientation::Medal,
+        Orientation::Variable,
+        Orientation::Three,
+        Orientation::Nine,
+    ]
+);
+
+/// The grade (state of preservation) of an item.
+///
+/// Grades are ordered from the lowest to the highest, so they can be used
+/// directly to sort or filter by grade. Intermediate grades such as "F/VF" sit
+/// between the two grades they span, and grades not known to this crate sort
+/// below all others.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(from = "String", into = "String")]
+#[non_exhaustive]
+pub enum Grade {
+    G,
+    GVg,
+    Vg,
+    VgF,
+    F,
+    FVf,
+    Vf,
+    VfXf,
+    Xf,
+    XfAu,
+    Au,
+    AuUnc,
+    Unc,
+    /// A grade returned by the API that this crate does not know about.
+    Other(String),
+}
+
+impl Grade {
+    /// All grades, from the lowest to the highest.
+    pub const ALL: [Grade; 13] = [
+        Grade::G,
+        Grade::GVg,
+        Grade::Vg,
+        Grade::VgF,
+        Grade::F,
+        Grade::FVf,
+        Grade::Vf,
+        Grade::VfXf,
+        Grade::Xf,
+        Grade::XfAu,
+        Grade::Au,
+        Grade::AuUnc,
+        Grade::Unc,
+    ];
+
+    /// Returns the numeric rank of the grade, from 1 (Good) to 13 (Uncirculated),
+    /// or 0 for a grade not known to this crate.
+    pub fn rank(&self) -> u8 {
+        Grade::ALL
+            .iter()
+            .position(|grade| grade == self)
+            .map_or(0, |i| i as u8 + 1)
+    }
+
+    /// Returns the name of the grade, as used by the API, e.g. "vf" or "f/vf".
+    pub fn as_str(&self) -> &str {
+        match self {
+            Grade::G => "g",
+            Grade::GVg => "g/vg",
+            Grade::Vg => "vg",
+            Grade::VgF => "vg/f",
+            Grade::F => "f",
+            Grade::FVf => "f/vf",
+            Grade::Vf => "vf",
+            Grade::VfXf => "vf/xf",
+            Grade::Xf => "xf",
+            Grade::XfAu => "xf/au",
+            Grade::Au => "au",
+            Grade::AuUnc => "au/unc",
+            Grade::Unc => "unc",
+            Grade::Other(s) => s,
+        }
+    }
+
+    /// Returns the abbreviation of the grade, e.g. "VF" or "F/VF".
+    ///
+    /// A grade not known to this crate is returned as the API named it.
+    pub fn abbreviation(&self) -> &str {
+        match self {
+            Grade::G => "G",
+            Grade::GVg => "G/VG",
+            Grade::Vg => "VG",
+            Grade::VgF => "VG/F",
+            Grade::F => "F",
+            Grade::FVf => "F/VF",
+            Grade::Vf => "VF",
+            Grade::VfXf => "VF/XF",
+            Grade::Xf => "XF",
+            Grade::XfAu => "XF/AU",
+            Grade::Au => "AU",
+            Grade::AuUnc => "AU/UNC",
+            Grade::Unc => "UNC",
+            Grade::Other(s) => s,
+        }
+    }
+
+    /// Returns the full name of the grade, e.g. "Very Fine" or "Fine/Very Fine".
+    ///
+    /// A grade not known to this crate is returned as the API named it.
+    pub fn full_name(&self) -> &str {
+        match self {
+            Grade::G => "Good",
+            Grade::GVg => "Good/Very Good",
+            Grade::Vg => "Very Good",
+            Grade::VgF => "Very Good/Fine",
+            Grade::F => "Fine",
+            Grade::FVf => "Fine/Very Fine",
+            Grade::Vf => "Very Fine",
+            Grade::VfXf => "Very Fine/Extremely Fine",
+            Grade::Xf => "Extremely Fine",
+            Grade::XfAu => "Extremely Fine/About Uncirculated",
+            Grade::Au => "About Uncirculated",
+            Grade::AuUnc => "About Uncirculated/Uncirculated",
+            Grade::Unc => "Uncirculated",
+            Grade::Other(s) => s,
+        }
+    }
+
+    fn from_single(s: &str) -> Option<Grade> {
+        match s.trim().to_lowercase().as_str() {
+            "g" | "good" => Some(Grade::G),
+            "vg" | "very good" => Some(Grade::Vg),
+            "f" | "fine" => Some(Grade::F),
+            "vf" | "very fine" => Some(Grade::Vf),
+            "xf" | "ef" | "extremely fine" => Some(Grade::Xf),
+            "au" | "about uncirculated" => Some(Grade::Au),
+            "unc" | "uncirculated" => Some(Grade::Unc),
+            _ => None,
+        }
+    }
+}
+
+impl PartialOrd for Grade {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Grade {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.rank(), self.as_str()).cmp(&(other.rank(), other.as_str()))
+    }
+}
+
+impl From<String> for Grade {
+    fn from(s: String) -> Self {
+        Grade::ALL
+            .iter()
+            .find(|grade| grade.as_str().eq_ignore_ascii_case(&s))
+            .cloned()
+            .unwrap_or(Grade::Other(s))
+    }
+}
+
+impl From<Grade> for String {
+    fn from(grade: Grade) -> Self {
+        match grade {
+            Grade::Other(s) => s,
+            known => known.as_str().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Grade {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.abbreviation())
+    }
+}
+
+/// An error returned when parsing a [`Grade`] from a string fails.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("unknown grade: {0}")]
+pub struct ParseGradeError(String);
+
+impl FromStr for Grade {
+    type Err = ParseGradeError;
+
+    /// Parses a grade from its abbreviation or full name, ignoring case.
+    ///
+    /// Intermediate grades may be written with either a `/` or a `-` between
+    /// two adjacent grades, e.g. "F/VF" or "Fine-Very Fine".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseGradeError(s.to_string());
+        if let Some(grade) = Grade::from_single(s) {
+            return Ok(grade);
+        }
+
+        let (low, high) = s.split_once(['/', '-']).ok_or_else(err)?;
+        let low = Grade::from_single(low).ok_or_else(err)?;
+        let high = Grade::from_single(high).ok_or_else(err)?;
+        Grade::ALL
+            .iter()
+            .find(|g| g.rank() == low.rank() + 1 && high.rank() == low.rank() + 2)
+            .cloned()
+            .ok_or_else(err)
+    }
+}
+
+/// The grades are given by their names in the API, e.g. `vf` or `f/vf`, with
+/// their full names as aliases, e.g. `Very Fine`.
+#[cfg(feature = "clap")]
+impl clap::ValueEnum for Grade {
+    fn value_variants<'a>() -> &'a [Self] {
+        static GRADES: [Grade; 13] = Grade::ALL;
+        &GRADES
+    }
+
+    fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
+        let known = Self::value_variants().iter().find(|grade| *grade == self)?;
+        Some(clap::builder::PossibleValue::new(known.as_str()).alias(known.full_name()))
+    }
+}
+
+open_enum! {
+    /// The type of a publication of the bibliography.
+    pub enum PublicationType {
+        Volume => "volume",
+        Article => "article",
+        VolumeGroup => "volume_group",
+        ArticleGroup => "article_group",
+    }
+}
+
+open_enum! {
+    /// The binding of a volume.
+    pub enum Cover {
+        Softcover => "softcover",
+        Hardcover => "hardcover",
+        Spiral => "spiral",
+        HiddenSpiral => "hidden_spiral",
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum GrantType {
+    AuthorizationCode,
+    ClientCredentials,
+}
+
+impl GrantType {
+    /// Returns the name of the grant type, as used by the API, e.g.
+    /// "client_credentials".
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GrantType::AuthorizationCode => "authorization_code",
+            GrantType::ClientCredentials => "client_credentials",
+        }
+    }
+}
+
+impl fmt::Display for GrantType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// An error returned when parsing a [`GrantType`] from a string fails.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("unknown grant type: {0}")]
+pub struct ParseGrantTypeError(String);
+
+impl FromStr for GrantType {
+    type Err = ParseGrantTypeError;
+
+    /// Parses a grant type from its name, ignoring case and accepting `-` for
+    /// `_`, e.g. "client-credentials".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "authorization_code" => Ok(GrantType::AuthorizationCode),
+            "client_credentials" => Ok(GrantType::ClientCredentials),
+            _ => Err(ParseGrantTypeError(s.to_string())),
+        }
+    }
+}
+
+#[cfg(feature = "clap")]
+value_enum!(
+    GrantType,
+    [GrantType::AuthorizationCode, GrantType::ClientCredentials]
+);
+
+/// A date which may only be known to the year or to the month, such as
+/// "1990", "1990-06", or "1990-06-15". Years before the common era are
+/// negative, e.g. "-0044-03-15".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum PartialDate {
+    /// Only the year is known.
+    Year(i32),
+    /// Only the year and month are known.
+    YearMonth(i32, u32),
+    /// The full date is known.
+    Date(NaiveDate),
+}
+
+impl PartialDate {
+    /// Returns the year of the date.
+    pub fn year(&self) -> i32 {
+        match self {
+            PartialDate::Year(year) | PartialDate::YearMonth(year, _) => *year,
+            PartialDate::Date(date) => chrono::Datelike::year(date),
+        }
+    }
+
+    /// Returns the month of the date, from 1 to 12, if known.
+    pub fn month(&self) -> Option<u32> {
+        match self {
+            PartialDate::Year(_) => None,
+            PartialDate::YearMonth(_, month) => Some(*month),
+            PartialDate::Date(date) => Some(chrono::Datelike::month(date)),
+        }
+    }
+
+    /// Returns the day of the month, from 1 to 31, if known.
+    pub fn day(&self) -> Option<u32> {
+        match self {
+            PartialDate::Date(date) => Some(chrono::Datelike::day(date)),
+            _ => None,
+        }
+    }
+
+    /// Returns the full date, if known.
+    pub fn as_date(&self) -> Option<NaiveDate> {
+        match self {
+            PartialDate::Date(date) => Some(*date),
+            _ => None,
+        }
+    }
+
+    /// Returns the earliest full date matching this date, e.g. January 1st
+    /// when only the year is known.
+    pub fn first_day(&self) -> Option<NaiveDate> {
+        match self {
+            PartialDate::Year(year) => NaiveDate::from_ymd_opt(*year, 1, 1),
+            PartialDate::YearMonth(year, month) => NaiveDate::from_ymd_opt(*year, *month, 1),
+            PartialDate::Date(date) => Some(*date),
+        }
+    }
+}
+
+impl fmt::Display for PartialDate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Four digits after the sign, as for the years of full dates.
+        let year = |year: i32| match year {
+            ..=-1 => format!("-{:04}", year.unsigned_abs()),
+            _ => format!("{:04}", year),
+        };
+        match self {
+            PartialDate::Year(y) => write!(f, "{}", year(*y)),
+            PartialDate::YearMonth(y, month) => write!(f, "{}-{:02}", year(*y), month),
+            PartialDate::Date(date) => write!(f, "{}", date.format("%Y-%m-%d")),
+        }
+    }
+}
+
+/// An error returned when parsing a [`PartialDate`] from a string fails.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid partial date: {0}")]
+pub struct ParsePartialDateError(String);
+
+impl FromStr for PartialDate {
+    type Err = ParsePartialDateError;
+
+    /// Parses a date in the `YYYY`, `YYYY-MM`, or `YYYY-MM-DD` format. Years
+    /// before the common era have a leading `-`, as written by `Display`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParsePartialDateError(s.to_string());
+        let s = s.trim();
+        let (sign, unsigned) = match s.strip_prefix('-') {
+            Some(unsigned) => (-1, unsigned),
+            None => (1, s),
+        };
+        let mut parts = unsigned.split('-');
+        let year: i32 = parts
+            .next()
+            .and_then(|year| year.parse().ok())
+            .ok_or_else(err)?;
+        let year = sign * year;
+        let rest: Vec<u32> = parts
+            .map(|part| part.parse().map_err(|_| err()))
+            .collect::<Result<_, _>>()?;
+        match rest.as_slice() {
+            [] => Ok(PartialDate::Year(year)),
+            [month] if (1..=12).contains(month) => Ok(PartialDate::YearMonth(year, *month)),
+            [month, day] => NaiveDate::from_ymd_opt(year, *month, *day)
+                .map(PartialDate::Date)
+                .ok_or_else(err),
+            _ => Err(err()),
+        }
+    }
+}
+
+impl TryFrom<String> for PartialDate {
+    type Error = ParsePartialDateError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<PartialDate> for String {
+    fn from(date: PartialDate) -> Self {
+        date.to_string()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+#[serde(from = "String", into = "String")]
+#[non_exhaustive]
+pub enum Category {
+    Coin,
+    Banknote,
+    Exonumia,
+    /// A category returned by the API that this crate does not know about.
+    Other(String),
+}
+
+impl Category {
+    /// Returns the name of the category, as used by the API, e.g. "coin".
+    pub fn as_str(&self) -> &str {
+        match self {
+            Category::Coin => "coin",
+            Category::Banknote => "banknote",
+            Category::Exonumia => "exonumia",
+            Category::Other(s) => s,
+        }
+    }
+}
+
+impl From<String> for Category {
+    fn from(s: String) -> Self {
+        match s.to_ascii_lowercase().as_str() {
+            "coin" => Category::Coin,
+            "banknote" => Category::Banknote,
+            "exonumia" => Category::Exonumia,
+            _ => Category::Other(s),
+        }
+    }
+}
+
+impl From<Category> for String {
+    fn from(category: Category) -> Self {
+        match category {
+            Category::Other(s) => s,
+            known => known.as_str().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Category::Coin => write!(f, "Coin"),
+            Category::Banknote => write!(f, "Banknote"),
+            Category::Exonumia => write!(f, "Exonumia"),
+            Category::Other(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl FromStr for Category {
+    type Err = std::convert::Infallible;
+
+    /// Parses a category from its name, ignoring case. Other names give
+    /// [`Category::Other`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Category::from(s.trim().to_string()))
+    }
+}
+
+#[cfg(feature = "clap")]
+value_enum!(
+    Category,
+    [Category::Coin, Category::Banknote, Category::Exonumia]
+);
@@ -0,0 +1,725 @@
+//! Models generated from the OpenAPI document of the Numista API.
+//!
+//! @generated by `cargo xtask codegen`. Do not edit by hand: edit the
+//! handwritten models wrapping these instead, or regenerate this file.
+#![allow(clippy::all)]
+use serde::{Deserialize, Serialize};
+
+/// A catalogue, as referenced by a reference.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Catalogue {
+    pub code: String,
+    pub id: i64,
+}
+
+/// A catalogue, as listed by `/catalogues`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CatalogueDetail {
+    pub author: String,
+    pub code: String,
+    pub id: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub isbn13: Option<String>,
+    pub publisher: String,
+    pub title: String,
+}
+
+/// The catalogues listed by `/catalogues`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CataloguesResponse {
+    pub catalogues: Vec<CatalogueDetail>,
+    pub count: i64,
+}
+
+/// The category of a type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum Category {
+    #[serde(rename = "coin")]
+    Coin,
+    #[serde(rename = "banknote")]
+    Banknote,
+    #[serde(rename = "exonumia")]
+    Exonumia,
+}
+
+/// A side of a type, or its edge or watermark.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CoinSide {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub designers: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub engravers: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lettering: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lettering_scripts: Option<Vec<LetteringScript>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lettering_translation: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub picture: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub picture_copyright: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub picture_copyright_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub picture_license_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub picture_license_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unabridged_legend: Option<String>,
+}
+
+/// An item of a collection.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CollectedItem {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acquisition_date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acquisition_place: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub axis: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collection: Option<Collection>,
+    pub for_swap: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grade: Option<Grade>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grading_details: Option<GradingDetails>,
+    pub id: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub internal_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issue: Option<Issue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pictures: Option<Vec<Picture>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price: Option<ItemPrice>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub private_comment: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_comment: Option<String>,
+    pub quantity: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub serial_number: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage_location: Option<String>,
+    #[serde(rename = "type")]
+    pub type_: CollectedItemType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<f64>,
+}
+
+/// The type of a collected item.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CollectedItemType {
+    pub category: Category,
+    pub id: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<Issuer>,
+    pub title: String,
+}
+
+/// The collected items of a user.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CollectedItems {
+    pub item_count: i64,
+    pub item_for_swap_count: i64,
+    pub item_type_count: i64,
+    pub item_type_for_swap_count: i64,
+    pub items: Vec<CollectedItem>,
+}
+
+/// A collection of a user.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Collection {
+    pub id: i64,
+    pub name: String,
+}
+
+/// The collections of a user.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CollectionsResponse {
+    pub collections: Vec<Collection>,
+    pub count: i64,
+}
+
+/// The composition of a type.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Composition {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+/// A contributor to a publication.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Contributor {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
+    pub name: String,
+    pub role: String,
+}
+
+/// A currency.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Currency {
+    pub full_name: String,
+    pub id: i64,
+    pub name: String,
+}
+
+/// Whether a type is demonetized.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Demonetization {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub demonetization_date: Option<String>,
+    pub is_demonetized: bool,
+}
+
+/// A grade of preservation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum Grade {
+    #[serde(rename = "g")]
+    G,
+    #[serde(rename = "g/vg")]
+    GVg,
+    #[serde(rename = "vg")]
+    Vg,
+    #[serde(rename = "vg/f")]
+    VgF,
+    #[serde(rename = "f")]
+    F,
+    #[serde(rename = "f/vf")]
+    FVf,
+    #[serde(rename = "vf")]
+    Vf,
+    #[serde(rename = "vf/xf")]
+    VfXf,
+    #[serde(rename = "xf")]
+    Xf,
+    #[serde(rename = "xf/au")]
+    XfAu,
+    #[serde(rename = "au")]
+    Au,
+    #[serde(rename = "au/unc")]
+    AuUnc,
+    #[serde(rename = "unc")]
+    Unc,
+}
+
+/// The estimated price of an issue in a grade.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct GradePrice {
+    pub grade: Grade,
+    pub price: f64,
+}
+
+/// The estimated prices of an issue.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct GradePrices {
+    pub currency: String,
+    pub prices: Vec<GradePrice>,
+}
+
+/// A grading company.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct GradingCompany {
+    pub id: i64,
+    pub name: String,
+}
+
+/// A designation given by a grading company.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct GradingDesignation {
+    pub id: i64,
+    pub value: String,
+}
+
+/// The grading of a collected item by a grading company.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct GradingDetails {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cac_sticker: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grading_company: Option<GradingCompany>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grading_designations: Option<Vec<GradingDesignation>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grading_strike: Option<GradingStrike>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grading_surface: Option<GradingSurface>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slab_grade: Option<SlabGrade>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slab_number: Option<String>,
+}
+
+/// The strike noted by a grading company.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct GradingStrike {
+    pub id: i64,
+    pub value: String,
+}
+
+/// The surface noted by a grading company.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct GradingSurface {
+    pub id: i64,
+    pub value: String,
+}
+
+/// An issue of a type.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Issue {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calendar: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gregorian_year: Option<i64>,
+    pub id: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_dated: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub marks: Option<Vec<Mark>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_year: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_year: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mint_letter: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mintage: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub references: Option<Vec<Reference>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signatures: Option<Vec<Signature>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub year: Option<i64>,
+}
+
+/// An issuer, as referenced by other objects.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Issuer {
+    pub code: String,
+    pub name: String,
+}
+
+/// An issuer, as listed by `/issuers`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct IssuerDetail {
+    pub code: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<i64>,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Issuer>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wikidata_id: Option<String>,
+}
+
+/// The issuers listed by `/issuers`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct IssuersResponse {
+    pub count: i64,
+    pub issuers: Vec<IssuerDetail>,
+}
+
+/// An entity issuing a type.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct IssuingEntity {
+    pub id: i64,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wikidata_id: Option<String>,
+}
+
+/// The price paid for a collected item.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ItemPrice {
+    pub currency: String,
+    pub value: f64,
+}
+
+/// A script of the lettering of a side.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct LetteringScript {
+    pub name: String,
+}
+
+/// A mark on an issue, e.g. a mintmark.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Mark {
+    pub id: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub letters: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub picture: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+/// A mint, as referenced by a type.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Mint {
+    pub id: i64,
+    pub name: String,
+}
+
+/// A mint.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct MintDetail {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country: Option<Issuer>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_year: Option<i64>,
+    pub id: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nomisma_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub place: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_year: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wikidata_id: Option<String>,
+}
+
+/// The mints listed by `/mints`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct MintsResponse {
+    pub count: i64,
+    pub mints: Vec<MintDetail>,
+}
+
+/// An OAuth access token.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct OauthToken {
+    pub access_token: String,
+    pub expires_in: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    pub token_type: String,
+    pub user_id: i64,
+}
+
+/// A picture of a collected item.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Picture {
+    pub thumbnail_url: String,
+    pub url: String,
+}
+
+/// A printer of banknotes.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Printer {
+    pub id: i64,
+    pub name: String,
+}
+
+/// A publication of the bibliography.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Publication {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bibliographical_notice: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contributors: Option<Vec<Contributor>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cover: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub download_urls: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub edition: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub homepage_url: Option<String>,
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub isbn10: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub isbn13: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issn: Option<String>,
+    pub languages: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oclc_number: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_count: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pages: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part_of: Option<Vec<PublicationPart>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publication_places: Option<Vec<PublicationPlace>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publishers: Option<Vec<Publisher>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subtitle: Option<String>,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub translated_subtitle: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub translated_title: Option<String>,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume_number: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub year: Option<i64>,
+}
+
+/// A publication another is part of.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct PublicationPart {
+    pub id: String,
+    pub title: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume_number: Option<String>,
+}
+
+/// A place of publication.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct PublicationPlace {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geonames_id: Option<i64>,
+    pub name: String,
+}
+
+/// A publisher of a publication.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Publisher {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
+    pub name: String,
+}
+
+/// A reference of a type or issue in a catalogue.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Reference {
+    pub catalogue: Catalogue,
+    pub number: String,
+}
+
+/// A type related to another.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct RelatedType {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<Category>,
+    pub id: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<Issuer>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_year: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_year: Option<i64>,
+    pub title: String,
+}
+
+/// A ruler or other ruling authority.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct RulingAuthority {
+    pub id: i64,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nomisma_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wikidata_id: Option<String>,
+}
+
+/// The types found by `/search_by_image`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SearchByImageResponse {
+    pub count: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub experimental_tentative_grade: Option<Grade>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub experimental_tentative_year: Option<i64>,
+    pub types: Vec<SearchByImageTypeResult>,
+}
+
+/// A type found by `/search_by_image`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SearchByImageTypeResult {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<Category>,
+    pub id: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<Issuer>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_year: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_year: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub obverse_thumbnail: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reverse_thumbnail: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub similarity_distance: Option<f64>,
+    pub title: String,
+}
+
+/// A type found by `/types`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SearchTypeResult {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<Category>,
+    pub id: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<Issuer>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_year: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_year: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub obverse_thumbnail: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reverse_thumbnail: Option<String>,
+    pub title: String,
+}
+
+/// The types found by `/types`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SearchTypesResponse {
+    pub count: i64,
+    pub types: Vec<SearchTypeResult>,
+}
+
+/// A signature on an issue of a banknote.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Signature {
+    pub signer_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signer_title: Option<String>,
+}
+
+/// The grade written on a slab.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SlabGrade {
+    pub id: i64,
+    pub value: String,
+}
+
+/// The manufacturing technique of a type.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Technique {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum TypeOrientation {
+    #[serde(rename = "coin")]
+    Coin,
+    #[serde(rename = "medal")]
+    Medal,
+    #[serde(rename = "variable")]
+    Variable,
+    #[serde(rename = "three")]
+    Three,
+    #[serde(rename = "nine")]
+    Nine,
+}
+
+/// A type of the catalogue.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Type {
+    pub category: Category,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commemorated_topic: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comments: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub composition: Option<Composition>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub demonetization: Option<Demonetization>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub edge: Option<CoinSide>,
+    pub id: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<Issuer>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issuing_entity: Option<IssuingEntity>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_year: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_year: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mints: Option<Vec<Mint>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub obverse: Option<CoinSide>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub orientation: Option<TypeOrientation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub printers: Option<Vec<Printer>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub references: Option<Vec<Reference>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub related_types: Option<Vec<RelatedType>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reverse: Option<CoinSide>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ruler: Option<Vec<RulingAuthority>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secondary_issuing_entity: Option<IssuingEntity>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub series: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shape: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size2: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub technique: Option<Technique>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thickness: Option<f64>,
+    pub title: String,
+    #[serde(rename = "type")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub type_: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watermark: Option<CoinSide>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<f64>,
+}
+
+/// A user of Numista.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct User {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<String>,
+    pub username: String,
+}
+
+/// The face value of a type.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Value {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<Currency>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub denominator: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub numerator: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub numeric_value: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
@@ -0,0 +1,231 @@
+//! Standard identifiers of publications: ISBNs, ISSNs, and OCLC numbers.
+//!
+//! Identifiers are kept exactly as returned by the API, so that malformed
+//! values never cause a deserialization error. Use `is_valid` to verify the
+//! check digit, and `canonical` to get the form expected by library systems.
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Removes hyphens and spaces, and uppercases a trailing `x` check digit.
+fn compact(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| !matches!(c, '-' | ' '))
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// Returns the digits of `value`, allowing an `X` as its last character,
+/// which is returned as 10.
+fn digits(value: &str, len: usize) -> Option<Vec<u32>> {
+    if value.len() != len {
+        return None;
+    }
+    value
+        .chars()
+        .enumerate()
+        .map(|(i, c)| match c {
+            'X' if i == len - 1 => Some(10),
+            c => c.to_digit(10),
+        })
+        .collect()
+}
+
+macro_rules! identifier {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+        #[serde(transparent)]
+        pub struct $name(String);
+
+        impl $name {
+            /// Creates an identifier from a value, which is not validated.
+            pub fn new<S: Into<String>>(value: S) -> Self {
+                Self(value.into())
+            }
+
+            /// Returns the identifier as returned by the API.
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            /// Returns whether the identifier is well formed, including its
+            /// check digit if it has one.
+            pub fn is_valid(&self) -> bool {
+                self.canonical().is_some()
+            }
+        }
+
+        /// Formats the canonical form of the identifier, or the raw value if
+        /// it is not valid.
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self.canonical() {
+                    Some(canonical) => f.write_str(&canonical),
+                    None => f.write_str(&self.0),
+                }
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(value: String) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> Self {
+                Self(value.to_string())
+            }
+        }
+    };
+}
+
+identifier!(
+    /// A 10-digit International Standard Book Number.
+    Isbn10
+);
+
+identifier!(
+    /// A 13-digit International Standard Book Number.
+    Isbn13
+);
+
+identifier!(
+    /// An International Standard Serial Number.
+    Issn
+);
+
+identifier!(
+    /// A record number in the OCLC WorldCat database.
+    OclcNumber
+);
+
+impl Isbn10 {
+    /// Returns the ISBN without hyphens or spaces, e.g. `1787194949`, if it is
+    /// valid.
+    pub fn canonical(&self) -> Option<String> {
+        let value = compact(&self.0);
+        let digits = digits(&value, 10)?;
+        let sum: u32 = digits.iter().zip((1..=10).rev()).map(|(d, w)| d * w).sum();
+        sum.is_multiple_of(11).then_some(value)
+    }
+
+    /// Converts the ISBN to its 13-digit form, if it is valid.
+    pub fn to_isbn13(&self) -> Option<Isbn13> {
+        let value = self.canonical()?;
+        let body = format!("978{}", &value[..9]);
+        let sum: u32 = body
+            .chars()
+            .filter_map(|c| c.to_digit(10))
+            .zip([1, 3].into_iter().cycle())
+            .map(|(d, w)| d * w)
+            .sum();
+        Some(Isbn13(format!("{}{}", body, (10 - sum % 10) % 10)))
+    }
+}
+
+impl Isbn13 {
+    /// Returns the ISBN without hyphens or spaces, e.g. `9781787194946`, if it
+    /// is valid.
+    pub fn canonical(&self) -> Option<String> {
+        let value = compact(&self.0);
+        if !(value.starts_with("978") || value.starts_with("979")) {
+            return None;
+        }
+        let digits = digits(&value, 13)?;
+        if digits.contains(&10) {
+            return None;
+        }
+        let sum: u32 = digits
+            .iter()
+            .zip([1, 3].into_iter().cycle())
+            .map(|(d, w)| d * w)
+            .sum();
+        sum.is_multiple_of(10).then_some(value)
+    }
+}
+
+impl Issn {
+    /// Returns the ISSN as two groups of four characters, e.g. `0317-8471`, if
+    /// it is valid.
+    pub fn canonical(&self) -> Option<String> {
+        let value = compact(&self.0);
+        let digits = digits(&value, 8)?;
+        let sum: u32 = digits[..7]
+            .iter()
+            .zip((2..=8).rev())
+            .map(|(d, w)| d * w)
+            .sum();
+        if (11 - sum % 11) % 11 != digits[7] {
+            return None;
+        }
+        Some(format!("{}-{}", &value[..4], &value[4..]))
+    }
+}
+
+impl OclcNumber {
+    /// Returns the number without any `(OCoLC)`, `ocm`, `ocn`, or `on` prefix
+    /// or leading zeros, e.g. `1000342699`, if it is valid.
+    pub fn canonical(&self) -> Option<String> {
+        let value = self.0.trim();
+        let value = ["(OCoLC)", "ocm", "ocn", "on"]
+            .iter()
+            .find_map(|prefix| value.strip_prefix(prefix))
+            .unwrap_or(value)
+            .trim_start_matches('0');
+        (!value.is_empty() && value.chars().all(|c| c.is_ascii_digit())).then(|| value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_isbn10() {
+        assert_eq!(
+            Isbn10::new("1-78719-494-9").canonical().as_deref(),
+            Some("1787194949")
+        );
+        assert!(Isbn10::new("080442957x").is_valid());
+        assert!(!Isbn10::new("1787194948").is_valid());
+        assert!(!Isbn10::new("17871949").is_valid());
+        assert_eq!(
+            Isbn10::new("1787194949").to_isbn13(),
+            Some(Isbn13::new("9781787194946"))
+        );
+    }
+
+    #[test]
+    fn test_isbn13() {
+        let isbn = Isbn13::new("978-1-78719-494-6");
+        assert!(isbn.is_valid());
+        assert_eq!(isbn.to_string(), "9781787194946");
+        assert!(!Isbn13::new("9781787194947").is_valid());
+        assert!(!Isbn13::new("1231787194946").is_valid());
+        assert_eq!(Isbn13::new("not an isbn").to_string(), "not an isbn");
+    }
+
+    #[test]
+    fn test_issn() {
+        assert_eq!(
+            Issn::new("03178471").canonical().as_deref(),
+            Some("0317-8471")
+        );
+        assert!(Issn::new("2434-561x").is_valid());
+        assert!(!Issn::new("0317-8472").is_valid());
+    }
+
+    #[test]
+    fn test_oclc_number() {
+        for value in ["1000342699", "(OCoLC)1000342699", "ocm01000342699"] {
+            assert_eq!(
+                OclcNumber::new(value).canonical().as_deref(),
+                Some("1000342699")
+            );
+        }
+        assert!(!OclcNumber::new("ocn").is_valid());
+        assert!(!OclcNumber::new("12a4").is_valid());
+    }
+}
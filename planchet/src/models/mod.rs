@@ -0,0 +1,202 @@
+//! The models of the requests and responses of the API.
+//!
+//! The models are split by role, and all re-exported here, so that
+//! `planchet::models::NumistaType` works whichever module defines it:
+//!
+//! * [`request`]: the parameters of the endpoints, such as
+//!   [`SearchTypesParams`].
+//! * [`response`]: the objects returned by the endpoints, such as
+//!   [`NumistaType`] and [`SearchTypesResponse`].
+//! * [`common`]: the types used by both, such as [`Category`], [`Grade`] and
+//!   [`PartialDate`].
+//! * [`borrowed`]: borrowing variants of the largest responses.
+//! * [`identifier`]: validated identifiers of publications, such as
+//!   [`Isbn13`].
+//! * [`generated`]: plain models generated from the OpenAPI document of the
+//!   API by `cargo xtask codegen`, which the handwritten ones are tested
+//!   against. They are not re-exported here.
+//!
+//! # Migrating from `model`
+//!
+//! These models used to be in `planchet::model`, which is now a deprecated
+//! alias of this module and will be removed in a future release. No item was
+//! renamed, so migrating only takes replacing the path:
+//!
+//! ```diff
+//! -use planchet::model::{Grade, SearchTypesParams};
+//! +use planchet::models::{Grade, SearchTypesParams};
+//! ```
+//!
+//! Paths through submodules change the same way, e.g.
+//! `planchet::model::request::MimeType` becomes
+//! `planchet::models::request::MimeType`. Note that the compiler does not warn
+//! about paths through the old module, so searching for `model::` is the
+//! surest way to find them.
+pub mod borrowed;
+pub mod common;
+pub mod generated;
+pub mod identifier;
+pub mod request;
+pub mod response;
+
+pub use borrowed::*;
+pub use common::*;
+pub use identifier::*;
+pub use request::*;
+pub use response::*;
+
+#[cfg(test)]
+mod tests {
+    /// The handwritten models have the fields of the generated ones, so that
+    /// they do not drift from the schema of the API. Flattening the `extra`
+    /// fields of lenient models hides the fields of the structs.
+    #[cfg(any(not(feature = "lenient-models"), feature = "strict-models"))]
+    mod generated_fields {
+        use crate::models::generated;
+        use serde::de::{self, Deserialize, Deserializer, Visitor};
+        use std::collections::BTreeSet;
+
+        /// A deserializer recording the fields of the struct deserialized from
+        /// it, and deserializing nothing.
+        struct Fields<'a>(&'a mut &'static [&'static str]);
+
+        impl<'de> Deserializer<'de> for Fields<'_> {
+            type Error = de::value::Error;
+
+            fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+                Err(de::Error::custom("not a struct"))
+            }
+
+            fn deserialize_struct<V: Visitor<'de>>(
+                self,
+                _: &'static str,
+                fields: &'static [&'static str],
+                _: V,
+            ) -> Result<V::Value, Self::Error> {
+                *self.0 = fields;
+                Err(de::Error::custom("fields recorded"))
+            }
+
+            serde::forward_to_deserialize_any! {
+                bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes
+                byte_buf option unit unit_struct newtype_struct seq tuple tuple_struct map enum
+                identifier ignored_any
+            }
+        }
+
+        /// Returns the names of the fields of a struct, as deserialized.
+        fn fields<T: for<'de> Deserialize<'de>>() -> BTreeSet<&'static str> {
+            let mut fields: &'static [&'static str] = &[];
+            let _ = T::deserialize(Fields(&mut fields));
+            fields.iter().copied().collect()
+        }
+
+        #[test]
+        fn test_fields_of_generated_models() {
+            macro_rules! assert_same_fields {
+                ($($model:ident = $generated:ident),+ $(,)?) => {$(
+                    let expected = fields::<generated::$generated>();
+                    assert!(!expected.is_empty(), "{}", stringify!($generated));
+                    assert_eq!(
+                        fields::<crate::models::$model>(),
+                        expected,
+                        "{}",
+                        stringify!($model)
+                    );
+                )+};
+            }
+            assert_same_fields!(
+                Catalogue = Catalogue,
+                CatalogueDetail = CatalogueDetail,
+                CataloguesResponse = CataloguesResponse,
+                CoinSide = CoinSide,
+                CollectedItem = CollectedItem,
+                CollectedItemType = CollectedItemType,
+                CollectedItems = CollectedItems,
+                Collection = Collection,
+                CollectionsResponse = CollectionsResponse,
+                Composition = Composition,
+                Contributor = Contributor,
+                Currency = Currency,
+                Demonetization = Demonetization,
+                GradePrice = GradePrice,
+                GradePrices = GradePrices,
+                GradingCompany = GradingCompany,
+                GradingDesignation = GradingDesignation,
+                GradingDetails = GradingDetails,
+                GradingStrike = GradingStrike,
+                GradingSurface = GradingSurface,
+                Issue = Issue,
+                Issuer = Issuer,
+                IssuerDetail = IssuerDetail,
+                IssuersResponse = IssuersResponse,
+                IssuingEntity = IssuingEntity,
+                ItemPrice = ItemPrice,
+                LetteringScript = LetteringScript,
+                Mark = Mark,
+                Mint = Mint,
+                MintDetail = MintDetail,
+                MintsResponse = MintsResponse,
+                NumistaType = Type,
+                OAuthToken = OauthToken,
+                Picture = Picture,
+                Printer = Printer,
+                Publication = Publication,
+                PublicationPart = PublicationPart,
+                PublicationPlace = PublicationPlace,
+                Publisher = Publisher,
+                Reference = Reference,
+                RelatedType = RelatedType,
+                RulingAuthority = RulingAuthority,
+                SearchByImageResponse = SearchByImageResponse,
+                SearchByImageTypeResult = SearchByImageTypeResult,
+                SearchTypeResult = SearchTypeResult,
+                SearchTypesResponse = SearchTypesResponse,
+                Signature = Signature,
+                SlabGrade = SlabGrade,
+                Technique = Technique,
+                User = User,
+                Value = Value,
+            );
+        }
+    }
+
+    #[test]
+    fn test_edit_collected_item_params() {
+        use super::{EditCollectedItemParams, Grade, Orientation};
+        use serde_json::json;
+
+        // Set fields are sent, cleared ones are sent as null, and the others
+        // are left out, so that they are unchanged.
+        let params = EditCollectedItemParams::new()
+            .grade(Grade::Xf)
+            .storage_location("Album 3")
+            .clear_private_comment()
+            .clear_acquisition_date()
+            .orientation(Orientation::Coin);
+        assert_eq!(
+            serde_json::to_value(&params).unwrap(),
+            json!({
+                "grade": "xf",
+                "storage_location": "Album 3",
+                "private_comment": null,
+                "acquisition_date": null,
+                "axis": 6,
+            })
+        );
+
+        // The last call wins.
+        let params = EditCollectedItemParams::new()
+            .clear_serial_number()
+            .serial_number("AB 123")
+            .weight(5.into())
+            .clear_weight();
+        assert_eq!(
+            serde_json::to_value(&params).unwrap(),
+            json!({ "serial_number": "AB 123", "weight": null })
+        );
+
+        let params = EditCollectedItemParams::new();
+        assert_eq!(serde_json::to_value(&params).unwrap(), json!({}));
+    }
+}
@@ -1,6 +1,5 @@
-use crate::model::{Category, Grade, GrantType};
+use super::common::{open_enum, Category, Grade, GrantType, Number, Orientation};
 use chrono;
-use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 #[derive(Debug, Serialize)]
 pub struct OAuthTokenParams {
@@ -79,10 +78,13 @@ impl GetCollectedItemsParams {
     }
 }
 
+/// The setters shared by [`AddCollectedItemParams`] and
+/// [`EditCollectedItemParams`]. `$set` wraps the values of the fields that
+/// can be cleared when editing an item.
 macro_rules! impl_collected_item_common_setters {
-    () => {
+    ($set:path) => {
         pub fn issue(mut self, issue: i64) -> Self {
-            self.issue = Some(issue);
+            self.issue = $set(issue);
             self
         }
 
@@ -92,7 +94,7 @@ macro_rules! impl_collected_item_common_setters {
         }
 
         pub fn grade(mut self, grade: Grade) -> Self {
-            self.grade = Some(grade);
+            self.grade = $set(grade);
             self
         }
 
@@ -102,17 +104,17 @@ macro_rules! impl_collected_item_common_setters {
         }
 
         pub fn private_comment<S: Into<String>>(mut self, private_comment: S) -> Self {
-            self.private_comment = Some(private_comment.into());
+            self.private_comment = $set(private_comment.into());
             self
         }
 
         pub fn public_comment<S: Into<String>>(mut self, public_comment: S) -> Self {
-            self.public_comment = Some(public_comment.into());
+            self.public_comment = $set(public_comment.into());
             self
         }
 
         pub fn price(mut self, price: ItemPriceParams) -> Self {
-            self.price = Some(price);
+            self.price = $set(price);
             self
         }
 
@@ -122,52 +124,97 @@ macro_rules! impl_collected_item_common_setters {
         }
 
         pub fn storage_location<S: Into<String>>(mut self, storage_location: S) -> Self {
-            self.storage_location = Some(storage_location.into());
+            self.storage_location = $set(storage_location.into());
             self
         }
 
         pub fn acquisition_place<S: Into<String>>(mut self, acquisition_place: S) -> Self {
-            self.acquisition_place = Some(acquisition_place.into());
+            self.acquisition_place = $set(acquisition_place.into());
             self
         }
 
         pub fn acquisition_date(mut self, acquisition_date: chrono::NaiveDate) -> Self {
-            self.acquisition_date = Some(acquisition_date);
+            self.acquisition_date = $set(acquisition_date);
             self
         }
 
         pub fn serial_number<S: Into<String>>(mut self, serial_number: S) -> Self {
-            self.serial_number = Some(serial_number.into());
+            self.serial_number = $set(serial_number.into());
             self
         }
 
         pub fn internal_id<S: Into<String>>(mut self, internal_id: S) -> Self {
-            self.internal_id = Some(internal_id.into());
+            self.internal_id = $set(internal_id.into());
             self
         }
 
-        pub fn weight(mut self, weight: Decimal) -> Self {
-            self.weight = Some(weight);
+        pub fn weight(mut self, weight: Number) -> Self {
+            self.weight = $set(weight);
             self
         }
 
-        pub fn size(mut self, size: Decimal) -> Self {
-            self.size = Some(size);
+        pub fn size(mut self, size: Number) -> Self {
+            self.size = $set(size);
             self
         }
 
         pub fn axis(mut self, axis: i64) -> Self {
-            self.axis = Some(axis);
+            self.axis = $set(axis);
+            self
+        }
+
+        /// Sets the axis from a clockwise rotation of the reverse in
+        /// degrees, rounded to the nearest clock hour.
+        ///
+        /// # Errors
+        ///
+        /// Returns [`Error::Validation`](crate::Error::Validation) if the
+        /// rotation is not a finite number, e.g. NaN.
+        pub fn axis_degrees(mut self, degrees: f64) -> crate::Result<Self> {
+            let axis = crate::models::axis_from_degrees(degrees).ok_or_else(|| {
+                crate::Error::Validation(vec![crate::validation::ValidationIssue::new(
+                    "axis",
+                    format!("must be a finite number of degrees, got {}", degrees),
+                )])
+            })?;
+            self.axis = $set(axis);
+            Ok(self)
+        }
+
+        /// Sets the axis from a named orientation. [`Orientation::Variable`]
+        /// and [`Orientation::Other`] have no axis, and leave it unchanged.
+        pub fn orientation(mut self, orientation: Orientation) -> Self {
+            if let Some(axis) = orientation.to_axis() {
+                self.axis = $set(axis);
+            }
             self
         }
 
         pub fn grading_details(mut self, grading_details: GradingDetailsParams) -> Self {
-            self.grading_details = Some(grading_details);
+            self.grading_details = $set(grading_details);
             self
         }
     };
 }
 
+/// Sets a field of [`EditCollectedItemParams`] that can be cleared.
+fn set<T>(value: T) -> Option<Option<T>> {
+    Some(Some(value))
+}
+
+/// Generates a method clearing each of the given fields.
+macro_rules! impl_clear_setters {
+    ($($name:ident => $field:ident),* $(,)?) => {
+        $(
+            #[doc = concat!("Clears the `", stringify!($field), "` of the item.")]
+            pub fn $name(mut self) -> Self {
+                self.$field = Some(None);
+                self
+            }
+        )*
+    };
+}
+
 #[derive(Debug, Serialize)]
 pub struct AddCollectedItemParams {
     #[serde(rename = "type")]
@@ -185,8 +232,8 @@ pub struct AddCollectedItemParams {
     pub acquisition_date: Option<chrono::NaiveDate>,
     pub serial_number: Option<String>,
     pub internal_id: Option<String>,
-    pub weight: Option<Decimal>,
-    pub size: Option<Decimal>,
+    pub weight: Option<Number>,
+    pub size: Option<Number>,
     pub axis: Option<i64>,
     pub grading_details: Option<GradingDetailsParams>,
 }
@@ -215,30 +262,53 @@ impl AddCollectedItemParams {
         }
     }
 
-    impl_collected_item_common_setters!();
+    impl_collected_item_common_setters!(Some);
 }
 
+/// The fields to change on a collected item. Fields left as `None` are not
+/// sent, and so are left unchanged.
+///
+/// The fields an item may lack are `Option<Option<T>>`: `Some(None)` is sent
+/// as `null`, which clears the field, e.g. with
+/// [`clear_storage_location`](Self::clear_storage_location).
 #[derive(Debug, Default, Serialize)]
 pub struct EditCollectedItemParams {
-    #[serde(rename = "type")]
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
     pub type_id: Option<i64>,
-    pub issue: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issue: Option<Option<i64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub quantity: Option<i64>,
-    pub grade: Option<Grade>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grade: Option<Option<Grade>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub for_swap: Option<bool>,
-    pub private_comment: Option<String>,
-    pub public_comment: Option<String>,
-    pub price: Option<ItemPriceParams>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub private_comment: Option<Option<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_comment: Option<Option<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<Option<ItemPriceParams>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub collection: Option<i64>,
-    pub storage_location: Option<String>,
-    pub acquisition_place: Option<String>,
-    pub acquisition_date: Option<chrono::NaiveDate>,
-    pub serial_number: Option<String>,
-    pub internal_id: Option<String>,
-    pub weight: Option<Decimal>,
-    pub size: Option<Decimal>,
-    pub axis: Option<i64>,
-    pub grading_details: Option<GradingDetailsParams>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_location: Option<Option<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub acquisition_place: Option<Option<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub acquisition_date: Option<Option<chrono::NaiveDate>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serial_number: Option<Option<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub internal_id: Option<Option<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weight: Option<Option<Number>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<Option<Number>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub axis: Option<Option<i64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grading_details: Option<Option<GradingDetailsParams>>,
 }
 
 impl EditCollectedItemParams {
@@ -251,12 +321,29 @@ impl EditCollectedItemParams {
         self
     }
 
-    impl_collected_item_common_setters!();
+    impl_collected_item_common_setters!(set);
+
+    impl_clear_setters!(
+        clear_issue => issue,
+        clear_grade => grade,
+        clear_private_comment => private_comment,
+        clear_public_comment => public_comment,
+        clear_price => price,
+        clear_storage_location => storage_location,
+        clear_acquisition_place => acquisition_place,
+        clear_acquisition_date => acquisition_date,
+        clear_serial_number => serial_number,
+        clear_internal_id => internal_id,
+        clear_weight => weight,
+        clear_size => size,
+        clear_axis => axis,
+        clear_grading_details => grading_details,
+    );
 }
 
 #[derive(Debug, Serialize)]
 pub struct ItemPriceParams {
-    pub value: Decimal,
+    pub value: Number,
     pub currency: String,
 }
 
@@ -319,13 +406,12 @@ pub struct SearchByImageParams {
     pub max_results: Option<i64>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-#[serde(rename_all = "snake_case")]
-pub enum MimeType {
-    #[serde(rename = "image/jpeg")]
-    Jpeg,
-    #[serde(rename = "image/png")]
-    Png,
+open_enum! {
+    /// The media type of a picture searched by image.
+    pub enum MimeType {
+        Jpeg => "image/jpeg",
+        Png => "image/png",
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -338,19 +424,19 @@ pub struct Image {
 /// Parameters for searching for types.
 #[derive(Debug, Default, Serialize, Clone)]
 pub struct SearchTypesParams {
-    category: Option<Category>,
-    q: Option<String>,
-    issuer: Option<String>,
-    catalogue: Option<i64>,
-    number: Option<String>,
-    ruler: Option<i64>,
-    material: Option<i64>,
-    year: Option<String>,
-    date: Option<String>,
-    size: Option<String>,
-    weight: Option<String>,
-    page: Option<i64>,
-    count: Option<i64>,
+    pub(crate) category: Option<Category>,
+    pub(crate) q: Option<String>,
+    pub(crate) issuer: Option<String>,
+    pub(crate) catalogue: Option<i64>,
+    pub(crate) number: Option<String>,
+    pub(crate) ruler: Option<i64>,
+    pub(crate) material: Option<i64>,
+    pub(crate) year: Option<String>,
+    pub(crate) date: Option<String>,
+    pub(crate) size: Option<String>,
+    pub(crate) weight: Option<String>,
+    pub(crate) page: Option<i64>,
+    pub(crate) count: Option<i64>,
 }
 
 impl SearchTypesParams {
@@ -0,0 +1,962 @@
+//! The responses of the API.
+use crate::lang::SupportedLanguage;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use super::common::*;
+use super::identifier::{Isbn10, Isbn13, Issn, OclcNumber};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct Mark {
+    pub id: i64,
+    pub title: Option<String>,
+    pub picture: Option<Url>,
+    pub letters: Option<String>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct Signature {
+    pub signer_name: String,
+    pub signer_title: Option<String>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "decimal", derive(Eq))]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct GradePrice {
+    pub grade: Grade,
+    pub price: Number,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "decimal", derive(Eq))]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct ItemPrice {
+    pub value: Number,
+    pub currency: CurrencyCode,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "decimal", derive(Eq))]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct GradePrices {
+    pub currency: CurrencyCode,
+    pub prices: Vec<GradePrice>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct IssuerDetail {
+    pub code: String,
+    pub name: String,
+    pub flag: Option<Url>,
+    pub wikidata_id: Option<String>,
+    pub parent: Option<Issuer>,
+    pub level: Option<i8>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct MintDetail {
+    /// The ID of the mint. The API may return this as either a string or an
+    /// integer.
+    #[serde(deserialize_with = "crate::de::string_or_i64")]
+    pub id: i64,
+    pub name: Option<String>,
+    pub local_name: Option<String>,
+    pub place: Option<String>,
+    pub country: Option<Issuer>,
+    pub start_year: Option<i32>,
+    pub end_year: Option<i32>,
+    pub nomisma_id: Option<String>,
+    pub wikidata_id: Option<String>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct CatalogueDetail {
+    pub id: i64,
+    pub code: String,
+    pub title: String,
+    pub author: String,
+    pub publisher: String,
+    pub isbn13: Option<Isbn13>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct Issuer {
+    pub code: String,
+    pub name: String,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct Currency {
+    pub id: i64,
+    pub name: String,
+    pub full_name: String,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "decimal", derive(Eq))]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct Value {
+    pub text: Option<String>,
+    pub numeric_value: Option<Number>,
+    pub numerator: Option<i64>,
+    pub denominator: Option<i64>,
+    pub currency: Option<Currency>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct RulingAuthority {
+    pub id: i64,
+    pub name: String,
+    pub wikidata_id: Option<String>,
+    pub nomisma_id: Option<String>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct Composition {
+    pub text: Option<String>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct Technique {
+    pub text: Option<String>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct Demonetization {
+    #[serde(deserialize_with = "crate::de::bool_from_int")]
+    pub is_demonetized: bool,
+    pub demonetization_date: Option<PartialDate>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct LetteringScript {
+    pub name: Script,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct CoinSide {
+    pub engravers: Option<Vec<String>>,
+    pub designers: Option<Vec<String>>,
+    pub description: Option<String>,
+    pub lettering: Option<String>,
+    pub lettering_scripts: Option<Vec<LetteringScript>>,
+    pub unabridged_legend: Option<String>,
+    pub lettering_translation: Option<String>,
+    pub picture: Option<Url>,
+    pub thumbnail: Option<Url>,
+    pub picture_copyright: Option<String>,
+    pub picture_copyright_url: Option<Url>,
+    pub picture_license_name: Option<String>,
+    pub picture_license_url: Option<Url>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct Mint {
+    /// The ID of the mint. The API may return this as either a string or an
+    /// integer.
+    #[serde(deserialize_with = "crate::de::string_or_i64")]
+    pub id: i64,
+    pub name: String,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct Reference {
+    pub catalogue: Catalogue,
+    pub number: String,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct Catalogue {
+    pub id: i64,
+    pub code: String,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+/// The entity which issued a type, such as a central bank for banknotes or a
+/// company for exonumia.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct IssuingEntity {
+    pub id: i64,
+    pub name: String,
+    pub wikidata_id: Option<String>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "decimal", derive(Eq))]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct NumistaType {
+    pub id: i64,
+    pub url: Option<Url>,
+    pub title: String,
+    pub category: Category,
+    pub issuer: Option<Issuer>,
+    /// The entity which issued the type.
+    pub issuing_entity: Option<IssuingEntity>,
+    /// A second issuing entity, e.g. for banknotes issued jointly by two banks.
+    pub secondary_issuing_entity: Option<IssuingEntity>,
+    pub min_year: Option<i32>,
+    pub max_year: Option<i32>,
+    #[serde(rename = "type")]
+    pub type_name: Option<String>,
+    pub value: Option<Value>,
+    pub ruler: Option<Vec<RulingAuthority>>,
+    pub shape: Option<String>,
+    pub composition: Option<Composition>,
+    pub technique: Option<Technique>,
+    pub demonetization: Option<Demonetization>,
+    pub weight: Option<Number>,
+    pub size: Option<Number>,
+    pub size2: Option<Number>,
+    pub thickness: Option<Number>,
+    pub orientation: Option<Orientation>,
+    pub obverse: Option<CoinSide>,
+    pub reverse: Option<CoinSide>,
+    pub edge: Option<CoinSide>,
+    pub watermark: Option<CoinSide>,
+    pub mints: Option<Vec<Mint>>,
+    pub printers: Option<Vec<Printer>>,
+    pub series: Option<String>,
+    pub commemorated_topic: Option<String>,
+    /// HTML-formatted comments.
+    pub comments: Option<String>,
+    pub related_types: Option<Vec<RelatedType>>,
+    pub tags: Option<Vec<String>>,
+    pub references: Option<Vec<Reference>>,
+    /// The language of the translatable fields, set by the client.
+    #[serde(skip)]
+    pub(crate) lang: Option<SupportedLanguage>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+impl NumistaType {
+    /// Returns the language of the translatable fields, e.g. the title, if
+    /// known.
+    ///
+    /// This is the language of the response when the type was fetched with
+    /// [`Client::get_type`](crate::Client::get_type), which is the one given
+    /// in its `Content-Language` header, or else the one requested, or else
+    /// English, the default of the API. It is `None` for types deserialized
+    /// otherwise. Caches of types in several languages should key them by
+    /// it, so as not to mix translations.
+    pub fn lang(&self) -> Option<SupportedLanguage> {
+        self.lang
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct Printer {
+    pub id: i64,
+    pub name: String,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct RelatedType {
+    pub id: i64,
+    pub title: String,
+    pub category: Option<Category>,
+    pub issuer: Option<Issuer>,
+    pub min_year: Option<i32>,
+    pub max_year: Option<i32>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct Issue {
+    pub id: i64,
+    pub is_dated: Option<bool>,
+    /// The year shown on the item, in the calendar given by `calendar`.
+    pub year: Option<i32>,
+    /// The calendar in which `year` is expressed, when it is not Gregorian.
+    pub calendar: Option<Calendar>,
+    /// The year converted to the Gregorian calendar.
+    pub gregorian_year: Option<i32>,
+    pub min_year: Option<i32>,
+    pub max_year: Option<i32>,
+    pub mint_letter: Option<String>,
+    pub mintage: Option<i64>,
+    pub comment: Option<String>,
+    pub marks: Option<Vec<Mark>>,
+    pub signatures: Option<Vec<Signature>>,
+    pub references: Option<Vec<Reference>>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+impl Issue {
+    /// Checks whether the issue matches the given criteria.
+    ///
+    /// # Arguments
+    ///
+    /// * `year` - The year on the item. This is compared against both the
+    ///   displayed and the gregorian year, and against the year range of
+    ///   undated issues.
+    /// * `mint_letter` - The mint letter on the item, compared ignoring case.
+    /// * `marks` - The IDs of marks which must all be present on the issue.
+    ///
+    /// Criteria which are `None` or empty match any issue.
+    pub fn matches(&self, year: Option<i32>, mint_letter: Option<&str>, marks: &[i64]) -> bool {
+        if let Some(y) = year {
+            let in_range = self.year.is_none()
+                && self.min_year.is_some_and(|min| min <= y)
+                && self.max_year.is_some_and(|max| y <= max);
+            if self.year != Some(y) && self.gregorian_year != Some(y) && !in_range {
+                return false;
+            }
+        }
+
+        if let Some(letter) = mint_letter {
+            match &self.mint_letter {
+                Some(l) if l.eq_ignore_ascii_case(letter) => {}
+                _ => return false,
+            }
+        }
+
+        let issue_marks = self.marks.as_deref().unwrap_or_default();
+        marks
+            .iter()
+            .all(|id| issue_marks.iter().any(|m| m.id == *id))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct SearchTypeResult {
+    pub id: i64,
+    pub title: String,
+    pub category: Option<Category>,
+    pub issuer: Option<Issuer>,
+    pub min_year: Option<i32>,
+    pub max_year: Option<i32>,
+    pub obverse_thumbnail: Option<Url>,
+    pub reverse_thumbnail: Option<Url>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct Publication {
+    pub id: String,
+    pub url: Url,
+    #[serde(rename = "type")]
+    pub type_name: PublicationType,
+    pub title: String,
+    pub translated_title: Option<String>,
+    pub volume_number: Option<String>,
+    pub subtitle: Option<String>,
+    pub translated_subtitle: Option<String>,
+    pub edition: Option<String>,
+    pub languages: Vec<LanguageCode>,
+    #[serde(deserialize_with = "crate::de::empty_string_as_none", default)]
+    pub year: Option<i32>,
+    pub page_count: Option<i64>,
+    pub pages: Option<String>,
+    pub cover: Option<Cover>,
+    pub isbn10: Option<Isbn10>,
+    pub isbn13: Option<Isbn13>,
+    pub issn: Option<Issn>,
+    pub oclc_number: Option<OclcNumber>,
+    pub contributors: Option<Vec<Contributor>>,
+    pub publishers: Option<Vec<Publisher>>,
+    pub publication_places: Option<Vec<PublicationPlace>>,
+    pub part_of: Option<Vec<PublicationPart>>,
+    /// HTML-formatted bibliographical notice.
+    pub bibliographical_notice: Option<String>,
+    pub homepage_url: Option<Url>,
+    pub download_urls: Option<Vec<Url>>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct Contributor {
+    pub role: String,
+    pub name: String,
+    #[serde(deserialize_with = "crate::de::empty_string_as_none", default)]
+    pub id: Option<i64>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct Publisher {
+    pub name: String,
+    #[serde(deserialize_with = "crate::de::empty_string_as_none", default)]
+    pub id: Option<i64>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct PublicationPlace {
+    pub name: String,
+    /// The GeoNames ID of the place. The API may return this as either a
+    /// string or an integer.
+    #[serde(deserialize_with = "crate::de::empty_string_as_none", default)]
+    pub geonames_id: Option<i64>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct PublicationPart {
+    #[serde(rename = "type")]
+    pub type_name: PublicationType,
+    pub id: String,
+    pub title: String,
+    pub volume_number: Option<String>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+/// A Numista user, as returned by [`Client::get_user`](crate::Client::get_user).
+///
+/// Version 3 of the API only exposes the username and avatar of a user.
+/// Profile details shown on the website, such as the registration date,
+/// location, and swap ratings, are not available through the API. With the
+/// `lenient-models` feature, any such fields added to the API later are kept
+/// in `extra`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct User {
+    /// The username.
+    pub username: String,
+    /// The URL of the user's avatar, if they have one.
+    pub avatar: Option<Url>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct Collection {
+    pub id: i64,
+    pub name: String,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "decimal", derive(Eq))]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct CollectedItem {
+    pub id: i64,
+    pub quantity: i64,
+    #[serde(rename = "type")]
+    pub type_info: CollectedItemType,
+    pub issue: Option<Issue>,
+    #[serde(deserialize_with = "crate::de::bool_from_int")]
+    pub for_swap: bool,
+    pub grade: Option<Grade>,
+    pub private_comment: Option<String>,
+    pub public_comment: Option<String>,
+    pub price: Option<ItemPrice>,
+    pub collection: Option<Collection>,
+    pub pictures: Option<Vec<Picture>>,
+    pub storage_location: Option<String>,
+    pub acquisition_place: Option<String>,
+    pub acquisition_date: Option<NaiveDate>,
+    pub serial_number: Option<String>,
+    pub internal_id: Option<String>,
+    pub weight: Option<Number>,
+    pub size: Option<Number>,
+    pub axis: Option<i64>,
+    pub grading_details: Option<GradingDetails>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+impl CollectedItem {
+    /// Returns the named orientation of the item's axis, if it has one.
+    pub fn orientation(&self) -> Option<Orientation> {
+        self.axis.and_then(Orientation::from_axis)
+    }
+
+    /// Returns the clockwise rotation of the reverse in degrees, from 0 to
+    /// 330, if the item has a valid axis.
+    pub fn axis_degrees(&self) -> Option<u16> {
+        self.axis.and_then(axis_to_degrees)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct CollectedItemType {
+    pub id: i64,
+    pub title: String,
+    pub category: Category,
+    pub issuer: Option<Issuer>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct Picture {
+    pub url: Url,
+    pub thumbnail_url: Url,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct GradingDetails {
+    pub grading_company: Option<GradingCompany>,
+    pub slab_grade: Option<SlabGrade>,
+    pub slab_number: Option<String>,
+    pub cac_sticker: Option<String>,
+    pub grading_designations: Option<Vec<GradingDesignation>>,
+    pub grading_strike: Option<GradingStrike>,
+    pub grading_surface: Option<GradingSurface>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct GradingCompany {
+    pub id: i64,
+    pub name: String,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct SlabGrade {
+    pub id: i64,
+    pub value: String,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct GradingDesignation {
+    pub id: i64,
+    pub value: String,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct GradingStrike {
+    pub id: i64,
+    pub value: String,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct GradingSurface {
+    pub id: i64,
+    pub value: String,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "decimal", derive(Eq))]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct CollectedItems {
+    pub item_count: i64,
+    pub item_for_swap_count: i64,
+    pub item_type_count: i64,
+    pub item_type_for_swap_count: i64,
+    pub items: Vec<CollectedItem>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct OAuthToken {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: i64,
+    pub user_id: i64,
+    /// The scopes granted, separated by commas or spaces, if the API lists
+    /// them.
+    #[serde(default)]
+    pub scope: Option<String>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "decimal", derive(Eq))]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct SearchByImageTypeResult {
+    pub id: i64,
+    pub title: String,
+    pub category: Option<Category>,
+    pub issuer: Option<Issuer>,
+    pub min_year: Option<i32>,
+    pub max_year: Option<i32>,
+    pub obverse_thumbnail: Option<Url>,
+    pub reverse_thumbnail: Option<Url>,
+    pub similarity_distance: Option<Number>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct IssuersResponse {
+    pub count: i64,
+    pub issuers: Vec<IssuerDetail>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct MintsResponse {
+    pub count: i64,
+    pub mints: Vec<MintDetail>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct CataloguesResponse {
+    pub count: i64,
+    pub catalogues: Vec<CatalogueDetail>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct SearchTypesResponse {
+    pub count: i64,
+    pub types: Vec<SearchTypeResult>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct CollectionsResponse {
+    pub count: i64,
+    pub collections: Vec<Collection>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "decimal", derive(Eq))]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[non_exhaustive]
+pub struct SearchByImageResponse {
+    pub count: i64,
+    pub types: Vec<SearchByImageTypeResult>,
+    pub experimental_tentative_year: Option<i64>,
+    pub experimental_tentative_grade: Option<Grade>,
+    /// Fields not recognized by this version of the crate.
+    #[cfg(feature = "lenient-models")]
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: Extra,
+}
+
+/// Implements `Hash` for models with an ID by hashing the ID only, which is
+/// consistent with their equality, so that they can be stored in sets.
+macro_rules! hash_by_id {
+    ($($name:ident),+ $(,)?) => {
+        $(
+            impl std::hash::Hash for $name {
+                fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                    self.id.hash(state);
+                }
+            }
+        )+
+    };
+}
+
+hash_by_id!(
+    NumistaType,
+    Issue,
+    SearchTypeResult,
+    SearchByImageTypeResult,
+    Publication,
+    Collection,
+    CollectedItem,
+    MintDetail,
+    CatalogueDetail,
+);
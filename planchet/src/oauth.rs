@@ -0,0 +1,367 @@
+//! The OAuth authorization code flow, through which users let an application
+//! access their collection.
+//!
+//! An [`Authorization`] builds the URL of the page of the Numista website
+//! where the user grants access, and which then redirects the browser to the
+//! `redirect_uri` of the application, with a code in its query.
+//! [`Authorization::code_from_redirect`] reads the code back from that URL,
+//! and [`Authorization::token_params`] gives the parameters exchanging it for
+//! a token with [`Client::get_oauth_token`]. A [`Token`] keeps the token with
+//! its expiry, e.g. to cache it between runs of a command line tool, and
+//! [`ClientBuilder::token`] makes a client use it, checking its [`Scope`]s
+//! before sending requests which need them.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use planchet::oauth::{Authorization, Token};
+//! use planchet::ClientBuilder;
+//! use url::Url;
+//!
+//! # async fn run(redirect: Url) -> planchet::Result<()> {
+//! let redirect_uri = Url::parse("http://localhost:8765/callback").unwrap();
+//! let authorization = Authorization::new("YOUR_CLIENT_ID", redirect_uri).scope("view_collection");
+//! println!("Open {}", authorization.url());
+//!
+//! // Once the browser is redirected to `redirect`:
+//! let code = authorization.code_from_redirect(&redirect)?;
+//! let client = ClientBuilder::new().api_key("YOUR_API_KEY").build()?;
+//! let params = authorization.token_params(&code).client_secret("YOUR_API_KEY");
+//! let token = Token::new(&client.get_oauth_token(&params).await?, authorization.scopes());
+//! println!("Authorized as user {} until {}", token.user_id, token.expires_at);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Client::get_oauth_token`]: crate::Client::get_oauth_token
+//! [`ClientBuilder::token`]: crate::ClientBuilder::token
+use crate::error::{Error, Result};
+use crate::models::{GrantType, OAuthToken, OAuthTokenParams};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use url::Url;
+
+/// The page of the website where users grant access, in each language.
+const AUTHORIZE_URL: &str = "https://{lang}.numista.com/api/oauth_authorize.php";
+
+/// The time before its expiry from which a token is considered expired, so
+/// that it does not expire during a command.
+const EXPIRY_MARGIN_SECONDS: i64 = 60;
+
+/// A permission users grant to applications over their collection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scope {
+    /// Reading the collection, even if it is not public.
+    ViewCollection,
+    /// Adding, editing, and deleting the items of the collection.
+    EditCollection,
+}
+
+impl Scope {
+    /// Returns the name of the scope in the API, e.g. `view_collection`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::ViewCollection => "view_collection",
+            Scope::EditCollection => "edit_collection",
+        }
+    }
+
+    /// Returns the scope of a name in the API, if it is a known one.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim() {
+            "view_collection" => Some(Scope::ViewCollection),
+            "edit_collection" => Some(Scope::EditCollection),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A request to a user for access to their collection.
+#[derive(Debug, Clone)]
+pub struct Authorization {
+    client_id: String,
+    redirect_uri: Url,
+    scopes: Vec<String>,
+    state: String,
+    lang: Option<String>,
+}
+
+impl Authorization {
+    /// Creates a request, with a random `state` protecting the redirect from
+    /// forgery.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - The client ID of the application, shown with its API
+    ///   key.
+    /// * `redirect_uri` - Where the browser is redirected once the user has
+    ///   granted access.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the operating system cannot provide random bytes for the
+    /// `state`.
+    pub fn new<S: Into<String>>(client_id: S, redirect_uri: Url) -> Self {
+        // 128 bits from the random number generator of the operating system.
+        let mut bytes = [0u8; 16];
+        getrandom::getrandom(&mut bytes)
+            .expect("the operating system has no random number generator");
+        let state = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        Self {
+            client_id: client_id.into(),
+            redirect_uri,
+            scopes: Vec::new(),
+            state,
+            lang: None,
+        }
+    }
+
+    /// Adds scopes to request, separated by commas, e.g.
+    /// `view_collection,edit_collection`.
+    pub fn scope(mut self, scope: &str) -> Self {
+        self.scopes.extend(
+            scope
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(String::from),
+        );
+        self
+    }
+
+    /// Sets the language of the authorization page (2-letter ISO code).
+    pub fn lang<S: Into<String>>(mut self, lang: S) -> Self {
+        self.lang = Some(lang.into());
+        self
+    }
+
+    /// Returns the scopes requested.
+    pub fn scopes(&self) -> &[String] {
+        &self.scopes
+    }
+
+    /// Returns the `state` sent with the request, and expected back in the
+    /// redirect.
+    pub fn state(&self) -> &str {
+        &self.state
+    }
+
+    /// Returns the URL of the page where the user grants access.
+    pub fn url(&self) -> Url {
+        let base = AUTHORIZE_URL.replace("{lang}", self.lang.as_deref().unwrap_or("en"));
+        let mut url = Url::parse(&base).expect("the authorization URL is valid");
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.client_id)
+            .append_pair("redirect_uri", self.redirect_uri.as_str())
+            .append_pair("scope", &self.scopes.join(","))
+            .append_pair("state", &self.state);
+        url
+    }
+
+    /// Reads the code from the URL the browser was redirected to.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::OAuth`] if the user denied access, or if the `state`
+    /// of the redirect is not the one sent, i.e. it was not caused by this
+    /// request.
+    pub fn code_from_redirect(&self, redirect: &Url) -> Result<String> {
+        let param = |name: &str| {
+            redirect
+                .query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+        };
+        if let Some(error) = param("error") {
+            let description = param("error_description").unwrap_or_default();
+            return Err(Error::OAuth(
+                format!("{} {}", error, description).trim().to_string(),
+            ));
+        }
+        if param("state").as_deref() != Some(self.state.as_str()) {
+            return Err(Error::OAuth(
+                "the state of the redirect does not match".to_string(),
+            ));
+        }
+        param("code").ok_or_else(|| Error::OAuth("the redirect has no code".to_string()))
+    }
+
+    /// Returns the parameters exchanging a code for a token. The API may also
+    /// require the API key as `client_secret`.
+    pub fn token_params(&self, code: &str) -> OAuthTokenParams {
+        OAuthTokenParams::new(GrantType::AuthorizationCode)
+            .code(code)
+            .client_id(self.client_id.as_str())
+            .redirect_uri(self.redirect_uri.as_str())
+    }
+}
+
+/// An access token, with the scopes it was granted and its expiry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Token {
+    /// The bearer token to send with requests.
+    pub access_token: String,
+    /// The ID of the user who granted access.
+    pub user_id: i64,
+    /// The scopes granted, e.g. `view_collection`.
+    pub scopes: Vec<String>,
+    /// When the token expires.
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Token {
+    /// Creates a token from the response of the API, expiring after the
+    /// number of seconds given in the response.
+    ///
+    /// Its scopes are those listed by the response, or else the `scopes`
+    /// requested.
+    pub fn new(token: &OAuthToken, scopes: &[String]) -> Self {
+        let scopes = match &token.scope {
+            Some(granted) => granted
+                .split([',', ' '])
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+                .collect(),
+            None => scopes.to_vec(),
+        };
+        Self {
+            access_token: token.access_token.clone(),
+            user_id: token.user_id,
+            scopes,
+            expires_at: Utc::now() + Duration::seconds(token.expires_in),
+        }
+    }
+
+    /// Returns the known scopes granted, leaving out any other.
+    pub fn granted_scopes(&self) -> Vec<Scope> {
+        self.scopes
+            .iter()
+            .filter_map(|s| Scope::from_name(s))
+            .collect()
+    }
+
+    /// Checks if the token has expired, or expires within a minute.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at - Duration::seconds(EXPIRY_MARGIN_SECONDS) <= Utc::now()
+    }
+
+    /// Checks if the token was granted every scope of a list separated by
+    /// commas.
+    pub fn has_scope(&self, scope: &str) -> bool {
+        scope
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .all(|s| self.scopes.iter().any(|granted| granted == s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn authorization() -> Authorization {
+        let redirect_uri = Url::parse("http://localhost:8765/callback").unwrap();
+        Authorization::new("abc", redirect_uri).scope("view_collection, edit_collection")
+    }
+
+    #[test]
+    fn test_authorization_url() {
+        let authorization = authorization().lang("fr");
+        let url = authorization.url();
+        assert_eq!(url.host_str(), Some("fr.numista.com"));
+        assert_eq!(url.path(), "/api/oauth_authorize.php");
+        let query: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+        assert_eq!(
+            query,
+            [
+                ("response_type", "code"),
+                ("client_id", "abc"),
+                ("redirect_uri", "http://localhost:8765/callback"),
+                ("scope", "view_collection,edit_collection"),
+                ("state", authorization.state()),
+            ]
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+        );
+        assert_eq!(authorization.state().len(), 32);
+        assert_ne!(authorization.state(), self::authorization().state());
+    }
+
+    #[test]
+    fn test_code_from_redirect() {
+        let authorization = authorization();
+        let redirect =
+            |query: &str| Url::parse(&format!("http://localhost:8765/callback?{}", query)).unwrap();
+
+        let ok = redirect(&format!("code=xyz&state={}", authorization.state()));
+        assert_eq!(authorization.code_from_redirect(&ok).unwrap(), "xyz");
+
+        let forged = redirect("code=xyz&state=other");
+        assert!(matches!(
+            authorization.code_from_redirect(&forged),
+            Err(Error::OAuth(_))
+        ));
+        let denied = redirect("error=access_denied&error_description=Denied+by+user");
+        match authorization.code_from_redirect(&denied) {
+            Err(Error::OAuth(message)) => assert_eq!(message, "access_denied Denied by user"),
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let params = authorization.token_params("xyz");
+        assert_eq!(params.code.as_deref(), Some("xyz"));
+        assert_eq!(params.client_id.as_deref(), Some("abc"));
+        assert_eq!(
+            params.redirect_uri.as_deref(),
+            Some("http://localhost:8765/callback")
+        );
+    }
+
+    #[test]
+    fn test_token_expiry_and_scopes() {
+        let response: OAuthToken = serde_json::from_value(json!({
+            "access_token": "t0k3n",
+            "token_type": "bearer",
+            "expires_in": 3600,
+            "user_id": 1
+        }))
+        .unwrap();
+        let mut token = Token::new(&response, authorization().scopes());
+        assert!(!token.is_expired());
+        assert!(token.has_scope("view_collection"));
+        assert!(token.has_scope("edit_collection,view_collection"));
+        assert!(!token.has_scope("view_collection,delete_account"));
+
+        token.expires_at = Utc::now() + Duration::seconds(30);
+        assert!(token.is_expired());
+    }
+
+    #[test]
+    fn test_scopes_of_response() {
+        let response: OAuthToken = serde_json::from_value(json!({
+            "access_token": "t0k3n",
+            "token_type": "bearer",
+            "expires_in": 3600,
+            "user_id": 1,
+            "scope": "view_collection delete_account"
+        }))
+        .unwrap();
+        let token = Token::new(&response, authorization().scopes());
+        assert!(!token.has_scope("edit_collection"));
+        assert!(token.has_scope("delete_account"));
+        assert_eq!(token.granted_scopes(), vec![Scope::ViewCollection]);
+        assert_eq!(
+            Scope::from_name("edit_collection"),
+            Some(Scope::EditCollection)
+        );
+        assert_eq!(Scope::EditCollection.to_string(), "edit_collection");
+    }
+}
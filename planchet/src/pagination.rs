@@ -0,0 +1,392 @@
+//! Paginated pulls of search results.
+//!
+//! [`Client::stream_types`] fetches every page of a search, like
+//! [`Client::stream_all_types`], with [`StreamOptions`] to harden long pulls
+//! and [`StreamStats`] to check what happened once the stream completes.
+//!
+//! Every pull is guarded against paginating forever: the stream ends with a
+//! [`PaginationError`] if the API returns the same page twice in a row, if
+//! the total number of results shrinks during the pull, or if more than
+//! [`StreamOptions::max_pages`] pages would be fetched.
+//!
+//! For searches small enough to hold in memory, [`Client::search_types_all`]
+//! collects the results into a `Vec`, up to a cap.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use futures::TryStreamExt;
+//! use planchet::models::SearchTypesParams;
+//! use planchet::pagination::StreamOptions;
+//! use planchet::ClientBuilder;
+//!
+//! # async fn run() -> planchet::Result<()> {
+//! let client = ClientBuilder::new().api_key("YOUR_API_KEY").build()?;
+//! let options = StreamOptions::new().deduplicate(true).page_retries(2);
+//!
+//! let mut stream = client.stream_types(SearchTypesParams::new().q("victoria"), options);
+//! let mut types = Vec::new();
+//! while let Some(t) = stream.try_next().await? {
+//!     types.push(t);
+//! }
+//! let stats = stream.stats();
+//! assert!(stats.completed);
+//! println!("{} duplicates skipped", stats.duplicates);
+//! # Ok(())
+//! # }
+//! ```
+use crate::error::{Error, Result};
+use crate::models::{SearchTypeResult, SearchTypesParams};
+use crate::Client;
+use futures::stream::{self, Stream, TryStreamExt};
+use std::collections::HashSet;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+/// The default delay before the first retry of a page.
+pub const DEFAULT_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Options for [`Client::stream_types`].
+#[derive(Debug, Clone)]
+pub struct StreamOptions {
+    deduplicate: bool,
+    max_pages: Option<i64>,
+    page_retries: u32,
+    retry_delay: Duration,
+}
+
+impl Default for StreamOptions {
+    fn default() -> Self {
+        Self {
+            deduplicate: false,
+            max_pages: None,
+            page_retries: 0,
+            retry_delay: DEFAULT_RETRY_DELAY,
+        }
+    }
+}
+
+impl StreamOptions {
+    /// Creates a new `StreamOptions` with the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether types already yielded are skipped. Defaults to `false`.
+    ///
+    /// The API can return the same type on two pages when the catalogue
+    /// changes during a pull. Skipped types are counted in
+    /// [`StreamStats::duplicates`].
+    pub fn deduplicate(mut self, deduplicate: bool) -> Self {
+        self.deduplicate = deduplicate;
+        self
+    }
+
+    /// Sets the maximum number of pages fetched. If more pages are needed,
+    /// the stream ends with [`PaginationError::MaxPagesReached`]. By default,
+    /// there is no limit.
+    pub fn max_pages(mut self, max_pages: i64) -> Self {
+        self.max_pages = Some(max_pages);
+        self
+    }
+
+    /// Sets how many times a page is retried after a request error, a rate
+    /// limit error, or a server error. Defaults to 0.
+    pub fn page_retries(mut self, page_retries: u32) -> Self {
+        self.page_retries = page_retries;
+        self
+    }
+
+    /// Sets the delay before the first retry of a page, which doubles with
+    /// each further retry. Defaults to [`DEFAULT_RETRY_DELAY`].
+    pub fn retry_delay(mut self, retry_delay: Duration) -> Self {
+        self.retry_delay = retry_delay;
+        self
+    }
+}
+
+/// Why a paginated pull was stopped before fetching every page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PaginationError {
+    /// More than the maximum number of pages would have been fetched.
+    MaxPagesReached {
+        /// The maximum number of pages.
+        max_pages: i64,
+    },
+    /// The total number of results shrank during the pull, so results may
+    /// have moved to pages already fetched and been missed.
+    CountShrank {
+        /// The total number of results when the pull started.
+        initial: i64,
+        /// The total number of results returned with the last page.
+        current: i64,
+    },
+    /// The API returned the same results for two pages in a row.
+    RepeatedPage {
+        /// The number of the repeated page.
+        page: i64,
+    },
+}
+
+impl fmt::Display for PaginationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PaginationError::MaxPagesReached { max_pages } => {
+                write!(f, "more than {} pages would be fetched", max_pages)
+            }
+            PaginationError::CountShrank { initial, current } => write!(
+                f,
+                "the number of results shrank from {} to {} during the pull",
+                initial, current
+            ),
+            PaginationError::RepeatedPage { page } => {
+                write!(f, "page {} repeats the previous page", page)
+            }
+        }
+    }
+}
+
+/// What a paginated pull has done so far.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StreamStats {
+    /// The number of pages fetched.
+    pub pages_fetched: i64,
+    /// The number of types yielded.
+    pub items: i64,
+    /// The number of duplicate types skipped.
+    pub duplicates: usize,
+    /// The number of times a page was retried.
+    pub retries: u32,
+    /// The total number of results reported by the API with the first page.
+    pub expected_items: Option<i64>,
+    /// Whether the stream ended after fetching every page, rather than after
+    /// an error.
+    pub completed: bool,
+}
+
+/// The results of a search collected up to a cap, returned by
+/// [`Client::search_types_all`].
+#[derive(Debug, Clone)]
+pub struct CappedResults<T> {
+    /// The results collected, no more than the cap.
+    pub items: Vec<T>,
+    /// Whether more results matched the search than were collected.
+    pub truncated: bool,
+    /// The total number of results reported by the API, if any page was
+    /// fetched.
+    pub total: Option<i64>,
+}
+
+/// A stream of all types matching a search, returned by
+/// [`Client::stream_types`].
+pub struct TypeStream<'a> {
+    inner: Pin<Box<dyn Stream<Item = Result<SearchTypeResult>> + Send + 'a>>,
+    stats: Arc<Mutex<StreamStats>>,
+}
+
+impl TypeStream<'_> {
+    /// Returns what the stream has done so far.
+    pub fn stats(&self) -> StreamStats {
+        *self.stats.lock().unwrap()
+    }
+}
+
+impl Stream for TypeStream<'_> {
+    type Item = Result<SearchTypeResult>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+struct State {
+    client: Client,
+    params: SearchTypesParams,
+    options: StreamOptions,
+    stats: Arc<Mutex<StreamStats>>,
+    current_page: i64,
+    buffer: std::vec::IntoIter<SearchTypeResult>,
+    items_fetched: i64,
+    total_items: Option<i64>,
+    seen: HashSet<i64>,
+    previous_page: Vec<i64>,
+    done: bool,
+}
+
+impl State {
+    fn update_stats(&self, update: impl FnOnce(&mut StreamStats)) {
+        update(&mut self.stats.lock().unwrap());
+    }
+
+    /// Returns the next type of the buffer, skipping duplicates if needed.
+    fn next_buffered(&mut self) -> Option<SearchTypeResult> {
+        while let Some(item) = self.buffer.next() {
+            self.items_fetched += 1;
+            if self.options.deduplicate && !self.seen.insert(item.id) {
+                self.update_stats(|s| s.duplicates += 1);
+                continue;
+            }
+            self.update_stats(|s| s.items += 1);
+            return Some(item);
+        }
+        None
+    }
+
+    /// Ends the stream normally.
+    fn complete(&mut self) {
+        self.done = true;
+        self.update_stats(|s| s.completed = true);
+    }
+
+    /// Ends the stream with an error.
+    fn fail(mut self, error: Error) -> Option<(Result<SearchTypeResult>, State)> {
+        self.done = true;
+        Some((Err(error), self))
+    }
+
+    /// Fetches the current page, retrying it as configured.
+    async fn fetch_page(&mut self) -> Result<crate::models::SearchTypesResponse> {
+        let params = self.params.clone().page(self.current_page);
+        let mut delay = self.options.retry_delay;
+        let mut retries = 0;
+        loop {
+            match self.client.search_types(&params).await {
+                Err(e) if retries < self.options.page_retries && e.is_retryable() => {
+                    retries += 1;
+                    self.update_stats(|s| s.retries += 1);
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+pub(crate) async fn collect_types(
+    client: &Client,
+    params: SearchTypesParams,
+    max_items: usize,
+) -> Result<CappedResults<SearchTypeResult>> {
+    let mut stream = stream_types(client, params, StreamOptions::default());
+    let mut items = Vec::new();
+    while items.len() < max_items {
+        match stream.try_next().await? {
+            Some(item) => items.push(item),
+            None => {
+                return Ok(CappedResults {
+                    items,
+                    truncated: false,
+                    total: stream.stats().expected_items,
+                })
+            }
+        }
+    }
+    // Only look for a further result if the API reported more, as it may
+    // take fetching another page.
+    let truncated = match stream.stats().expected_items {
+        Some(total) if total <= items.len() as i64 => false,
+        _ => stream.try_next().await?.is_some(),
+    };
+    Ok(CappedResults {
+        items,
+        truncated,
+        total: stream.stats().expected_items,
+    })
+}
+
+pub(crate) fn stream_types<'a>(
+    client: &Client,
+    params: SearchTypesParams,
+    options: StreamOptions,
+) -> TypeStream<'a> {
+    let stats = Arc::new(Mutex::new(StreamStats::default()));
+    let initial_state = State {
+        client: client.clone(),
+        params,
+        options,
+        stats: stats.clone(),
+        current_page: 1,
+        buffer: Vec::new().into_iter(),
+        items_fetched: 0,
+        total_items: None,
+        seen: HashSet::new(),
+        previous_page: Vec::new(),
+        done: false,
+    };
+
+    let inner = stream::unfold(initial_state, |mut state| async move {
+        loop {
+            if state.done {
+                return None;
+            }
+
+            // Stop if we have fetched all items OR if the last page was empty.
+            if let Some(total) = state.total_items {
+                if state.items_fetched >= total {
+                    state.complete();
+                    return None;
+                }
+            }
+
+            // If we have items in the buffer, return the next one
+            if let Some(item) = state.next_buffered() {
+                return Some((Ok(item), state));
+            }
+
+            // Buffer is empty, fetch the next page
+            if let Some(max_pages) = state.options.max_pages {
+                if state.current_page > max_pages {
+                    return state.fail(Error::Pagination(PaginationError::MaxPagesReached {
+                        max_pages,
+                    }));
+                }
+            }
+
+            let response = match state.fetch_page().await {
+                Ok(response) => response,
+                Err(e) => return state.fail(e),
+            };
+            state.update_stats(|s| s.pages_fetched += 1);
+
+            match state.total_items {
+                None => {
+                    state.total_items = Some(response.count);
+                    state.update_stats(|s| s.expected_items = Some(response.count));
+                }
+                Some(initial) if response.count < initial => {
+                    return state.fail(Error::Pagination(PaginationError::CountShrank {
+                        initial,
+                        current: response.count,
+                    }));
+                }
+                Some(_) => {}
+            }
+
+            // If the page is empty, we're done for good.
+            if response.types.is_empty() {
+                state.complete();
+                return None;
+            }
+
+            let ids: Vec<i64> = response.types.iter().map(|t| t.id).collect();
+            if ids == state.previous_page {
+                let page = state.current_page;
+                return state.fail(Error::Pagination(PaginationError::RepeatedPage { page }));
+            }
+            state.previous_page = ids;
+
+            // Increment page number and refill buffer
+            state.current_page += 1;
+            state.buffer = response.types.into_iter();
+        }
+    });
+
+    TypeStream {
+        inner: Box::pin(inner),
+        stats,
+    }
+}
@@ -0,0 +1,250 @@
+//! Downloading of pictures attached to types and collected items, and helpers
+//! for working with picture URLs.
+use crate::models::{CoinSide, CollectedItem, IssuerDetail, NumistaType, Picture};
+use std::path::PathBuf;
+use url::Url;
+
+/// The maximum number of pictures downloaded at the same time.
+pub const DOWNLOAD_CONCURRENCY: usize = 4;
+
+/// The size suffix used by Numista for full-size pictures.
+pub const ORIGINAL_SIZE: &str = "original";
+
+/// The size suffix used by Numista for thumbnails.
+pub const THUMBNAIL_SIZE: &str = "180";
+
+/// Splits the file name of a Numista picture URL into its base name, size
+/// suffix, and extension, e.g. `5044-original.jpg` into `("5044", "original",
+/// "jpg")`.
+fn split_picture_name(url: &Url) -> Option<(&str, &str, &str)> {
+    let name = url.path_segments()?.next_back()?;
+    let (stem, ext) = name.rsplit_once('.')?;
+    let (base, size) = stem.rsplit_once('-')?;
+    if base.is_empty() || ext.is_empty() {
+        return None;
+    }
+    if size != ORIGINAL_SIZE && (size.is_empty() || !size.chars().all(|c| c.is_ascii_digit())) {
+        return None;
+    }
+    Some((base, size, ext))
+}
+
+/// Checks whether a URL is a well-formed Numista picture URL, i.e. an HTTP(S)
+/// URL whose file name has a size suffix such as `5044-original.jpg` or
+/// `5044-180.jpg`.
+pub fn is_picture_url(url: &Url) -> bool {
+    matches!(url.scheme(), "http" | "https")
+        && url.host_str().is_some()
+        && split_picture_name(url).is_some()
+}
+
+/// Returns the URL of the same picture with a different size suffix, e.g.
+/// [`THUMBNAIL_SIZE`] or [`ORIGINAL_SIZE`].
+///
+/// Returns `None` if the URL is not a well-formed Numista picture URL.
+pub fn with_size(url: &Url, size: &str) -> Option<Url> {
+    if !is_picture_url(url) {
+        return None;
+    }
+    let (base, _, ext) = split_picture_name(url)?;
+    let name = format!("{}-{}.{}", base, size, ext);
+    let mut resized = url.clone();
+    resized.path_segments_mut().ok()?.pop().push(&name);
+    Some(resized)
+}
+
+/// Returns the URL of the thumbnail of a picture.
+pub fn thumbnail_url(url: &Url) -> Option<Url> {
+    with_size(url, THUMBNAIL_SIZE)
+}
+
+/// Returns the URL of the full-size version of a picture.
+pub fn original_url(url: &Url) -> Option<Url> {
+    with_size(url, ORIGINAL_SIZE)
+}
+
+impl CoinSide {
+    /// Returns the URL of the full-size picture, deriving it from the
+    /// thumbnail if needed.
+    pub fn original_url(&self) -> Option<Url> {
+        self.picture
+            .clone()
+            .or_else(|| self.thumbnail.as_ref().and_then(original_url))
+    }
+
+    /// Returns the URL of the thumbnail, deriving it from the full-size
+    /// picture if needed.
+    pub fn thumbnail_url(&self) -> Option<Url> {
+        self.thumbnail
+            .clone()
+            .or_else(|| self.picture.as_ref().and_then(thumbnail_url))
+    }
+}
+
+impl Picture {
+    /// Returns the URL of the picture with a different size suffix.
+    ///
+    /// Returns `None` if the URL is not a well-formed Numista picture URL.
+    pub fn url_with_size(&self, size: &str) -> Option<Url> {
+        with_size(&self.url, size)
+    }
+
+    /// Checks whether both the picture and thumbnail URLs are well-formed
+    /// Numista picture URLs.
+    pub fn is_valid(&self) -> bool {
+        is_picture_url(&self.url) && is_picture_url(&self.thumbnail_url)
+    }
+}
+
+/// A picture to download, along with the file name (without extension) it
+/// should be saved under.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PictureRef {
+    /// The file name of the picture, without extension.
+    pub name: String,
+    /// The URL of the full-size picture.
+    pub url: Url,
+}
+
+/// A picture which has been saved to disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadedPicture {
+    /// The path of the file on disk.
+    pub path: PathBuf,
+    /// Whether the file already existed, in which case it was not downloaded
+    /// again.
+    pub skipped: bool,
+}
+
+/// A catalogue entry or collected item which has pictures.
+pub trait Pictures {
+    /// The directory, relative to the download destination, in which the
+    /// pictures are stored.
+    fn picture_dir(&self) -> String;
+
+    /// The pictures to download.
+    fn picture_refs(&self) -> Vec<PictureRef>;
+}
+
+impl Pictures for NumistaType {
+    fn picture_dir(&self) -> String {
+        format!("type-{}", self.id)
+    }
+
+    fn picture_refs(&self) -> Vec<PictureRef> {
+        [
+            ("obverse", &self.obverse),
+            ("reverse", &self.reverse),
+            ("edge", &self.edge),
+            ("watermark", &self.watermark),
+        ]
+        .into_iter()
+        .filter_map(|(name, side)| {
+            side.as_ref()
+                .and_then(|s| s.picture.clone())
+                .map(|url| PictureRef {
+                    name: name.to_string(),
+                    url,
+                })
+        })
+        .collect()
+    }
+}
+
+impl Pictures for CollectedItem {
+    fn picture_dir(&self) -> String {
+        format!("item-{}", self.id)
+    }
+
+    fn picture_refs(&self) -> Vec<PictureRef> {
+        self.pictures
+            .iter()
+            .flatten()
+            .enumerate()
+            .map(|(i, p)| PictureRef {
+                name: (i + 1).to_string(),
+                url: p.url.clone(),
+            })
+            .collect()
+    }
+}
+
+/// The flag of an issuer, saved as `issuer-<code>/flag.<ext>`.
+impl Pictures for IssuerDetail {
+    fn picture_dir(&self) -> String {
+        format!("issuer-{}", self.code)
+    }
+
+    fn picture_refs(&self) -> Vec<PictureRef> {
+        self.flag
+            .iter()
+            .map(|url| PictureRef {
+                name: "flag".to_string(),
+                url: url.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Returns the file extension to use for a picture, based on its content type,
+/// or failing that, on its URL.
+pub(crate) fn extension_for(content_type: Option<&str>, url: &Url) -> String {
+    let from_content_type = content_type.and_then(|ct| {
+        match ct.split(';').next().unwrap_or_default().trim() {
+            "image/jpeg" | "image/jpg" => Some("jpg"),
+            "image/png" => Some("png"),
+            "image/gif" => Some("gif"),
+            "image/webp" => Some("webp"),
+            "image/svg+xml" => Some("svg"),
+            _ => None,
+        }
+    });
+
+    if let Some(ext) = from_content_type {
+        return ext.to_string();
+    }
+
+    url.path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .and_then(|name| name.rsplit_once('.'))
+        .map(|(_, ext)| ext.to_lowercase())
+        .filter(|ext| !ext.is_empty() && ext.chars().all(|c| c.is_ascii_alphanumeric()))
+        .unwrap_or_else(|| "bin".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extension_for() {
+        let url = Url::parse("https://en.numista.com/catalogue/photos/canada/5044-original.jpg")
+            .unwrap();
+        assert_eq!(extension_for(Some("image/png"), &url), "png");
+        assert_eq!(extension_for(Some("image/jpeg; charset=binary"), &url), "jpg");
+        assert_eq!(extension_for(Some("application/octet-stream"), &url), "jpg");
+        assert_eq!(extension_for(None, &url), "jpg");
+
+        let url = Url::parse("https://example.com/picture").unwrap();
+        assert_eq!(extension_for(None, &url), "bin");
+    }
+
+    #[test]
+    fn test_with_size() {
+        let url = Url::parse("https://en.numista.com/catalogue/photos/canada/5044-original.jpg")
+            .unwrap();
+        assert!(is_picture_url(&url));
+        assert_eq!(
+            thumbnail_url(&url).unwrap().as_str(),
+            "https://en.numista.com/catalogue/photos/canada/5044-180.jpg"
+        );
+        assert_eq!(original_url(&thumbnail_url(&url).unwrap()).unwrap(), url);
+
+        let url = Url::parse("https://en.numista.com/catalogue/photos/canada/5044.jpg").unwrap();
+        assert!(!is_picture_url(&url));
+        assert_eq!(thumbnail_url(&url), None);
+
+        let url = Url::parse("ftp://en.numista.com/5044-180.jpg").unwrap();
+        assert!(!is_picture_url(&url));
+    }
+}
@@ -0,0 +1,186 @@
+//! QR codes linking to the pages of types.
+//!
+//! Codes are encoded with the [`qrcode`] crate, at the medium error
+//! correction level, which recovers up to 15% of damaged modules, in the
+//! smallest version holding the text. They are written as SVG or PNG
+//! pictures, with the quiet zone of four light modules around them that
+//! scanners expect.
+//!
+//! This module is only available with the `qr` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use planchet::models::Category;
+//! use planchet::qr::QrCode;
+//! use planchet::urls::TypeLink;
+//!
+//! let qr = QrCode::for_type(&TypeLink::new(420, Category::Coin));
+//! assert_eq!(qr.size(), 33);
+//!
+//! let svg = qr.to_svg();
+//! assert!(svg.starts_with("<svg"));
+//! let png = qr.to_png(8).unwrap();
+//! assert!(png.starts_with(b"\x89PNG"));
+//! ```
+use crate::error::{Error, Result};
+use crate::urls::TypeLink;
+use qrcode::EcLevel;
+use std::fmt;
+use std::fmt::Write as _;
+use std::io::Cursor;
+
+/// The number of light modules around a code.
+const QUIET_ZONE: usize = 4;
+
+/// A QR code, as a square of dark and light modules.
+#[derive(Clone, PartialEq, Eq)]
+pub struct QrCode {
+    size: usize,
+    modules: Vec<bool>,
+}
+
+impl fmt::Debug for QrCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QrCode").field("size", &self.size).finish()
+    }
+}
+
+impl QrCode {
+    /// Encodes a text.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Qr`] if the text is too long for a QR code at the
+    /// medium error correction level, e.g. more than 2331 bytes of binary
+    /// data.
+    pub fn encode(text: &str) -> Result<Self> {
+        let code = qrcode::QrCode::with_error_correction_level(text, EcLevel::M)
+            .map_err(|e| Error::Qr(e.to_string()))?;
+        Ok(Self {
+            size: code.width(),
+            modules: code
+                .into_colors()
+                .into_iter()
+                .map(|color| color == qrcode::Color::Dark)
+                .collect(),
+        })
+    }
+
+    /// Encodes the URL of the page of a type.
+    pub fn for_type(link: &TypeLink) -> Self {
+        Self::encode(link.url().as_str()).expect("type URLs are short")
+    }
+
+    /// Returns the number of modules on a side, without the quiet zone.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Checks if the module at column `x` and row `y` is dark.
+    pub fn is_dark(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+
+    /// Writes the code as an SVG picture, with one unit per module.
+    ///
+    /// The picture has no fixed size, so it scales to its container.
+    pub fn to_svg(&self) -> String {
+        let side = self.size + 2 * QUIET_ZONE;
+        let mut path = String::new();
+        for y in 0..self.size {
+            for x in (0..self.size).filter(|&x| self.is_dark(x, y)) {
+                let _ = write!(path, "M{},{}h1v1h-1z", x + QUIET_ZONE, y + QUIET_ZONE);
+            }
+        }
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {side} {side}\" \
+             shape-rendering=\"crispEdges\">\
+             <rect width=\"{side}\" height=\"{side}\" fill=\"#fff\"/>\
+             <path d=\"{path}\" fill=\"#000\"/></svg>\n"
+        )
+    }
+
+    /// Writes the code as a black and white PNG picture, with `scale` pixels
+    /// on the side of each module.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Qr`] if the picture is too large to be encoded.
+    pub fn to_png(&self, scale: u32) -> Result<Vec<u8>> {
+        let scale = scale.max(1) as usize;
+        let side = (self.size + 2 * QUIET_ZONE) * scale;
+        let picture = image::GrayImage::from_fn(side as u32, side as u32, |x, y| {
+            let (x, y) = (x as usize / scale, y as usize / scale);
+            let dark = (QUIET_ZONE..QUIET_ZONE + self.size).contains(&x)
+                && (QUIET_ZONE..QUIET_ZONE + self.size).contains(&y)
+                && self.is_dark(x - QUIET_ZONE, y - QUIET_ZONE);
+            image::Luma([if dark { 0 } else { 255 }])
+        });
+        let mut png = Cursor::new(Vec::new());
+        picture
+            .write_to(&mut png, image::ImageFormat::Png)
+            .map_err(|e| Error::Qr(e.to_string()))?;
+        Ok(png.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        let qr = QrCode::encode("https://en.numista.com/catalogue/pieces420.html").unwrap();
+        assert_eq!(qr.size(), 33);
+        // The finder patterns, and the dark module next to the bottom left one.
+        for (x, y) in [(0, 0), (6, 6), (32, 0), (0, 32), (3, 29), (8, 25)] {
+            assert!(qr.is_dark(x, y), "({}, {})", x, y);
+        }
+        assert!(!qr.is_dark(7, 7));
+        // The format bits are read the same from both copies.
+        let first: Vec<bool> = (0..=5).map(|y| qr.is_dark(8, y)).collect();
+        let second: Vec<bool> = (0..=5).map(|i| qr.is_dark(32 - i, 8)).collect();
+        assert_eq!(first, second);
+
+        assert!(matches!(
+            QrCode::encode(&"x".repeat(2332)),
+            Err(Error::Qr(_))
+        ));
+        assert_eq!(QrCode::encode(&"x".repeat(2331)).unwrap().size(), 177);
+    }
+
+    #[test]
+    fn test_for_type() {
+        let link = TypeLink::new(420, crate::models::Category::Coin);
+        let url = "https://en.numista.com/catalogue/pieces420.html";
+        assert_eq!(QrCode::for_type(&link), QrCode::encode(url).unwrap());
+    }
+
+    #[test]
+    fn test_svg() {
+        let qr = QrCode::encode("planchet").unwrap();
+        let svg = qr.to_svg();
+        assert!(svg.contains("viewBox=\"0 0 29 29\""));
+        // The top left corner of the top left finder pattern.
+        assert!(svg.contains("M4,4h1v1h-1z"));
+        let dark = (0..21)
+            .flat_map(|y| (0..21).map(move |x| (x, y)))
+            .filter(|&(x, y)| qr.is_dark(x, y))
+            .count();
+        assert_eq!(svg.matches('z').count(), dark);
+    }
+
+    #[test]
+    fn test_png() {
+        let qr = QrCode::encode("planchet").unwrap();
+        let png = qr.to_png(2).unwrap();
+        let picture = image::load_from_memory(&png).unwrap().to_luma8();
+        assert_eq!(picture.dimensions(), (58, 58));
+        assert_eq!(picture.get_pixel(7, 7).0, [255]);
+        assert_eq!(picture.get_pixel(8, 8).0, [0]);
+        assert_eq!(picture.get_pixel(9, 9).0, [0]);
+        // The light ring inside the finder pattern.
+        assert_eq!(picture.get_pixel(10, 10).0, [255]);
+    }
+}
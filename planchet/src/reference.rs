@@ -0,0 +1,227 @@
+//! A cache of the reference data of the API: issuers, mints, and catalogues.
+//!
+//! These lists are long, change rarely, and are needed over and over to
+//! resolve codes and names, e.g. the catalogue of each row of an import.
+//! [`ReferenceCache`] wraps an implementation of [`NumistaApi`], fetches each
+//! list on first use, and keeps it for its lifetime. It implements
+//! [`NumistaApi`] itself, forwarding the other endpoints, so it can be used
+//! in place of the client.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use planchet::reference::ReferenceCache;
+//! use planchet::ClientBuilder;
+//!
+//! # async fn run() -> planchet::Result<()> {
+//! let client = ClientBuilder::new().api_key("YOUR_API_KEY").build()?;
+//! let reference = ReferenceCache::new(client);
+//!
+//! // Only the first lookup fetches the list of catalogues.
+//! for code in ["KM", "Schön"] {
+//!     if let Some(catalogue) = reference.catalogue(code).await? {
+//!         println!("{}: {}", catalogue.code, catalogue.title);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use crate::api::NumistaApi;
+use crate::client::{Client, DeleteOutcome};
+use crate::error::Result;
+use crate::models::{
+    self,
+    request::{
+        AddCollectedItemParams, EditCollectedItemParams, GetCollectedItemsParams, OAuthTokenParams,
+        SearchByImageParams, SearchTypesParams,
+    },
+    response::{
+        CataloguesResponse, CollectionsResponse, IssuersResponse, MintsResponse,
+        SearchByImageResponse, SearchTypesResponse,
+    },
+    CatalogueDetail, CollectedItem, CollectedItems, GradePrices, IssuerDetail, MintDetail,
+    NumistaType, OAuthToken, Publication, User,
+};
+use tokio::sync::OnceCell;
+
+/// An implementation of [`NumistaApi`] fetching the lists of issuers, mints,
+/// and catalogues once.
+#[derive(Debug)]
+pub struct ReferenceCache<A = Client> {
+    api: A,
+    issuers: OnceCell<IssuersResponse>,
+    mints: OnceCell<MintsResponse>,
+    catalogues: OnceCell<CataloguesResponse>,
+}
+
+impl<A: NumistaApi> ReferenceCache<A> {
+    /// Creates an empty cache in front of `api`.
+    pub fn new(api: A) -> Self {
+        Self {
+            api,
+            issuers: OnceCell::new(),
+            mints: OnceCell::new(),
+            catalogues: OnceCell::new(),
+        }
+    }
+
+    /// Returns the implementation the requests are forwarded to.
+    pub fn inner(&self) -> &A {
+        &self.api
+    }
+
+    /// Forgets the lists fetched, so that they are fetched again on next use.
+    pub fn clear(&mut self) {
+        self.issuers = OnceCell::new();
+        self.mints = OnceCell::new();
+        self.catalogues = OnceCell::new();
+    }
+
+    /// Returns the issuers, fetching them on first use.
+    pub async fn issuers(&self) -> Result<&[IssuerDetail]> {
+        let response = self
+            .issuers
+            .get_or_try_init(|| self.api.get_issuers())
+            .await?;
+        Ok(&response.issuers)
+    }
+
+    /// Returns the mints, fetching them on first use.
+    pub async fn mints(&self) -> Result<&[MintDetail]> {
+        let response = self.mints.get_or_try_init(|| self.api.get_mints()).await?;
+        Ok(&response.mints)
+    }
+
+    /// Returns the catalogues, fetching them on first use.
+    pub async fn catalogues(&self) -> Result<&[CatalogueDetail]> {
+        let response = self
+            .catalogues
+            .get_or_try_init(|| self.api.get_catalogues())
+            .await?;
+        Ok(&response.catalogues)
+    }
+
+    /// Finds an issuer by its code, e.g. `canada`.
+    pub async fn issuer(&self, code: &str) -> Result<Option<&IssuerDetail>> {
+        Ok(self.issuers().await?.iter().find(|i| i.code == code))
+    }
+
+    /// Finds a mint by its ID.
+    pub async fn mint(&self, mint_id: i64) -> Result<Option<&MintDetail>> {
+        Ok(self.mints().await?.iter().find(|m| m.id == mint_id))
+    }
+
+    /// Finds a catalogue by its code, e.g. `KM`, ignoring case.
+    pub async fn catalogue(&self, code: &str) -> Result<Option<&CatalogueDetail>> {
+        Ok(self
+            .catalogues()
+            .await?
+            .iter()
+            .find(|c| c.code.eq_ignore_ascii_case(code)))
+    }
+}
+
+#[async_trait::async_trait]
+impl<A: NumistaApi> NumistaApi for ReferenceCache<A> {
+    async fn get_type(&self, type_id: i64) -> Result<NumistaType> {
+        self.api.get_type(type_id).await
+    }
+
+    async fn get_issues(&self, type_id: i64) -> Result<Vec<models::Issue>> {
+        self.api.get_issues(type_id).await
+    }
+
+    async fn get_prices(
+        &self,
+        type_id: i64,
+        issue_id: i64,
+        currency: Option<&str>,
+    ) -> Result<GradePrices> {
+        self.api.get_prices(type_id, issue_id, currency).await
+    }
+
+    async fn search_types(&self, params: &SearchTypesParams) -> Result<SearchTypesResponse> {
+        self.api.search_types(params).await
+    }
+
+    async fn get_issuers(&self) -> Result<IssuersResponse> {
+        let response = self
+            .issuers
+            .get_or_try_init(|| self.api.get_issuers())
+            .await?;
+        Ok(response.clone())
+    }
+
+    async fn get_mints(&self) -> Result<MintsResponse> {
+        let response = self.mints.get_or_try_init(|| self.api.get_mints()).await?;
+        Ok(response.clone())
+    }
+
+    async fn get_mint(&self, mint_id: i64) -> Result<MintDetail> {
+        self.api.get_mint(mint_id).await
+    }
+
+    async fn get_catalogues(&self) -> Result<CataloguesResponse> {
+        let response = self
+            .catalogues
+            .get_or_try_init(|| self.api.get_catalogues())
+            .await?;
+        Ok(response.clone())
+    }
+
+    async fn get_publication(&self, id: &str) -> Result<Publication> {
+        self.api.get_publication(id).await
+    }
+
+    async fn get_user(&self, user_id: i64) -> Result<User> {
+        self.api.get_user(user_id).await
+    }
+
+    async fn get_user_collections(&self, user_id: i64) -> Result<CollectionsResponse> {
+        self.api.get_user_collections(user_id).await
+    }
+
+    async fn get_collected_items(
+        &self,
+        user_id: i64,
+        params: &GetCollectedItemsParams,
+    ) -> Result<CollectedItems> {
+        self.api.get_collected_items(user_id, params).await
+    }
+
+    async fn get_collected_item(&self, user_id: i64, item_id: i64) -> Result<CollectedItem> {
+        self.api.get_collected_item(user_id, item_id).await
+    }
+
+    async fn add_collected_item(
+        &self,
+        user_id: i64,
+        item: &AddCollectedItemParams,
+    ) -> Result<CollectedItem> {
+        self.api.add_collected_item(user_id, item).await
+    }
+
+    async fn edit_collected_item(
+        &self,
+        user_id: i64,
+        item_id: i64,
+        item: &EditCollectedItemParams,
+    ) -> Result<CollectedItem> {
+        self.api.edit_collected_item(user_id, item_id, item).await
+    }
+
+    async fn delete_collected_item(&self, user_id: i64, item_id: i64) -> Result<DeleteOutcome> {
+        self.api.delete_collected_item(user_id, item_id).await
+    }
+
+    async fn get_oauth_token(&self, params: &OAuthTokenParams) -> Result<OAuthToken> {
+        self.api.get_oauth_token(params).await
+    }
+
+    async fn search_by_image(
+        &self,
+        request: &SearchByImageParams,
+    ) -> Result<SearchByImageResponse> {
+        self.api.search_by_image(request).await
+    }
+}
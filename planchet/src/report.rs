@@ -0,0 +1,230 @@
+//! Shareable summaries of a collection.
+//!
+//! [`render`] turns the [`CollectionStats`] of a collection into a standalone
+//! HTML page, with an SVG bar chart per distribution, or into a Markdown
+//! document with a table per distribution.
+//!
+//! This module is only available with the `report` feature.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use planchet::analysis;
+//! use planchet::models::GetCollectedItemsParams;
+//! use planchet::report::{self, ReportFormat};
+//! use planchet::ClientBuilder;
+//! use std::collections::HashMap;
+//!
+//! # async fn run() -> planchet::Result<()> {
+//! let client = ClientBuilder::new().api_key("YOUR_API_KEY").build()?;
+//! let items = client
+//!     .get_collected_items(123, &GetCollectedItemsParams::new())
+//!     .await?
+//!     .items;
+//! let stats = analysis::collection_stats(&items, &HashMap::new());
+//!
+//! let html = report::render(&stats, "My collection", ReportFormat::Html)?;
+//! std::fs::write("report.html", html)?;
+//! # Ok(())
+//! # }
+//! ```
+use crate::analysis::CollectionStats;
+use crate::error::{Error, Result};
+use plotters::prelude::*;
+use std::fmt::Write;
+
+/// The maximum number of bars of a chart. Smaller values are left out.
+const MAX_BARS: usize = 15;
+
+/// The format of a report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// A standalone HTML page, with embedded SVG charts.
+    Html,
+    /// A Markdown document, with tables.
+    Markdown,
+}
+
+/// One distribution of the statistics, in the order it is shown.
+struct Section {
+    title: &'static str,
+    counts: Vec<(String, i64)>,
+}
+
+fn sections(stats: &CollectionStats) -> Vec<Section> {
+    // Decades are shown in chronological order, other values from the most
+    // common.
+    let by_count = |counts: &std::collections::BTreeMap<String, i64>| {
+        let mut counts: Vec<(String, i64)> = counts.iter().map(|(k, v)| (k.clone(), *v)).collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts
+    };
+    vec![
+        Section {
+            title: "Issuers",
+            counts: by_count(&stats.by_issuer),
+        },
+        Section {
+            title: "Categories",
+            counts: by_count(&stats.by_category),
+        },
+        Section {
+            title: "Decades",
+            counts: stats
+                .by_decade
+                .iter()
+                .map(|(decade, count)| (format!("{}s", decade), *count))
+                .collect(),
+        },
+        Section {
+            title: "Grades",
+            counts: by_count(&stats.by_grade),
+        },
+        Section {
+            title: "Compositions",
+            counts: by_count(&stats.by_composition),
+        },
+    ]
+}
+
+/// Renders the statistics of a collection as a report.
+///
+/// # Arguments
+///
+/// * `stats` - The statistics of the collection.
+/// * `title` - The title of the report.
+/// * `format` - The format of the report.
+pub fn render(stats: &CollectionStats, title: &str, format: ReportFormat) -> Result<String> {
+    match format {
+        ReportFormat::Html => render_html(stats, title),
+        ReportFormat::Markdown => Ok(render_markdown(stats, title)),
+    }
+}
+
+fn render_markdown(stats: &CollectionStats, title: &str) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# {}\n", title);
+    let _ = writeln!(
+        out,
+        "{} items of {} types.",
+        stats.total_items, stats.distinct_types
+    );
+    for section in sections(stats).iter().filter(|s| !s.counts.is_empty()) {
+        let _ = writeln!(out, "\n## {}\n", section.title);
+        let _ = writeln!(out, "| Value | Items |\n| --- | ---: |");
+        for (label, count) in &section.counts {
+            let _ = writeln!(out, "| {} | {} |", label.replace('|', "\\|"), count);
+        }
+    }
+    out
+}
+
+fn render_html(stats: &CollectionStats, title: &str) -> Result<String> {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>",
+        escape(title)
+    );
+    let _ = writeln!(out, "<h1>{}</h1>", escape(title));
+    let _ = writeln!(
+        out,
+        "<p>{} items of {} types.</p>",
+        stats.total_items, stats.distinct_types
+    );
+    for section in sections(stats).iter().filter(|s| !s.counts.is_empty()) {
+        let _ = writeln!(out, "<h2>{}</h2>", section.title);
+        let _ = writeln!(out, "{}", bar_chart(&section.counts)?);
+    }
+    let _ = writeln!(out, "</body>\n</html>");
+    Ok(out)
+}
+
+/// Draws the largest counts as an SVG bar chart.
+fn bar_chart(counts: &[(String, i64)]) -> Result<String> {
+    let counts = &counts[..counts.len().min(MAX_BARS)];
+    let max = counts.iter().map(|(_, c)| *c).max().unwrap_or(0).max(1);
+    let mut svg = String::new();
+    {
+        let root = SVGBackend::with_string(&mut svg, (720, 360)).into_drawing_area();
+        root.fill(&WHITE).map_err(chart_error)?;
+        let mut chart = ChartBuilder::on(&root)
+            .margin(10)
+            .x_label_area_size(60)
+            .y_label_area_size(50)
+            .build_cartesian_2d((0..counts.len() as i32).into_segmented(), 0..max + 1)
+            .map_err(chart_error)?;
+        chart
+            .configure_mesh()
+            .disable_x_mesh()
+            .x_labels(counts.len())
+            .x_label_formatter(&|x| match x {
+                SegmentValue::CenterOf(i) => counts
+                    .get(*i as usize)
+                    .map(|(label, _)| label.clone())
+                    .unwrap_or_default(),
+                _ => String::new(),
+            })
+            .draw()
+            .map_err(chart_error)?;
+        chart
+            .draw_series(
+                Histogram::vertical(&chart)
+                    .style(BLUE.filled())
+                    .margin(5)
+                    .data(counts.iter().enumerate().map(|(i, (_, c))| (i as i32, *c))),
+            )
+            .map_err(chart_error)?;
+        root.present().map_err(chart_error)?;
+    }
+    Ok(svg)
+}
+
+fn chart_error<E: std::error::Error>(e: E) -> Error {
+    Error::Report(e.to_string())
+}
+
+/// Escapes the characters with a special meaning in HTML text.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats() -> CollectionStats {
+        CollectionStats {
+            total_items: 4,
+            distinct_types: 3,
+            by_issuer: [("Canada".to_string(), 3), ("<France>".to_string(), 1)].into(),
+            by_decade: [(1850, 1), (1920, 3)].into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let md = render(&stats(), "My collection", ReportFormat::Markdown).unwrap();
+        assert!(md.starts_with("# My collection\n"));
+        assert!(md.contains("4 items of 3 types."));
+        assert!(md.contains(
+            "## Issuers\n\n| Value | Items |\n| --- | ---: |\n| Canada | 3 |\n| <France> | 1 |"
+        ));
+        assert!(md.contains("| 1850s | 1 |\n| 1920s | 3 |"));
+        assert!(!md.contains("## Grades"));
+    }
+
+    #[test]
+    fn test_render_html() {
+        let html = render(&stats(), "A & B", ReportFormat::Html).unwrap();
+        assert!(html.contains("<title>A &amp; B</title>"));
+        assert!(html.contains("<h2>Issuers</h2>"));
+        assert_eq!(html.matches("<svg").count(), 2);
+        assert!(html.contains("&lt;France&gt;"));
+        assert!(!html.contains("<France>"));
+    }
+}
@@ -0,0 +1,114 @@
+//! Versions of the Numista API and the paths of their endpoints.
+use std::fmt;
+
+/// The host of the Numista API, without any version.
+pub const API_HOST: &str = "https://api.numista.com";
+
+/// A version of the Numista API.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ApiVersion {
+    /// Version 3, the current version.
+    #[default]
+    V3,
+}
+
+impl ApiVersion {
+    /// Returns the path segment of the version, e.g. `v3`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiVersion::V3 => "v3",
+        }
+    }
+
+    /// Returns the base URL of the version on the official API host.
+    pub fn base_url(&self) -> String {
+        format!("{}/{}", API_HOST, self.as_str())
+    }
+}
+
+impl fmt::Display for ApiVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An endpoint of the API.
+///
+/// All request paths are built here, so that endpoints which differ between
+/// versions can be handled in one place.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Route<'a> {
+    Type(i64),
+    Issues(i64),
+    Prices { type_id: i64, issue_id: i64 },
+    Types,
+    Issuers,
+    Mints,
+    Mint(i64),
+    Catalogues,
+    Publication(&'a str),
+    User(i64),
+    UserCollections(i64),
+    CollectedItems(i64),
+    CollectedItem { user_id: i64, item_id: i64 },
+    OAuthToken,
+    SearchByImage,
+}
+
+impl Route<'_> {
+    /// Returns the path of the endpoint, relative to the base URL of `version`.
+    pub(crate) fn path(&self, version: ApiVersion) -> String {
+        match version {
+            ApiVersion::V3 => match self {
+                Route::Type(id) => format!("/types/{}", id),
+                Route::Issues(type_id) => format!("/types/{}/issues", type_id),
+                Route::Prices { type_id, issue_id } => {
+                    format!("/types/{}/issues/{}/prices", type_id, issue_id)
+                }
+                Route::Types => "/types".to_string(),
+                Route::Issuers => "/issuers".to_string(),
+                Route::Mints => "/mints".to_string(),
+                Route::Mint(id) => format!("/mints/{}", id),
+                Route::Catalogues => "/catalogues".to_string(),
+                Route::Publication(id) => format!("/publications/{}", id),
+                Route::User(id) => format!("/users/{}", id),
+                Route::UserCollections(user_id) => format!("/users/{}/collections", user_id),
+                Route::CollectedItems(user_id) => format!("/users/{}/collected_items", user_id),
+                Route::CollectedItem { user_id, item_id } => {
+                    format!("/users/{}/collected_items/{}", user_id, item_id)
+                }
+                Route::OAuthToken => "/oauth_token".to_string(),
+                Route::SearchByImage => "/search_by_image".to_string(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_v3_paths() {
+        let v3 = ApiVersion::V3;
+        assert_eq!(v3.base_url(), "https://api.numista.com/v3");
+        assert_eq!(Route::Type(420).path(v3), "/types/420");
+        assert_eq!(
+            Route::Prices {
+                type_id: 420,
+                issue_id: 1
+            }
+            .path(v3),
+            "/types/420/issues/1/prices"
+        );
+        assert_eq!(
+            Route::CollectedItem {
+                user_id: 1,
+                item_id: 2
+            }
+            .path(v3),
+            "/users/1/collected_items/2"
+        );
+    }
+}
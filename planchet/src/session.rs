@@ -0,0 +1,141 @@
+//! A session of an authenticated user, to manage their own collection without
+//! passing their ID to every call.
+//!
+//! A [`Session`] is started by [`Client::authenticate`], which requests a token
+//! for the user of the API key, or by [`Session::new`] with a token from the
+//! authorization code flow of the [`oauth`](crate::oauth) module. It sends its
+//! requests with its token, whatever the bearer token of the client, and checks
+//! the scopes of the token before the requests which need them.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use planchet::models::{AddCollectedItemParams, Grade};
+//! use planchet::oauth::Scope;
+//! use planchet::ClientBuilder;
+//!
+//! # async fn run() -> planchet::Result<()> {
+//! let client = ClientBuilder::new().api_key("YOUR_API_KEY").build()?;
+//! let session = client
+//!     .authenticate(&[Scope::ViewCollection, Scope::EditCollection])
+//!     .await?;
+//!
+//! println!("{} items", session.my_items().await?.item_count);
+//! session
+//!     .add_item(&AddCollectedItemParams::new(12345).grade(Grade::Xf))
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Client::authenticate`]: crate::Client::authenticate
+use crate::client::{Client, DeleteOutcome};
+use crate::error::Result;
+use crate::models::{
+    request::{AddCollectedItemParams, EditCollectedItemParams, GetCollectedItemsParams},
+    response::CollectionsResponse,
+    CollectedItem, CollectedItems, User,
+};
+use crate::oauth::{Scope, Token};
+
+/// A client acting for an authenticated user.
+#[derive(Debug, Clone)]
+pub struct Session {
+    client: Client,
+    token: Token,
+}
+
+impl Session {
+    /// Starts a session with a token of a user, sending requests through a
+    /// client.
+    ///
+    /// Returns [`Error::InvalidToken`](crate::Error::InvalidToken) if the token
+    /// cannot be sent in a header.
+    pub fn new(client: &Client, token: Token) -> Result<Self> {
+        Ok(Self {
+            client: client.with_token(&token)?,
+            token,
+        })
+    }
+
+    /// Returns the client of the session, which sends requests with its token,
+    /// e.g. for the calls without a session method.
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Returns the token of the session.
+    pub fn token(&self) -> &Token {
+        &self.token
+    }
+
+    /// Returns the ID of the user of the session.
+    pub fn user_id(&self) -> i64 {
+        self.token.user_id
+    }
+
+    /// Returns the known scopes granted to the token of the session.
+    pub fn scopes(&self) -> Vec<Scope> {
+        self.token.granted_scopes()
+    }
+
+    /// Checks if the token of the session has expired, or expires within a
+    /// minute.
+    pub fn is_expired(&self) -> bool {
+        self.token.is_expired()
+    }
+
+    /// Gets the user of the session. See [`Client::get_user`].
+    pub async fn me(&self) -> Result<User> {
+        self.client.get_user(self.user_id()).await
+    }
+
+    /// Gets the collections of the user. See
+    /// [`Client::get_user_collections`].
+    pub async fn my_collections(&self) -> Result<CollectionsResponse> {
+        self.client.get_user_collections(self.user_id()).await
+    }
+
+    /// Gets every collected item of the user. See
+    /// [`Client::get_collected_items`].
+    pub async fn my_items(&self) -> Result<CollectedItems> {
+        self.search_my_items(&GetCollectedItemsParams::new()).await
+    }
+
+    /// Gets the collected items of the user matching the parameters. See
+    /// [`Client::get_collected_items`].
+    pub async fn search_my_items(
+        &self,
+        params: &GetCollectedItemsParams,
+    ) -> Result<CollectedItems> {
+        self.client
+            .get_collected_items(self.user_id(), params)
+            .await
+    }
+
+    /// Adds a collected item to the collection of the user. See
+    /// [`Client::add_collected_item`].
+    pub async fn add_item(&self, item: &AddCollectedItemParams) -> Result<CollectedItem> {
+        self.client.add_collected_item(self.user_id(), item).await
+    }
+
+    /// Edits a collected item of the user. See
+    /// [`Client::edit_collected_item`].
+    pub async fn edit_item(
+        &self,
+        item_id: i64,
+        item: &EditCollectedItemParams,
+    ) -> Result<CollectedItem> {
+        self.client
+            .edit_collected_item(self.user_id(), item_id, item)
+            .await
+    }
+
+    /// Deletes a collected item of the user. See
+    /// [`Client::delete_collected_item`].
+    pub async fn delete_item(&self, item_id: i64) -> Result<DeleteOutcome> {
+        self.client
+            .delete_collected_item(self.user_id(), item_id)
+            .await
+    }
+}
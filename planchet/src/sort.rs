@@ -0,0 +1,94 @@
+//! Deterministic ordering of search results.
+//!
+//! The API does not guarantee the order of search results, which can shift
+//! between pages when the catalogue changes during a long pull. Sorting by a
+//! [`SortKey`] gives a total order which does not depend on the API.
+use crate::models::SearchTypeResult;
+use std::cmp::Reverse;
+
+/// The order in which to sort search results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SortBy {
+    /// By ID, lowest first.
+    #[default]
+    Id,
+    /// By title, ignoring case, then by ID.
+    Title,
+    /// By first year of issue, oldest first and types without a year last,
+    /// then by ID.
+    Year,
+}
+
+/// A key which sorts search results in the order given by a [`SortBy`].
+///
+/// Keys are only comparable with keys built with the same `SortBy`. The ID
+/// always breaks ties, so no two types have the same key.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SortKey(KeyInner);
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum KeyInner {
+    Id(i64),
+    Title(String, String, i64),
+    // `Reverse(None)` sorts after every `Reverse(Some(_))`, and the inner
+    // `Reverse` restores the ascending order of years.
+    Year(Reverse<Option<Reverse<i32>>>, i64),
+}
+
+/// Sorting helpers for [`SearchTypeResult`].
+pub trait SearchTypeResultExt {
+    /// Returns the key sorting the result in the given order.
+    fn sort_key(&self, by: SortBy) -> SortKey;
+}
+
+impl SearchTypeResultExt for SearchTypeResult {
+    fn sort_key(&self, by: SortBy) -> SortKey {
+        SortKey(match by {
+            SortBy::Id => KeyInner::Id(self.id),
+            SortBy::Title => {
+                KeyInner::Title(self.title.to_lowercase(), self.title.clone(), self.id)
+            }
+            SortBy::Year => KeyInner::Year(Reverse(self.min_year.map(Reverse)), self.id),
+        })
+    }
+}
+
+/// Sorts search results in the given order.
+pub fn sort_types(types: &mut [SearchTypeResult], by: SortBy) {
+    types.sort_by_cached_key(|t| t.sort_key(by));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(id: i64, title: &str, min_year: Option<i32>) -> SearchTypeResult {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "title": title,
+            "min_year": min_year,
+        }))
+        .unwrap()
+    }
+
+    fn ids(types: &[SearchTypeResult]) -> Vec<i64> {
+        types.iter().map(|t| t.id).collect()
+    }
+
+    #[test]
+    fn test_sort_types() {
+        let mut types = vec![
+            result(3, "b", Some(1900)),
+            result(1, "C", None),
+            result(4, "a", Some(-50)),
+            result(2, "B", Some(1900)),
+        ];
+
+        sort_types(&mut types, SortBy::Id);
+        assert_eq!(ids(&types), vec![1, 2, 3, 4]);
+        sort_types(&mut types, SortBy::Title);
+        assert_eq!(ids(&types), vec![4, 2, 3, 1]);
+        sort_types(&mut types, SortBy::Year);
+        assert_eq!(ids(&types), vec![4, 2, 3, 1]);
+    }
+}
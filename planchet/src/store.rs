@@ -0,0 +1,994 @@
+//! SQLite persistence for fetched catalogue and collection data.
+//!
+//! This module is only available with the `store` feature.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use planchet::store::SqliteStore;
+//! use planchet::ClientBuilder;
+//!
+//! # async fn run() -> planchet::Result<()> {
+//! let client = ClientBuilder::new().api_key("YOUR_API_KEY").build()?;
+//! let store = SqliteStore::open("catalogue.db")?;
+//!
+//! let type_ = client.get_type(420).await?;
+//! store.upsert_type(&type_)?;
+//! store.upsert_issues(type_.id, &client.get_issues(type_.id).await?)?;
+//!
+//! for t in store.types_by_issuer("canada")? {
+//!     println!("{}", t.title);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use crate::error::Result;
+use crate::models::{Category, CollectedItem, Grade, Issue, NumistaType, PartialDate};
+use chrono::NaiveDate;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use std::fmt;
+use std::path::Path;
+
+/// The schema migrations, applied in order. The index of a migration plus one
+/// is the schema version it upgrades to.
+const MIGRATIONS: &[&str] = &[
+    // Version 1: types, tags, issues, and collected items.
+    "CREATE TABLE types (
+        id INTEGER PRIMARY KEY,
+        title TEXT NOT NULL,
+        category TEXT NOT NULL,
+        issuer_code TEXT,
+        issuer_name TEXT,
+        min_year INTEGER,
+        max_year INTEGER
+    );
+    CREATE INDEX types_issuer_code ON types (issuer_code);
+    CREATE TABLE type_tags (
+        type_id INTEGER NOT NULL REFERENCES types (id) ON DELETE CASCADE,
+        tag TEXT NOT NULL,
+        PRIMARY KEY (type_id, tag)
+    );
+    CREATE TABLE issues (
+        id INTEGER PRIMARY KEY,
+        type_id INTEGER NOT NULL,
+        year INTEGER,
+        gregorian_year INTEGER,
+        min_year INTEGER,
+        max_year INTEGER,
+        mint_letter TEXT,
+        mintage INTEGER,
+        comment TEXT
+    );
+    CREATE INDEX issues_type_id ON issues (type_id);
+    CREATE TABLE collected_items (
+        id INTEGER PRIMARY KEY,
+        type_id INTEGER NOT NULL,
+        type_title TEXT NOT NULL,
+        issue_id INTEGER,
+        quantity INTEGER NOT NULL,
+        grade TEXT,
+        for_swap INTEGER NOT NULL,
+        private_comment TEXT,
+        public_comment TEXT,
+        collection_id INTEGER,
+        storage_location TEXT,
+        acquisition_date TEXT
+    );",
+    // Version 2: sync state of collected items, see `crate::sync`.
+    "ALTER TABLE collected_items ADD COLUMN sync_state TEXT NOT NULL DEFAULT 'synced';
+    ALTER TABLE collected_items ADD COLUMN remote_fingerprint TEXT;
+    ALTER TABLE collected_items ADD COLUMN conflict INTEGER NOT NULL DEFAULT 0;",
+    // Version 3: demonetization of types, unknown for the types stored before.
+    "ALTER TABLE types ADD COLUMN demonetized INTEGER;
+    ALTER TABLE types ADD COLUMN demonetization_date TEXT;",
+];
+
+/// The columns of a [`TypeRecord`], in the order read by `type_from_row`.
+const TYPE_COLUMNS: &str =
+    "id, title, category, issuer_code, issuer_name, min_year, max_year, demonetized,
+    demonetization_date";
+
+/// The columns of a [`CollectedItemRecord`], in the order read by
+/// `collected_item_from_row`.
+const COLLECTED_ITEM_COLUMNS: &str = "id, type_id, type_title, issue_id, quantity, grade, for_swap,
+    private_comment, public_comment, collection_id, storage_location, acquisition_date,
+    sync_state, conflict";
+
+/// A type, as stored in a [`SqliteStore`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeRecord {
+    pub id: i64,
+    pub title: String,
+    pub category: Category,
+    pub issuer_code: Option<String>,
+    pub issuer_name: Option<String>,
+    pub min_year: Option<i32>,
+    pub max_year: Option<i32>,
+    pub tags: Vec<String>,
+    /// Whether the type is demonetized, unknown for types stored before
+    /// version 3 of the schema.
+    pub demonetized: Option<bool>,
+    pub demonetization_date: Option<PartialDate>,
+}
+
+impl TypeRecord {
+    /// Compares the stored type and issues with those fetched from the API,
+    /// e.g. to find the types updated in the catalogue since they were
+    /// stored.
+    ///
+    /// # Arguments
+    ///
+    /// * `stored_issues` - The stored issues of the type.
+    /// * `live` - The type, as fetched from the API.
+    /// * `live_issues` - The issues of the type, as fetched from the API.
+    pub fn discrepancies(
+        &self,
+        stored_issues: &[IssueRecord],
+        live: &NumistaType,
+        live_issues: &[Issue],
+    ) -> Vec<Discrepancy> {
+        let mut discrepancies = Vec::new();
+        if self.title != live.title {
+            discrepancies.push(Discrepancy::Title {
+                stored: self.title.clone(),
+                live: live.title.clone(),
+            });
+        }
+        if let Some(demonetized) = self.demonetized {
+            let demonetization = live.demonetization.as_ref();
+            let live_demonetized = demonetization.is_some_and(|d| d.is_demonetized);
+            let live_date = demonetization.and_then(|d| d.demonetization_date);
+            if demonetized != live_demonetized || self.demonetization_date != live_date {
+                discrepancies.push(Discrepancy::Demonetization {
+                    demonetized: live_demonetized,
+                    date: live_date,
+                });
+            }
+        }
+        let new: Vec<i64> = live_issues
+            .iter()
+            .map(|issue| issue.id)
+            .filter(|id| !stored_issues.iter().any(|issue| issue.id == *id))
+            .collect();
+        if !new.is_empty() {
+            discrepancies.push(Discrepancy::NewIssues(new));
+        }
+        let removed: Vec<i64> = stored_issues
+            .iter()
+            .map(|issue| issue.id)
+            .filter(|id| !live_issues.iter().any(|issue| issue.id == *id))
+            .collect();
+        if !removed.is_empty() {
+            discrepancies.push(Discrepancy::RemovedIssues(removed));
+        }
+        discrepancies
+    }
+}
+
+/// A difference between a stored type and the catalogue, found by
+/// [`TypeRecord::discrepancies`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Discrepancy {
+    /// The title of the type changed.
+    Title { stored: String, live: String },
+    /// The type was demonetized, or its demonetization changed.
+    Demonetization {
+        demonetized: bool,
+        date: Option<PartialDate>,
+    },
+    /// Issues were added to the type, with these IDs.
+    NewIssues(Vec<i64>),
+    /// Issues were removed from the type, with these IDs.
+    RemovedIssues(Vec<i64>),
+}
+
+impl fmt::Display for Discrepancy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ids = |ids: &[i64]| {
+            ids.iter()
+                .map(i64::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match self {
+            Discrepancy::Title { stored, live } => {
+                write!(f, "title changed from '{}' to '{}'", stored, live)
+            }
+            Discrepancy::Demonetization {
+                demonetized: false, ..
+            } => write!(f, "no longer demonetized"),
+            Discrepancy::Demonetization {
+                demonetized: true,
+                date: Some(date),
+            } => write!(f, "demonetized on {}", date),
+            Discrepancy::Demonetization {
+                demonetized: true,
+                date: None,
+            } => write!(f, "demonetized"),
+            Discrepancy::NewIssues(new) => write!(f, "new issues: {}", ids(new)),
+            Discrepancy::RemovedIssues(removed) => write!(f, "removed issues: {}", ids(removed)),
+        }
+    }
+}
+
+/// An issue, as stored in a [`SqliteStore`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssueRecord {
+    pub id: i64,
+    pub type_id: i64,
+    pub year: Option<i32>,
+    pub gregorian_year: Option<i32>,
+    pub min_year: Option<i32>,
+    pub max_year: Option<i32>,
+    pub mint_letter: Option<String>,
+    pub mintage: Option<i64>,
+    pub comment: Option<String>,
+}
+
+/// A collected item, as stored in a [`SqliteStore`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollectedItemRecord {
+    pub id: i64,
+    pub type_id: i64,
+    pub type_title: String,
+    pub issue_id: Option<i64>,
+    pub quantity: i64,
+    pub grade: Option<Grade>,
+    pub for_swap: bool,
+    pub private_comment: Option<String>,
+    pub public_comment: Option<String>,
+    pub collection_id: Option<i64>,
+    pub storage_location: Option<String>,
+    pub acquisition_date: Option<NaiveDate>,
+    /// Whether the item has local changes which have not been pushed yet.
+    pub sync_state: SyncState,
+    /// Whether the item was also changed remotely since it was last synced.
+    /// Conflicting items are not pushed until the conflict is resolved with
+    /// [`SqliteStore::resolve_conflict`].
+    pub conflict: bool,
+}
+
+impl From<&CollectedItem> for CollectedItemRecord {
+    fn from(item: &CollectedItem) -> Self {
+        Self {
+            id: item.id,
+            type_id: item.type_info.id,
+            type_title: item.type_info.title.clone(),
+            issue_id: item.issue.as_ref().map(|i| i.id),
+            quantity: item.quantity,
+            grade: item.grade.clone(),
+            for_swap: item.for_swap,
+            private_comment: item.private_comment.clone(),
+            public_comment: item.public_comment.clone(),
+            collection_id: item.collection.as_ref().map(|c| c.id),
+            storage_location: item.storage_location.clone(),
+            acquisition_date: item.acquisition_date,
+            sync_state: SyncState::Synced,
+            conflict: false,
+        }
+    }
+}
+
+impl CollectedItemRecord {
+    /// Returns a fingerprint of the synced fields of the item, used to detect
+    /// remote changes.
+    pub(crate) fn fingerprint(&self) -> String {
+        serde_json::json!([
+            self.type_id,
+            self.issue_id,
+            self.quantity,
+            self.grade,
+            self.for_swap,
+            self.private_comment,
+            self.public_comment,
+            self.collection_id,
+            self.storage_location,
+            self.acquisition_date,
+        ])
+        .to_string()
+    }
+}
+
+/// The sync state of a stored collected item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SyncState {
+    /// The item matches the remote collection as of the last sync.
+    Synced,
+    /// The item was added locally and does not exist remotely yet.
+    Added,
+    /// The item was edited locally.
+    Modified,
+    /// The item was deleted locally.
+    Deleted,
+}
+
+impl SyncState {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SyncState::Synced => "synced",
+            SyncState::Added => "added",
+            SyncState::Modified => "modified",
+            SyncState::Deleted => "deleted",
+        }
+    }
+
+    fn from_column(idx: usize, text: &str) -> rusqlite::Result<Self> {
+        match text {
+            "synced" => Ok(SyncState::Synced),
+            "added" => Ok(SyncState::Added),
+            "modified" => Ok(SyncState::Modified),
+            "deleted" => Ok(SyncState::Deleted),
+            _ => Err(rusqlite::Error::FromSqlConversionFailure(
+                idx,
+                rusqlite::types::Type::Text,
+                format!("unknown sync state: {}", text).into(),
+            )),
+        }
+    }
+}
+
+/// How to resolve a conflict between local and remote changes to an item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    /// Keep the local changes, overwriting the remote item on the next sync.
+    KeepLocal,
+    /// Discard the local changes, and take the remote item on the next sync.
+    KeepRemote,
+}
+
+/// A local SQLite database of catalogue and collection data.
+#[derive(Debug)]
+pub struct SqliteStore {
+    conn: Connection,
+}
+
+impl SqliteStore {
+    /// Opens the database at `path`, creating it if needed, and applies any
+    /// pending schema migrations.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    /// Opens a new in-memory database.
+    pub fn open_in_memory() -> Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+        let store = Self { conn };
+        store.migrate()?;
+        Ok(store)
+    }
+
+    fn migrate(&self) -> Result<()> {
+        let version = self.schema_version()?;
+        for (i, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+            let tx = self.conn.unchecked_transaction()?;
+            tx.execute_batch(migration)?;
+            tx.pragma_update(None, "user_version", i as u32 + 1)?;
+            tx.commit()?;
+        }
+        Ok(())
+    }
+
+    /// Returns the version of the database schema.
+    pub fn schema_version(&self) -> Result<u32> {
+        Ok(self
+            .conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))?)
+    }
+
+    /// Returns the underlying connection, e.g. to run custom queries.
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Inserts or replaces a type and its tags.
+    pub fn upsert_type(&self, type_: &NumistaType) -> Result<()> {
+        let demonetization = type_.demonetization.as_ref();
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO types (id, title, category, issuer_code, issuer_name, min_year, max_year,
+                demonetized, demonetization_date)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+             ON CONFLICT (id) DO UPDATE SET
+                title = excluded.title,
+                category = excluded.category,
+                issuer_code = excluded.issuer_code,
+                issuer_name = excluded.issuer_name,
+                min_year = excluded.min_year,
+                max_year = excluded.max_year,
+                demonetized = excluded.demonetized,
+                demonetization_date = excluded.demonetization_date",
+            params![
+                type_.id,
+                type_.title,
+                to_text(&type_.category)?,
+                type_.issuer.as_ref().map(|i| &i.code),
+                type_.issuer.as_ref().map(|i| &i.name),
+                type_.min_year,
+                type_.max_year,
+                demonetization.is_some_and(|d| d.is_demonetized),
+                demonetization
+                    .and_then(|d| d.demonetization_date)
+                    .map(|date| date.to_string()),
+            ],
+        )?;
+        tx.execute("DELETE FROM type_tags WHERE type_id = ?1", [type_.id])?;
+        for tag in type_.tags.iter().flatten() {
+            tx.execute(
+                "INSERT OR IGNORE INTO type_tags (type_id, tag) VALUES (?1, ?2)",
+                params![type_.id, tag],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Inserts or replaces the issues of a type.
+    pub fn upsert_issues(&self, type_id: i64, issues: &[Issue]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for issue in issues {
+            tx.execute(
+                "INSERT OR REPLACE INTO issues
+                    (id, type_id, year, gregorian_year, min_year, max_year, mint_letter, mintage, comment)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    issue.id,
+                    type_id,
+                    issue.year,
+                    issue.gregorian_year,
+                    issue.min_year,
+                    issue.max_year,
+                    issue.mint_letter,
+                    issue.mintage,
+                    issue.comment,
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Inserts or replaces a collected item, as fetched from the API.
+    ///
+    /// This overwrites any local changes to the item.
+    pub fn upsert_collected_item(&self, item: &CollectedItem) -> Result<()> {
+        let record = CollectedItemRecord::from(item);
+        self.write_collected_item(&record, Some(&record.fingerprint()))
+    }
+
+    /// Gets a stored collected item by ID.
+    pub fn get_collected_item(&self, id: i64) -> Result<Option<CollectedItemRecord>> {
+        Ok(self
+            .conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM collected_items WHERE id = ?1",
+                    COLLECTED_ITEM_COLUMNS
+                ),
+                [id],
+                collected_item_from_row,
+            )
+            .optional()?)
+    }
+
+    /// Adds a collected item locally, to be pushed on the next sync.
+    ///
+    /// The ID of `record` is ignored. The item is given a negative ID until it
+    /// is pushed, which is returned.
+    pub fn add_local_item(&self, record: &CollectedItemRecord) -> Result<i64> {
+        let id: i64 = self.conn.query_row(
+            "SELECT MIN(COALESCE(MIN(id), 0), 0) - 1 FROM collected_items",
+            [],
+            |row| row.get(0),
+        )?;
+        let record = CollectedItemRecord {
+            id,
+            sync_state: SyncState::Added,
+            conflict: false,
+            ..record.clone()
+        };
+        self.write_collected_item(&record, None)?;
+        Ok(id)
+    }
+
+    /// Edits a stored collected item locally, to be pushed on the next sync.
+    pub fn edit_local_item(&self, record: &CollectedItemRecord) -> Result<()> {
+        self.conn.execute(
+            "UPDATE collected_items SET
+                type_id = ?2, type_title = ?3, issue_id = ?4, quantity = ?5, grade = ?6,
+                for_swap = ?7, private_comment = ?8, public_comment = ?9, collection_id = ?10,
+                storage_location = ?11, acquisition_date = ?12,
+                sync_state = CASE sync_state WHEN 'added' THEN 'added' ELSE 'modified' END
+             WHERE id = ?1",
+            params![
+                record.id,
+                record.type_id,
+                record.type_title,
+                record.issue_id,
+                record.quantity,
+                record.grade.as_ref().map(to_text).transpose()?,
+                record.for_swap,
+                record.private_comment,
+                record.public_comment,
+                record.collection_id,
+                record.storage_location,
+                record.acquisition_date.map(|d| d.to_string()),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Deletes a stored collected item locally, to be pushed on the next sync.
+    ///
+    /// Items which were added locally and never pushed are removed right away.
+    pub fn delete_local_item(&self, id: i64) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM collected_items WHERE id = ?1 AND sync_state = 'added'",
+            [id],
+        )?;
+        self.conn.execute(
+            "UPDATE collected_items SET sync_state = 'deleted' WHERE id = ?1",
+            [id],
+        )?;
+        Ok(())
+    }
+
+    /// Gets the stored collected items which are in conflict with the remote
+    /// collection, ordered by ID.
+    pub fn conflicts(&self) -> Result<Vec<CollectedItemRecord>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM collected_items WHERE conflict = 1 ORDER BY id",
+            COLLECTED_ITEM_COLUMNS
+        ))?;
+        let records = stmt
+            .query_map([], collected_item_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(records)
+    }
+
+    /// Resolves a conflict on a collected item. The resolution is applied on
+    /// the next sync.
+    pub fn resolve_conflict(&self, id: i64, resolution: Resolution) -> Result<()> {
+        let sql = match resolution {
+            // The remote fingerprint was updated when the conflict was
+            // detected, so the local changes are pushed on the next sync. If
+            // the item was deleted remotely, it is added again.
+            Resolution::KeepLocal => {
+                "UPDATE collected_items SET conflict = 0,
+                    sync_state = CASE
+                        WHEN remote_fingerprint IS NULL AND sync_state = 'modified' THEN 'added'
+                        ELSE sync_state
+                    END
+                 WHERE id = ?1 AND conflict = 1"
+            }
+            // Clearing the remote fingerprint makes the next sync pull the
+            // remote item.
+            Resolution::KeepRemote => {
+                "UPDATE collected_items
+                 SET conflict = 0, sync_state = 'synced', remote_fingerprint = NULL
+                 WHERE id = ?1 AND conflict = 1"
+            }
+        };
+        self.conn.execute(sql, [id])?;
+        Ok(())
+    }
+
+    /// Gets the fingerprint of the remote item as of the last sync.
+    pub(crate) fn remote_fingerprint(&self, id: i64) -> Result<Option<String>> {
+        Ok(self.conn.query_row(
+            "SELECT remote_fingerprint FROM collected_items WHERE id = ?1",
+            [id],
+            |row| row.get(0),
+        )?)
+    }
+
+    /// Flags a collected item as conflicting, recording the fingerprint of the
+    /// remote item, or `None` if it was deleted remotely.
+    pub(crate) fn flag_conflict(&self, id: i64, remote_fingerprint: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE collected_items SET conflict = 1, remote_fingerprint = ?2 WHERE id = ?1",
+            params![id, remote_fingerprint],
+        )?;
+        Ok(())
+    }
+
+    /// Removes a collected item from the store.
+    pub(crate) fn remove_collected_item(&self, id: i64) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM collected_items WHERE id = ?1", [id])?;
+        Ok(())
+    }
+
+    /// Replaces a locally added item with the item created remotely.
+    pub(crate) fn replace_local_item(&self, local_id: i64, item: &CollectedItem) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM collected_items WHERE id = ?1", [local_id])?;
+        self.upsert_collected_item(item)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn write_collected_item(
+        &self,
+        record: &CollectedItemRecord,
+        remote_fingerprint: Option<&str>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO collected_items
+                (id, type_id, type_title, issue_id, quantity, grade, for_swap, private_comment,
+                 public_comment, collection_id, storage_location, acquisition_date, sync_state,
+                 remote_fingerprint, conflict)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+            params![
+                record.id,
+                record.type_id,
+                record.type_title,
+                record.issue_id,
+                record.quantity,
+                record.grade.as_ref().map(to_text).transpose()?,
+                record.for_swap,
+                record.private_comment,
+                record.public_comment,
+                record.collection_id,
+                record.storage_location,
+                record.acquisition_date.map(|d| d.to_string()),
+                record.sync_state.as_str(),
+                remote_fingerprint,
+                record.conflict,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Gets a stored type by ID.
+    pub fn get_type(&self, id: i64) -> Result<Option<TypeRecord>> {
+        let record = self
+            .conn
+            .query_row(
+                &format!("SELECT {} FROM types WHERE id = ?1", TYPE_COLUMNS),
+                [id],
+                type_from_row,
+            )
+            .optional()?;
+        record.map(|r| self.with_tags(r)).transpose()
+    }
+
+    /// Gets all stored types, ordered by ID.
+    pub fn types(&self) -> Result<Vec<TypeRecord>> {
+        self.query_types(
+            &format!("SELECT {} FROM types ORDER BY id", TYPE_COLUMNS),
+            [],
+        )
+    }
+
+    /// Gets the stored types of an issuer, ordered by title.
+    pub fn types_by_issuer(&self, issuer_code: &str) -> Result<Vec<TypeRecord>> {
+        self.query_types(
+            &format!(
+                "SELECT {} FROM types WHERE issuer_code = ?1 ORDER BY title, id",
+                TYPE_COLUMNS
+            ),
+            [issuer_code],
+        )
+    }
+
+    /// Gets the stored types whose years overlap the range `min..=max`,
+    /// ordered by first year.
+    pub fn types_by_year_range(&self, min: i32, max: i32) -> Result<Vec<TypeRecord>> {
+        self.query_types(
+            &format!(
+                "SELECT {} FROM types
+                 WHERE COALESCE(min_year, max_year) <= ?2 AND COALESCE(max_year, min_year) >= ?1
+                 ORDER BY min_year, id",
+                TYPE_COLUMNS
+            ),
+            [min, max],
+        )
+    }
+
+    /// Gets the stored types with a tag, ordered by title.
+    pub fn types_by_tag(&self, tag: &str) -> Result<Vec<TypeRecord>> {
+        self.query_types(
+            &format!(
+                "SELECT {} FROM types JOIN type_tags ON type_id = id
+                 WHERE tag = ?1 ORDER BY title, id",
+                TYPE_COLUMNS
+            ),
+            [tag],
+        )
+    }
+
+    /// Gets the stored issues of a type, ordered by year.
+    pub fn issues_of_type(&self, type_id: i64) -> Result<Vec<IssueRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, type_id, year, gregorian_year, min_year, max_year, mint_letter, mintage, comment
+             FROM issues WHERE type_id = ?1 ORDER BY gregorian_year, id",
+        )?;
+        let records = stmt
+            .query_map([type_id], |row| {
+                Ok(IssueRecord {
+                    id: row.get(0)?,
+                    type_id: row.get(1)?,
+                    year: row.get(2)?,
+                    gregorian_year: row.get(3)?,
+                    min_year: row.get(4)?,
+                    max_year: row.get(5)?,
+                    mint_letter: row.get(6)?,
+                    mintage: row.get(7)?,
+                    comment: row.get(8)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(records)
+    }
+
+    /// Gets all stored collected items, ordered by ID.
+    ///
+    /// This includes items deleted locally but not yet synced; see
+    /// [`CollectedItemRecord::sync_state`].
+    pub fn collected_items(&self) -> Result<Vec<CollectedItemRecord>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM collected_items ORDER BY id",
+            COLLECTED_ITEM_COLUMNS
+        ))?;
+        let records = stmt
+            .query_map([], collected_item_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(records)
+    }
+
+    fn query_types<P: rusqlite::Params>(&self, sql: &str, params: P) -> Result<Vec<TypeRecord>> {
+        let mut stmt = self.conn.prepare(sql)?;
+        let records = stmt
+            .query_map(params, type_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        records.into_iter().map(|r| self.with_tags(r)).collect()
+    }
+
+    fn with_tags(&self, mut record: TypeRecord) -> Result<TypeRecord> {
+        let mut stmt = self
+            .conn
+            .prepare_cached("SELECT tag FROM type_tags WHERE type_id = ?1 ORDER BY tag")?;
+        record.tags = stmt
+            .query_map([record.id], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(record)
+    }
+}
+
+/// Converts a unit enum to the text used for it by the API.
+fn to_text<T: serde::Serialize>(value: &T) -> Result<String> {
+    match serde_json::to_value(value)? {
+        serde_json::Value::String(s) => Ok(s),
+        other => Ok(other.to_string()),
+    }
+}
+
+/// Converts text stored with [`to_text`] back to a unit enum.
+fn from_text<T: serde::de::DeserializeOwned>(idx: usize, text: String) -> rusqlite::Result<T> {
+    serde_json::from_value(serde_json::Value::String(text)).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+fn type_from_row(row: &Row) -> rusqlite::Result<TypeRecord> {
+    Ok(TypeRecord {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        category: from_text(2, row.get(2)?)?,
+        issuer_code: row.get(3)?,
+        issuer_name: row.get(4)?,
+        min_year: row.get(5)?,
+        max_year: row.get(6)?,
+        tags: Vec::new(),
+        demonetized: row.get(7)?,
+        demonetization_date: row
+            .get::<_, Option<String>>(8)?
+            .map(|date| {
+                date.parse().map_err(|e| {
+                    rusqlite::Error::FromSqlConversionFailure(
+                        8,
+                        rusqlite::types::Type::Text,
+                        Box::new(e),
+                    )
+                })
+            })
+            .transpose()?,
+    })
+}
+
+fn collected_item_from_row(row: &Row) -> rusqlite::Result<CollectedItemRecord> {
+    Ok(CollectedItemRecord {
+        id: row.get(0)?,
+        type_id: row.get(1)?,
+        type_title: row.get(2)?,
+        issue_id: row.get(3)?,
+        quantity: row.get(4)?,
+        grade: row
+            .get::<_, Option<String>>(5)?
+            .map(|g| from_text(5, g))
+            .transpose()?,
+        for_swap: row.get(6)?,
+        private_comment: row.get(7)?,
+        public_comment: row.get(8)?,
+        collection_id: row.get(9)?,
+        storage_location: row.get(10)?,
+        acquisition_date: row
+            .get::<_, Option<String>>(11)?
+            .map(|d| {
+                d.parse().map_err(|e| {
+                    rusqlite::Error::FromSqlConversionFailure(
+                        11,
+                        rusqlite::types::Type::Text,
+                        Box::new(e),
+                    )
+                })
+            })
+            .transpose()?,
+        sync_state: SyncState::from_column(12, &row.get::<_, String>(12)?)?,
+        conflict: row.get(13)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numista_type(
+        id: i64,
+        issuer: &str,
+        min_year: i32,
+        max_year: i32,
+        tags: &[&str],
+    ) -> NumistaType {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "title": format!("Type {}", id),
+            "category": "coin",
+            "issuer": { "code": issuer, "name": issuer.to_uppercase() },
+            "min_year": min_year,
+            "max_year": max_year,
+            "tags": tags,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_migrations() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        assert_eq!(store.schema_version().unwrap(), MIGRATIONS.len() as u32);
+        // Migrating again is a no-op.
+        store.migrate().unwrap();
+        assert_eq!(store.schema_version().unwrap(), MIGRATIONS.len() as u32);
+    }
+
+    #[test]
+    fn test_upsert_and_query_types() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        store
+            .upsert_type(&numista_type(1, "canada", 1858, 1901, &["Queen"]))
+            .unwrap();
+        store
+            .upsert_type(&numista_type(2, "canada", 1920, 1936, &["King"]))
+            .unwrap();
+        store
+            .upsert_type(&numista_type(3, "france", 1870, 1880, &["Queen", "Ship"]))
+            .unwrap();
+        // Upserting again replaces the tags.
+        store
+            .upsert_type(&numista_type(3, "france", 1870, 1880, &["Ship"]))
+            .unwrap();
+
+        let canada = store.types_by_issuer("canada").unwrap();
+        assert_eq!(canada.iter().map(|t| t.id).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(canada[0].issuer_name.as_deref(), Some("CANADA"));
+        assert_eq!(canada[0].category, Category::Coin);
+
+        let years = store.types_by_year_range(1875, 1925).unwrap();
+        assert_eq!(
+            years.iter().map(|t| t.id).collect::<Vec<_>>(),
+            vec![1, 3, 2]
+        );
+
+        let queens = store.types_by_tag("Queen").unwrap();
+        assert_eq!(queens.iter().map(|t| t.id).collect::<Vec<_>>(), vec![1]);
+
+        let ship = store.get_type(3).unwrap().unwrap();
+        assert_eq!(ship.tags, vec!["Ship".to_string()]);
+        assert!(store.get_type(4).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_discrepancies() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        let stored: NumistaType = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "title": "5 Cents",
+            "category": "coin",
+            "demonetization": { "is_demonetized": 0 },
+        }))
+        .unwrap();
+        store.upsert_type(&stored).unwrap();
+        let issues: Vec<Issue> =
+            serde_json::from_value(serde_json::json!([{ "id": 10 }, { "id": 11 }])).unwrap();
+        store.upsert_issues(1, &issues).unwrap();
+        let record = store.get_type(1).unwrap().unwrap();
+        let stored_issues = store.issues_of_type(1).unwrap();
+        assert_eq!(record.demonetized, Some(false));
+        assert!(record
+            .discrepancies(&stored_issues, &stored, &issues)
+            .is_empty());
+
+        let live: NumistaType = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "title": "5 Cents - Victoria",
+            "category": "coin",
+            "demonetization": { "is_demonetized": 1, "demonetization_date": "1920-01-01" },
+        }))
+        .unwrap();
+        let live_issues: Vec<Issue> =
+            serde_json::from_value(serde_json::json!([{ "id": 10 }, { "id": 12 }])).unwrap();
+        let discrepancies = record.discrepancies(&stored_issues, &live, &live_issues);
+        assert_eq!(
+            discrepancies
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "title changed from '5 Cents' to '5 Cents - Victoria'",
+                "demonetized on 1920-01-01",
+                "new issues: 12",
+                "removed issues: 11",
+            ]
+        );
+
+        // Once stored, the live type has no discrepancies.
+        store.upsert_type(&live).unwrap();
+        let record = store.get_type(1).unwrap().unwrap();
+        assert_eq!(
+            record.demonetization_date.unwrap().to_string(),
+            "1920-01-01"
+        );
+        assert!(record
+            .discrepancies(&stored_issues, &live, &issues)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_upsert_issues_and_collected_items() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        let issues: Vec<Issue> = serde_json::from_value(serde_json::json!([
+            { "id": 11, "year": 1859, "gregorian_year": 1859, "mintage": 500 },
+            { "id": 10, "year": 1858, "gregorian_year": 1858, "mint_letter": "H" },
+        ]))
+        .unwrap();
+        store.upsert_issues(1, &issues).unwrap();
+        let stored = store.issues_of_type(1).unwrap();
+        assert_eq!(
+            stored.iter().map(|i| i.id).collect::<Vec<_>>(),
+            vec![10, 11]
+        );
+        assert_eq!(stored[0].mint_letter.as_deref(), Some("H"));
+
+        let item: CollectedItem = serde_json::from_value(serde_json::json!({
+            "id": 5,
+            "quantity": 2,
+            "type": { "id": 1, "title": "Type 1", "category": "coin" },
+            "issue": { "id": 10 },
+            "for_swap": true,
+            "grade": "f/vf",
+            "acquisition_date": "2024-05-01",
+        }))
+        .unwrap();
+        store.upsert_collected_item(&item).unwrap();
+        let items = store.collected_items().unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].issue_id, Some(10));
+        assert_eq!(items[0].grade, Some(Grade::FVf));
+        assert!(items[0].for_swap);
+        assert_eq!(items[0].acquisition_date.unwrap().to_string(), "2024-05-01");
+    }
+}
@@ -0,0 +1,364 @@
+//! Two-way sync of a user's collection with a local [`SqliteStore`].
+//!
+//! Local changes are made with [`SqliteStore::add_local_item`],
+//! [`SqliteStore::edit_local_item`], and [`SqliteStore::delete_local_item`],
+//! then pushed by [`Engine::sync`], which also pulls any remote changes.
+//!
+//! An item changed both locally and remotely since the last sync is not
+//! overwritten in either direction. Instead it is flagged as a conflict, and
+//! left alone until resolved with [`SqliteStore::resolve_conflict`].
+//!
+//! This module is only available with the `store` feature.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use planchet::store::SqliteStore;
+//! use planchet::sync::Engine;
+//! use planchet::ClientBuilder;
+//!
+//! # async fn run() -> planchet::Result<()> {
+//! let client = ClientBuilder::new().bearer_token("YOUR_TOKEN").build()?;
+//! let mut store = SqliteStore::open("collection.db")?;
+//!
+//! let report = Engine::new(&client, &mut store, 123).sync().await?;
+//! for id in &report.conflicts {
+//!     println!("Item {} was changed both locally and remotely", id);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use crate::error::Result;
+use crate::models::request::{
+    AddCollectedItemParams, EditCollectedItemParams, GetCollectedItemsParams,
+};
+use crate::models::CollectedItem;
+use crate::store::{CollectedItemRecord, SqliteStore, SyncState};
+use crate::Client;
+use std::collections::HashMap;
+
+/// What a sync did.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    /// The IDs of items added or updated locally from the remote collection.
+    pub pulled: Vec<i64>,
+    /// The IDs of items removed locally because they were deleted remotely.
+    pub removed: Vec<i64>,
+    /// The local and remote IDs of items added remotely.
+    pub added: Vec<(i64, i64)>,
+    /// The IDs of items edited remotely.
+    pub edited: Vec<i64>,
+    /// The IDs of items deleted remotely.
+    pub deleted: Vec<i64>,
+    /// The IDs of items flagged as conflicts, including those flagged during
+    /// previous syncs and not yet resolved.
+    pub conflicts: Vec<i64>,
+}
+
+/// Reconciles a [`SqliteStore`] with the collection of a user.
+pub struct Engine<'a> {
+    client: &'a Client,
+    store: &'a mut SqliteStore,
+    user_id: i64,
+}
+
+impl<'a> Engine<'a> {
+    /// Creates a new `Engine`.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client, which must be authorized to edit the collection.
+    /// * `store` - The local store.
+    /// * `user_id` - The ID of the user whose collection is synced.
+    pub fn new(client: &'a Client, store: &'a mut SqliteStore, user_id: i64) -> Self {
+        Self {
+            client,
+            store,
+            user_id,
+        }
+    }
+
+    /// Pulls remote changes and pushes local changes.
+    ///
+    /// Each change is saved to the store as soon as it is made, so a sync
+    /// which fails part way can safely be retried.
+    pub async fn sync(&mut self) -> Result<SyncReport> {
+        let mut report = SyncReport::default();
+        let mut remote: HashMap<i64, CollectedItem> = self
+            .client
+            .get_collected_items(self.user_id, &GetCollectedItemsParams::new())
+            .await?
+            .items
+            .into_iter()
+            .map(|item| (item.id, item))
+            .collect();
+
+        for local in self.store.collected_items()? {
+            let remote_item = remote.remove(&local.id);
+            if local.conflict {
+                report.conflicts.push(local.id);
+                continue;
+            }
+            if local.sync_state == SyncState::Added {
+                let item = self
+                    .client
+                    .add_collected_item(self.user_id, &add_params(&local))
+                    .await?;
+                self.store.replace_local_item(local.id, &item)?;
+                report.added.push((local.id, item.id));
+                continue;
+            }
+
+            let base = self.store.remote_fingerprint(local.id)?;
+            let remote_fingerprint = remote_item
+                .as_ref()
+                .map(|item| CollectedItemRecord::from(item).fingerprint());
+            let changed_remotely = remote_fingerprint != base;
+
+            match (local.sync_state, remote_item) {
+                (SyncState::Synced, None) => {
+                    self.store.remove_collected_item(local.id)?;
+                    report.removed.push(local.id);
+                }
+                (SyncState::Synced, Some(item)) => {
+                    if changed_remotely {
+                        self.store.upsert_collected_item(&item)?;
+                        report.pulled.push(local.id);
+                    }
+                }
+                (SyncState::Deleted, None) => {
+                    self.store.remove_collected_item(local.id)?;
+                }
+                (_, _) if changed_remotely => {
+                    self.store
+                        .flag_conflict(local.id, remote_fingerprint.as_deref())?;
+                    report.conflicts.push(local.id);
+                }
+                (SyncState::Modified, _) => {
+                    let item = self
+                        .client
+                        .edit_collected_item(self.user_id, local.id, &edit_params(&local))
+                        .await?;
+                    self.store.upsert_collected_item(&item)?;
+                    report.edited.push(local.id);
+                }
+                (SyncState::Deleted, Some(_)) => {
+                    self.client
+                        .delete_collected_item(self.user_id, local.id)
+                        .await?;
+                    self.store.remove_collected_item(local.id)?;
+                    report.deleted.push(local.id);
+                }
+                (SyncState::Added, _) => unreachable!("added items are pushed above"),
+            }
+        }
+
+        // Whatever is left only exists remotely.
+        let mut new_items: Vec<_> = remote.into_values().collect();
+        new_items.sort_by_key(|item| item.id);
+        for item in new_items {
+            self.store.upsert_collected_item(&item)?;
+            report.pulled.push(item.id);
+        }
+
+        Ok(report)
+    }
+}
+
+fn add_params(record: &CollectedItemRecord) -> AddCollectedItemParams {
+    let mut params = AddCollectedItemParams::new(record.type_id)
+        .quantity(record.quantity)
+        .for_swap(record.for_swap);
+    params.issue = record.issue_id;
+    params.grade = record.grade.clone();
+    params.private_comment = record.private_comment.clone();
+    params.public_comment = record.public_comment.clone();
+    params.collection = record.collection_id;
+    params.storage_location = record.storage_location.clone();
+    params.acquisition_date = record.acquisition_date;
+    params
+}
+
+/// Builds the parameters to push a local edit.
+///
+/// Cleared fields are sent as `null`, which clears them remotely, except for
+/// the collection, which is left unchanged.
+fn edit_params(record: &CollectedItemRecord) -> EditCollectedItemParams {
+    let mut params = EditCollectedItemParams::new()
+        .type_id(record.type_id)
+        .quantity(record.quantity)
+        .for_swap(record.for_swap);
+    params.issue = Some(record.issue_id);
+    params.grade = Some(record.grade.clone());
+    params.private_comment = Some(record.private_comment.clone());
+    params.public_comment = Some(record.public_comment.clone());
+    params.collection = record.collection_id;
+    params.storage_location = Some(record.storage_location.clone());
+    params.acquisition_date = Some(record.acquisition_date);
+    params
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::Resolution;
+    use crate::ClientBuilder;
+    use serde_json::json;
+
+    fn item_json(id: i64, quantity: i64) -> serde_json::Value {
+        json!({
+            "id": id,
+            "quantity": quantity,
+            "type": { "id": 420, "title": "5 Cents - Victoria", "category": "coin" },
+            "for_swap": false,
+        })
+    }
+
+    fn item(id: i64, quantity: i64) -> CollectedItem {
+        serde_json::from_value(item_json(id, quantity)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_sync() {
+        let mut server = mockito::Server::new_async().await;
+        let client = ClientBuilder::new()
+            .api_key("test_key")
+            .base_url(server.url())
+            .build()
+            .unwrap();
+
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        for id in [1, 2, 3, 4, 5, 7] {
+            store.upsert_collected_item(&item(id, 1)).unwrap();
+        }
+        // Edited locally only.
+        let mut edited = store.get_collected_item(3).unwrap().unwrap();
+        edited.quantity = 3;
+        store.edit_local_item(&edited).unwrap();
+        // Edited both locally and remotely.
+        let mut conflicting = store.get_collected_item(4).unwrap().unwrap();
+        conflicting.quantity = 4;
+        store.edit_local_item(&conflicting).unwrap();
+        store.delete_local_item(5).unwrap();
+        let local_id = store
+            .add_local_item(&CollectedItemRecord::from(&item(0, 2)))
+            .unwrap();
+        assert_eq!(local_id, -1);
+
+        let remote = json!({
+            "item_count": 6,
+            "item_for_swap_count": 0,
+            "item_type_count": 1,
+            "item_type_for_swap_count": 0,
+            "items": [item_json(1, 1), item_json(2, 2), item_json(3, 1), item_json(4, 5), item_json(5, 1), item_json(6, 1)],
+        });
+        let get_mock = server
+            .mock("GET", "/users/9/collected_items")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(remote.to_string())
+            .create_async()
+            .await;
+        let add_mock = server
+            .mock("POST", "/users/9/collected_items")
+            .match_query(mockito::Matcher::Any)
+            .match_body(mockito::Matcher::PartialJson(
+                json!({ "type": 420, "quantity": 2 }),
+            ))
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(item_json(8, 2).to_string())
+            .create_async()
+            .await;
+        let edit_mock = server
+            .mock("PATCH", "/users/9/collected_items/3")
+            .match_query(mockito::Matcher::Any)
+            .match_body(mockito::Matcher::PartialJson(json!({ "quantity": 3 })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(item_json(3, 3).to_string())
+            .create_async()
+            .await;
+        let delete_mock = server
+            .mock("DELETE", "/users/9/collected_items/5")
+            .match_query(mockito::Matcher::Any)
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let report = Engine::new(&client, &mut store, 9).sync().await.unwrap();
+
+        get_mock.assert_async().await;
+        add_mock.assert_async().await;
+        edit_mock.assert_async().await;
+        delete_mock.assert_async().await;
+        assert_eq!(
+            report,
+            SyncReport {
+                pulled: vec![2, 6],
+                removed: vec![7],
+                added: vec![(-1, 8)],
+                edited: vec![3],
+                deleted: vec![5],
+                conflicts: vec![4],
+            }
+        );
+
+        let items = store.collected_items().unwrap();
+        assert_eq!(
+            items.iter().map(|i| (i.id, i.quantity)).collect::<Vec<_>>(),
+            vec![(1, 1), (2, 2), (3, 3), (4, 4), (6, 1), (8, 2)]
+        );
+        assert!(items
+            .iter()
+            .all(|i| i.sync_state == SyncState::Synced || i.id == 4));
+        assert_eq!(store.conflicts().unwrap()[0].id, 4);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_conflict() {
+        let mut server = mockito::Server::new_async().await;
+        let client = ClientBuilder::new()
+            .api_key("test_key")
+            .base_url(server.url())
+            .build()
+            .unwrap();
+
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        store.upsert_collected_item(&item(4, 1)).unwrap();
+        let mut conflicting = store.get_collected_item(4).unwrap().unwrap();
+        conflicting.quantity = 4;
+        store.edit_local_item(&conflicting).unwrap();
+        store
+            .flag_conflict(
+                4,
+                Some(&CollectedItemRecord::from(&item(4, 5)).fingerprint()),
+            )
+            .unwrap();
+
+        let remote = json!({
+            "item_count": 1,
+            "item_for_swap_count": 0,
+            "item_type_count": 1,
+            "item_type_for_swap_count": 0,
+            "items": [item_json(4, 5)],
+        });
+        let _get_mock = server
+            .mock("GET", "/users/9/collected_items")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(remote.to_string())
+            .create_async()
+            .await;
+
+        store.resolve_conflict(4, Resolution::KeepRemote).unwrap();
+        let report = Engine::new(&client, &mut store, 9).sync().await.unwrap();
+        assert_eq!(report.pulled, vec![4]);
+        let resolved = store.get_collected_item(4).unwrap().unwrap();
+        assert_eq!(resolved.quantity, 5);
+        assert_eq!(resolved.sync_state, SyncState::Synced);
+        assert!(!resolved.conflict);
+    }
+}
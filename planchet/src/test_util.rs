@@ -0,0 +1,852 @@
+//! Generators of valid but unusual API payloads, for property-based testing.
+//!
+//! The API is loose about its schema: optional fields are sometimes `null`
+//! and sometimes missing, IDs are sometimes strings, and strings hold
+//! anything from nothing to HTML. [`ArbitraryPayload`] generates JSON
+//! payloads exercising these variations for a model, and [`check_payloads`]
+//! deserializes many of them, reporting the first which fails with the seed
+//! reproducing it.
+//!
+//! Generation is deterministic: the same seed always gives the same payload.
+//!
+//! For property tests with [proptest](https://docs.rs/proptest),
+//! [`payload_strategy`] generates the payloads of a model as a strategy. A
+//! failing payload then shrinks towards the simplest one: optional fields
+//! missing, lists empty, and the first of the unusual values picked.
+//!
+//! To write tests of code using the models, [`ModelBuilder`] creates models
+//! with only the fields a test cares about, e.g. with
+//! [`NumistaType::builder`]. The other fields are `None`, or have plain
+//! default values when they are required.
+//!
+//! This module is only available with the `test-util` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use planchet::models::NumistaType;
+//! use planchet::test_util::check_payloads;
+//!
+//! if let Err(failure) = check_payloads::<NumistaType>(100, 42) {
+//!     panic!("{}", failure);
+//! }
+//! ```
+//!
+//! ```
+//! use planchet::models::{Category, CollectedItem, Grade, NumistaType};
+//!
+//! let t = NumistaType::builder()
+//!     .id(420)
+//!     .title("5 Cents - Victoria")
+//!     .category(Category::Coin)
+//!     .years(1858, 1901)
+//!     .build();
+//! let item = CollectedItem::builder().type_info(&t).grade(Grade::Vf).build();
+//! assert_eq!(item.type_info.title, "5 Cents - Victoria");
+//! assert_eq!(item.quantity, 1);
+//! ```
+//!
+//! ```
+//! use planchet::models::NumistaType;
+//! use planchet::test_util::payload_strategy;
+//! use proptest::prelude::*;
+//!
+//! proptest!(|(payload in payload_strategy::<NumistaType>())| {
+//!     prop_assert!(serde_json::from_value::<NumistaType>(payload).is_ok());
+//! });
+//! ```
+use crate::lang::SupportedLanguage;
+use crate::models::{
+    Category, CollectedItem, CollectedItems, CurrencyCode, Grade, GradePrices, Issue, MintDetail,
+    Number, NumistaType, Orientation, SearchTypeResult, SearchTypesResponse,
+};
+use chrono::NaiveDate;
+use proptest::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// Unusual strings the API can return.
+const STRINGS: &[&str] = &[
+    "",
+    " ",
+    "5 Cents - Victoria",
+    "¼ Dollar \"Washington Quarter\"",
+    "Ⅻ 漢字 ру́сский العربية 🪙",
+    "<em>Cast</em> Chinese Coins<br />",
+    "line\r\nbreaks\tand\ttabs",
+    "\u{0}\u{1f}",
+];
+
+/// Unusual decimals the API can return, within the range of `Decimal`.
+const DECIMALS: &[&str] = &[
+    "0",
+    "-0.0",
+    "0.25",
+    "1e3",
+    "0.000000001",
+    "-12.5",
+    "123456789012345.6789",
+    "12345678901234567890.5",
+    "-7922816251426433759354395033",
+];
+
+/// Where a [`Gen`] takes its choices from.
+enum Source {
+    Rng(fastrand::Rng),
+    Choices { choices: Vec<u32>, next: usize },
+}
+
+/// A deterministic generator of payload values.
+pub struct Gen {
+    source: Source,
+}
+
+impl Gen {
+    /// Creates a generator from a seed.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            source: Source::Rng(fastrand::Rng::with_seed(seed)),
+        }
+    }
+
+    /// Creates a generator making the given choices, in order.
+    ///
+    /// A choice of 0 is the simplest: an optional field missing, an empty
+    /// list, or the first value picked. Once the choices run out, the
+    /// simplest choices are made. This is how [`payload_strategy`] shrinks.
+    pub fn from_choices(choices: Vec<u32>) -> Self {
+        Self {
+            source: Source::Choices { choices, next: 0 },
+        }
+    }
+
+    /// Returns a number below `n`.
+    fn below(&mut self, n: usize) -> usize {
+        match &mut self.source {
+            Source::Rng(rng) => rng.usize(..n),
+            Source::Choices { choices, next } => {
+                let choice = choices.get(*next).copied().unwrap_or(0);
+                *next += 1;
+                choice as usize % n
+            }
+        }
+    }
+
+    /// Returns `true` with the given probability.
+    pub fn chance(&mut self, probability: f64) -> bool {
+        const STEPS: usize = 1_000;
+        // `false` is the simplest choice, made from 0.
+        self.below(STEPS) as f64 >= STEPS as f64 * (1.0 - probability)
+    }
+
+    /// Returns one of the values.
+    pub fn pick<T: Clone>(&mut self, values: &[T]) -> T {
+        values[self.below(values.len())].clone()
+    }
+
+    /// Returns a positive ID.
+    pub fn id(&mut self) -> i64 {
+        self.pick(&[1, 420, 99700, i64::from(i32::MAX), i64::MAX])
+    }
+
+    /// Returns a positive ID, as an integer or a string.
+    pub fn id_or_string(&mut self) -> Value {
+        let id = self.id();
+        if self.chance(0.5) {
+            json!(id.to_string())
+        } else {
+            json!(id)
+        }
+    }
+
+    /// Returns a year, possibly before the common era.
+    pub fn year(&mut self) -> Value {
+        json!(self.pick(&[-500, 0, 1, 1858, 2017, 9999]))
+    }
+
+    /// Returns a string.
+    pub fn string(&mut self) -> Value {
+        if self.chance(0.05) {
+            return json!("x".repeat(1_000));
+        }
+        json!(self.pick(STRINGS))
+    }
+
+    /// Returns a decimal number.
+    pub fn decimal(&mut self) -> Value {
+        let decimal = self.pick(DECIMALS);
+        serde_json::from_str(decimal).expect("decimals are valid JSON numbers")
+    }
+
+    /// Returns a URL.
+    pub fn url(&mut self) -> Value {
+        json!(self.pick(&[
+            "https://en.numista.com/catalogue/pieces420.html",
+            "http://www.usmint.gov",
+            "https://en.numista.com/catalogue/photos/canada/1-original.jpg?v=2",
+        ]))
+    }
+
+    /// Returns a grade.
+    pub fn grade(&mut self) -> Value {
+        json!(self.pick(&Grade::ALL))
+    }
+
+    /// Returns a category.
+    pub fn category(&mut self) -> Value {
+        json!(self.pick(&["coin", "banknote", "exonumia"]))
+    }
+
+    /// Returns a list of between 0 and `max` values.
+    pub fn list(&mut self, max: usize, mut value: impl FnMut(&mut Self) -> Value) -> Value {
+        let len = self.below(max + 1);
+        Value::Array((0..len).map(|_| value(self)).collect())
+    }
+
+    /// Inserts an optional field, which is either missing, `null`, or set.
+    pub fn optional(
+        &mut self,
+        object: &mut Map<String, Value>,
+        key: &str,
+        value: impl FnOnce(&mut Self) -> Value,
+    ) {
+        match self.below(3) {
+            0 => {}
+            1 => {
+                object.insert(key.to_string(), Value::Null);
+            }
+            _ => {
+                object.insert(key.to_string(), value(self));
+            }
+        }
+    }
+
+    fn issuer(&mut self) -> Value {
+        json!({"code": self.pick(&["canada", "etats-unis", ""]), "name": self.string()})
+    }
+}
+
+/// A model for which unusual but valid payloads can be generated.
+pub trait ArbitraryPayload {
+    /// Generates a payload which deserializes into the model.
+    fn arbitrary_payload(g: &mut Gen) -> Value;
+}
+
+impl<T: ArbitraryPayload> ArbitraryPayload for Vec<T> {
+    fn arbitrary_payload(g: &mut Gen) -> Value {
+        g.list(5, T::arbitrary_payload)
+    }
+}
+
+impl ArbitraryPayload for SearchTypeResult {
+    fn arbitrary_payload(g: &mut Gen) -> Value {
+        let mut t = Map::new();
+        t.insert("id".into(), json!(g.id()));
+        t.insert("title".into(), g.string());
+        g.optional(&mut t, "category", Gen::category);
+        g.optional(&mut t, "issuer", Gen::issuer);
+        g.optional(&mut t, "min_year", Gen::year);
+        g.optional(&mut t, "max_year", Gen::year);
+        g.optional(&mut t, "obverse_thumbnail", Gen::url);
+        g.optional(&mut t, "reverse_thumbnail", Gen::url);
+        Value::Object(t)
+    }
+}
+
+impl ArbitraryPayload for SearchTypesResponse {
+    fn arbitrary_payload(g: &mut Gen) -> Value {
+        let types = g.list(5, SearchTypeResult::arbitrary_payload);
+        json!({"count": g.pick(&[0, 5, i64::MAX]), "types": types})
+    }
+}
+
+impl ArbitraryPayload for NumistaType {
+    fn arbitrary_payload(g: &mut Gen) -> Value {
+        let mut t = Map::new();
+        t.insert("id".into(), json!(g.id()));
+        t.insert("title".into(), g.string());
+        t.insert("category".into(), g.category());
+        g.optional(&mut t, "url", Gen::url);
+        g.optional(&mut t, "issuer", Gen::issuer);
+        g.optional(&mut t, "min_year", Gen::year);
+        g.optional(&mut t, "max_year", Gen::year);
+        g.optional(&mut t, "type", Gen::string);
+        g.optional(&mut t, "value", |g| {
+            let mut v = Map::new();
+            g.optional(&mut v, "text", Gen::string);
+            g.optional(&mut v, "numeric_value", Gen::decimal);
+            g.optional(&mut v, "numerator", |g| json!(g.id()));
+            g.optional(&mut v, "denominator", |g| json!(g.id()));
+            Value::Object(v)
+        });
+        g.optional(&mut t, "ruler", |g| {
+            g.list(3, |g| json!({"id": g.id(), "name": g.string()}))
+        });
+        g.optional(&mut t, "shape", Gen::string);
+        g.optional(&mut t, "composition", |g| json!({"text": g.string()}));
+        g.optional(
+            &mut t,
+            "demonetization",
+            |g| json!({"is_demonetized": g.pick(&[json!(true), json!(0), json!("1")])}),
+        );
+        g.optional(&mut t, "weight", Gen::decimal);
+        g.optional(&mut t, "size", Gen::decimal);
+        g.optional(&mut t, "thickness", Gen::decimal);
+        g.optional(&mut t, "orientation", |g| {
+            json!(g.pick(&["coin", "medal", "variable", "three", "nine", "sideways"]))
+        });
+        g.optional(&mut t, "obverse", |g| {
+            let mut side = Map::new();
+            g.optional(&mut side, "description", Gen::string);
+            g.optional(&mut side, "lettering", Gen::string);
+            g.optional(&mut side, "lettering_scripts", |g| {
+                g.list(2, |g| json!({"name": g.string()}))
+            });
+            g.optional(&mut side, "picture", Gen::url);
+            Value::Object(side)
+        });
+        g.optional(&mut t, "mints", |g| {
+            g.list(3, |g| json!({"id": g.id_or_string(), "name": g.string()}))
+        });
+        g.optional(&mut t, "tags", |g| g.list(3, Gen::string));
+        g.optional(&mut t, "references", |g| {
+            g.list(
+                2,
+                |g| json!({"catalogue": {"id": g.id(), "code": g.string()}, "number": g.string()}),
+            )
+        });
+        Value::Object(t)
+    }
+}
+
+impl ArbitraryPayload for Issue {
+    fn arbitrary_payload(g: &mut Gen) -> Value {
+        let mut i = Map::new();
+        i.insert("id".into(), json!(g.id()));
+        g.optional(&mut i, "is_dated", |g| json!(g.chance(0.5)));
+        g.optional(&mut i, "year", Gen::year);
+        g.optional(&mut i, "calendar", Gen::string);
+        g.optional(&mut i, "gregorian_year", Gen::year);
+        g.optional(&mut i, "min_year", Gen::year);
+        g.optional(&mut i, "max_year", Gen::year);
+        g.optional(&mut i, "mint_letter", Gen::string);
+        g.optional(&mut i, "mintage", |g| json!(g.pick(&[0, 1, i64::MAX])));
+        g.optional(&mut i, "comment", Gen::string);
+        Value::Object(i)
+    }
+}
+
+impl ArbitraryPayload for GradePrices {
+    fn arbitrary_payload(g: &mut Gen) -> Value {
+        let prices = g.list(13, |g| json!({"grade": g.grade(), "price": g.decimal()}));
+        json!({"currency": g.pick(&["USD", "EUR", "JPY"]), "prices": prices})
+    }
+}
+
+impl ArbitraryPayload for MintDetail {
+    fn arbitrary_payload(g: &mut Gen) -> Value {
+        let mut m = Map::new();
+        m.insert("id".into(), g.id_or_string());
+        g.optional(&mut m, "name", Gen::string);
+        g.optional(&mut m, "local_name", Gen::string);
+        g.optional(&mut m, "place", Gen::string);
+        g.optional(&mut m, "country", Gen::issuer);
+        g.optional(&mut m, "start_year", Gen::year);
+        g.optional(&mut m, "end_year", Gen::year);
+        g.optional(&mut m, "nomisma_id", Gen::string);
+        g.optional(&mut m, "wikidata_id", Gen::string);
+        Value::Object(m)
+    }
+}
+
+impl ArbitraryPayload for CollectedItem {
+    fn arbitrary_payload(g: &mut Gen) -> Value {
+        let mut item = Map::new();
+        item.insert("id".into(), json!(g.id()));
+        item.insert("quantity".into(), json!(g.pick(&[0, 1, 1000])));
+        let mut t = Map::new();
+        t.insert("id".into(), json!(g.id()));
+        t.insert("title".into(), g.string());
+        t.insert("category".into(), g.category());
+        g.optional(&mut t, "issuer", Gen::issuer);
+        item.insert("type".into(), Value::Object(t));
+        item.insert(
+            "for_swap".into(),
+            g.pick(&[json!(false), json!(true), json!(0), json!(1), json!("1")]),
+        );
+        g.optional(&mut item, "issue", Issue::arbitrary_payload);
+        g.optional(&mut item, "grade", Gen::grade);
+        g.optional(&mut item, "private_comment", Gen::string);
+        g.optional(&mut item, "public_comment", Gen::string);
+        g.optional(
+            &mut item,
+            "price",
+            |g| json!({"value": g.decimal(), "currency": g.pick(&["USD", "EUR", "JPY"])}),
+        );
+        g.optional(
+            &mut item,
+            "collection",
+            |g| json!({"id": g.id(), "name": g.string()}),
+        );
+        g.optional(&mut item, "storage_location", Gen::string);
+        g.optional(&mut item, "acquisition_place", Gen::string);
+        g.optional(&mut item, "acquisition_date", |g| {
+            json!(g.pick(&["2017-09-01", "1900-02-28", "2024-02-29"]))
+        });
+        g.optional(&mut item, "serial_number", Gen::string);
+        g.optional(&mut item, "weight", Gen::decimal);
+        g.optional(&mut item, "size", Gen::decimal);
+        g.optional(&mut item, "axis", |g| json!(g.pick(&[0, 6, 12])));
+        Value::Object(item)
+    }
+}
+
+impl ArbitraryPayload for CollectedItems {
+    fn arbitrary_payload(g: &mut Gen) -> Value {
+        let items = g.list(4, CollectedItem::arbitrary_payload);
+        json!({
+            "item_count": g.pick(&[0, 3]),
+            "item_for_swap_count": 0,
+            "item_type_count": g.pick(&[0, 2]),
+            "item_type_for_swap_count": 0,
+            "items": items,
+        })
+    }
+}
+
+/// A generated payload which could not be deserialized.
+#[derive(Debug, Clone)]
+pub struct PayloadFailure {
+    /// The seed generating the payload.
+    pub seed: u64,
+    /// The payload.
+    pub payload: Value,
+    /// The deserialization error.
+    pub error: String,
+}
+
+impl fmt::Display for PayloadFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "payload from seed {} failed to deserialize: {}\n{}",
+            self.seed, self.error, self.payload
+        )
+    }
+}
+
+/// Generates `cases` payloads for a model, from the seeds `seed`,
+/// `seed + 1`, and so on, and deserializes them.
+///
+/// Payloads are deserialized both from a `Value` and from their JSON text.
+/// Returns the first failure, if any.
+pub fn check_payloads<T: ArbitraryPayload + DeserializeOwned>(
+    cases: u64,
+    seed: u64,
+) -> Result<(), PayloadFailure> {
+    for case_seed in seed..seed.saturating_add(cases) {
+        let payload = T::arbitrary_payload(&mut Gen::new(case_seed));
+        let result = serde_json::from_str::<T>(&payload.to_string())
+            .and_then(|_| serde_json::from_value::<T>(payload.clone()));
+        if let Err(e) = result {
+            return Err(PayloadFailure {
+                seed: case_seed,
+                payload,
+                error: e.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Returns a proptest strategy generating payloads for a model.
+///
+/// The payloads are the ones [`ArbitraryPayload`] generates, from choices
+/// which proptest shrinks, so that a failing payload is reported in its
+/// simplest form.
+pub fn payload_strategy<T: ArbitraryPayload>() -> impl Strategy<Value = Value> {
+    proptest::collection::vec(any::<u32>(), 0..256)
+        .prop_map(|choices| T::arbitrary_payload(&mut Gen::from_choices(choices)))
+}
+
+/// A builder of a model for tests, created by the `builder` function of the
+/// model, e.g. [`NumistaType::builder`].
+///
+/// The builder sets the fields of a body of the API, which [`build`] then
+/// deserializes, so that the models it creates are the ones the client would
+/// return for the same body. Fields without a setter can be set with
+/// [`field`].
+///
+/// [`build`]: ModelBuilder::build
+/// [`field`]: ModelBuilder::field
+#[derive(Debug, Clone)]
+pub struct ModelBuilder<T> {
+    body: Map<String, Value>,
+    model: PhantomData<T>,
+}
+
+impl<T: DeserializeOwned> ModelBuilder<T> {
+    fn new(body: Value) -> Self {
+        let Value::Object(body) = body else {
+            unreachable!("the default bodies of models are objects");
+        };
+        ModelBuilder {
+            body,
+            model: PhantomData,
+        }
+    }
+
+    /// Sets a field by its name in the API, e.g. `"min_year"`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value cannot be serialized.
+    pub fn field(mut self, name: &str, value: impl Serialize) -> Self {
+        let value = serde_json::to_value(value).expect("the value of a field must serialize");
+        self.body.insert(name.to_string(), value);
+        self
+    }
+
+    /// Creates the model.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the fields do not deserialize into the model, e.g. when a
+    /// field set with [`field`](ModelBuilder::field) has a value of the wrong
+    /// type.
+    pub fn build(self) -> T {
+        let body = Value::Object(self.body);
+        match serde_json::from_value(body.clone()) {
+            Ok(model) => model,
+            Err(e) => panic!("invalid model: {}\n{}", e, body),
+        }
+    }
+}
+
+impl NumistaType {
+    /// Returns a builder of a type, for tests. The type is a coin with the ID
+    /// 1 and the title "Type 1" unless set.
+    pub fn builder() -> ModelBuilder<NumistaType> {
+        ModelBuilder::new(json!({ "id": 1, "title": "Type 1", "category": "coin" }))
+    }
+}
+
+impl ModelBuilder<NumistaType> {
+    /// Sets the ID of the type.
+    pub fn id(self, id: i64) -> Self {
+        self.field("id", id)
+    }
+
+    /// Sets the title of the type.
+    pub fn title(self, title: &str) -> Self {
+        self.field("title", title)
+    }
+
+    /// Sets the category of the type.
+    pub fn category(self, category: Category) -> Self {
+        self.field("category", category)
+    }
+
+    /// Sets the issuer of the type, by its code and name.
+    pub fn issuer(self, code: &str, name: &str) -> Self {
+        self.field("issuer", json!({ "code": code, "name": name }))
+    }
+
+    /// Sets the first and last years of the type.
+    pub fn years(self, min_year: i32, max_year: i32) -> Self {
+        self.field("min_year", min_year).field("max_year", max_year)
+    }
+
+    /// Sets the weight of the type, in grams.
+    pub fn weight(self, weight: Number) -> Self {
+        self.field("weight", weight)
+    }
+
+    /// Sets the size of the type, in millimetres.
+    pub fn size(self, size: Number) -> Self {
+        self.field("size", size)
+    }
+
+    /// Sets the orientation of the type.
+    pub fn orientation(self, orientation: Orientation) -> Self {
+        self.field("orientation", orientation)
+    }
+
+    /// Sets the tags of the type.
+    pub fn tags(self, tags: &[&str]) -> Self {
+        self.field("tags", tags)
+    }
+
+    /// Sets the language of the type, as returned by [`NumistaType::lang`].
+    ///
+    /// This is not part of the body, so it is set on the model built.
+    pub fn lang(self, lang: SupportedLanguage) -> LangBuilder {
+        LangBuilder {
+            builder: self,
+            lang,
+        }
+    }
+}
+
+/// A builder of a type in a language, returned by
+/// [`ModelBuilder::<NumistaType>::lang`].
+#[derive(Debug, Clone)]
+pub struct LangBuilder {
+    builder: ModelBuilder<NumistaType>,
+    lang: SupportedLanguage,
+}
+
+impl LangBuilder {
+    /// Creates the type.
+    pub fn build(self) -> NumistaType {
+        let mut t = self.builder.build();
+        t.lang = Some(self.lang);
+        t
+    }
+}
+
+impl Issue {
+    /// Returns a builder of an issue, for tests. The issue has the ID 1 unless
+    /// set.
+    pub fn builder() -> ModelBuilder<Issue> {
+        ModelBuilder::new(json!({ "id": 1 }))
+    }
+}
+
+impl ModelBuilder<Issue> {
+    /// Sets the ID of the issue.
+    pub fn id(self, id: i64) -> Self {
+        self.field("id", id)
+    }
+
+    /// Sets the year of the issue, in the Gregorian calendar, and marks it as
+    /// dated.
+    pub fn year(self, year: i32) -> Self {
+        self.field("is_dated", true)
+            .field("year", year)
+            .field("gregorian_year", year)
+    }
+
+    /// Sets the mint letter of the issue.
+    pub fn mint_letter(self, mint_letter: &str) -> Self {
+        self.field("mint_letter", mint_letter)
+    }
+
+    /// Sets the mintage of the issue.
+    pub fn mintage(self, mintage: i64) -> Self {
+        self.field("mintage", mintage)
+    }
+
+    /// Sets the comment of the issue.
+    pub fn comment(self, comment: &str) -> Self {
+        self.field("comment", comment)
+    }
+}
+
+impl SearchTypeResult {
+    /// Returns a builder of a search result, for tests. The result is a coin
+    /// with the ID 1 and the title "Type 1" unless set.
+    pub fn builder() -> ModelBuilder<SearchTypeResult> {
+        ModelBuilder::new(json!({ "id": 1, "title": "Type 1", "category": "coin" }))
+    }
+}
+
+impl ModelBuilder<SearchTypeResult> {
+    /// Sets the ID of the type.
+    pub fn id(self, id: i64) -> Self {
+        self.field("id", id)
+    }
+
+    /// Sets the title of the type.
+    pub fn title(self, title: &str) -> Self {
+        self.field("title", title)
+    }
+
+    /// Sets the category of the type.
+    pub fn category(self, category: Category) -> Self {
+        self.field("category", category)
+    }
+
+    /// Sets the issuer of the type, by its code and name.
+    pub fn issuer(self, code: &str, name: &str) -> Self {
+        self.field("issuer", json!({ "code": code, "name": name }))
+    }
+
+    /// Sets the first and last years of the type.
+    pub fn years(self, min_year: i32, max_year: i32) -> Self {
+        self.field("min_year", min_year).field("max_year", max_year)
+    }
+}
+
+impl CollectedItem {
+    /// Returns a builder of a collected item, for tests. The item is one copy,
+    /// not for swap, with the ID 1, of the type built by default by
+    /// [`NumistaType::builder`], unless set.
+    pub fn builder() -> ModelBuilder<CollectedItem> {
+        ModelBuilder::new(json!({
+            "id": 1,
+            "quantity": 1,
+            "type": { "id": 1, "title": "Type 1", "category": "coin" },
+            "for_swap": false,
+        }))
+    }
+}
+
+impl ModelBuilder<CollectedItem> {
+    /// Sets the ID of the item.
+    pub fn id(self, id: i64) -> Self {
+        self.field("id", id)
+    }
+
+    /// Sets the number of copies of the item.
+    pub fn quantity(self, quantity: i64) -> Self {
+        self.field("quantity", quantity)
+    }
+
+    /// Sets the type of the item, from its ID, title, category and issuer.
+    pub fn type_info(self, t: &NumistaType) -> Self {
+        self.field(
+            "type",
+            json!({
+                "id": t.id,
+                "title": t.title,
+                "category": t.category,
+                "issuer": t.issuer,
+            }),
+        )
+    }
+
+    /// Sets the issue of the item.
+    pub fn issue(self, issue: &Issue) -> Self {
+        self.field("issue", issue)
+    }
+
+    /// Sets whether the item is for swap.
+    pub fn for_swap(self, for_swap: bool) -> Self {
+        self.field("for_swap", for_swap)
+    }
+
+    /// Sets the grade of the item.
+    pub fn grade(self, grade: Grade) -> Self {
+        self.field("grade", grade)
+    }
+
+    /// Sets the price of the item.
+    pub fn price(self, value: Number, currency: CurrencyCode) -> Self {
+        self.field("price", json!({ "value": value, "currency": currency }))
+    }
+
+    /// Sets the storage location of the item.
+    pub fn storage_location(self, storage_location: &str) -> Self {
+        self.field("storage_location", storage_location)
+    }
+
+    /// Sets the acquisition date of the item.
+    pub fn acquisition_date(self, date: NaiveDate) -> Self {
+        self.field("acquisition_date", date)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CASES: u64 = 500;
+
+    fn check<T: ArbitraryPayload + DeserializeOwned>() {
+        if let Err(failure) = check_payloads::<T>(CASES, 0) {
+            panic!("{}", failure);
+        }
+    }
+
+    #[test]
+    fn test_payloads_deserialize() {
+        check::<SearchTypesResponse>();
+        check::<NumistaType>();
+        check::<Vec<Issue>>();
+        check::<GradePrices>();
+        check::<MintDetail>();
+        check::<CollectedItems>();
+    }
+
+    #[test]
+    fn test_builders() {
+        let t = NumistaType::builder()
+            .id(420)
+            .title("5 Cents - Victoria")
+            .issuer("canada", "Canada")
+            .years(1858, 1901)
+            .tags(&["Queen"])
+            .lang(SupportedLanguage::French)
+            .build();
+        assert_eq!(t.id, 420);
+        assert_eq!(t.category, Category::Coin);
+        assert_eq!(t.issuer.as_ref().unwrap().name, "Canada");
+        assert_eq!((t.min_year, t.max_year), (Some(1858), Some(1901)));
+        assert_eq!(t.lang(), Some(SupportedLanguage::French));
+        assert!(t.weight.is_none());
+
+        let issue = Issue::builder().id(7).year(1858).mintage(1_500_000).build();
+        let item = CollectedItem::builder()
+            .type_info(&t)
+            .issue(&issue)
+            .grade(Grade::Vf)
+            .quantity(2)
+            .field("serial_number", "A123")
+            .build();
+        assert_eq!(item.type_info.id, 420);
+        assert_eq!(item.issue.unwrap().gregorian_year, Some(1858));
+        assert_eq!(item.grade, Some(Grade::Vf));
+        assert_eq!(item.serial_number.as_deref(), Some("A123"));
+        assert!(!item.for_swap);
+
+        let result = SearchTypeResult::builder()
+            .category(Category::Banknote)
+            .build();
+        assert_eq!(result.category, Some(Category::Banknote));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid model")]
+    fn test_builder_invalid_field() {
+        Issue::builder().field("year", "not a year").build();
+    }
+
+    #[test]
+    fn test_generation_is_deterministic() {
+        let a = CollectedItem::arbitrary_payload(&mut Gen::new(7));
+        let b = CollectedItem::arbitrary_payload(&mut Gen::new(7));
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_payload_strategy_shrinks() {
+        use proptest::test_runner::{Config, TestError, TestRunner};
+
+        // A property failing whenever the type has a URL shrinks to a type
+        // with only its required fields and the URL.
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            ..Config::default()
+        });
+        let result = runner.run(&payload_strategy::<NumistaType>(), |payload| {
+            prop_assert!(!payload["url"].is_string());
+            Ok(())
+        });
+        match result {
+            Err(TestError::Fail(_, payload)) => assert_eq!(
+                payload,
+                json!({
+                    "id": 1,
+                    "title": "",
+                    "category": "coin",
+                    "url": "https://en.numista.com/catalogue/pieces420.html",
+                })
+            ),
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//! Links between API objects and the pages of the Numista website.
+//!
+//! Each type has a page on the website, e.g.
+//! `https://en.numista.com/catalogue/pieces420.html` for the coin with ID 420.
+//! The path depends on the category of the type, and the subdomain on the
+//! language of the page. [`parse_type_url`] reads such a URL back into a
+//! [`TypeLink`], e.g. to look up a type shared as a link.
+//!
+//! # Examples
+//!
+//! ```
+//! use isolang::Language;
+//! use planchet::models::Category;
+//! use planchet::urls::{self, TypeLink};
+//!
+//! let link = urls::parse_type_url("https://fr.numista.com/catalogue/note2143.html").unwrap();
+//! assert_eq!(link.id, 2143);
+//! assert_eq!(link.category, Category::Banknote);
+//!
+//! let url = TypeLink::new(420, Category::Coin).lang(Language::Deu).url();
+//! assert_eq!(url.as_str(), "https://de.numista.com/catalogue/pieces420.html");
+//! ```
+use crate::models::Category;
+use isolang::Language;
+use url::Url;
+
+/// The domain of the website, under which each language has a subdomain.
+const DOMAIN: &str = "numista.com";
+
+/// The language of pages when none is given.
+const DEFAULT_LANG: &str = "en";
+
+/// Returns the prefix of the page names of types of a category.
+///
+/// Categories not known to this crate are assumed to be named after their
+/// category, like exonumia.
+fn page_prefix(category: &Category) -> &str {
+    match category {
+        Category::Coin => "pieces",
+        Category::Banknote => "note",
+        Category::Exonumia => "exonumia",
+        Category::Other(name) => name,
+    }
+}
+
+/// The page of a type on the website.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeLink {
+    /// The ID of the type.
+    pub id: i64,
+    /// The category of the type, which is part of the URL.
+    pub category: Category,
+    /// The language of the page, if it is not the default one (English).
+    pub lang: Option<Language>,
+}
+
+impl TypeLink {
+    /// Creates a link to the English page of a type.
+    pub fn new(id: i64, category: Category) -> Self {
+        Self {
+            id,
+            category,
+            lang: None,
+        }
+    }
+
+    /// Sets the language of the page.
+    pub fn lang(mut self, lang: Language) -> Self {
+        self.lang = Some(lang);
+        self
+    }
+
+    /// Returns the URL of the page.
+    pub fn url(&self) -> Url {
+        let lang = self.lang.and_then(|l| l.to_639_1()).unwrap_or(DEFAULT_LANG);
+        let url = format!(
+            "https://{}.{}/catalogue/{}{}.html",
+            lang,
+            DOMAIN,
+            page_prefix(&self.category),
+            self.id
+        );
+        Url::parse(&url).expect("type URLs are valid")
+    }
+}
+
+/// Parses the URL of the page of a type on the website.
+///
+/// Any language subdomain is accepted, as is the bare domain. Returns `None`
+/// if the URL is not the page of a type.
+pub fn parse_type_url(url: &str) -> Option<TypeLink> {
+    let url = Url::parse(url).ok()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+    let host = url.host_str()?;
+    let lang = match host.strip_suffix(DOMAIN)? {
+        "" | "www." => None,
+        subdomain => {
+            let code = subdomain.strip_suffix('.')?;
+            Some(Language::from_639_1(code)?).filter(|l| l.to_639_1() != Some(DEFAULT_LANG))
+        }
+    };
+
+    let mut segments = url.path_segments()?;
+    if segments.next()? != "catalogue" {
+        return None;
+    }
+    let page = segments.next()?.strip_suffix(".html")?;
+    if segments.next().is_some() {
+        return None;
+    }
+    [Category::Coin, Category::Banknote, Category::Exonumia]
+        .into_iter()
+        .find_map(|category| {
+            let digits = page.strip_prefix(page_prefix(&category))?;
+            if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            Some(TypeLink {
+                id: digits.parse().ok()?,
+                category,
+                lang,
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_type_url() {
+        let link = parse_type_url("https://en.numista.com/catalogue/pieces420.html").unwrap();
+        assert_eq!(link, TypeLink::new(420, Category::Coin));
+
+        let link = parse_type_url("http://numista.com/catalogue/exonumia7.html?tab=2#top").unwrap();
+        assert_eq!(link, TypeLink::new(7, Category::Exonumia));
+
+        let link = parse_type_url("https://es.numista.com/catalogue/note99.html").unwrap();
+        assert_eq!(
+            link,
+            TypeLink::new(99, Category::Banknote).lang(Language::Spa)
+        );
+
+        for url in [
+            "https://en.numista.com/catalogue/pieces.html",
+            "https://en.numista.com/catalogue/pieces42a.html",
+            "https://en.numista.com/catalogue/canada-1.html",
+            "https://en.numista.com/forum/pieces42.html",
+            "https://en.example.com/catalogue/pieces42.html",
+            "https://xx.numista.com/catalogue/pieces42.html",
+            "ftp://en.numista.com/catalogue/pieces42.html",
+            "not a url",
+        ] {
+            assert_eq!(parse_type_url(url), None, "{}", url);
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for link in [
+            TypeLink::new(420, Category::Coin),
+            TypeLink::new(1, Category::Banknote).lang(Language::Fra),
+            TypeLink::new(12345, Category::Exonumia).lang(Language::Deu),
+        ] {
+            assert_eq!(parse_type_url(link.url().as_str()), Some(link));
+        }
+    }
+}
@@ -0,0 +1,292 @@
+//! Client-side validation of request parameters.
+//!
+//! Search requests and collected items are validated before being sent, and
+//! rejected with [`Error::Validation`] listing every problem found, rather
+//! than with the generic error the API returns for the first one.
+use crate::error::{Error, Result};
+use crate::models::request::{
+    AddCollectedItemParams, EditCollectedItemParams, SearchByImageParams, SearchTypesParams,
+};
+use chrono::Datelike;
+use std::fmt;
+
+/// The maximum length, in characters, of a search query.
+pub const MAX_QUERY_LENGTH: usize = 255;
+
+/// The maximum number of results the API returns per request.
+pub const MAX_COUNT: i64 = 50;
+
+/// The earliest year accepted in year and date filters.
+pub const MIN_YEAR: i32 = -3000;
+
+/// The maximum size, in bytes, of the base64-encoded data of an image sent to
+/// search by image.
+pub const MAX_IMAGE_DATA_LENGTH: usize = 8 * 1024 * 1024;
+
+/// The highest axis of a collected item, as a clock hour. Axes go from 1 to
+/// 12, with 12 for medal alignment and 6 for coin alignment.
+pub const MAX_AXIS: i64 = 12;
+
+/// A problem with a request parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    /// The name of the parameter, as sent to the API.
+    pub field: String,
+    /// What is wrong with the parameter.
+    pub message: String,
+}
+
+impl ValidationIssue {
+    pub(crate) fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// Request parameters which can be checked before being sent.
+pub trait Validate {
+    /// Returns the problems with the parameters, if any.
+    fn validate(&self) -> Vec<ValidationIssue>;
+}
+
+/// Validates parameters, returning [`Error::Validation`] if there are any
+/// problems.
+pub(crate) fn check<T: Validate + ?Sized>(params: &T) -> Result<()> {
+    let issues = params.validate();
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::Validation(issues))
+    }
+}
+
+fn check_count(issues: &mut Vec<ValidationIssue>, field: &str, count: Option<i64>) {
+    if let Some(count) = count {
+        if !(1..=MAX_COUNT).contains(&count) {
+            issues.push(ValidationIssue::new(
+                field,
+                format!("must be between 1 and {}, got {}", MAX_COUNT, count),
+            ));
+        }
+    }
+}
+
+/// Checks a year filter, which is either a single year or a range such as
+/// `1850-1900`.
+fn check_years(issues: &mut Vec<ValidationIssue>, field: &str, value: Option<&str>) {
+    let Some(value) = value else {
+        return;
+    };
+    // The separator is the first `-` which is not a leading minus sign.
+    let range = value
+        .char_indices()
+        .skip(1)
+        .find(|&(_, c)| c == '-')
+        .map(|(i, _)| (&value[..i], &value[i + 1..]))
+        .unwrap_or((value, value));
+    let (Ok(min), Ok(max)) = (range.0.parse::<i32>(), range.1.parse::<i32>()) else {
+        issues.push(ValidationIssue::new(
+            field,
+            format!("expected a year or a range of years, got {:?}", value),
+        ));
+        return;
+    };
+
+    let max_year = chrono::Utc::now().year() + 1;
+    if min > max {
+        issues.push(ValidationIssue::new(
+            field,
+            format!("range starts after it ends: {}", value),
+        ));
+    }
+    for year in [min, max] {
+        if !(MIN_YEAR..=max_year).contains(&year) {
+            issues.push(ValidationIssue::new(
+                field,
+                format!("year {} is not between {} and {}", year, MIN_YEAR, max_year),
+            ));
+            break;
+        }
+    }
+}
+
+fn check_axis(issues: &mut Vec<ValidationIssue>, axis: Option<i64>) {
+    if let Some(axis) = axis {
+        if !(1..=MAX_AXIS).contains(&axis) {
+            issues.push(ValidationIssue::new(
+                "axis",
+                format!(
+                    "must be a clock hour between 1 and {}, got {}",
+                    MAX_AXIS, axis
+                ),
+            ));
+        }
+    }
+}
+
+impl Validate for SearchTypesParams {
+    fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        if let Some(q) = &self.q {
+            let length = q.chars().count();
+            if length > MAX_QUERY_LENGTH {
+                issues.push(ValidationIssue::new(
+                    "q",
+                    format!(
+                        "must be at most {} characters, got {}",
+                        MAX_QUERY_LENGTH, length
+                    ),
+                ));
+            }
+        }
+        check_years(&mut issues, "year", self.year.as_deref());
+        check_years(&mut issues, "date", self.date.as_deref());
+        if let Some(page) = self.page {
+            if page < 1 {
+                issues.push(ValidationIssue::new(
+                    "page",
+                    format!("must be at least 1, got {}", page),
+                ));
+            }
+        }
+        check_count(&mut issues, "count", self.count);
+        issues
+    }
+}
+
+impl Validate for SearchByImageParams {
+    fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        if self.images.is_empty() {
+            issues.push(ValidationIssue::new("images", "at least one image is required"));
+        }
+        for (i, image) in self.images.iter().enumerate() {
+            let field = format!("images[{}].image_data", i);
+            if image.image_data.is_empty() {
+                issues.push(ValidationIssue::new(field, "must not be empty"));
+            } else if image.image_data.len() > MAX_IMAGE_DATA_LENGTH {
+                issues.push(ValidationIssue::new(
+                    field,
+                    format!(
+                        "must be at most {} bytes once encoded, got {}",
+                        MAX_IMAGE_DATA_LENGTH,
+                        image.image_data.len()
+                    ),
+                ));
+            }
+        }
+        check_count(&mut issues, "max_results", self.max_results);
+        issues
+    }
+}
+
+impl Validate for AddCollectedItemParams {
+    fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        check_axis(&mut issues, self.axis);
+        issues
+    }
+}
+
+impl Validate for EditCollectedItemParams {
+    fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        check_axis(&mut issues, self.axis.flatten());
+        issues
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::request::{Image, MimeType};
+    use crate::models::Orientation;
+
+    fn fields(issues: &[ValidationIssue]) -> Vec<&str> {
+        issues.iter().map(|i| i.field.as_str()).collect()
+    }
+
+    #[test]
+    fn test_search_types_params() {
+        assert!(SearchTypesParams::new()
+            .q("victoria")
+            .year_range(-500, -100)
+            .date(1858)
+            .count(MAX_COUNT)
+            .validate()
+            .is_empty());
+
+        let issues = SearchTypesParams::new()
+            .q("x".repeat(MAX_QUERY_LENGTH + 1))
+            .year_range(1900, 1850)
+            .date(5000)
+            .page(0)
+            .count(MAX_COUNT + 1)
+            .validate();
+        assert_eq!(fields(&issues), vec!["q", "year", "date", "page", "count"]);
+        assert!(matches!(
+            check(&SearchTypesParams::new().count(0)),
+            Err(Error::Validation(issues)) if issues.len() == 1
+        ));
+    }
+
+    #[test]
+    fn test_search_by_image_params() {
+        let params = SearchByImageParams {
+            category: None,
+            images: vec![
+                Image {
+                    mime_type: MimeType::Jpeg,
+                    image_data: String::new(),
+                },
+                Image {
+                    mime_type: MimeType::Png,
+                    image_data: "A".repeat(MAX_IMAGE_DATA_LENGTH + 1),
+                },
+            ],
+            max_results: Some(0),
+        };
+        assert_eq!(
+            fields(&params.validate()),
+            vec!["images[0].image_data", "images[1].image_data", "max_results"]
+        );
+    }
+
+    #[test]
+    fn test_collected_item_axis() {
+        assert!(AddCollectedItemParams::new(1)
+            .axis(12)
+            .validate()
+            .is_empty());
+        assert_eq!(
+            fields(&AddCollectedItemParams::new(1).axis(0).validate()),
+            vec!["axis"]
+        );
+        assert_eq!(
+            fields(&EditCollectedItemParams::new().axis(13).validate()),
+            vec!["axis"]
+        );
+
+        // Rotations are rounded to the nearest clock hour.
+        let params = EditCollectedItemParams::new().axis_degrees(-5.0).unwrap();
+        assert_eq!(params.axis, Some(Some(12)));
+        let params = EditCollectedItemParams::new().axis_degrees(185.0).unwrap();
+        assert_eq!(params.axis, Some(Some(6)));
+        for degrees in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let err = EditCollectedItemParams::new()
+                .axis_degrees(degrees)
+                .unwrap_err();
+            assert!(matches!(err, Error::Validation(issues) if issues[0].field == "axis"));
+        }
+        let params = AddCollectedItemParams::new(1).orientation(Orientation::Nine);
+        assert_eq!(params.axis, Some(9));
+    }
+}
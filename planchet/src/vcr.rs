@@ -0,0 +1,407 @@
+//! Recording responses to cassettes, and replaying them in tests.
+//!
+//! When a [`Cassette`] is set with [`ClientBuilder::cassette`], the requests
+//! of the client go through it. While the cassette is recording, each request
+//! is sent and its response saved in the cassette file as an [`Interaction`].
+//! While it is replaying, no request is sent: each one gets the response
+//! recorded for the same method, path, query, and body, in the order they
+//! were recorded. This gives fast and deterministic tests of code built on
+//! this crate, run against real responses recorded once.
+//!
+//! Cassettes are YAML files if their name ends in `.yaml` or `.yml`, and JSON
+//! files otherwise. Request headers are not recorded, so neither the API key
+//! nor the bearer token end up in cassettes; neither do the `client_secret`
+//! and `code` query parameters, nor the `access_token` of OAuth responses,
+//! which are replaced with `REDACTED`. The rest of the responses is recorded
+//! as received, including the data of the collections fetched.
+//!
+//! Pictures downloaded with the client are not recorded.
+//!
+//! This module is only available with the `vcr` feature.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use planchet::vcr::{Cassette, Mode};
+//! use planchet::ClientBuilder;
+//!
+//! # async fn run() -> planchet::Result<()> {
+//! // Recorded on the first run, and replayed on the following ones.
+//! let cassette = Cassette::open("tests/cassettes/get_type.yaml", Mode::Once)?;
+//! let client = ClientBuilder::new()
+//!     .api_key("YOUR_API_KEY")
+//!     .cassette(cassette)
+//!     .build()?;
+//!
+//! let type_ = client.get_type(420).await?;
+//! assert_eq!(type_.id, 420);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`ClientBuilder::cassette`]: crate::ClientBuilder::cassette
+use crate::error::{Error, Result};
+use http::Extensions;
+use reqwest::ResponseBuilderExt;
+use reqwest_middleware::{Middleware, Next};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use url::Url;
+
+/// The value replacing credentials in cassettes.
+const REDACTED: &str = "REDACTED";
+
+/// The query parameters holding credentials.
+const SECRET_PARAMS: &[&str] = &["client_secret", "code"];
+
+/// Whether a cassette records or replays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    /// Replays the cassette if its file exists, and records it otherwise.
+    #[default]
+    Once,
+    /// Replays the cassette, failing the requests it has no response for.
+    Replay,
+    /// Sends every request, recording the cassette again from scratch.
+    Record,
+}
+
+/// A request, as recorded in a cassette.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    /// The HTTP method, e.g. `GET`.
+    pub method: String,
+    /// The path of the URL, with its query, e.g. `/types/420?lang=fr`.
+    pub path: String,
+    /// The body, if the request had one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+}
+
+/// A response, as recorded in a cassette.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    /// The HTTP status code.
+    pub status: u16,
+    /// The headers, by lowercase name.
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// The body.
+    #[serde(default)]
+    pub body: String,
+}
+
+/// A request and the response it got.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Interaction {
+    /// The request sent.
+    pub request: RecordedRequest,
+    /// The response received, with its credentials redacted.
+    pub response: RecordedResponse,
+}
+
+/// The content of a cassette file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Tape {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug)]
+struct State {
+    path: PathBuf,
+    recording: bool,
+    interactions: Vec<Interaction>,
+    /// Which interactions have been replayed.
+    replayed: Vec<bool>,
+}
+
+/// A file of recorded interactions with the API.
+#[derive(Debug, Clone)]
+pub struct Cassette {
+    state: Arc<Mutex<State>>,
+}
+
+impl Cassette {
+    /// Opens a cassette file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Vcr`] if the file cannot be read or parsed, or if it
+    /// does not exist in [`Mode::Replay`].
+    pub fn open<P: AsRef<Path>>(path: P, mode: Mode) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let recording = match mode {
+            Mode::Once => !path.exists(),
+            Mode::Replay => false,
+            Mode::Record => true,
+        };
+        let interactions = if recording {
+            Vec::new()
+        } else {
+            let content = fs::read_to_string(&path)
+                .map_err(|e| Error::Vcr(format!("cannot read {}: {}", path.display(), e)))?;
+            let tape: std::result::Result<Tape, String> = if is_yaml(&path) {
+                serde_yaml::from_str(&content).map_err(|e| e.to_string())
+            } else {
+                serde_json::from_str(&content).map_err(|e| e.to_string())
+            };
+            tape.map_err(|e| Error::Vcr(format!("cannot parse {}: {}", path.display(), e)))?
+                .interactions
+        };
+        let replayed = vec![false; interactions.len()];
+        Ok(Self {
+            state: Arc::new(Mutex::new(State {
+                path,
+                recording,
+                interactions,
+                replayed,
+            })),
+        })
+    }
+
+    /// Checks if the requests are sent and recorded, rather than replayed.
+    pub fn is_recording(&self) -> bool {
+        self.state.lock().unwrap().recording
+    }
+
+    /// Returns the interactions recorded so far, or loaded from the file.
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.state.lock().unwrap().interactions.clone()
+    }
+
+    /// Adds an interaction and saves the cassette file.
+    fn record(&self, interaction: Interaction) -> std::result::Result<(), CassetteError> {
+        let mut state = self.state.lock().unwrap();
+        state.interactions.push(interaction);
+        let tape = Tape {
+            interactions: state.interactions.clone(),
+        };
+        let content = if is_yaml(&state.path) {
+            serde_yaml::to_string(&tape).map_err(|e| CassetteError(e.to_string()))?
+        } else {
+            serde_json::to_string_pretty(&tape).map_err(|e| CassetteError(e.to_string()))?
+        };
+        if let Some(dir) = state.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(|e| CassetteError(e.to_string()))?;
+        }
+        fs::write(&state.path, content)
+            .map_err(|e| CassetteError(format!("cannot write {}: {}", state.path.display(), e)))
+    }
+
+    /// Finds the first response recorded for a request and not yet replayed.
+    fn replay(&self, request: &RecordedRequest) -> Option<RecordedResponse> {
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+        let i = (0..state.interactions.len())
+            .find(|&i| !state.replayed[i] && state.interactions[i].request == *request)?;
+        state.replayed[i] = true;
+        Some(state.interactions[i].response.clone())
+    }
+
+    fn path(&self) -> PathBuf {
+        self.state.lock().unwrap().path.clone()
+    }
+}
+
+fn is_yaml(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("yaml" | "yml")
+    )
+}
+
+/// Returns the path and query of a URL, with the credentials redacted.
+fn redacted_path(url: &Url) -> String {
+    let mut url = url.clone();
+    if url.query().is_some() {
+        let pairs: Vec<(String, String)> = url
+            .query_pairs()
+            .map(|(name, value)| {
+                let value = if SECRET_PARAMS.contains(&name.as_ref()) {
+                    REDACTED.to_string()
+                } else {
+                    value.into_owned()
+                };
+                (name.into_owned(), value)
+            })
+            .collect();
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+    match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    }
+}
+
+/// Replaces the access token of an OAuth response.
+fn redacted_body(body: String) -> String {
+    let Ok(serde_json::Value::Object(mut object)) = serde_json::from_str(&body) else {
+        return body;
+    };
+    match object.get_mut("access_token") {
+        Some(token) => {
+            *token = REDACTED.into();
+            serde_json::Value::Object(object).to_string()
+        }
+        None => body,
+    }
+}
+
+/// The error of a request which could not be recorded or replayed.
+#[derive(Debug)]
+pub(crate) struct CassetteError(pub(crate) String);
+
+impl fmt::Display for CassetteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for CassetteError {}
+
+/// Records or replays the requests passing through it with a [`Cassette`].
+pub(crate) struct VcrMiddleware {
+    cassette: Cassette,
+}
+
+impl VcrMiddleware {
+    pub(crate) fn new(cassette: Cassette) -> Self {
+        Self { cassette }
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for VcrMiddleware {
+    async fn handle(
+        &self,
+        req: reqwest::Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<reqwest::Response> {
+        let request = RecordedRequest {
+            method: req.method().to_string(),
+            path: redacted_path(req.url()),
+            body: req
+                .body()
+                .and_then(|b| b.as_bytes())
+                .map(|b| String::from_utf8_lossy(b).into_owned()),
+        };
+
+        if !self.cassette.is_recording() {
+            let url = req.url().clone();
+            let response = self.cassette.replay(&request).ok_or_else(|| {
+                reqwest_middleware::Error::middleware(CassetteError(format!(
+                    "no response recorded in {} for {} {}",
+                    self.cassette.path().display(),
+                    request.method,
+                    request.path
+                )))
+            })?;
+            return to_response(response, url).map_err(reqwest_middleware::Error::middleware);
+        }
+
+        let response = next.run(req, extensions).await?;
+        let url = response.url().clone();
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter(|(name, _)| *name != http::header::SET_COOKIE)
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        let body = String::from_utf8_lossy(&response.bytes().await?).into_owned();
+        let recorded = RecordedResponse {
+            status,
+            headers,
+            body,
+        };
+        let mut saved = recorded.clone();
+        saved.body = redacted_body(saved.body);
+        self.cassette
+            .record(Interaction {
+                request,
+                response: saved,
+            })
+            .map_err(reqwest_middleware::Error::middleware)?;
+        to_response(recorded, url).map_err(reqwest_middleware::Error::middleware)
+    }
+}
+
+/// Builds a response from a recorded one.
+fn to_response(
+    recorded: RecordedResponse,
+    url: Url,
+) -> std::result::Result<reqwest::Response, http::Error> {
+    let mut builder = http::Response::builder().status(recorded.status).url(url);
+    for (name, value) in &recorded.headers {
+        builder = builder.header(name, value);
+    }
+    Ok(builder.body(recorded.body)?.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redaction() {
+        let url = Url::parse(
+            "https://api.numista.com/v3/oauth_token?grant_type=authorization_code&code=abc&client_secret=s3cr3t",
+        )
+        .unwrap();
+        assert_eq!(
+            redacted_path(&url),
+            "/v3/oauth_token?grant_type=authorization_code&code=REDACTED&client_secret=REDACTED"
+        );
+        let url = Url::parse("https://api.numista.com/v3/types/420").unwrap();
+        assert_eq!(redacted_path(&url), "/v3/types/420");
+
+        let body = r#"{"access_token":"t0k3n","token_type":"bearer","user_id":1}"#;
+        let redacted: serde_json::Value =
+            serde_json::from_str(&redacted_body(body.into())).unwrap();
+        assert_eq!(redacted["access_token"], "REDACTED");
+        assert_eq!(redacted["user_id"], 1);
+        assert_eq!(redacted_body("not json".into()), "not json");
+    }
+
+    #[test]
+    fn test_replay_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cassette.json");
+        let cassette = Cassette::open(&path, Mode::Once).unwrap();
+        assert!(cassette.is_recording());
+        let request = RecordedRequest {
+            method: "GET".to_string(),
+            path: "/types/1".to_string(),
+            body: None,
+        };
+        for body in ["first", "second"] {
+            let response = RecordedResponse {
+                status: 200,
+                headers: BTreeMap::new(),
+                body: body.to_string(),
+            };
+            let interaction = Interaction {
+                request: request.clone(),
+                response,
+            };
+            cassette.record(interaction).unwrap();
+        }
+
+        let cassette = Cassette::open(&path, Mode::Once).unwrap();
+        assert!(!cassette.is_recording());
+        assert_eq!(cassette.replay(&request).unwrap().body, "first");
+        assert_eq!(cassette.replay(&request).unwrap().body, "second");
+        assert_eq!(cassette.replay(&request), None);
+
+        let missing = dir.path().join("missing.yaml");
+        assert!(matches!(
+            Cassette::open(&missing, Mode::Replay),
+            Err(Error::Vcr(_))
+        ));
+    }
+}
@@ -0,0 +1,123 @@
+//! Notifications of changes to a collection, by polling.
+//!
+//! The API does not push changes, so [`Client::watch_collection`] fetches the
+//! collected items of a user periodically, and compares each fetch with the
+//! previous one to find the items which were added, edited, or removed.
+//!
+//! [`Client::watch_collection`]: crate::Client::watch_collection
+use crate::client::Client;
+use crate::error::{Error, Result};
+use crate::models::{CollectedItem, GetCollectedItemsParams};
+use crate::validation::ValidationIssue;
+use futures::stream::{self, Stream};
+use std::collections::{BTreeMap, VecDeque};
+use std::time::Duration;
+use tokio::time::{Interval, MissedTickBehavior};
+
+/// A change to a collection, found by [`Client::watch_collection`].
+///
+/// [`Client::watch_collection`]: crate::Client::watch_collection
+#[derive(Debug, Clone)]
+pub enum CollectionEvent {
+    /// An item was added to the collection.
+    ItemAdded(CollectedItem),
+    /// An item of the collection was edited.
+    ItemEdited {
+        /// The item as of the previous fetch.
+        before: Box<CollectedItem>,
+        /// The item as of the last fetch.
+        after: Box<CollectedItem>,
+    },
+    /// An item was removed from the collection, as it was last fetched.
+    ItemRemoved(CollectedItem),
+}
+
+impl CollectionEvent {
+    /// Returns the ID of the item which changed.
+    pub fn item_id(&self) -> i64 {
+        match self {
+            CollectionEvent::ItemAdded(item) | CollectionEvent::ItemRemoved(item) => item.id,
+            CollectionEvent::ItemEdited { after, .. } => after.id,
+        }
+    }
+}
+
+type Items = BTreeMap<i64, CollectedItem>;
+
+/// Compares two fetches of a collection, in order of item ID.
+fn diff(before: &Items, after: &Items) -> Vec<CollectionEvent> {
+    let mut events = Vec::new();
+    for (id, item) in after {
+        match before.get(id) {
+            None => events.push(CollectionEvent::ItemAdded(item.clone())),
+            Some(old) if old != item => events.push(CollectionEvent::ItemEdited {
+                before: Box::new(old.clone()),
+                after: Box::new(item.clone()),
+            }),
+            Some(_) => {}
+        }
+    }
+    for (id, item) in before {
+        if !after.contains_key(id) {
+            events.push(CollectionEvent::ItemRemoved(item.clone()));
+        }
+    }
+    events
+}
+
+struct State {
+    client: Client,
+    user_id: i64,
+    interval: Interval,
+    items: Option<Items>,
+    pending: VecDeque<CollectionEvent>,
+}
+
+pub(crate) fn watch_collection<'a>(
+    client: &Client,
+    user_id: i64,
+    period: Duration,
+) -> Result<impl Stream<Item = Result<CollectionEvent>> + 'a> {
+    // `tokio::time::interval` panics on a zero period.
+    if period.is_zero() {
+        return Err(Error::Validation(vec![ValidationIssue::new(
+            "interval",
+            "must be greater than zero",
+        )]));
+    }
+    let mut interval = tokio::time::interval(period);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let state = State {
+        client: client.clone(),
+        user_id,
+        interval,
+        items: None,
+        pending: VecDeque::new(),
+    };
+    Ok(stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(event) = state.pending.pop_front() {
+                return Some((Ok(event), state));
+            }
+            state.interval.tick().await;
+            let fetched = match state
+                .client
+                .get_collected_items(state.user_id, &GetCollectedItemsParams::new())
+                .await
+            {
+                Ok(fetched) => fetched,
+                Err(e) => return Some((Err(e), state)),
+            };
+            let items: Items = fetched
+                .items
+                .into_iter()
+                .map(|item| (item.id, item))
+                .collect();
+            // The first fetch is the baseline, so it yields no events.
+            if let Some(previous) = &state.items {
+                state.pending.extend(diff(previous, &items));
+            }
+            state.items = Some(items);
+        }
+    }))
+}
@@ -0,0 +1,401 @@
+//! A list of issues whose prices are watched, to be alerted when they cross a
+//! threshold, e.g. to buy a coin once it becomes affordable.
+//!
+//! A [`Watchlist`] is plain data, which can be serialized to keep it between
+//! runs. [`Watchlist::check`] fetches the price of each watched issue in its
+//! grade, and reports an [`Alert`] for each watch whose price crossed its
+//! threshold since the previous check, so that running it periodically only
+//! reports each crossing once.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use planchet::models::{Grade, Number};
+//! use planchet::watchlist::{Watch, Watchlist};
+//! use planchet::ClientBuilder;
+//!
+//! # async fn run() -> planchet::Result<()> {
+//! let client = ClientBuilder::new().api_key("YOUR_API_KEY").build()?;
+//! let mut watchlist = Watchlist::new();
+//! watchlist.add(Watch::new(12345, 67890, Grade::Vf, Number::from(25)));
+//!
+//! let report = watchlist.check(&client).await;
+//! for alert in &report.alerts {
+//!     println!("Issue {} is now at {}", alert.watch.issue_id, alert.price);
+//! }
+//! for (id, error) in &report.errors {
+//!     eprintln!("Watch {} could not be checked: {}", id, error);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use crate::api::NumistaApi;
+use crate::error::Error;
+use crate::models::{CurrencyCode, Grade, GradePrices, Number};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+/// The side of its threshold a price is watched for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    /// Alert when the price falls to the threshold or below, e.g. to buy.
+    #[default]
+    Below,
+    /// Alert when the price rises to the threshold or above, e.g. to sell.
+    Above,
+}
+
+impl Direction {
+    /// Checks if a price is on the watched side of a threshold.
+    pub fn reached(&self, price: Number, threshold: Number) -> bool {
+        match self {
+            Direction::Below => price <= threshold,
+            Direction::Above => price >= threshold,
+        }
+    }
+}
+
+/// The price of an issue in a grade, watched for crossing a threshold.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Watch {
+    /// The ID of the watch in its list, set by [`Watchlist::add`].
+    pub id: u32,
+    pub type_id: i64,
+    pub issue_id: i64,
+    pub grade: Grade,
+    pub threshold: Number,
+    /// The currency of the threshold, or the default currency of the API.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<CurrencyCode>,
+    #[serde(default)]
+    pub direction: Direction,
+    /// The title of the type, to show the watch by.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// The price found by the previous check, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_price: Option<Number>,
+}
+
+impl Watch {
+    /// Creates a watch for the price of an issue in a grade falling to a
+    /// threshold.
+    pub fn new(type_id: i64, issue_id: i64, grade: Grade, threshold: Number) -> Self {
+        Self {
+            id: 0,
+            type_id,
+            issue_id,
+            grade,
+            threshold,
+            currency: None,
+            direction: Direction::Below,
+            title: None,
+            last_price: None,
+        }
+    }
+
+    /// Sets the currency of the threshold.
+    pub fn currency(mut self, currency: CurrencyCode) -> Self {
+        self.currency = Some(currency);
+        self
+    }
+
+    /// Sets the side of the threshold to watch the price for.
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Sets the title of the type, to show the watch by.
+    pub fn title<S: Into<String>>(mut self, title: S) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Checks if a price crossed the threshold since the previous check: it
+    /// reached the threshold, and the previous price, if any, did not.
+    pub fn crossed(&self, price: Number) -> bool {
+        let reached = |price| self.direction.reached(price, self.threshold);
+        reached(price) && !self.last_price.is_some_and(reached)
+    }
+}
+
+/// A watched price which crossed its threshold, found by
+/// [`Watchlist::check`].
+#[derive(Debug, Clone, Serialize)]
+pub struct Alert {
+    /// The watch, as of before the check.
+    pub watch: Watch,
+    /// The current price.
+    pub price: Number,
+    /// The currency of the price.
+    pub currency: CurrencyCode,
+}
+
+/// A list of watched prices.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Watchlist {
+    #[serde(default)]
+    pub watches: Vec<Watch>,
+}
+
+impl Watchlist {
+    /// Creates an empty list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a watch to the list, returning the ID given to it.
+    pub fn add(&mut self, mut watch: Watch) -> u32 {
+        watch.id = self.watches.iter().map(|w| w.id).max().unwrap_or(0) + 1;
+        watch.last_price = None;
+        let id = watch.id;
+        self.watches.push(watch);
+        id
+    }
+
+    /// Removes a watch by its ID, returning it if it was in the list.
+    pub fn remove(&mut self, id: u32) -> Option<Watch> {
+        let index = self.watches.iter().position(|w| w.id == id)?;
+        Some(self.watches.remove(index))
+    }
+
+    /// Fetches the price of each watched issue in its grade, and reports the
+    /// watches whose price crossed their threshold since the previous check.
+    ///
+    /// The prices of each issue are fetched once, whatever the number of its
+    /// watches. The prices found are kept as the last prices of the watches,
+    /// so the list should be saved after a check. Watches of an issue without
+    /// a price in their grade are left as they are.
+    ///
+    /// A watch whose prices cannot be fetched does not stop the check of the
+    /// others: its error is reported alongside the alerts, and it is left as
+    /// it is. Failed fetches are not kept, so each watch of the issue tries
+    /// again and reports its own error.
+    pub async fn check<A: NumistaApi>(&mut self, api: &A) -> CheckReport {
+        let mut fetched: HashMap<(i64, i64, Option<String>), GradePrices> = HashMap::new();
+        let mut report = CheckReport::default();
+        for watch in &mut self.watches {
+            let currency = watch.currency.as_ref().map(currency_code);
+            let key = (watch.type_id, watch.issue_id, currency);
+            let prices = match fetched.entry(key) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let (type_id, issue_id, currency) = entry.key();
+                    match api
+                        .get_prices(*type_id, *issue_id, currency.as_deref())
+                        .await
+                    {
+                        Ok(prices) => entry.insert(prices),
+                        Err(e) => {
+                            report.errors.push((watch.id, e));
+                            continue;
+                        }
+                    }
+                }
+            };
+            let Some(price) = prices.prices.iter().find(|p| p.grade == watch.grade) else {
+                continue;
+            };
+            if watch.crossed(price.price) {
+                report.alerts.push(Alert {
+                    watch: watch.clone(),
+                    price: price.price,
+                    currency: prices.currency.to_owned(),
+                });
+            }
+            watch.last_price = Some(price.price);
+        }
+        report
+    }
+}
+
+/// What a check of a [`Watchlist`] found.
+#[derive(Debug, Default)]
+pub struct CheckReport {
+    /// The watches whose price crossed their threshold.
+    pub alerts: Vec<Alert>,
+    /// The IDs of the watches whose prices could not be fetched, with the
+    /// error of each.
+    pub errors: Vec<(u32, Error)>,
+}
+
+#[cfg(feature = "iso-codes")]
+fn currency_code(currency: &CurrencyCode) -> String {
+    currency.code().to_string()
+}
+
+#[cfg(not(feature = "iso-codes"))]
+fn currency_code(currency: &CurrencyCode) -> String {
+    currency.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::DeleteOutcome;
+    use crate::error::{ApiError, Result};
+    use crate::models::request::{
+        AddCollectedItemParams, EditCollectedItemParams, GetCollectedItemsParams, OAuthTokenParams,
+        SearchByImageParams, SearchTypesParams,
+    };
+    use crate::models::response::{
+        CataloguesResponse, CollectionsResponse, IssuersResponse, MintsResponse,
+        SearchByImageResponse, SearchTypesResponse,
+    };
+    use crate::models::{
+        CollectedItem, CollectedItems, Issue, MintDetail, NumistaType, OAuthToken, Publication,
+        User,
+    };
+
+    /// Has prices for the issues of type 1, and fails to get any other.
+    struct Prices;
+
+    #[async_trait::async_trait]
+    impl NumistaApi for Prices {
+        async fn get_prices(
+            &self,
+            type_id: i64,
+            _issue_id: i64,
+            _currency: Option<&str>,
+        ) -> Result<GradePrices> {
+            if type_id != 1 {
+                return Err(Error::ApiError(ApiError::new(500, "Server error")));
+            }
+            Ok(serde_json::from_value(serde_json::json!({
+                "currency": "EUR",
+                "prices": [{ "grade": "vf", "price": 8 }]
+            }))?)
+        }
+
+        async fn get_type(&self, _: i64) -> Result<NumistaType> {
+            unimplemented!()
+        }
+        async fn get_issues(&self, _: i64) -> Result<Vec<Issue>> {
+            unimplemented!()
+        }
+        async fn search_types(&self, _: &SearchTypesParams) -> Result<SearchTypesResponse> {
+            unimplemented!()
+        }
+        async fn get_issuers(&self) -> Result<IssuersResponse> {
+            unimplemented!()
+        }
+        async fn get_mints(&self) -> Result<MintsResponse> {
+            unimplemented!()
+        }
+        async fn get_mint(&self, _: i64) -> Result<MintDetail> {
+            unimplemented!()
+        }
+        async fn get_catalogues(&self) -> Result<CataloguesResponse> {
+            unimplemented!()
+        }
+        async fn get_publication(&self, _: &str) -> Result<Publication> {
+            unimplemented!()
+        }
+        async fn get_user(&self, _: i64) -> Result<User> {
+            unimplemented!()
+        }
+        async fn get_user_collections(&self, _: i64) -> Result<CollectionsResponse> {
+            unimplemented!()
+        }
+        async fn get_collected_items(
+            &self,
+            _: i64,
+            _: &GetCollectedItemsParams,
+        ) -> Result<CollectedItems> {
+            unimplemented!()
+        }
+        async fn get_collected_item(&self, _: i64, _: i64) -> Result<CollectedItem> {
+            unimplemented!()
+        }
+        async fn add_collected_item(
+            &self,
+            _: i64,
+            _: &AddCollectedItemParams,
+        ) -> Result<CollectedItem> {
+            unimplemented!()
+        }
+        async fn edit_collected_item(
+            &self,
+            _: i64,
+            _: i64,
+            _: &EditCollectedItemParams,
+        ) -> Result<CollectedItem> {
+            unimplemented!()
+        }
+        async fn delete_collected_item(&self, _: i64, _: i64) -> Result<DeleteOutcome> {
+            unimplemented!()
+        }
+        async fn get_oauth_token(&self, _: &OAuthTokenParams) -> Result<OAuthToken> {
+            unimplemented!()
+        }
+        async fn search_by_image(&self, _: &SearchByImageParams) -> Result<SearchByImageResponse> {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn test_crossed() {
+        let mut watch = Watch::new(1, 2, Grade::Vf, Number::from(10));
+        assert!(watch.crossed(Number::from(10)));
+        assert!(!watch.crossed(Number::from(11)));
+
+        // Only the first check below the threshold is a crossing.
+        watch.last_price = Some(Number::from(9));
+        assert!(!watch.crossed(Number::from(8)));
+        watch.last_price = Some(Number::from(12));
+        assert!(watch.crossed(Number::from(8)));
+
+        let watch = watch.direction(Direction::Above);
+        assert!(!watch.crossed(Number::from(15)));
+        let watch = Watch {
+            last_price: Some(Number::from(8)),
+            ..watch
+        };
+        assert!(watch.crossed(Number::from(15)));
+    }
+
+    #[test]
+    fn test_add_and_remove() {
+        let mut watchlist = Watchlist::new();
+        assert_eq!(
+            watchlist.add(Watch::new(1, 2, Grade::Vf, Number::from(10))),
+            1
+        );
+        assert_eq!(
+            watchlist.add(Watch::new(3, 4, Grade::F, Number::from(5))),
+            2
+        );
+        assert_eq!(watchlist.remove(1).map(|w| w.type_id), Some(1));
+        assert!(watchlist.remove(1).is_none());
+        assert_eq!(
+            watchlist.add(Watch::new(5, 6, Grade::Xf, Number::from(20))),
+            3
+        );
+
+        let json = serde_json::to_string(&watchlist).unwrap();
+        let watchlist: Watchlist = serde_json::from_str(&json).unwrap();
+        assert_eq!(watchlist.watches.len(), 2);
+        assert_eq!(watchlist.watches[1].grade, Grade::Xf);
+    }
+
+    #[tokio::test]
+    async fn test_check_with_errors() {
+        let mut watchlist = Watchlist::new();
+        watchlist.add(Watch::new(2, 1, Grade::Vf, Number::from(10)));
+        watchlist.add(Watch::new(1, 1, Grade::Vf, Number::from(10)));
+        watchlist.add(Watch::new(2, 1, Grade::F, Number::from(10)));
+
+        // The failures of type 2 do not stop the check of type 1.
+        let report = watchlist.check(&Prices).await;
+        assert_eq!(report.alerts.len(), 1);
+        assert_eq!(report.alerts[0].watch.id, 2);
+        let failed: Vec<u32> = report.errors.iter().map(|(id, _)| *id).collect();
+        assert_eq!(failed, [1, 3]);
+        assert!(report.errors[0].1.is_server());
+        assert_eq!(watchlist.watches[0].last_price, None);
+        assert_eq!(watchlist.watches[1].last_price, Some(Number::from(8)));
+    }
+}
@@ -1,13 +1,18 @@
 use planchet::{
-    model::{
+    auth::AuthProvider,
+    models::{
         self, AddCollectedItemParams, Category, EditCollectedItemParams, GetCollectedItemsParams,
         GrantType, OAuthTokenParams, Orientation, SearchByImageParams, SearchTypesParams,
     },
-    ClientBuilder, Error,
+    oauth::{Scope, Token},
+    pagination::{PaginationError, StreamOptions},
+    sort::SortBy,
+    watch::CollectionEvent,
+    AddOutcome, ClientBuilder, Error, ErrorKind, SupportedLanguage,
 };
 use futures::StreamExt;
+use reqwest::header::{HeaderMap, HeaderValue};
 use rust_decimal::Decimal;
-use serde_json;
 
 #[tokio::test]
 async fn get_publication_full_test() {
@@ -72,6 +77,39 @@ async fn get_publication_full_test() {
 
     mock.assert();
     assert_eq!(response.id, "L106610");
+    assert_eq!(response.year, Some(2017));
+    assert!(response.isbn10.unwrap().is_valid());
+    assert_eq!(response.isbn13.unwrap().to_string(), "9781787194946");
+    assert_eq!(response.contributors.unwrap()[0].id, Some(369));
+    assert_eq!(response.publication_places.unwrap()[0].geonames_id, Some(2643743));
+}
+
+#[tokio::test]
+async fn numista_api_trait_test() {
+    use planchet::NumistaApi;
+
+    async fn type_title(api: &dyn NumistaApi, type_id: i64) -> String {
+        api.get_type(type_id).await.unwrap().title
+    }
+
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let mock = server
+        .mock("GET", "/types/420")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id": 420, "url": "https://en.numista.com/catalogue/pieces420.html", "title": "5 Cents - Victoria", "category": "coin"}"#)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key")
+        .base_url(url)
+        .build()
+        .unwrap();
+
+    assert_eq!(type_title(&client, 420).await, "5 Cents - Victoria");
+    mock.assert();
 }
 
 #[tokio::test]
@@ -164,7 +202,7 @@ async fn get_type_full_test() {
     assert_eq!(currency.id, 59);
     assert_eq!(currency.name, "Dollar");
     assert_eq!(currency.full_name, "Dollar (1785-date)");
-    assert_eq!(response.demonetization.unwrap().is_demonetized, false);
+    assert!(!response.demonetization.unwrap().is_demonetized);
     assert_eq!(response.size.unwrap(), Decimal::new(243, 1));
     assert_eq!(response.thickness.unwrap(), Decimal::new(175, 2));
     assert_eq!(response.shape.unwrap(), "Round");
@@ -177,7 +215,7 @@ async fn get_type_full_test() {
     assert_eq!(obverse.lettering.unwrap(), "UNITED STATES OF AMERICA\r\nIN \r\nGOD WE \r\nTRUST\r\nLIBERTY  P\r\nJF  WC\r\nQUARTER DOLLAR");
     let obverse_lettering_scripts = obverse.lettering_scripts.unwrap();
     assert_eq!(obverse_lettering_scripts.len(), 1);
-    assert_eq!(obverse_lettering_scripts[0].name, "Latin");
+    assert_eq!(obverse_lettering_scripts[0].name, models::Script::Latin);
     assert_eq!(obverse.picture.unwrap().as_str(), "https://en.numista.com/catalogue/photos/etats-unis/5044-original.jpg");
     assert_eq!(obverse.thumbnail.unwrap().as_str(), "https://en.numista.com/catalogue/photos/etats-unis/5044-180.jpg");
     assert_eq!(obverse.picture_copyright.unwrap(), "Image courtesy of United States Mint");
@@ -187,7 +225,7 @@ async fn get_type_full_test() {
     assert_eq!(reverse.lettering.unwrap(), "GEORGE ROGERS CLARK\r\nMG\r\nFM\r\nINDIANA   2017   E PLURIBUS UNUM");
     let reverse_lettering_scripts = reverse.lettering_scripts.unwrap();
     assert_eq!(reverse_lettering_scripts.len(), 1);
-    assert_eq!(reverse_lettering_scripts[0].name, "Latin");
+    assert_eq!(reverse_lettering_scripts[0].name, models::Script::Latin);
     assert_eq!(reverse.picture.unwrap().as_str(), "https://en.numista.com/catalogue/photos/etats-unis/5045-original.jpg");
     assert_eq!(reverse.thumbnail.unwrap().as_str(), "https://en.numista.com/catalogue/photos/etats-unis/5045-180.jpg");
     assert_eq!(reverse.picture_copyright.unwrap(), "United States Mint");
@@ -359,8 +397,8 @@ async fn stream_all_types_test() {
     let params = SearchTypesParams::new().q("victoria");
     let stream = client.stream_all_types(params);
 
-    let results: Vec<Result<model::SearchTypeResult, Error>> = stream.collect().await;
-    let results: Result<Vec<model::SearchTypeResult>, Error> = results.into_iter().collect();
+    let results: Vec<Result<models::SearchTypeResult, Error>> = stream.collect().await;
+    let results: Result<Vec<models::SearchTypeResult>, Error> = results.into_iter().collect();
     let results = results.unwrap();
 
     assert_eq!(results.len(), 2);
@@ -368,6 +406,337 @@ async fn stream_all_types_test() {
     assert_eq!(results[1].id, 2);
 }
 
+#[tokio::test]
+async fn stream_types_deduplicate_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    // A type added to the catalogue between the two requests pushes type 2
+    // onto the second page.
+    for (page, body) in [
+        ("1", r#"{"count": 5, "types": [{"id": 1, "title": "A"}, {"id": 2, "title": "B"}]}"#),
+        ("2", r#"{"count": 5, "types": [{"id": 2, "title": "B"}, {"id": 3, "title": "C"}]}"#),
+        ("3", r#"{"count": 5, "types": [{"id": 4, "title": "D"}]}"#),
+    ] {
+        server
+            .mock("GET", "/types")
+            .match_query(mockito::Matcher::UrlEncoded("page".into(), page.into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(body)
+            .create();
+    }
+
+    let client = ClientBuilder::new()
+        .api_key("test_key")
+        .base_url(url)
+        .build()
+        .unwrap();
+
+    let mut stream = client.stream_types(
+        SearchTypesParams::new(),
+        StreamOptions::new().deduplicate(true),
+    );
+    let mut ids = Vec::new();
+    while let Some(t) = stream.next().await {
+        ids.push(t.unwrap().id);
+    }
+    assert_eq!(ids, vec![1, 2, 3, 4]);
+    let stats = stream.stats();
+    assert_eq!(stats.duplicates, 1);
+    assert_eq!(stats.pages_fetched, 3);
+    assert_eq!(stats.items, 4);
+    assert_eq!(stats.expected_items, Some(5));
+    assert!(stats.completed);
+
+    let all: Vec<_> = client.stream_all_types(SearchTypesParams::new()).collect().await;
+    assert_eq!(all.len(), 5);
+}
+
+#[tokio::test]
+async fn stream_types_guards_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    // "repeat" returns the same page forever and "shrink" loses results after
+    // the first page.
+    for (q, page, body) in [
+        ("repeat", "1", r#"{"count": 10, "types": [{"id": 1, "title": "A"}]}"#),
+        ("repeat", "2", r#"{"count": 10, "types": [{"id": 1, "title": "A"}]}"#),
+        ("shrink", "1", r#"{"count": 3, "types": [{"id": 1, "title": "A"}]}"#),
+        ("shrink", "2", r#"{"count": 2, "types": [{"id": 3, "title": "C"}]}"#),
+        ("retry", "1", r#"{"count": 2, "types": [{"id": 1, "title": "A"}]}"#),
+    ] {
+        server
+            .mock("GET", "/types")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("q".into(), q.into()),
+                mockito::Matcher::UrlEncoded("page".into(), page.into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(body)
+            .create();
+    }
+
+    // The second page of "retry" fails once, then succeeds.
+    let retry_page = mockito::Matcher::AllOf(vec![
+        mockito::Matcher::UrlEncoded("q".into(), "retry".into()),
+        mockito::Matcher::UrlEncoded("page".into(), "2".into()),
+    ]);
+    let failing = server
+        .mock("GET", "/types")
+        .match_query(retry_page.clone())
+        .with_status(503)
+        .with_body(r#"{"error_message": "Unavailable"}"#)
+        .expect(1)
+        .create();
+    server
+        .mock("GET", "/types")
+        .match_query(retry_page)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"count": 2, "types": [{"id": 2, "title": "B"}]}"#)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key")
+        .base_url(url)
+        .build()
+        .unwrap();
+
+    let results: Vec<_> = client
+        .stream_types(SearchTypesParams::new().q("repeat"), StreamOptions::new())
+        .collect()
+        .await;
+    assert_eq!(results.len(), 2);
+    assert!(matches!(
+        results[1],
+        Err(Error::Pagination(PaginationError::RepeatedPage { page: 2 }))
+    ));
+
+    let mut stream = client.stream_types(
+        SearchTypesParams::new().q("repeat"),
+        StreamOptions::new().max_pages(1),
+    );
+    assert!(stream.next().await.unwrap().is_ok());
+    assert!(matches!(
+        stream.next().await,
+        Some(Err(Error::Pagination(PaginationError::MaxPagesReached { max_pages: 1 })))
+    ));
+    assert!(stream.next().await.is_none());
+    assert!(!stream.stats().completed);
+
+    let results: Vec<_> = client
+        .stream_types(SearchTypesParams::new().q("shrink"), StreamOptions::new())
+        .collect()
+        .await;
+    assert!(matches!(
+        results[1],
+        Err(Error::Pagination(PaginationError::CountShrank {
+            initial: 3,
+            current: 2
+        }))
+    ));
+
+    let mut stream = client.stream_types(
+        SearchTypesParams::new().q("retry"),
+        StreamOptions::new()
+            .page_retries(1)
+            .retry_delay(std::time::Duration::ZERO),
+    );
+    assert_eq!(stream.next().await.unwrap().unwrap().id, 1);
+    assert_eq!(stream.next().await.unwrap().unwrap().id, 2);
+    assert!(stream.next().await.is_none());
+    failing.assert();
+    let stats = stream.stats();
+    assert_eq!(stats.retries, 1);
+    assert_eq!(stats.pages_fetched, 2);
+    assert!(stats.completed);
+}
+
+#[tokio::test]
+async fn search_types_all_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    // The second page is only fetched by the two last searches.
+    let mut mocks = Vec::new();
+    for (page, types, hits) in [
+        ("1", r#"[{"id": 1, "title": "A"}, {"id": 2, "title": "B"}]"#, 3),
+        ("2", r#"[{"id": 3, "title": "C"}]"#, 2),
+    ] {
+        let mock = server
+            .mock("GET", "/types")
+            .match_query(mockito::Matcher::UrlEncoded("page".into(), page.into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(r#"{{"count": 3, "types": {}}}"#, types))
+            .expect(hits)
+            .create();
+        mocks.push(mock);
+    }
+
+    let client = ClientBuilder::new()
+        .api_key("test_key")
+        .base_url(url)
+        .build()
+        .unwrap();
+
+    let capped = client
+        .search_types_all(SearchTypesParams::new(), 1)
+        .await
+        .unwrap();
+    assert_eq!(capped.items.len(), 1);
+    assert!(capped.truncated);
+    assert_eq!(capped.total, Some(3));
+
+    let all = client
+        .search_types_all(SearchTypesParams::new(), 10)
+        .await
+        .unwrap();
+    let ids: Vec<i64> = all.items.iter().map(|t| t.id).collect();
+    assert_eq!(ids, vec![1, 2, 3]);
+    assert!(!all.truncated);
+
+    let exact = client
+        .search_types_all(SearchTypesParams::new(), 3)
+        .await
+        .unwrap();
+    assert_eq!(exact.items.len(), 3);
+    assert!(!exact.truncated);
+    for mock in mocks {
+        mock.assert();
+    }
+}
+
+#[tokio::test]
+async fn hydrate_test() {
+    use planchet::hydrate::TypeCache;
+
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    server
+        .mock("GET", "/types")
+        .match_query(mockito::Matcher::Any)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"count": 3, "types": [{"id": 1, "title": "A"}, {"id": 2, "title": "B"}, {"id": 1, "title": "A"}]}"#)
+        .create();
+    let mut mocks = Vec::new();
+    for id in [1, 2] {
+        let mock = server
+            .mock("GET", format!("/types/{}", id).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(r#"{{"id": {}, "title": "Type {}", "category": "coin"}}"#, id, id))
+            .expect(1)
+            .create();
+        mocks.push(mock);
+    }
+
+    let client = ClientBuilder::new()
+        .api_key("test_key")
+        .base_url(url)
+        .build()
+        .unwrap();
+    let results = client.search_types(&SearchTypesParams::new()).await.unwrap();
+
+    // Type 1 is only fetched once, and kept by the cache for later calls.
+    let cache = TypeCache::new();
+    let types: Vec<_> = client
+        .hydrate_with_cache(&results.types, &cache)
+        .map(|t| t.unwrap().title)
+        .collect()
+        .await;
+    assert_eq!(types, vec!["Type 1", "Type 2", "Type 1"]);
+    assert_eq!(cache.len(), 2);
+
+    let again: Vec<_> = client
+        .hydrate_with_cache(&results.types[1..2], &cache)
+        .collect()
+        .await;
+    assert_eq!(again[0].as_ref().unwrap().id, 2);
+    for mock in mocks {
+        mock.assert();
+    }
+}
+
+#[tokio::test]
+async fn stream_all_types_sorted_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    for (page, body) in [
+        ("1", r#"{"count": 3, "types": [{"id": 3, "title": "C", "min_year": 1900}, {"id": 1, "title": "A"}]}"#),
+        ("2", r#"{"count": 3, "types": [{"id": 2, "title": "B", "min_year": 1850}]}"#),
+    ] {
+        server
+            .mock("GET", "/types")
+            .match_query(mockito::Matcher::UrlEncoded("page".into(), page.into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(body)
+            .create();
+    }
+
+    let client = ClientBuilder::new()
+        .api_key("test_key")
+        .base_url(url)
+        .build()
+        .unwrap();
+
+    let ids = |by| {
+        client
+            .stream_all_types_sorted(SearchTypesParams::new(), by)
+            .map(|t| t.unwrap().id)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(ids(SortBy::Id).await, vec![1, 2, 3]);
+    assert_eq!(ids(SortBy::Year).await, vec![2, 3, 1]);
+}
+
+#[tokio::test]
+async fn stream_all_types_with_cancellation_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let page_mock = server
+        .mock("GET", "/types")
+        .match_query(mockito::Matcher::UrlEncoded("page".into(), "1".into()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+            "count": 3,
+            "types": [
+                { "id": 1, "title": "Type 1", "category": "coin" },
+                { "id": 2, "title": "Type 2", "category": "coin" }
+            ]
+        }"#,
+        )
+        .expect(1)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key")
+        .base_url(url)
+        .build()
+        .unwrap();
+
+    let token = tokio_util::sync::CancellationToken::new();
+    let mut stream = Box::pin(
+        client.stream_all_types_with_cancellation(SearchTypesParams::new(), token.clone()),
+    );
+
+    assert_eq!(stream.next().await.unwrap().unwrap().id, 1);
+    token.cancel();
+    assert!(matches!(stream.next().await, Some(Err(Error::Cancelled))));
+    assert!(stream.next().await.is_none());
+    page_mock.assert();
+}
+
 #[tokio::test]
 async fn get_issues_test() {
     let mut server = mockito::Server::new_async().await;
@@ -440,6 +809,76 @@ async fn get_issuers_test() {
     assert_eq!(response.issuers[0].code, "canada");
 }
 
+#[tokio::test]
+async fn reference_cache_test() {
+    use planchet::reference::ReferenceCache;
+    use planchet::NumistaApi;
+
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let mock = server.mock("GET", "/issuers")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"count": 2, "issuers": [{"code": "canada", "name": "Canada"}, {"code": "france", "name": "France"}]}"#)
+        .expect(1)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key")
+        .base_url(url)
+        .build()
+        .unwrap();
+    let reference = ReferenceCache::new(client);
+
+    assert_eq!(reference.issuers().await.unwrap().len(), 2);
+    assert_eq!(reference.issuer("france").await.unwrap().unwrap().name, "France");
+    assert!(reference.issuer("atlantis").await.unwrap().is_none());
+    assert_eq!(reference.get_issuers().await.unwrap().count, 2);
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn watchlist_check_test() {
+    use planchet::models::{Grade, Number};
+    use planchet::watchlist::{Watch, Watchlist};
+
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let mock = server.mock("GET", "/types/42/issues/7/prices")
+        .match_query(mockito::Matcher::UrlEncoded("currency".into(), "EUR".into()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"currency": "EUR", "prices": [{"grade": "f", "price": 8}, {"grade": "vf", "price": 12}]}"#)
+        .expect(2)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key")
+        .base_url(url)
+        .build()
+        .unwrap();
+    let mut watchlist = Watchlist::new();
+    let currency = "EUR".parse().unwrap();
+    watchlist.add(Watch::new(42, 7, Grade::F, Number::from(10)).currency(currency));
+    watchlist.add(Watch::new(42, 7, Grade::Vf, Number::from(10)).currency(currency));
+
+    let report = watchlist.check(&client).await;
+    assert!(report.errors.is_empty());
+    let alerts = report.alerts;
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].watch.grade, Grade::F);
+    assert_eq!(alerts[0].price, Number::from(8));
+    assert_eq!(watchlist.watches[1].last_price, Some(Number::from(12)));
+
+    // A price which stays below the threshold is not reported again.
+    assert!(watchlist.check(&client).await.alerts.is_empty());
+
+    mock.assert();
+}
+
 #[tokio::test]
 async fn get_mints_test() {
     let mut server = mockito::Server::new_async().await;
@@ -488,6 +927,51 @@ async fn get_mint_test() {
     assert_eq!(response.id, 1);
 }
 
+#[tokio::test]
+async fn get_types_by_mint_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    server
+        .mock("GET", "/mints/11")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id": "11", "name": "Ottawa", "country": {"code": "canada", "name": "Canada"}}"#)
+        .create();
+    server
+        .mock("GET", "/types")
+        .match_query(mockito::Matcher::UrlEncoded(
+            "issuer".into(),
+            "canada".into(),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"count": 2, "types": [{"id": 1, "title": "A"}, {"id": 2, "title": "B"}]}"#)
+        .create();
+    for (id, mint) in [(1, 11), (2, 12)] {
+        server
+            .mock("GET", format!("/types/{}", id).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"id": {}, "title": "Type {}", "category": "coin", "mints": [{{"id": "{}", "name": "Mint"}}]}}"#,
+                id, id, mint
+            ))
+            .create();
+    }
+
+    let client = ClientBuilder::new()
+        .api_key("test_key")
+        .base_url(url)
+        .build()
+        .unwrap();
+
+    let types = client.get_types_by_mint(11, 100).await.unwrap();
+    let ids: Vec<i64> = types.items.iter().map(|t| t.id).collect();
+    assert_eq!(ids, vec![1]);
+    assert!(!types.truncated);
+}
+
 #[tokio::test]
 async fn get_catalogues_test() {
     let mut server = mockito::Server::new_async().await;
@@ -611,37 +1095,74 @@ async fn get_collected_items_test() {
 }
 
 #[tokio::test]
-async fn add_collected_item_test() {
+async fn watch_collection_test() {
     let mut server = mockito::Server::new_async().await;
     let url = server.url();
 
-    let mock = server.mock("POST", "/users/1/collected_items")
+    let item = |id: i64, quantity: i64| {
+        format!(
+            r#"{{"id": {}, "quantity": {}, "type": {{"id": 1, "title": "Test", "category": "coin"}}, "for_swap": false}}"#,
+            id, quantity
+        )
+    };
+    let collection = |items: &[String]| {
+        format!(
+            r#"{{"item_count": {}, "item_for_swap_count": 0, "item_type_count": 1, "item_type_for_swap_count": 0, "items": [{}]}}"#,
+            items.len(),
+            items.join(",")
+        )
+    };
+    let first = server
+        .mock("GET", "/users/1/collected_items")
+        .match_query(mockito::Matcher::Any)
         .with_status(200)
         .with_header("content-type", "application/json")
-        .with_body(r#"{"id": 1, "quantity": 1, "type": {"id": 1, "title": "Test", "category": "coin"}, "for_swap": false}"#)
+        .with_body(collection(&[item(1, 1), item(2, 1)]))
+        .expect(1)
+        .create();
+    let second = server
+        .mock("GET", "/users/1/collected_items")
+        .match_query(mockito::Matcher::Any)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(collection(&[item(2, 3), item(3, 1)]))
+        .expect_at_least(1)
         .create();
 
     let client = ClientBuilder::new()
-        .api_key("test_key".to_string())
+        .api_key("test_key")
         .base_url(url)
         .build()
         .unwrap();
 
-    let item = AddCollectedItemParams::new(1)
-        .quantity(2)
-        .private_comment("Test comment");
-    let response = client.add_collected_item(1, &item).await.unwrap();
-
-    mock.assert();
-    assert_eq!(response.id, 1);
+    let events: Vec<CollectionEvent> = client
+        .watch_collection(1, std::time::Duration::from_millis(10))
+        .unwrap()
+        .take(3)
+        .map(|event| event.unwrap())
+        .collect()
+        .await;
+
+    first.assert();
+    second.assert();
+    assert!(matches!(&events[0], CollectionEvent::ItemEdited { before, after }
+        if before.quantity == 1 && after.quantity == 3));
+    assert!(matches!(&events[1], CollectionEvent::ItemAdded(item) if item.id == 3));
+    assert!(matches!(&events[2], CollectionEvent::ItemRemoved(item) if item.id == 1));
+    assert_eq!(events[0].item_id(), 2);
+
+    assert!(matches!(
+        client.watch_collection(1, std::time::Duration::ZERO),
+        Err(Error::Validation(_))
+    ));
 }
 
 #[tokio::test]
-async fn get_collected_item_test() {
+async fn add_collected_item_test() {
     let mut server = mockito::Server::new_async().await;
     let url = server.url();
 
-    let mock = server.mock("GET", "/users/1/collected_items/1")
+    let mock = server.mock("POST", "/users/1/collected_items")
         .with_status(200)
         .with_header("content-type", "application/json")
         .with_body(r#"{"id": 1, "quantity": 1, "type": {"id": 1, "title": "Test", "category": "coin"}, "for_swap": false}"#)
@@ -653,38 +1174,288 @@ async fn get_collected_item_test() {
         .build()
         .unwrap();
 
-    let response = client.get_collected_item(1, 1).await.unwrap();
+    let item = AddCollectedItemParams::new(1)
+        .quantity(2)
+        .private_comment("Test comment");
+    let response = client.add_collected_item(1, &item).await.unwrap();
 
     mock.assert();
     assert_eq!(response.id, 1);
 }
 
 #[tokio::test]
-async fn edit_collected_item_test() {
+async fn missing_scope_test() {
     let mut server = mockito::Server::new_async().await;
     let url = server.url();
 
-    let mock = server.mock("PATCH", "/users/1/collected_items/1")
-        .with_status(200)
-        .with_header("content-type", "application/json")
-        .with_body(r#"{"id": 1, "quantity": 1, "type": {"id": 1, "title": "Test", "category": "coin"}, "for_swap": false}"#)
+    let add_mock = server
+        .mock("POST", "/users/1/collected_items")
+        .expect(0)
+        .create();
+    let delete_mock = server
+        .mock("DELETE", "/users/1/collected_items/2")
+        .match_header("authorization", "Bearer t0k3n")
+        .with_status(204)
         .create();
 
+    let response: models::OAuthToken = serde_json::from_value(serde_json::json!({
+        "access_token": "t0k3n",
+        "token_type": "bearer",
+        "expires_in": 3600,
+        "user_id": 1,
+        "scope": "view_collection"
+    }))
+    .unwrap();
+    let token = Token::new(&response, &[]);
     let client = ClientBuilder::new()
-        .api_key("test_key".to_string())
-        .base_url(url)
+        .api_key("test_key")
+        .token(&token)
+        .base_url(url.clone())
         .build()
         .unwrap();
+    assert_eq!(client.scopes(), Some(&[Scope::ViewCollection][..]));
+
+    let error = client
+        .add_collected_item(1, &AddCollectedItemParams::new(1))
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(error, Error::MissingScope(Scope::EditCollection)),
+        "{:?}",
+        error
+    );
+    assert!(error.is_auth());
+    add_mock.assert();
 
-    let item = EditCollectedItemParams::new().quantity(5);
-    let response = client.edit_collected_item(1, 1, &item).await.unwrap();
-
-    mock.assert();
-    assert_eq!(response.id, 1);
+    let client = ClientBuilder::new()
+        .api_key("test_key")
+        .bearer_token("t0k3n")
+        .scopes([Scope::ViewCollection, Scope::EditCollection])
+        .base_url(url)
+        .build()
+        .unwrap();
+    client.delete_collected_item(1, 2).await.unwrap();
+    delete_mock.assert();
 }
 
 #[tokio::test]
-async fn delete_collected_item_test() {
+async fn session_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let token_mock = server
+        .mock("GET", "/oauth_token")
+        .match_query(mockito::Matcher::AllOf(vec![
+            mockito::Matcher::UrlEncoded("grant_type".into(), "client_credentials".into()),
+            mockito::Matcher::UrlEncoded("scope".into(), "view_collection".into()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"access_token": "session_token", "token_type": "bearer", "expires_in": 3600, "user_id": 7}"#,
+        )
+        .create();
+    let items_mock = server
+        .mock("GET", "/users/7/collected_items")
+        .match_header("authorization", "Bearer session_token")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"item_count": 0, "item_for_swap_count": 0, "item_type_count": 0, "item_type_for_swap_count": 0, "items": []}"#)
+        .create();
+    let add_mock = server
+        .mock("POST", "/users/7/collected_items")
+        .expect(0)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key")
+        .bearer_token("other_token")
+        .base_url(url)
+        .build()
+        .unwrap();
+    let session = client.authenticate(&[Scope::ViewCollection]).await.unwrap();
+    token_mock.assert();
+    assert_eq!(session.user_id(), 7);
+    assert_eq!(session.scopes(), vec![Scope::ViewCollection]);
+    assert!(!session.is_expired());
+
+    assert_eq!(session.my_items().await.unwrap().item_count, 0);
+    items_mock.assert();
+
+    let error = session
+        .add_item(&AddCollectedItemParams::new(1))
+        .await
+        .unwrap_err();
+    assert!(matches!(error, Error::MissingScope(Scope::EditCollection)));
+    add_mock.assert();
+}
+
+#[tokio::test]
+async fn add_collected_item_field_errors_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let mock = server
+        .mock("POST", "/users/1/collected_items")
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"error_message": "Invalid parameters", "errors": [
+                {"field": "grade", "message": "Unknown grade"},
+                {"field": "price", "message": "Missing currency"}
+            ]}"#,
+        )
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key")
+        .base_url(url)
+        .build()
+        .unwrap();
+
+    let item = AddCollectedItemParams::new(1).quantity(1);
+    let err = client.add_collected_item(1, &item).await.unwrap_err();
+
+    mock.assert();
+    match err {
+        Error::ApiError(e) => {
+            assert_eq!(e.field_errors.len(), 2);
+            let grade: Vec<_> = e.errors_for("grade").collect();
+            assert_eq!(grade.len(), 1);
+            assert_eq!(grade[0].message, "Unknown grade");
+            assert_eq!(e.errors_for("price").count(), 1);
+            assert_eq!(e.errors_for("quantity").count(), 0);
+        }
+        _ => panic!("Expected ApiError"),
+    }
+
+    // Errors may also be keyed by field.
+    let mock = server
+        .mock("POST", "/users/1/collected_items")
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"error_message": "Invalid parameters",
+                "errors": {"grade": "Unknown grade", "price": ["Too low", "Missing currency"]}}"#,
+        )
+        .create();
+
+    let err = client.add_collected_item(1, &item).await.unwrap_err();
+
+    mock.assert();
+    match err {
+        Error::ApiError(e) => {
+            assert_eq!(e.field_errors.len(), 3);
+            assert_eq!(e.errors_for("price").count(), 2);
+        }
+        _ => panic!("Expected ApiError"),
+    }
+}
+
+#[tokio::test]
+async fn add_collected_item_if_absent_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let existing = r#"{"item_count": 2, "item_for_swap_count": 0, "item_type_count": 1, "item_type_for_swap_count": 0, "items": [
+        {"id": 7, "quantity": 1, "type": {"id": 420, "title": "Test", "category": "coin"}, "issue": {"id": 5}, "for_swap": false},
+        {"id": 8, "quantity": 2, "type": {"id": 420, "title": "Test", "category": "coin"}, "issue": {"id": 5}, "serial_number": "A1", "for_swap": false}
+    ]}"#;
+    let get_mock = server.mock("GET", "/users/1/collected_items")
+        .match_query(mockito::Matcher::UrlEncoded("type".into(), "420".into()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(existing)
+        .expect(3)
+        .create();
+    let add_mock = server.mock("POST", "/users/1/collected_items")
+        .with_status(201)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id": 9, "quantity": 1, "type": {"id": 420, "title": "Test", "category": "coin"}, "issue": {"id": 6}, "for_swap": false}"#)
+        .expect(1)
+        .create();
+    let edit_mock = server.mock("PATCH", "/users/1/collected_items/8")
+        .match_body(mockito::Matcher::Json(serde_json::json!({"quantity": 5})))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id": 8, "quantity": 5, "type": {"id": 420, "title": "Test", "category": "coin"}, "issue": {"id": 5}, "serial_number": "A1", "for_swap": false}"#)
+        .expect(1)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key".to_string())
+        .base_url(url)
+        .build()
+        .unwrap();
+
+    let duplicate = AddCollectedItemParams::new(420).issue(5).serial_number("A1").quantity(3);
+    let outcome = client.add_collected_item_if_absent(1, &duplicate).await.unwrap();
+    assert!(matches!(outcome, AddOutcome::Skipped(_)));
+    assert_eq!(outcome.item().id, 8);
+
+    let other_issue = AddCollectedItemParams::new(420).issue(6);
+    let outcome = client.add_collected_item_if_absent(1, &other_issue).await.unwrap();
+    assert!(matches!(outcome, AddOutcome::Added(_)));
+    assert_eq!(outcome.item().id, 9);
+
+    let outcome = client.add_or_merge_collected_item(1, &duplicate).await.unwrap();
+    assert!(matches!(outcome, AddOutcome::Merged(_)));
+    assert_eq!(outcome.item().quantity, 5);
+
+    get_mock.assert();
+    add_mock.assert();
+    edit_mock.assert();
+}
+
+#[tokio::test]
+async fn get_collected_item_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let mock = server.mock("GET", "/users/1/collected_items/1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id": 1, "quantity": 1, "type": {"id": 1, "title": "Test", "category": "coin"}, "for_swap": false}"#)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key".to_string())
+        .base_url(url)
+        .build()
+        .unwrap();
+
+    let response = client.get_collected_item(1, 1).await.unwrap();
+
+    mock.assert();
+    assert_eq!(response.id, 1);
+}
+
+#[tokio::test]
+async fn edit_collected_item_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let mock = server.mock("PATCH", "/users/1/collected_items/1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id": 1, "quantity": 1, "type": {"id": 1, "title": "Test", "category": "coin"}, "for_swap": false}"#)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key".to_string())
+        .base_url(url)
+        .build()
+        .unwrap();
+
+    let item = EditCollectedItemParams::new().quantity(5);
+    let response = client.edit_collected_item(1, 1, &item).await.unwrap();
+
+    mock.assert();
+    assert_eq!(response.id, 1);
+}
+
+#[tokio::test]
+async fn delete_collected_item_test() {
     let mut server = mockito::Server::new_async().await;
     let url = server.url();
 
@@ -704,6 +1475,172 @@ async fn delete_collected_item_test() {
     assert!(response.is_ok());
 }
 
+#[tokio::test]
+async fn write_outcome_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let delete_mock = server
+        .mock("DELETE", "/users/1/collected_items/1")
+        .with_status(204)
+        .with_header("warning", "299 - \"Deprecated endpoint\"")
+        .create();
+    let edit_mock = server
+        .mock("PATCH", "/users/1/collected_items/2")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id": 2, "quantity": 3, "type": {"id": 1, "title": "Test", "category": "coin"}, "for_swap": false}"#)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key")
+        .base_url(url)
+        .build()
+        .unwrap();
+
+    let deleted = client
+        .delete_collected_item(1, 1)
+        .await
+        .unwrap()
+        .into_confirmation()
+        .unwrap();
+    delete_mock.assert();
+    assert_eq!(deleted.status, 204);
+    assert_eq!(deleted.request_id.unwrap().len(), 17);
+    assert_eq!(deleted.warnings, vec!["299 - \"Deprecated endpoint\""]);
+
+    let params = EditCollectedItemParams::new().quantity(3);
+    let edited = client
+        .edit_collected_item_with_outcome(1, 2, &params)
+        .await
+        .unwrap()
+        .into_confirmation()
+        .unwrap();
+    edit_mock.assert();
+    assert_eq!(edited.status, 200);
+    assert_eq!(edited.value.quantity, 3);
+    assert!(edited.request_id.is_some());
+    assert!(edited.warnings.is_empty());
+}
+
+#[tokio::test]
+async fn dry_run_write_requests_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let mock = server.mock("POST", "/users/1/collected_items")
+        .expect(0)
+        .create();
+    let edit_mock = server.mock("PATCH", "/users/1/collected_items/1")
+        .expect(0)
+        .create();
+    let delete_mock = server.mock("DELETE", "/users/1/collected_items/1")
+        .expect(0)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key".to_string())
+        .base_url(url.clone())
+        .lang_code("fr")
+        .dry_run(true)
+        .build()
+        .unwrap();
+
+    let item = AddCollectedItemParams::new(420).quantity(2);
+    let outcome = client
+        .add_collected_item_with_outcome(1, &item)
+        .await
+        .unwrap();
+    let planned = outcome.planned().unwrap();
+    assert_eq!(planned.method, reqwest::Method::POST);
+    assert_eq!(planned.url.as_str(), format!("{}/users/1/collected_items?lang=fr", url));
+    let body = planned.body.as_ref().unwrap();
+    assert_eq!(body["type"], 420);
+    assert_eq!(body["quantity"], 2);
+
+    let item = EditCollectedItemParams::new().quantity(5);
+    let outcome = client
+        .edit_collected_item_with_outcome(1, 1, &item)
+        .await
+        .unwrap();
+    let planned = outcome.planned().unwrap();
+    assert_eq!(planned.method, reqwest::Method::PATCH);
+    assert_eq!(planned.body, Some(serde_json::json!({ "quantity": 5 })));
+
+    let outcome = client.delete_collected_item(1, 1).await.unwrap();
+    let planned = outcome.planned().unwrap();
+    assert_eq!(planned.method, reqwest::Method::DELETE);
+    assert_eq!(planned.body, None);
+
+    // The methods returning the written item have none to return.
+    let err = client
+        .add_collected_item(1, &AddCollectedItemParams::new(420))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::DryRunWithoutOutcome));
+    assert!(err.is_client_bug());
+    let err = client.edit_collected_item(1, 1, &item).await.unwrap_err();
+    assert!(matches!(err, Error::DryRunWithoutOutcome));
+
+    mock.assert();
+    edit_mock.assert();
+    delete_mock.assert();
+}
+
+#[tokio::test]
+async fn search_types_validation_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let mock = server.mock("GET", "/types")
+        .match_query(mockito::Matcher::Any)
+        .expect(0)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key".to_string())
+        .base_url(url)
+        .build()
+        .unwrap();
+
+    let params = SearchTypesParams::new().q("victoria").year_range(1901, 1858).count(500);
+    let response = client.search_types(&params).await;
+
+    mock.assert();
+    match response {
+        Err(Error::Validation(issues)) => {
+            let fields: Vec<_> = issues.iter().map(|i| i.field.as_str()).collect();
+            assert_eq!(fields, vec!["year", "count"]);
+        }
+        other => panic!("Expected a validation error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn api_error_request_id_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let mock = server.mock("GET", "/types/1")
+        .match_header("X-Request-ID", mockito::Matcher::Regex("^[0-9a-f]{8}-[0-9a-f]{8}$".into()))
+        .with_status(404)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"error_message": "Type not found"}"#)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key".to_string())
+        .base_url(url)
+        .build()
+        .unwrap();
+
+    let err = client.get_type(1).await.unwrap_err();
+
+    mock.assert();
+    let request_id = err.request_id().unwrap().to_string();
+    assert!(err.to_string().ends_with(&format!("(request ID {})", request_id)));
+}
+
 #[tokio::test]
 async fn get_oauth_token_test() {
     let mut server = mockito::Server::new_async().await;
@@ -763,12 +1700,12 @@ async fn search_by_image_test() {
     let request = SearchByImageParams {
         category: None,
         images: vec![
-            model::request::Image {
-                mime_type: model::request::MimeType::Jpeg,
+            models::request::Image {
+                mime_type: models::request::MimeType::Jpeg,
                 image_data: "jpeg_data".to_string(),
             },
-            model::request::Image {
-                mime_type: model::request::MimeType::Png,
+            models::request::Image {
+                mime_type: models::request::MimeType::Png,
                 image_data: "png_data".to_string(),
             },
         ],
@@ -811,51 +1748,428 @@ async fn unauthorized_error_test() {
 }
 
 #[tokio::test]
-async fn not_found_error_test() {
+async fn private_collection_error_test() {
     let mut server = mockito::Server::new_async().await;
     let url = server.url();
 
-    let mock = server
-        .mock("GET", "/types/999999")
-        .with_status(404)
+    let public_mock = server
+        .mock("GET", "/users/1/collected_items")
+        .match_header("authorization", mockito::Matcher::Missing)
+        .with_status(200)
         .with_header("content-type", "application/json")
-        .with_body(r#"{"error_message": "Not found"}"#)
+        .with_body(r#"{"item_count": 0, "item_for_swap_count": 0, "item_type_count": 0, "item_type_for_swap_count": 0, "items": []}"#)
+        .create();
+    server
+        .mock("GET", "/users/2/collected_items")
+        .with_status(403)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"error_message": "This collection is private"}"#)
+        .create();
+    server
+        .mock("GET", "/users/3/collections")
+        .with_status(401)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"error_message": "Invalid API key", "error_code": "invalid_api_key"}"#)
         .create();
 
     let client = ClientBuilder::new()
-        .api_key("test_key")
+        .api_key("test_key".to_string())
         .base_url(url)
         .build()
         .unwrap();
 
-    let response = client.get_type(999999).await;
+    let params = GetCollectedItemsParams::new();
+    let items = client.get_collected_items(1, &params).await.unwrap();
+    assert!(items.items.is_empty());
+    public_mock.assert();
+
+    let error = client.get_collected_items(2, &params).await.unwrap_err();
+    assert!(
+        matches!(error, Error::AuthorizationRequired(2)),
+        "{:?}",
+        error
+    );
+    assert!(error.is_auth());
+
+    // A refused API key is not a private collection.
+    let error = client.get_user_collections(3).await.unwrap_err();
+    assert!(
+        matches!(error, Error::ApiError(ref e) if e.status == 401),
+        "{:?}",
+        error
+    );
 
-    mock.assert();
-    assert!(response.is_err());
-    match response.err().unwrap() {
-        Error::ApiError(e) => {
-            assert_eq!(e.status, 404);
-            assert!(e.is_not_found());
-        }
-        _ => panic!("Expected ApiError"),
-    }
+    // Nor is a refused token.
+    let client = ClientBuilder::new()
+        .api_key("test_key".to_string())
+        .bearer_token("other_user_token")
+        .base_url(server.url())
+        .build()
+        .unwrap();
+    let error = client.get_collected_items(2, &params).await.unwrap_err();
+    assert!(
+        matches!(error, Error::ApiError(ref e) if e.status == 403),
+        "{:?}",
+        error
+    );
 }
 
 #[tokio::test]
-async fn invalid_parameter_error_test() {
+async fn missing_key_test() {
     let mut server = mockito::Server::new_async().await;
     let url = server.url();
 
     let mock = server
-        .mock("GET", "/types")
-        .match_query(mockito::Matcher::UrlEncoded("q".into(), "a".repeat(101)))
-        .with_status(400)
+        .mock("GET", "/types/420")
+        .match_header("numista-api-key", mockito::Matcher::Missing)
+        .with_status(200)
         .with_header("content-type", "application/json")
-        .with_body(r#"{"error_message": "Invalid parameter"}"#)
+        .with_body(r#"{"id": 420, "title": "Test", "category": "coin"}"#)
         .create();
 
     let client = ClientBuilder::new()
-        .api_key("test_key")
+        .allow_missing_key()
+        .base_url(url)
+        .build()
+        .unwrap();
+    assert_eq!(client.get_type(420).await.unwrap().id, 420);
+    mock.assert();
+}
+
+#[tokio::test]
+async fn language_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let mock = server
+        .mock("GET", "/types/420")
+        .match_query(mockito::Matcher::UrlEncoded("lang".into(), "nl".into()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id": 420, "title": "Test", "category": "coin"}"#)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key")
+        .language(SupportedLanguage::Dutch)
+        .base_url(url.as_str())
+        .build()
+        .unwrap();
+    assert_eq!(client.language(), Some(SupportedLanguage::Dutch));
+    client.get_type(420).await.unwrap();
+    mock.assert();
+
+    // Codes are checked when the client is built.
+    let client = ClientBuilder::new()
+        .api_key("test_key")
+        .lang_code("NL")
+        .build();
+    assert_eq!(client.unwrap().lang(), Some("nl"));
+    for code in ["xx", "ja", ""] {
+        let client = ClientBuilder::new()
+            .api_key("test_key")
+            .lang_code(code)
+            .build();
+        assert!(
+            matches!(client, Err(Error::UnsupportedLanguage(_))),
+            "{:?}",
+            code
+        );
+    }
+}
+
+#[tokio::test]
+async fn type_language_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+    let body = r#"{"id": 420, "title": "Test", "category": "coin"}"#;
+
+    let translated = server
+        .mock("GET", "/types/420")
+        .match_query(mockito::Matcher::UrlEncoded("lang".into(), "fr".into()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_header("content-language", "es-ES")
+        .with_body(body)
+        .create();
+    let requested = server
+        .mock("GET", "/types/420")
+        .match_query(mockito::Matcher::UrlEncoded("lang".into(), "de".into()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(body)
+        .create();
+    let default = server
+        .mock("GET", "/types/420")
+        .match_query(mockito::Matcher::Missing)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(body)
+        .create();
+
+    // The language of the response prevails over the one requested.
+    for (lang, expected) in [
+        (Some(SupportedLanguage::French), SupportedLanguage::Spanish),
+        (Some(SupportedLanguage::German), SupportedLanguage::German),
+        (None, SupportedLanguage::English),
+    ] {
+        let mut builder = ClientBuilder::new()
+            .api_key("test_key")
+            .base_url(url.as_str());
+        if let Some(lang) = lang {
+            builder = builder.language(lang);
+        }
+        let numista_type = builder.build().unwrap().get_type(420).await.unwrap();
+        assert_eq!(numista_type.lang(), Some(expected));
+    }
+    translated.assert();
+    requested.assert();
+    default.assert();
+}
+
+#[tokio::test]
+async fn get_type_multilang_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let mut mocks = Vec::new();
+    for (lang, title) in [("en", "5 Cents"), ("fr", "5 centimes")] {
+        let mock = server
+            .mock("GET", "/types/420")
+            .match_query(mockito::Matcher::UrlEncoded("lang".into(), lang.into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"id": 420, "title": "{}", "category": "coin"}}"#,
+                title
+            ))
+            .expect(1)
+            .create();
+        mocks.push(mock);
+    }
+
+    // The language of the client does not matter.
+    let client = ClientBuilder::new()
+        .api_key("test_key")
+        .language(SupportedLanguage::German)
+        .base_url(url)
+        .build()
+        .unwrap();
+    let langs = [
+        SupportedLanguage::French,
+        SupportedLanguage::English,
+        SupportedLanguage::French,
+    ];
+    let multilang = client.get_type_multilang(420, &langs).await.unwrap();
+    assert_eq!(multilang.id(), 420);
+    assert_eq!(
+        multilang.languages().collect::<Vec<_>>(),
+        [SupportedLanguage::English, SupportedLanguage::French]
+    );
+    assert_eq!(multilang.title(SupportedLanguage::English), Some("5 Cents"));
+    assert_eq!(
+        multilang.title(SupportedLanguage::French),
+        Some("5 centimes")
+    );
+    assert_eq!(
+        multilang.get(SupportedLanguage::French).unwrap().lang(),
+        Some(SupportedLanguage::French)
+    );
+    assert!(multilang.get(SupportedLanguage::German).is_none());
+    for mock in mocks {
+        mock.assert();
+    }
+}
+
+#[tokio::test]
+async fn api_key_header_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let mock = server
+        .mock("GET", "/types/420")
+        .match_header("x-proxy-key", "test_key")
+        .match_header("numista-api-key", mockito::Matcher::Missing)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id": 420, "title": "Test", "category": "coin"}"#)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key")
+        .api_key_header("X-Proxy-Key")
+        .base_url(url)
+        .build()
+        .unwrap();
+    assert_eq!(client.get_type(420).await.unwrap().id, 420);
+    mock.assert();
+}
+
+/// Answers one request with a type, returning the request as received.
+async fn serve_one_request(listener: tokio::net::TcpListener) -> String {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let (mut stream, _) = listener.accept().await.unwrap();
+    let mut request = Vec::new();
+    let mut buf = [0; 1024];
+    while !request.ends_with(b"\r\n\r\n") {
+        let n = stream.read(&mut buf).await.unwrap();
+        assert!(n > 0, "the connection was closed");
+        request.extend_from_slice(&buf[..n]);
+    }
+    let body = r#"{"id": 420, "title": "Test", "category": "coin"}"#;
+    let response = format!(
+        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await.unwrap();
+    String::from_utf8(request).unwrap()
+}
+
+#[tokio::test]
+async fn api_key_header_casing_test() {
+    for (title_case, expected) in [
+        (false, "numista-api-key: test_key\r\n"),
+        (true, "Numista-Api-Key: test_key\r\n"),
+    ] {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(serve_one_request(listener));
+
+        let mut builder = ClientBuilder::new().api_key("test_key").base_url(url);
+        if title_case {
+            builder = builder.title_case_headers();
+        }
+        builder.build().unwrap().get_type(420).await.unwrap();
+
+        let request = server.await.unwrap();
+        assert!(request.contains(expected), "{}", request);
+    }
+}
+
+/// Adds a new gateway token to each request, counting them.
+struct GatewayToken(std::sync::atomic::AtomicUsize);
+
+#[async_trait::async_trait]
+impl AuthProvider for GatewayToken {
+    async fn headers(&self, _request: &reqwest::Request) -> planchet::Result<HeaderMap> {
+        let n = self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "X-Gateway-Token",
+            HeaderValue::from_str(&format!("jwt-{}", n)).unwrap(),
+        );
+        Ok(headers)
+    }
+}
+
+#[tokio::test]
+async fn auth_provider_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let first = server
+        .mock("GET", "/types/420")
+        .match_header("x-gateway-token", "jwt-0")
+        .match_header("numista-api-key", "test_key")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id": 420, "title": "Test", "category": "coin"}"#)
+        .create();
+    let second = server
+        .mock("GET", "/types/420")
+        .match_header("x-gateway-token", "jwt-1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id": 420, "title": "Test", "category": "coin"}"#)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key")
+        .auth_provider(GatewayToken(0.into()))
+        .base_url(url)
+        .build()
+        .unwrap();
+    client.get_type(420).await.unwrap();
+    client.get_type(420).await.unwrap();
+    first.assert();
+    second.assert();
+}
+
+struct FailingProvider;
+
+#[async_trait::async_trait]
+impl AuthProvider for FailingProvider {
+    async fn headers(&self, _request: &reqwest::Request) -> planchet::Result<HeaderMap> {
+        Err(Error::InvalidToken("the gateway refused".to_string()))
+    }
+}
+
+#[tokio::test]
+async fn auth_provider_error_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let mock = server.mock("GET", "/types/420").expect(0).create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key")
+        .auth_provider(FailingProvider)
+        .base_url(url)
+        .build()
+        .unwrap();
+    let result = client.get_type(420).await;
+    assert!(matches!(result, Err(Error::InvalidToken(_))));
+    mock.assert();
+}
+
+#[tokio::test]
+async fn not_found_error_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let mock = server
+        .mock("GET", "/types/999999")
+        .with_status(404)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"error_message": "Not found"}"#)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key")
+        .base_url(url)
+        .build()
+        .unwrap();
+
+    let response = client.get_type(999999).await;
+
+    mock.assert();
+    assert!(response.is_err());
+    match response.err().unwrap() {
+        Error::ApiError(e) => {
+            assert_eq!(e.status, 404);
+            assert!(e.is_not_found());
+        }
+        _ => panic!("Expected ApiError"),
+    }
+}
+
+#[tokio::test]
+async fn invalid_parameter_error_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let mock = server
+        .mock("GET", "/types")
+        .match_query(mockito::Matcher::UrlEncoded("q".into(), "a".repeat(101)))
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"error_message": "Invalid parameter"}"#)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key")
         .base_url(url)
         .build()
         .unwrap();
@@ -875,6 +2189,106 @@ async fn invalid_parameter_error_test() {
     }
 }
 
+#[tokio::test]
+async fn error_code_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let mock = server
+        .mock("GET", "/types/1")
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"error_message": "Token expired", "error_code": "expired_token"}"#)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key")
+        .base_url(url)
+        .build()
+        .unwrap();
+
+    let err = client.get_type(1).await.unwrap_err();
+
+    // The error code takes precedence over the status.
+    mock.assert();
+    assert!(err.is_auth());
+    assert!(!err.is_client_bug());
+    match err {
+        Error::ApiError(e) => {
+            assert_eq!(e.status, 400);
+            assert_eq!(e.code.as_deref(), Some("expired_token"));
+        }
+        _ => panic!("Expected ApiError"),
+    }
+}
+
+#[tokio::test]
+async fn unexpected_error_body_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    server
+        .mock("GET", "/types/1")
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"error_message": "Invalid type", "error_code": 42, "errors": "type"}"#)
+        .create();
+    server
+        .mock("GET", "/types/2")
+        .with_status(502)
+        .with_header("content-type", "text/html")
+        .with_body("<html>Bad Gateway</html>")
+        .create();
+    server.mock("GET", "/types/3").with_status(503).create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key")
+        .base_url(url)
+        .build()
+        .unwrap();
+
+    let api_error = |error: Error| match error {
+        Error::ApiError(e) => e,
+        e => panic!("Expected ApiError, got {:?}", e),
+    };
+    let e = api_error(client.get_type(1).await.unwrap_err());
+    assert_eq!(e.message, "Invalid type");
+    assert_eq!(e.code.as_deref(), Some("42"));
+    assert!(e.field_errors.is_empty());
+
+    let e = api_error(client.get_type(2).await.unwrap_err());
+    assert_eq!(e.status, 502);
+    assert_eq!(e.message, "<html>Bad Gateway</html>");
+
+    let e = api_error(client.get_type(3).await.unwrap_err());
+    assert_eq!(e.status, 503);
+    assert_eq!(e.message, "Service Unavailable");
+}
+
+#[tokio::test]
+async fn malformed_response_not_retryable_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    server
+        .mock("GET", "/types/1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id": "not an id", "title": 5"#)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key")
+        .base_url(url)
+        .build()
+        .unwrap();
+
+    // A body which does not decode would fail the same way if sent again.
+    let err = client.get_type(1).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Other, "{:?}", err);
+    assert!(!err.is_retryable());
+}
+
 #[tokio::test]
 async fn rate_limit_exceeded_error_test() {
     let mut server = mockito::Server::new_async().await;
@@ -972,3 +2386,938 @@ async fn generic_api_error_test() {
         _ => panic!("Expected a generic ApiError"),
     }
 }
+
+#[cfg(all(feature = "lenient-models", not(feature = "strict-models")))]
+#[tokio::test]
+async fn lenient_models_capture_unknown_fields_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let mock = server.mock("GET", "/users/1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"username": "test_user", "avatar": null, "member_since": "2012-03-04"}"#)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key".to_string())
+        .base_url(url)
+        .build()
+        .unwrap();
+
+    let response = client.get_user(1).await.unwrap();
+
+    mock.assert();
+    assert_eq!(response.username, "test_user");
+    assert_eq!(response.extra.len(), 1);
+    assert_eq!(response.extra["member_since"], "2012-03-04");
+}
+
+#[cfg(feature = "strict-models")]
+#[tokio::test]
+async fn strict_models_reject_unknown_fields_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let mock = server.mock("GET", "/users/1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"username": "test_user", "avatar": null, "member_since": "2012-03-04"}"#)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key".to_string())
+        .base_url(url)
+        .build()
+        .unwrap();
+
+    let response = client.get_user(1).await;
+
+    mock.assert();
+    assert!(
+        matches!(response, Err(Error::Request(_))),
+        "Expected a deserialization error, got {:?}",
+        response
+    );
+}
+
+#[cfg(all(feature = "lenient-models", feature = "strict-models"))]
+#[tokio::test]
+async fn strict_models_take_precedence_over_lenient_models_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let mock = server.mock("GET", "/users/1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"username": "test_user", "avatar": null}"#)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key".to_string())
+        .base_url(url)
+        .build()
+        .unwrap();
+
+    let response = client.get_user(1).await.unwrap();
+
+    mock.assert();
+    assert_eq!(response.username, "test_user");
+    assert!(response.extra.is_empty());
+}
+
+#[test]
+fn grade_parse_and_display_test() {
+    use models::Grade;
+
+    assert_eq!("VF".parse::<Grade>().unwrap(), Grade::Vf);
+    assert_eq!("vf".parse::<Grade>().unwrap(), Grade::Vf);
+    assert_eq!("Very Fine".parse::<Grade>().unwrap(), Grade::Vf);
+    assert_eq!("EF".parse::<Grade>().unwrap(), Grade::Xf);
+    assert_eq!("F/VF".parse::<Grade>().unwrap(), Grade::FVf);
+    assert_eq!("fine-very fine".parse::<Grade>().unwrap(), Grade::FVf);
+    assert!("F/XF".parse::<Grade>().is_err());
+    assert!("mint".parse::<Grade>().is_err());
+
+    assert_eq!(Grade::Vf.to_string(), "VF");
+    assert_eq!(Grade::AuUnc.to_string(), "AU/UNC");
+    assert_eq!(Grade::Xf.full_name(), "Extremely Fine");
+
+    assert_eq!(Grade::G.rank(), 1);
+    assert_eq!(Grade::Unc.rank(), 13);
+    assert!(Grade::F < Grade::FVf && Grade::FVf < Grade::Vf);
+
+    let grade: Grade = serde_json::from_str(r#""f/vf""#).unwrap();
+    assert_eq!(grade, Grade::FVf);
+    assert_eq!(serde_json::to_string(&Grade::Xf).unwrap(), r#""xf""#);
+}
+
+#[test]
+fn orientation_axis_conversion_test() {
+    assert_eq!(Orientation::from_axis(6), Some(Orientation::Coin));
+    assert_eq!(Orientation::from_axis(12), Some(Orientation::Medal));
+    // Axes go from 1 to 12, as validated before sending them.
+    assert_eq!(Orientation::from_axis(0), None);
+    assert_eq!(Orientation::from_axis(5), None);
+    assert_eq!(Orientation::from_degrees(270.0), Some(Orientation::Nine));
+    assert_eq!(Orientation::from_degrees(-90.0), Some(Orientation::Nine));
+    assert_eq!(Orientation::from_degrees(95.0), Some(Orientation::Three));
+    assert_eq!(Orientation::from_degrees(f64::NAN), None);
+    assert_eq!(Orientation::from_degrees(f64::INFINITY), None);
+
+    assert_eq!(Orientation::Coin.to_axis(), Some(6));
+    assert_eq!(Orientation::Medal.to_degrees(), Some(0));
+    assert_eq!(Orientation::Three.to_degrees(), Some(90));
+    assert_eq!(Orientation::Variable.to_axis(), None);
+
+    let orientation: Orientation = serde_json::from_str(r#""seven""#).unwrap();
+    assert_eq!(orientation, Orientation::Other("seven".to_string()));
+    assert_eq!(orientation.to_axis(), None);
+    assert_eq!(serde_json::to_string(&orientation).unwrap(), r#""seven""#);
+}
+
+#[test]
+fn unknown_enum_values_test() {
+    use models::{Category, Cover, Grade, PublicationType};
+
+    // Values added to the API are kept rather than failing the response.
+    let result: models::SearchTypeResult = serde_json::from_value(serde_json::json!({
+        "id": 1,
+        "title": "Token",
+        "category": "token",
+    }))
+    .unwrap();
+    assert_eq!(result.category, Some(Category::Other("token".to_string())));
+    assert_eq!(result.category.unwrap().to_string(), "token");
+
+    let grades: Vec<Grade> = serde_json::from_str(r#"["ms65", "vf", "f/vf", "G"]"#).unwrap();
+    assert_eq!(
+        grades,
+        [
+            Grade::Other("ms65".to_string()),
+            Grade::Vf,
+            Grade::FVf,
+            Grade::G
+        ]
+    );
+    assert_eq!(
+        serde_json::to_string(&grades).unwrap(),
+        r#"["ms65","vf","f/vf","g"]"#
+    );
+    assert!(grades[0] < Grade::G);
+    assert_eq!(grades[0].rank(), 0);
+
+    let types: Vec<PublicationType> =
+        serde_json::from_str(r#"["volume_group", "catalogue"]"#).unwrap();
+    assert_eq!(
+        types,
+        [
+            PublicationType::VolumeGroup,
+            PublicationType::Other("catalogue".to_string())
+        ]
+    );
+    let cover: Cover = serde_json::from_str(r#""leather""#).unwrap();
+    assert_eq!(cover, Cover::Other("leather".to_string()));
+    let mime_type: models::request::MimeType = serde_json::from_str(r#""image/webp""#).unwrap();
+    assert_eq!(
+        mime_type,
+        models::request::MimeType::Other("image/webp".to_string())
+    );
+}
+
+#[tokio::test]
+async fn add_collected_item_invalid_axis_test() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("POST", "/users/1/collected_items")
+        .expect(0)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key")
+        .base_url(server.url())
+        .build()
+        .unwrap();
+
+    let item = AddCollectedItemParams::new(42).axis(0);
+    let err = client.add_collected_item(1, &item).await.unwrap_err();
+    assert!(matches!(err, Error::Validation(issues) if issues[0].field == "axis"));
+    mock.assert();
+
+    let item: models::CollectedItem = serde_json::from_str(
+        r#"{"id": 1, "quantity": 1, "type": {"id": 42, "title": "Test", "category": "coin"}, "for_swap": false, "axis": 3}"#,
+    )
+    .unwrap();
+    assert_eq!(item.orientation(), Some(Orientation::Three));
+    assert_eq!(item.axis_degrees(), Some(90));
+
+    let item: models::CollectedItem = serde_json::from_str(
+        r#"{"id": 1, "quantity": 1, "type": {"id": 42, "title": "Test", "category": "coin"}, "for_swap": false, "axis": 0}"#,
+    )
+    .unwrap();
+    assert_eq!(item.orientation(), None);
+    assert_eq!(item.axis_degrees(), None);
+}
+
+#[tokio::test]
+async fn find_issue_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let mock = server.mock("GET", "/types/420/issues")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"[
+            {"id": 1, "is_dated": false, "min_year": 1858, "max_year": 1870},
+            {"id": 2, "is_dated": true, "year": 1858, "gregorian_year": 1858, "mint_letter": "H"},
+            {"id": 3, "is_dated": true, "year": 1858, "gregorian_year": 1858},
+            {"id": 4, "is_dated": true, "year": 1859, "gregorian_year": 1859, "marks": [{"id": 7}]}
+        ]"#)
+        .expect(4)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key".to_string())
+        .base_url(url)
+        .build()
+        .unwrap();
+
+    let issue = client.find_issue(420, Some(1858), None).await.unwrap();
+    assert_eq!(issue.unwrap().id, 3);
+
+    let issue = client.find_issue(420, Some(1858), Some("h")).await.unwrap();
+    assert_eq!(issue.unwrap().id, 2);
+
+    let issue = client.find_issue(420, Some(1865), None).await.unwrap();
+    assert_eq!(issue.unwrap().id, 1);
+
+    let issue = client.find_issue(420, Some(1900), None).await.unwrap();
+    assert!(issue.is_none());
+
+    mock.assert();
+}
+
+#[test]
+fn issue_matches_marks_test() {
+    let issue: models::Issue = serde_json::from_str(
+        r#"{"id": 4, "year": 1859, "gregorian_year": 1859, "marks": [{"id": 7}, {"id": 8}]}"#,
+    )
+    .unwrap();
+
+    assert!(issue.matches(Some(1859), None, &[7]));
+    assert!(issue.matches(None, None, &[7, 8]));
+    assert!(!issue.matches(None, None, &[9]));
+    assert!(!issue.matches(None, Some("D"), &[]));
+}
+
+#[tokio::test]
+async fn download_pictures_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let type_mock = server.mock("GET", "/types/420")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(format!(r#"{{
+            "id": 420,
+            "title": "5 Cents - Victoria",
+            "category": "coin",
+            "obverse": {{ "picture": "{url}/photos/obverse-original" }},
+            "reverse": {{ "picture": "{url}/photos/reverse-original.png" }}
+        }}"#))
+        .create();
+    let obverse_mock = server.mock("GET", "/photos/obverse-original")
+        .match_header("Numista-API-Key", mockito::Matcher::Missing)
+        .with_status(200)
+        .with_header("content-type", "image/jpeg")
+        .with_body("obverse")
+        .expect(1)
+        .create();
+    let reverse_mock = server.mock("GET", "/photos/reverse-original.png")
+        .with_status(200)
+        .with_header("content-type", "image/png")
+        .with_body("reverse")
+        .expect(1)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key".to_string())
+        .base_url(url)
+        .build()
+        .unwrap();
+    let numista_type = client.get_type(420).await.unwrap();
+    let dir = tempfile::tempdir().unwrap();
+
+    let pictures = client.download_pictures(&numista_type, dir.path()).await.unwrap();
+    assert_eq!(pictures.len(), 2);
+    assert_eq!(pictures[0].path, dir.path().join("type-420/obverse.jpg"));
+    assert!(!pictures[0].skipped);
+    assert_eq!(std::fs::read_to_string(&pictures[1].path).unwrap(), "reverse");
+
+    // A second download skips the existing files.
+    let pictures = client.download_pictures(&numista_type, dir.path()).await.unwrap();
+    assert!(pictures.iter().all(|p| p.skipped));
+
+    type_mock.assert();
+    obverse_mock.assert();
+    reverse_mock.assert();
+}
+
+#[tokio::test]
+async fn download_flag_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let flag_mock = server.mock("GET", "/flags/canada.svg")
+        .with_status(200)
+        .with_header("content-type", "image/svg+xml")
+        .with_body("<svg/>")
+        .expect(1)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key".to_string())
+        .base_url(url.clone())
+        .build()
+        .unwrap();
+    let issuer: models::IssuerDetail = serde_json::from_str(&format!(
+        r#"{{"code": "canada", "name": "Canada", "flag": "{url}/flags/canada.svg"}}"#
+    ))
+    .unwrap();
+    let dir = tempfile::tempdir().unwrap();
+
+    let flag = client.download_flag(&issuer, dir.path()).await.unwrap().unwrap();
+    assert_eq!(flag.path, dir.path().join("issuer-canada/flag.svg"));
+    assert!(!flag.skipped);
+    let flag = client.download_flag(&issuer, dir.path()).await.unwrap().unwrap();
+    assert!(flag.skipped);
+    flag_mock.assert();
+
+    let issuer: models::IssuerDetail =
+        serde_json::from_str(r#"{"code": "prusse", "name": "Prussia"}"#).unwrap();
+    assert!(client.download_flag(&issuer, dir.path()).await.unwrap().is_none());
+}
+
+#[test]
+fn coin_side_picture_urls_test() {
+    let side: models::CoinSide = serde_json::from_str(
+        r#"{"picture": "https://en.numista.com/catalogue/photos/etats-unis/5044-original.jpg"}"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        side.thumbnail_url().unwrap().as_str(),
+        "https://en.numista.com/catalogue/photos/etats-unis/5044-180.jpg"
+    );
+    assert_eq!(side.original_url(), side.picture);
+}
+
+#[tokio::test]
+async fn search_by_image_builder_hydrate_test() {
+    use planchet::image_search::SearchByImageBuilder;
+
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let search_mock = server.mock("POST", "/search_by_image")
+        .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+            "max_results": 5,
+            "images": [{"mime_type": "image/jpeg", "image_data": "AQID"}]
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"count": 2, "types": [
+            {"id": 420, "title": "5 Cents - Victoria", "similarity_distance": 0.12},
+            {"id": 421, "title": "10 Cents - Victoria", "similarity_distance": 0.34}
+        ]}"#)
+        .create();
+    let type_mock = server.mock("GET", "/types/420")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id": 420, "title": "5 Cents - Victoria", "category": "coin"}"#)
+        .expect(1)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key".to_string())
+        .base_url(url)
+        .build()
+        .unwrap();
+
+    let results = SearchByImageBuilder::new()
+        .image(models::request::Image::from_bytes(models::request::MimeType::Jpeg, &[1, 2, 3]))
+        .max_results(5)
+        .hydrate_top(1)
+        .send(&client)
+        .await
+        .unwrap();
+
+    search_mock.assert();
+    type_mock.assert();
+    assert_eq!(results.response.count, 2);
+    assert_eq!(results.types.len(), 1);
+    assert_eq!(results.types[0].id, 420);
+}
+
+#[test]
+fn demonetization_partial_date_test() {
+    use models::{Demonetization, PartialDate};
+
+    let d: Demonetization =
+        serde_json::from_str(r#"{"is_demonetized": true, "demonetization_date": "1990"}"#).unwrap();
+    assert_eq!(d.demonetization_date, Some(PartialDate::Year(1990)));
+
+    let d: Demonetization =
+        serde_json::from_str(r#"{"is_demonetized": true, "demonetization_date": "1990-06"}"#)
+            .unwrap();
+    let date = d.demonetization_date.unwrap();
+    assert_eq!(date, PartialDate::YearMonth(1990, 6));
+    assert_eq!(date.to_string(), "1990-06");
+    assert_eq!(date.first_day().unwrap().to_string(), "1990-06-01");
+
+    let d: Demonetization =
+        serde_json::from_str(r#"{"is_demonetized": true, "demonetization_date": "2002-02-28"}"#)
+            .unwrap();
+    let date = d.demonetization_date.unwrap();
+    assert_eq!((date.year(), date.month(), date.day()), (2002, Some(2), Some(28)));
+    assert_eq!(serde_json::to_string(&date).unwrap(), r#""2002-02-28""#);
+
+    assert!("1990-13".parse::<PartialDate>().is_err());
+    assert!("soon".parse::<PartialDate>().is_err());
+    assert!("-".parse::<PartialDate>().is_err());
+    assert!("1990-02-30".parse::<PartialDate>().is_err());
+
+    // Dates before the common era round-trip through their text.
+    for date in [
+        PartialDate::Year(-50),
+        PartialDate::YearMonth(-350, 3),
+        PartialDate::Date(chrono::NaiveDate::from_ymd_opt(-44, 3, 15).unwrap()),
+        PartialDate::Year(0),
+        PartialDate::Date(chrono::NaiveDate::from_ymd_opt(12345, 1, 2).unwrap()),
+    ] {
+        let text = String::from(date);
+        assert_eq!(PartialDate::try_from(text.clone()), Ok(date), "{}", text);
+    }
+    assert_eq!(PartialDate::YearMonth(-50, 6).to_string(), "-0050-06");
+}
+
+#[tokio::test]
+async fn get_type_issuing_entities_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let mock = server.mock("GET", "/types/8360")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{
+            "id": 8360,
+            "title": "5 Pounds",
+            "category": "banknote",
+            "issuing_entity": {"id": 10, "name": "Bank of Scotland", "wikidata_id": "Q627381"},
+            "secondary_issuing_entity": {"id": 11, "name": "Lloyds Banking Group"}
+        }"#)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key".to_string())
+        .base_url(url)
+        .build()
+        .unwrap();
+
+    let response = client.get_type(8360).await.unwrap();
+
+    mock.assert();
+    let entity = response.issuing_entity.unwrap();
+    assert_eq!(entity.id, 10);
+    assert_eq!(entity.name, "Bank of Scotland");
+    assert_eq!(entity.wikidata_id.unwrap(), "Q627381");
+    let secondary = response.secondary_issuing_entity.unwrap();
+    assert_eq!(secondary.id, 11);
+    assert!(secondary.wikidata_id.is_none());
+}
+
+#[test]
+fn script_and_calendar_enums_test() {
+    use models::{Calendar, Script};
+
+    let script: Script = serde_json::from_str(r#""Cyrillic""#).unwrap();
+    assert_eq!(script, Script::Cyrillic);
+    let script: Script = serde_json::from_str(r#""Glagolitic""#).unwrap();
+    assert_eq!(script, Script::Other("Glagolitic".to_string()));
+    assert_eq!(serde_json::to_string(&script).unwrap(), r#""Glagolitic""#);
+    assert_eq!(Script::Ethiopic.to_string(), "Ge'ez");
+
+    let issue: models::Issue =
+        serde_json::from_str(r#"{"id": 1, "year": 1420, "calendar": "Islamic", "gregorian_year": 1999}"#)
+            .unwrap();
+    assert_eq!(issue.calendar, Some(Calendar::Islamic));
+    assert_eq!("solar hijri".parse::<Calendar>().unwrap(), Calendar::SolarHijri);
+}
+
+#[tokio::test]
+async fn search_types_raw_borrowed_test() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let mock = server.mock("GET", "/types")
+        .match_query(mockito::Matcher::UrlEncoded("q".into(), "victoria".into()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"count": 1, "types": [{"id": 420, "title": "5 Cents - Victoria", "category": "coin", "issuer": {"code": "canada", "name": "Canada"}, "min_year": 1858, "max_year": 1901, "obverse_thumbnail": "https://en.numista.com/catalogue/photos/canada/1-180.jpg"}]}"#)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key".to_string())
+        .base_url(url)
+        .build()
+        .unwrap();
+
+    let body = client
+        .search_types_raw(&SearchTypesParams::new().q("victoria"))
+        .await
+        .unwrap();
+    let response = models::SearchTypesResponseRef::from_slice(&body).unwrap();
+
+    mock.assert();
+    assert_eq!(response.count, 1);
+    assert!(matches!(response.types[0].title, std::borrow::Cow::Borrowed("5 Cents - Victoria")));
+
+    let owned = response.to_owned();
+    assert_eq!(owned.types[0].id, 420);
+    assert_eq!(owned.types[0].issuer.as_ref().unwrap().name, "Canada");
+    assert_eq!(
+        owned.types[0].obverse_thumbnail.as_ref().unwrap().as_str(),
+        "https://en.numista.com/catalogue/photos/canada/1-180.jpg"
+    );
+}
+
+#[tokio::test]
+async fn max_concurrent_requests_test() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    // A server answering slowly, which records how many requests it handles
+    // at once.
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let (in_flight_server, peak_server) = (in_flight.clone(), peak.clone());
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let (in_flight, peak) = (in_flight_server.clone(), peak_server.clone());
+            tokio::spawn(async move {
+                let mut buf = [0; 4096];
+                let _ = socket.read(&mut buf).await;
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                let body = r#"{"id": 1, "title": "Test", "category": "coin"}"#;
+                let head = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+                    body.len()
+                );
+                // The headers are sent at once, and the body slowly.
+                let _ = socket.write_all(head.as_bytes()).await;
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                let _ = socket.write_all(body.as_bytes()).await;
+            });
+        }
+    });
+
+    let client = ClientBuilder::new()
+        .api_key("test_key")
+        .base_url(url)
+        .max_concurrent_requests(2)
+        .build()
+        .unwrap();
+
+    // The limit holds across clones of the client.
+    let tasks: Vec<_> = (0..6)
+        .map(|_| {
+            let client = client.clone();
+            tokio::spawn(async move { client.get_type(1).await })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap().unwrap();
+    }
+    assert_eq!(peak.load(Ordering::SeqCst), 2);
+
+    // A limit too large for a semaphore is lowered instead of panicking.
+    ClientBuilder::new()
+        .api_key("test_key")
+        .max_concurrent_requests(usize::MAX)
+        .build()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn circuit_breaker_test() {
+    use planchet::circuit_breaker::CircuitBreaker;
+    use std::time::Duration;
+
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let failing = server
+        .mock("GET", "/types/1")
+        .with_status(503)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"error_message": "Service unavailable"}"#)
+        .expect(2)
+        .create();
+
+    let breaker = CircuitBreaker::new()
+        .failure_threshold(2)
+        .cool_down(Duration::from_millis(200));
+    let client = ClientBuilder::new()
+        .api_key("test_key")
+        .base_url(url)
+        .circuit_breaker(breaker.clone())
+        .build()
+        .unwrap();
+
+    for _ in 0..2 {
+        assert!(client.get_type(1).await.unwrap_err().is_server());
+    }
+    assert!(breaker.is_open());
+
+    // The request fails without being sent.
+    let err = client.get_type(1).await.unwrap_err();
+    assert!(matches!(err, Error::CircuitOpen(d) if d <= Duration::from_millis(200)));
+    failing.assert();
+    failing.remove();
+
+    server
+        .mock("GET", "/types/1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id": 1, "title": "Test", "category": "coin"}"#)
+        .create();
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    client.get_type(1).await.unwrap();
+    assert!(!breaker.is_open());
+}
+
+#[cfg(feature = "audit")]
+#[tokio::test]
+async fn audit_log_test() {
+    use planchet::audit::{self, AuditLog};
+
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    server
+        .mock("GET", "/users/1/collected_items/1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id": 1, "quantity": 1, "type": {"id": 1, "title": "Test", "category": "coin"}, "for_swap": false}"#)
+        .create();
+    server
+        .mock("DELETE", "/users/1/collected_items/1")
+        .with_status(204)
+        .create();
+    server
+        .mock("DELETE", "/users/1/collected_items/2")
+        .with_status(404)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"error_message": "Not found"}"#)
+        .create();
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("audit.log");
+    let client = ClientBuilder::new()
+        .api_key("test_key")
+        .base_url(url)
+        .audit_log(AuditLog::file(&path).unwrap())
+        .build()
+        .unwrap();
+
+    client.get_collected_item(1, 1).await.unwrap();
+    let deleted = client
+        .delete_collected_item(1, 1)
+        .await
+        .unwrap()
+        .into_confirmation()
+        .unwrap();
+    client.delete_collected_item(1, 2).await.unwrap_err();
+
+    // Only the write requests are recorded.
+    assert_eq!(audit::verify(&path).unwrap(), 2);
+    let content = std::fs::read_to_string(&path).unwrap();
+    let entries: Vec<audit::AuditEntry> = content
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(entries[0].method, "DELETE");
+    assert_eq!(entries[0].path, "/users/1/collected_items/1");
+    assert_eq!(entries[0].status, Some(204));
+    assert_eq!(entries[0].request_id, deleted.request_id);
+    assert_eq!(entries[1].status, Some(404));
+    assert_eq!(entries[1].previous_hash, entries[0].hash);
+}
+
+#[cfg(feature = "vcr")]
+#[tokio::test]
+async fn vcr_cassette_test() {
+    use planchet::vcr::{Cassette, Mode};
+
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let mock = server
+        .mock("GET", "/types/420")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id": 420, "title": "5 Cents - Victoria", "category": "coin"}"#)
+        .expect(1)
+        .create();
+    server
+        .mock("GET", "/oauth_token")
+        .match_query(mockito::Matcher::Any)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"access_token": "t0k3n", "token_type": "bearer", "expires_in": 3600, "user_id": 1}"#)
+        .create();
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cassettes").join("types.yaml");
+    let client = |cassette| {
+        ClientBuilder::new()
+            .api_key("test_key")
+            .base_url(url.clone())
+            .cassette(cassette)
+            .build()
+            .unwrap()
+    };
+    let params = OAuthTokenParams::new(GrantType::ClientCredentials).scope("view_collection");
+
+    // The first run records the responses.
+    let cassette = Cassette::open(&path, Mode::Once).unwrap();
+    assert!(cassette.is_recording());
+    let recorder = client(cassette);
+    assert_eq!(recorder.get_type(420).await.unwrap().title, "5 Cents - Victoria");
+    assert_eq!(recorder.get_oauth_token(&params).await.unwrap().access_token, "t0k3n");
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert!(content.contains("path: /types/420"));
+    assert!(!content.contains("t0k3n"));
+    assert!(!content.contains("test_key"));
+
+    // The following runs replay them, without sending any request.
+    let cassette = Cassette::open(&path, Mode::Once).unwrap();
+    assert!(!cassette.is_recording());
+    assert_eq!(cassette.interactions().len(), 2);
+    let player = client(cassette);
+    let type_ = player.get_type(420).await.unwrap();
+    assert_eq!(type_.title, "5 Cents - Victoria");
+    assert_eq!(player.get_oauth_token(&params).await.unwrap().access_token, "REDACTED");
+    mock.assert();
+
+    // A request which was not recorded fails.
+    let err = player.get_type(421).await.unwrap_err();
+    assert!(matches!(err, Error::Vcr(_)), "{:?}", err);
+    // Each response is replayed once.
+    assert!(matches!(player.get_type(420).await, Err(Error::Vcr(_))));
+}
+
+#[cfg(feature = "spot-prices")]
+#[tokio::test]
+async fn gold_api_prices_test() {
+    use planchet::bullion::{GoldApiPrices, SpotPriceProvider};
+    use planchet::composition::Metal;
+
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/price/XAG")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"name": "Silver", "price": 30.5, "symbol": "XAG", "updatedAt": "2024-05-02T12:00:00Z"}"#)
+        .expect(1)
+        .create();
+    server.mock("GET", "/price/XAU").with_status(500).create();
+
+    let prices = GoldApiPrices::with_url(server.url());
+    assert_eq!(
+        prices.spot_price(&Metal::Silver).await.unwrap(),
+        Decimal::new(305, 1)
+    );
+    // The price is only fetched once.
+    assert_eq!(
+        prices.spot_price(&Metal::Silver).await.unwrap(),
+        Decimal::new(305, 1)
+    );
+    mock.assert();
+
+    assert!(matches!(
+        prices.spot_price(&Metal::Gold).await,
+        Err(Error::SpotPrice(_))
+    ));
+    assert!(matches!(
+        prices.spot_price(&Metal::Copper).await,
+        Err(Error::SpotPrice(_))
+    ));
+
+    // An expired price is fetched again.
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/price/XAG")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"name": "Silver", "price": 30.5, "symbol": "XAG"}"#)
+        .expect(2)
+        .create();
+    let prices = GoldApiPrices::with_url(server.url()).ttl(std::time::Duration::ZERO);
+    prices.spot_price(&Metal::Silver).await.unwrap();
+    prices.spot_price(&Metal::Silver).await.unwrap();
+    mock.assert();
+}
+
+#[test]
+fn model_compat_test() {
+    // The models are still available through the deprecated `model` module.
+    let grade: planchet::model::Grade = models::Grade::Xf;
+    let mime_type: models::request::MimeType = planchet::model::request::MimeType::Png;
+    assert_eq!(grade, models::Grade::Xf);
+    assert!(matches!(mime_type, models::request::MimeType::Png));
+}
+
+#[test]
+fn enum_display_from_str_test() {
+    use models::{Category, Grade, GrantType, Orientation};
+
+    assert_eq!("Banknote".parse(), Ok(Category::Banknote));
+    assert_eq!("token".parse(), Ok(Category::Other("token".to_string())));
+    assert_eq!(Category::Exonumia.to_string(), "Exonumia");
+
+    assert_eq!("vf".parse(), Ok(Grade::Vf));
+    assert_eq!(Grade::FVf.to_string(), "F/VF");
+
+    assert_eq!(
+        "client-credentials".parse(),
+        Ok(GrantType::ClientCredentials)
+    );
+    assert!("password".parse::<GrantType>().is_err());
+    assert_eq!(
+        GrantType::AuthorizationCode.to_string(),
+        "authorization_code"
+    );
+
+    assert_eq!("medal".parse(), Ok(Orientation::Medal));
+    assert_eq!("3 o'clock".parse(), Ok(Orientation::Three));
+    assert_eq!("6".parse(), Ok(Orientation::Coin));
+    for orientation in [Orientation::Coin, Orientation::Nine, Orientation::Variable] {
+        assert_eq!(orientation.to_string().parse(), Ok(orientation));
+    }
+}
+
+#[test]
+fn model_equality_test() {
+    use std::collections::HashSet;
+
+    let result = |id: i64, title: &str| -> models::SearchTypeResult {
+        serde_json::from_value(serde_json::json!({ "id": id, "title": title, "category": "coin" }))
+            .unwrap()
+    };
+    assert_eq!(result(1, "5 Cents"), result(1, "5 Cents"));
+    assert_ne!(result(1, "5 Cents"), result(1, "1 Cent"));
+
+    let results: HashSet<_> = [
+        result(1, "5 Cents"),
+        result(1, "5 Cents"),
+        result(2, "1 Cent"),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(results.len(), 2);
+    assert!(results.contains(&result(2, "1 Cent")));
+    // Models with the same ID but other fields are different elements.
+    assert!(!results.contains(&result(2, "2 Cents")));
+}
+
+#[cfg(feature = "import")]
+#[tokio::test]
+async fn import_collected_items_cancelled_in_flight_test() {
+    use planchet::import::ColumnMapping;
+
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let token = tokio_util::sync::CancellationToken::new();
+    let cancel = token.clone();
+    let mock = server
+        .mock("POST", "/users/1/collected_items")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body_from_request(move |_| {
+            // The token is cancelled while the first row is being added.
+            cancel.cancel();
+            br#"{"id": 1, "quantity": 1, "type": {"id": 420, "title": "Test", "category": "coin"}, "for_swap": false}"#.to_vec()
+        })
+        .expect(1)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test_key")
+        .base_url(url)
+        .build()
+        .unwrap();
+    let mapping = ColumnMapping::new()
+        .delay(std::time::Duration::ZERO)
+        .cancellation(token);
+
+    let report = client
+        .import_collected_items(1, "type\n420\n421\n".as_bytes(), &mapping)
+        .await
+        .unwrap();
+
+    mock.assert();
+    assert_eq!(report.rows.len(), 2);
+    // The row in flight is finished, so its item is known.
+    let confirmation = report.rows[0].result.as_ref().unwrap().confirmation();
+    assert_eq!(confirmation.unwrap().value.id, 1);
+    assert!(matches!(report.rows[1].result, Err(Error::Cancelled)));
+}
@@ -0,0 +1,323 @@
+//! Generation of models from the OpenAPI document of the API.
+//!
+//! Every schema of the document becomes a struct, or an enum for strings
+//! with a fixed set of values, deserializing the same JSON. Objects nested in
+//! a schema become structs named after their parent and field. Fields not
+//! required by the schema are `Option`s, and schemas which cannot be expressed
+//! as plain Rust types, e.g. `oneOf`, are `serde_json::Value`s.
+//!
+//! The output only depends on the document: schemas and fields are sorted by
+//! name, so that regenerating an unchanged document gives the same file.
+use anyhow::{bail, Context, Result};
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+/// The header of the generated file.
+const HEADER: &str = "\
+//! Models generated from the OpenAPI document of the Numista API.
+//!
+//! @generated by `cargo xtask codegen`. Do not edit by hand: edit the
+//! handwritten models wrapping these instead, or regenerate this file.
+#![allow(clippy::all)]
+use serde::{Deserialize, Serialize};
+";
+
+/// The keywords of Rust which cannot be used as field names.
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "box", "break", "const", "continue", "crate", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+    "mut", "pub", "ref", "return", "self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while", "yield",
+];
+
+/// Parses an OpenAPI document, in JSON or YAML.
+pub fn parse(text: &str) -> Result<Value> {
+    match serde_json::from_str(text) {
+        Ok(spec) => Ok(spec),
+        Err(_) => serde_yaml::from_str(text).context("The document is neither JSON nor YAML"),
+    }
+}
+
+/// Generates the models of the schemas of an OpenAPI 3 document, or of the
+/// definitions of a Swagger 2 one.
+pub fn generate(spec: &Value) -> Result<String> {
+    let schemas = spec
+        .pointer("/components/schemas")
+        .or_else(|| spec.get("definitions"))
+        .and_then(Value::as_object)
+        .context("The document has no schemas")?;
+    if schemas.is_empty() {
+        bail!("The document has no schemas");
+    }
+    let mut generator = Generator::default();
+    for (name, schema) in schemas {
+        generator.item(&pascal_case(name), schema);
+    }
+    Ok(format!("{}{}", HEADER, generator.out))
+}
+
+#[derive(Default)]
+struct Generator {
+    out: String,
+    /// The names of the items generated, to generate each only once.
+    emitted: BTreeSet<String>,
+}
+
+impl Generator {
+    /// Generates the item of a named schema, if it is an object or an enum.
+    fn item(&mut self, name: &str, schema: &Value) {
+        let ty = self.rust_type(name, schema);
+        if ty != name && self.emitted.insert(name.to_string()) {
+            // A schema of another type, e.g. an array, becomes an alias.
+            self.out.push('\n');
+            doc(&mut self.out, "", schema);
+            let _ = writeln!(self.out, "pub type {} = {};", name, ty);
+        }
+    }
+
+    /// Returns the Rust type of a schema, generating the items it needs.
+    /// `name` is the name of the item generated for an object or an enum.
+    fn rust_type(&mut self, name: &str, schema: &Value) -> String {
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let target = reference.rsplit('/').next().unwrap_or(reference);
+            return pascal_case(target);
+        }
+        if let Some([single]) = schema
+            .get("allOf")
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+        {
+            return self.rust_type(name, single);
+        }
+        let ty = match schema.get("type").and_then(Value::as_str) {
+            Some("string") => match schema.get("enum").and_then(Value::as_array) {
+                Some(values) => {
+                    self.enumeration(name, schema, values);
+                    name.to_string()
+                }
+                None => "String".to_string(),
+            },
+            Some("integer") => "i64".to_string(),
+            Some("number") => "f64".to_string(),
+            Some("boolean") => "bool".to_string(),
+            Some("array") => {
+                let items = schema.get("items").unwrap_or(&Value::Null);
+                format!("Vec<{}>", self.rust_type(&format!("{}Item", name), items))
+            }
+            Some("object") | None => match schema.get("properties").and_then(Value::as_object) {
+                Some(properties) => {
+                    self.structure(name, schema, properties);
+                    name.to_string()
+                }
+                None => "serde_json::Value".to_string(),
+            },
+            Some(_) => "serde_json::Value".to_string(),
+        };
+        if schema.get("nullable").and_then(Value::as_bool) == Some(true) {
+            format!("Option<{}>", ty)
+        } else {
+            ty
+        }
+    }
+
+    fn structure(&mut self, name: &str, schema: &Value, properties: &Map<String, Value>) {
+        if !self.emitted.insert(name.to_string()) {
+            return;
+        }
+        let required: BTreeSet<&str> = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .collect();
+        let mut fields = String::new();
+        for (property, field_schema) in properties {
+            let field = field_name(property);
+            let mut ty =
+                self.rust_type(&format!("{}{}", name, pascal_case(property)), field_schema);
+            if !required.contains(property.as_str()) && !ty.starts_with("Option<") {
+                ty = format!("Option<{}>", ty);
+            }
+            doc(&mut fields, "    ", field_schema);
+            if field != *property {
+                let _ = writeln!(fields, "    #[serde(rename = {:?})]", property);
+            }
+            if ty.starts_with("Option<") {
+                fields
+                    .push_str("    #[serde(default, skip_serializing_if = \"Option::is_none\")]\n");
+            }
+            let _ = writeln!(fields, "    pub {}: {},", field, ty);
+        }
+        self.out.push('\n');
+        doc(&mut self.out, "", schema);
+        self.out
+            .push_str("#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]\n");
+        let _ = writeln!(self.out, "pub struct {} {{\n{}}}", name, fields);
+    }
+
+    fn enumeration(&mut self, name: &str, schema: &Value, values: &[Value]) {
+        if !self.emitted.insert(name.to_string()) {
+            return;
+        }
+        self.out.push('\n');
+        doc(&mut self.out, "", schema);
+        self.out.push_str(
+            "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]\n",
+        );
+        let _ = writeln!(self.out, "pub enum {} {{", name);
+        for value in values.iter().filter_map(Value::as_str) {
+            let _ = writeln!(self.out, "    #[serde(rename = {:?})]", value);
+            let _ = writeln!(self.out, "    {},", variant_name(value));
+        }
+        self.out.push_str("}\n");
+    }
+}
+
+/// Writes the description of a schema as a doc comment.
+fn doc(out: &mut String, indent: &str, schema: &Value) {
+    let description = schema.get("description").and_then(Value::as_str);
+    for line in description.into_iter().flat_map(str::lines) {
+        let line = line.trim_end();
+        if line.is_empty() {
+            let _ = writeln!(out, "{}///", indent);
+        } else {
+            let _ = writeln!(out, "{}/// {}", indent, line);
+        }
+    }
+}
+
+/// Returns the words of a name, split at non-alphanumeric characters and
+/// before capitals following a lower case letter.
+fn words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut previous_lower = false;
+    for c in name.chars() {
+        if !c.is_ascii_alphanumeric() {
+            words.extend((!current.is_empty()).then(|| std::mem::take(&mut current)));
+            previous_lower = false;
+            continue;
+        }
+        if c.is_ascii_uppercase() && previous_lower {
+            words.push(std::mem::take(&mut current));
+        }
+        previous_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        current.push(c);
+    }
+    words.extend((!current.is_empty()).then_some(current));
+    words
+}
+
+/// Converts a name to `PascalCase`, e.g. `collected_item` to `CollectedItem`.
+fn pascal_case(name: &str) -> String {
+    words(name)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_ascii_uppercase().to_string() + &chars.as_str().to_ascii_lowercase()
+            })
+        })
+        .collect()
+}
+
+/// Returns the name of the field of a property, in `snake_case`, e.g.
+/// `type_` for `type`.
+fn field_name(property: &str) -> String {
+    let name = words(property)
+        .iter()
+        .map(|word| word.to_ascii_lowercase())
+        .collect::<Vec<_>>()
+        .join("_");
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("field_{}", name)
+    } else if KEYWORDS.contains(&name.as_str()) {
+        format!("{}_", name)
+    } else {
+        name
+    }
+}
+
+/// Returns the name of the variant of a value, e.g. `FVf` for `f/vf`.
+fn variant_name(value: &str) -> String {
+    let name = pascal_case(value);
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("V{}", name)
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r#"
+openapi: 3.0.0
+components:
+  schemas:
+    type:
+      description: A type of the catalogue.
+      type: object
+      required: [id, title]
+      properties:
+        id:
+          type: integer
+        title:
+          type: string
+        type:
+          type: string
+        issuer:
+          $ref: '#/components/schemas/issuer'
+        demonetization:
+          type: object
+          properties:
+            is_demonetized:
+              type: boolean
+        tags:
+          type: array
+          items:
+            type: string
+    issuer:
+      type: object
+      properties:
+        code:
+          type: string
+    grade:
+      type: string
+      enum: [g, f/vf, unc]
+"#;
+
+    #[test]
+    fn test_generate() {
+        let generated = generate(&parse(SPEC).unwrap()).unwrap();
+        for expected in [
+            "pub enum Grade {\n    #[serde(rename = \"g\")]\n    G,\n    #[serde(rename = \"f/vf\")]\n    FVf,",
+            "pub struct Issuer {\n    #[serde(default, skip_serializing_if = \"Option::is_none\")]\n    pub code: Option<String>,\n}",
+            "/// A type of the catalogue.\n#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]\npub struct Type {",
+            "    pub demonetization: Option<TypeDemonetization>,\n",
+            "    pub id: i64,\n",
+            "    pub issuer: Option<Issuer>,\n",
+            "    pub tags: Option<Vec<String>>,\n",
+            "    pub title: String,\n",
+            "    #[serde(rename = \"type\")]\n    #[serde(default, skip_serializing_if = \"Option::is_none\")]\n    pub type_: Option<String>,\n",
+            "pub struct TypeDemonetization {\n",
+        ] {
+            assert!(generated.contains(expected), "{}\n{}", expected, generated);
+        }
+        // Generating again gives the same file.
+        assert_eq!(generated, generate(&parse(SPEC).unwrap()).unwrap());
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(pascal_case("collected_item"), "CollectedItem");
+        assert_eq!(pascal_case("searchTypesResponse"), "SearchTypesResponse");
+        assert_eq!(field_name("isDemonetized"), "is_demonetized");
+        assert_eq!(field_name("ref"), "ref_");
+        assert_eq!(variant_name("f/vf"), "FVf");
+        assert_eq!(variant_name("2x2"), "V2x2");
+    }
+}
@@ -0,0 +1,85 @@
+//! Development tasks of the workspace, run with `cargo xtask <TASK>`.
+//!
+//! # `codegen`
+//!
+//! Generates models from the OpenAPI document of the Numista API, so that
+//! the models of `planchet` do not drift from the upstream schema:
+//!
+//! ```bash
+//! $ cargo xtask codegen
+//! Wrote 54 models to planchet/src/models/generated.rs
+//! ```
+//!
+//! The document is `spec/numista.yaml`, transcribed from the API
+//! documentation of Numista; another one, in JSON or YAML, can be given
+//! instead. The generated models are plain structs and
+//! enums, mirroring the schema; the handwritten models keep their
+//! conveniences, e.g. typed codes and parsed dates, by wrapping them or
+//! converting from them, and a test of `planchet` checks that both have the
+//! same fields. With `--check`, nothing is written, and the task fails if the
+//! generated file is not up to date, e.g. in CI.
+mod codegen;
+
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use std::fs;
+use std::path::PathBuf;
+
+/// The OpenAPI document of the API, relative to the workspace.
+const SPEC: &str = "spec/numista.yaml";
+
+/// The file the models are generated into, relative to the workspace.
+const GENERATED_MODELS: &str = "planchet/src/models/generated.rs";
+
+#[derive(Parser)]
+#[command(about = "Development tasks of the planchet workspace")]
+struct Cli {
+    #[command(subcommand)]
+    task: Task,
+}
+
+#[derive(Subcommand)]
+enum Task {
+    /// Generate the models from the OpenAPI document of the API.
+    Codegen {
+        /// The OpenAPI document, in JSON or YAML.
+        #[arg(default_value = SPEC)]
+        spec: PathBuf,
+
+        /// The file to write the models to.
+        #[arg(long, default_value = GENERATED_MODELS)]
+        out: PathBuf,
+
+        /// Fail if the file is not up to date, instead of writing it.
+        #[arg(long)]
+        check: bool,
+    },
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    match cli.task {
+        Task::Codegen { spec, out, check } => {
+            let text = fs::read_to_string(&spec)
+                .with_context(|| format!("Failed to read {}", spec.display()))?;
+            let generated = codegen::generate(&codegen::parse(&text)?)?;
+            let models = generated.matches("\npub ").count();
+            if check {
+                let current = fs::read_to_string(&out).unwrap_or_default();
+                if current != generated {
+                    bail!(
+                        "{} is not up to date: run `cargo xtask codegen {}`",
+                        out.display(),
+                        spec.display()
+                    );
+                }
+                println!("{} is up to date", out.display());
+            } else {
+                fs::write(&out, generated)
+                    .with_context(|| format!("Failed to write {}", out.display()))?;
+                println!("Wrote {} models to {}", models, out.display());
+            }
+        }
+    }
+    Ok(())
+}